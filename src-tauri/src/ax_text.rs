@@ -3,11 +3,16 @@
 //!
 //! Uses raw FFI to ApplicationServices.framework for capture,
 //! and clipboard + osascript for replacement (universally reliable).
+//! Also watches the focused app for change notifications and reports
+//! input idle time and screen lock for the trigger scheduler.
 
+use std::cell::RefCell;
 use std::ffi::c_void;
 use std::process::Command;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::Duration;
 
 use serde::Serialize;
 
@@ -17,7 +22,17 @@ type CFTypeRef = *const c_void;
 type CFStringRef = *const c_void;
 type AXUIElementRef = CFTypeRef;
 type AXValueRef = CFTypeRef;
+type AXObserverRef = CFTypeRef;
+type CFRunLoopRef = CFTypeRef;
+type CFRunLoopSourceRef = CFTypeRef;
+type CFDictionaryRef = CFTypeRef;
 type AXError = i32;
+type AXObserverCallback = unsafe extern "C" fn(
+    observer: AXObserverRef,
+    element: AXUIElementRef,
+    notification: CFStringRef,
+    refcon: *mut c_void,
+);
 
 const K_AX_ERROR_SUCCESS: AXError = 0;
 const K_AX_VALUE_CF_RANGE_TYPE: i32 = 4;
@@ -74,11 +89,26 @@ extern "C" {
     ) -> AXError;
     fn AXValueCreate(value_type: i32, value_ptr: *const c_void) -> AXValueRef;
     fn AXValueGetValue(value: AXValueRef, value_type: i32, value_out: *mut c_void) -> bool;
+    fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+    fn AXUIElementGetPid(element: AXUIElementRef, pid: *mut i32) -> AXError;
+    fn AXObserverCreate(
+        application: i32,
+        callback: AXObserverCallback,
+        out_observer: *mut AXObserverRef,
+    ) -> AXError;
+    fn AXObserverAddNotification(
+        observer: AXObserverRef,
+        element: AXUIElementRef,
+        notification: CFStringRef,
+        refcon: *mut c_void,
+    ) -> AXError;
+    fn AXObserverGetRunLoopSource(observer: AXObserverRef) -> CFRunLoopSourceRef;
 }
 
 #[link(name = "CoreFoundation", kind = "framework")]
 extern "C" {
     fn CFRelease(cf: CFTypeRef);
+    fn CFHash(cf: CFTypeRef) -> usize;
     fn CFStringCreateWithCString(
         alloc: CFTypeRef,
         c_str: *const u8,
@@ -91,8 +121,39 @@ extern "C" {
         buffer_size: i64,
         encoding: u32,
     ) -> bool;
+    fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+    fn CFRunLoopAddSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFStringRef);
+    fn CFRunLoopRemoveSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFStringRef);
+    fn CFRunLoopRunInMode(mode: CFStringRef, seconds: f64, return_after_source_handled: bool) -> i32;
+    fn CFDictionaryGetValue(dict: CFDictionaryRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFBooleanGetValue(boolean: CFTypeRef) -> bool;
+    fn CFURLCreateFromFileSystemRepresentation(
+        alloc: CFTypeRef,
+        buffer: *const u8,
+        buf_len: isize,
+        is_directory: bool,
+    ) -> CFTypeRef;
+    fn CFBundleCreate(alloc: CFTypeRef, bundle_url: CFTypeRef) -> CFTypeRef;
+    fn CFBundleGetIdentifier(bundle: CFTypeRef) -> CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kCFRunLoopDefaultMode: CFStringRef;
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
 }
 
+extern "C" {
+    fn proc_pidpath(pid: i32, buffer: *mut u8, buffer_size: u32) -> i32;
+}
+
+const PROC_PIDPATHINFO_MAXSIZE: usize = 4096;
+
+const K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION: i32 = 0;
+const K_CG_ANY_INPUT_EVENT_TYPE: u32 = !0;
+
 const K_CF_STRING_ENCODING_UTF8: u32 = 0x08000100;
 
 // --- Stored app name for clipboard+paste replacement ---
@@ -166,6 +227,85 @@ fn get_frontmost_app_name() -> Option<String> {
     }
 }
 
+/// Get the name of the application owning keyboard focus via the AX API.
+/// Much cheaper than osascript, so it is safe to call from the poller.
+pub fn focused_app_name() -> Option<String> {
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        let attr = ax_attr("AXFocusedApplication");
+        let mut app: CFTypeRef = ptr::null();
+        let err = AXUIElementCopyAttributeValue(system_wide, attr, &mut app);
+        CFRelease(attr);
+        CFRelease(system_wide);
+        if err != K_AX_ERROR_SUCCESS || app.is_null() {
+            return None;
+        }
+
+        let title_attr = ax_attr("AXTitle");
+        let mut title: CFTypeRef = ptr::null();
+        let err = AXUIElementCopyAttributeValue(app, title_attr, &mut title);
+        CFRelease(title_attr);
+        CFRelease(app);
+        if err != K_AX_ERROR_SUCCESS || title.is_null() {
+            return None;
+        }
+        let name = cfstring_to_rust(title as CFStringRef);
+        CFRelease(title);
+        name.filter(|n| !n.is_empty())
+    }
+}
+
+/// Bundle identifier of the last app looked up, by pid.
+static BUNDLE_ID: Mutex<Option<(i32, Option<String>)>> = Mutex::new(None);
+
+/// Bundle identifier of the app at `pid`, e.g. `com.apple.TextEdit`.
+/// `None` for processes that are not inside an app bundle.
+fn bundle_id_for_pid(pid: i32) -> Option<String> {
+    let mut buf = vec![0u8; PROC_PIDPATHINFO_MAXSIZE];
+    let len = unsafe { proc_pidpath(pid, buf.as_mut_ptr(), buf.len() as u32) };
+    if len <= 0 {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&buf[..len as usize]).to_string();
+    let end = path.rfind(".app/Contents/")? + ".app".len();
+    let bundle_path = &path[..end];
+    unsafe {
+        let url = CFURLCreateFromFileSystemRepresentation(
+            ptr::null(),
+            bundle_path.as_ptr(),
+            bundle_path.len() as isize,
+            true,
+        );
+        if url.is_null() {
+            return None;
+        }
+        let bundle = CFBundleCreate(ptr::null(), url);
+        CFRelease(url);
+        if bundle.is_null() {
+            return None;
+        }
+        // Get rule: the identifier is owned by the bundle.
+        let id = cfstring_to_rust(CFBundleGetIdentifier(bundle));
+        CFRelease(bundle);
+        id.filter(|id| !id.is_empty())
+    }
+}
+
+/// Bundle identifier of the application owning keyboard focus. Cached per
+/// pid, so it is safe to call from the poller.
+pub fn focused_app_bundle_id() -> Option<String> {
+    let pid = focused_app_pid()?;
+    let mut cached = BUNDLE_ID.lock().unwrap();
+    if let Some((cached_pid, id)) = cached.as_ref() {
+        if *cached_pid == pid {
+            return id.clone();
+        }
+    }
+    let id = bundle_id_for_pid(pid);
+    *cached = Some((pid, id.clone()));
+    id
+}
+
 /// Activate an application by name via osascript.
 fn activate_app(app_name: &str) -> Result<(), String> {
     let script = format!(
@@ -240,6 +380,34 @@ fn get_focused_element() -> Option<AXUIElementRef> {
     }
 }
 
+/// Read a string attribute such as `AXRole` from a UI element.
+fn read_string_attr(element: AXUIElementRef, name: &str) -> Option<String> {
+    unsafe {
+        let attr = ax_attr(name);
+        let mut value: CFTypeRef = ptr::null();
+        let err = AXUIElementCopyAttributeValue(element, attr, &mut value);
+        CFRelease(attr);
+        if err != K_AX_ERROR_SUCCESS || value.is_null() {
+            return None;
+        }
+        let text = cfstring_to_rust(value as CFStringRef);
+        CFRelease(value);
+        text
+    }
+}
+
+/// Whether the element is a password or other secure text field. Such
+/// fields never report their text, but apps that draw their own controls
+/// may only say so in the role or subrole name.
+fn is_secure_element(element: AXUIElementRef) -> bool {
+    ["AXRole", "AXSubrole"].iter().any(|attr| {
+        read_string_attr(element, attr).is_some_and(|role| {
+            let role = role.to_lowercase();
+            role.contains("secure") || role.contains("password")
+        })
+    })
+}
+
 /// Read the selected text from a UI element.
 fn read_selected_text(element: AXUIElementRef) -> Option<String> {
     unsafe {
@@ -412,6 +580,12 @@ pub struct CaptureResult {
     pub text: String,
     pub bounds: Option<SelectionBounds>,
     pub line_start_x: Option<f64>,
+    /// Process owning the focused element.
+    pub pid: Option<i32>,
+    /// `CFHash` of the focused element; equal for the same element.
+    pub element: usize,
+    /// Frontmost app name stored for replacement; `None` for a peek.
+    pub app_name: Option<String>,
 }
 
 /// Check if the process has accessibility permission.
@@ -451,6 +625,11 @@ fn capture_selection_ax_internal(should_store_app: bool) -> Result<CaptureResult
     let element = get_focused_element()
         .ok_or_else(|| "no_focused_element".to_string())?;
 
+    if is_secure_element(element) {
+        unsafe { CFRelease(element); }
+        return Err("secure_field".to_string());
+    }
+
     let text = read_selected_text(element)
         .ok_or_else(|| {
             unsafe { CFRelease(element); }
@@ -464,10 +643,13 @@ fn capture_selection_ax_internal(should_store_app: bool) -> Result<CaptureResult
 
     let bounds = get_selection_bounds(element);
     let line_start_x = get_current_line_start_x(element);
+    let element_hash = unsafe { CFHash(element) };
+    let mut pid = 0;
+    let pid = (unsafe { AXUIElementGetPid(element, &mut pid) } == K_AX_ERROR_SUCCESS).then_some(pid);
 
     // Store the app name for later replacement
     if should_store_app {
-        store_app_name(app_name);
+        store_app_name(app_name.clone());
     }
 
     unsafe { CFRelease(element); }
@@ -484,6 +666,9 @@ fn capture_selection_ax_internal(should_store_app: bool) -> Result<CaptureResult
         text,
         bounds,
         line_start_x,
+        pid,
+        element: element_hash,
+        app_name,
     })
 }
 
@@ -513,6 +698,11 @@ pub fn peek_paragraph_ax() -> Result<ParagraphResult, String> {
     let element = get_focused_element()
         .ok_or_else(|| "no_focused_element".to_string())?;
 
+    if is_secure_element(element) {
+        unsafe { CFRelease(element); }
+        return Err("secure_field".to_string());
+    }
+
     // Check that there is a caret but no selection
     let range = match get_selected_text_range(element) {
         Some(r) => r,
@@ -636,6 +826,16 @@ pub fn replace_via_clipboard(text: &str) -> Result<(), String> {
     let app_name = get_stored_app_name()
         .ok_or_else(|| "no_stored_app: capture first".to_string())?;
 
+    paste_into(&app_name, text)?;
+
+    // Clear stored state
+    clear_stored_element();
+
+    Ok(())
+}
+
+/// Paste `text` over the selection in `app_name`, whatever app was stored.
+pub fn paste_into(app_name: &str, text: &str) -> Result<(), String> {
     println!("[Polishr] Replace via clipboard+paste to app: {}", app_name);
 
     // Step 1: Set clipboard
@@ -643,7 +843,7 @@ pub fn replace_via_clipboard(text: &str) -> Result<(), String> {
     println!("[Polishr] Clipboard set ({} chars)", text.len());
 
     // Step 2: Activate the original app
-    activate_app(&app_name)?;
+    activate_app(app_name)?;
     println!("[Polishr] App '{}' activated", app_name);
 
     // Step 3: Wait for the app to become active
@@ -653,9 +853,6 @@ pub fn replace_via_clipboard(text: &str) -> Result<(), String> {
     simulate_paste()?;
     println!("[Polishr] Cmd+V simulated");
 
-    // Clear stored state
-    clear_stored_element();
-
     Ok(())
 }
 
@@ -674,3 +871,172 @@ pub fn get_mouse_position() -> (f64, f64) {
         (point.x, point.y)
     }
 }
+
+// --- Change notifications ---
+
+/// Notifications that may move or change the selection.
+const WATCHED_NOTIFICATIONS: [&str; 6] = [
+    "AXFocusedUIElementChanged",
+    "AXSelectedTextChanged",
+    "AXValueChanged",
+    "AXFocusedWindowChanged",
+    "AXWindowMoved",
+    "AXWindowResized",
+];
+
+/// How often the watcher checks which app has focus.
+const FOCUS_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+static OBSERVING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Callback of the watcher thread; AX observers call back on the run
+    /// loop they were added to.
+    static ON_CHANGE: RefCell<Option<Box<dyn Fn()>>> = RefCell::new(None);
+}
+
+unsafe extern "C" fn on_notification(
+    _observer: AXObserverRef,
+    _element: AXUIElementRef,
+    _notification: CFStringRef,
+    _refcon: *mut c_void,
+) {
+    ON_CHANGE.with(|cb| {
+        if let Some(cb) = cb.borrow().as_ref() {
+            cb();
+        }
+    });
+}
+
+/// Observer registered on one app, and the app element it watches.
+struct Observed {
+    observer: AXObserverRef,
+    app: AXUIElementRef,
+}
+
+fn focused_app_pid() -> Option<i32> {
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        let attr = ax_attr("AXFocusedApplication");
+        let mut app: CFTypeRef = ptr::null();
+        let err = AXUIElementCopyAttributeValue(system_wide, attr, &mut app);
+        CFRelease(attr);
+        CFRelease(system_wide);
+        if err != K_AX_ERROR_SUCCESS || app.is_null() {
+            return None;
+        }
+        let mut pid = 0;
+        let err = AXUIElementGetPid(app, &mut pid);
+        CFRelease(app);
+        (err == K_AX_ERROR_SUCCESS).then_some(pid)
+    }
+}
+
+/// Register for [`WATCHED_NOTIFICATIONS`] on `pid` and add the observer to
+/// the current run loop. `None` when the app supports none of them.
+fn observe(pid: i32) -> Option<Observed> {
+    unsafe {
+        let mut observer: AXObserverRef = ptr::null();
+        if AXObserverCreate(pid, on_notification, &mut observer) != K_AX_ERROR_SUCCESS
+            || observer.is_null()
+        {
+            return None;
+        }
+        let app = AXUIElementCreateApplication(pid);
+        let mut added = 0;
+        for name in WATCHED_NOTIFICATIONS {
+            let notification = ax_attr(name);
+            if AXObserverAddNotification(observer, app, notification, ptr::null_mut()) == K_AX_ERROR_SUCCESS {
+                added += 1;
+            }
+            CFRelease(notification);
+        }
+        if added == 0 {
+            CFRelease(app);
+            CFRelease(observer);
+            return None;
+        }
+        CFRunLoopAddSource(CFRunLoopGetCurrent(), AXObserverGetRunLoopSource(observer), kCFRunLoopDefaultMode);
+        Some(Observed { observer, app })
+    }
+}
+
+fn unobserve(observed: Observed) {
+    unsafe {
+        CFRunLoopRemoveSource(
+            CFRunLoopGetCurrent(),
+            AXObserverGetRunLoopSource(observed.observer),
+            kCFRunLoopDefaultMode,
+        );
+        CFRelease(observed.observer);
+        CFRelease(observed.app);
+    }
+}
+
+/// Call `on_change` whenever the focused app reports a change that may
+/// affect the selection, and when another app gets focus. Runs on its own
+/// thread for the rest of the app's life.
+pub fn watch_focused_app(on_change: impl Fn() + Send + 'static) {
+    std::thread::spawn(move || {
+        ON_CHANGE.with(|cb| *cb.borrow_mut() = Some(Box::new(on_change)));
+        let own_pid = std::process::id() as i32;
+        let mut current: Option<Observed> = None;
+        // Last app focused outside Polishr, observed or not, so an app that
+        // cannot be observed is tried once per focus change.
+        let mut focused: Option<i32> = None;
+
+        loop {
+            let pid = focused_app_pid();
+            // Keep watching the previous app while Polishr itself has focus.
+            if pid != Some(own_pid) && pid != focused {
+                focused = pid;
+                if let Some(previous) = current.take() {
+                    unobserve(previous);
+                }
+                current = pid.and_then(observe);
+                OBSERVING.store(current.is_some(), Ordering::SeqCst);
+                ON_CHANGE.with(|cb| {
+                    if let Some(cb) = cb.borrow().as_ref() {
+                        cb();
+                    }
+                });
+            }
+            if current.is_some() {
+                unsafe {
+                    CFRunLoopRunInMode(kCFRunLoopDefaultMode, FOCUS_CHECK_INTERVAL.as_secs_f64(), false);
+                }
+            } else {
+                // The run loop returns at once without sources.
+                std::thread::sleep(FOCUS_CHECK_INTERVAL);
+            }
+        }
+    });
+}
+
+/// Whether the focused app currently delivers change notifications.
+pub fn is_observing() -> bool {
+    OBSERVING.load(Ordering::SeqCst)
+}
+
+/// Seconds since the last keyboard or mouse event in this session.
+pub fn input_idle_seconds() -> f64 {
+    unsafe {
+        CGEventSourceSecondsSinceLastEventType(K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION, K_CG_ANY_INPUT_EVENT_TYPE)
+    }
+}
+
+/// Whether the login session's screen is locked.
+pub fn screen_locked() -> bool {
+    unsafe {
+        let session = CGSessionCopyCurrentDictionary();
+        if session.is_null() {
+            return false;
+        }
+        let key = ax_attr("CGSSessionScreenIsLocked");
+        let value = CFDictionaryGetValue(session, key);
+        let locked = !value.is_null() && CFBooleanGetValue(value);
+        CFRelease(key);
+        CFRelease(session);
+        locked
+    }
+}
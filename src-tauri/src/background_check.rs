@@ -0,0 +1,306 @@
+//! Opt-in grammar check of the paragraph under the caret.
+//!
+//! Once typing pauses, the paragraph the trigger is attached to is run
+//! through Improve with the configured provider (pick a cheap one). The
+//! number of changes is shown as a badge on the trigger, and the suggestion
+//! is kept so that clicking the badge opens the panel without another
+//! request. Paragraphs are checked once per content hash, and requests are
+//! limited per minute.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{self, DiffOptions};
+use crate::llm;
+use crate::profiles;
+use crate::prompts::{self, IMPROVE_PROMPT};
+use crate::providers;
+use crate::settings;
+use crate::trigger_scheduler;
+
+const DEFAULT_DEBOUNCE_MS: u64 = 1500;
+const DEFAULT_MAX_PER_MINUTE: u32 = 6;
+/// Shortest pause accepted as "typing stopped".
+pub const MIN_DEBOUNCE_MS: u64 = 300;
+/// Paragraphs shorter than this are not worth a request.
+const MIN_CHARS: usize = 20;
+/// Paragraphs longer than this are left to the panel.
+const MAX_CHARS: usize = 2000;
+const BUDGET_WINDOW: Duration = Duration::from_secs(60);
+/// Suggestions kept, most recent first.
+const CACHE_SIZE: usize = 32;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackgroundCheckSettings {
+    pub enabled: bool,
+    /// Provider for background checks; the app profile's or the active one
+    /// when unset.
+    pub provider_id: Option<String>,
+    /// How long typing has to pause before a paragraph is checked.
+    pub debounce_ms: u64,
+    /// Most requests sent in any minute.
+    pub max_per_minute: u32,
+}
+
+impl Default for BackgroundCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider_id: None,
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            max_per_minute: DEFAULT_MAX_PER_MINUTE,
+        }
+    }
+}
+
+/// Improve result computed for a paragraph in the background.
+#[derive(Debug, Serialize, Clone)]
+pub struct Suggestion {
+    pub explanation: String,
+    pub text: String,
+    /// Number of separate changes (diff hunks).
+    pub issues: usize,
+}
+
+struct Job {
+    hash: u64,
+    text: String,
+    app_id: Option<String>,
+    app: Option<String>,
+}
+
+static QUEUE: Mutex<Option<mpsc::Sender<Job>>> = Mutex::new(None);
+/// Hash of the last paragraph queued, so polling the same text is free.
+/// Cleared when its check fails, so the paragraph is tried again.
+static LAST_QUEUED: Mutex<Option<u64>> = Mutex::new(None);
+static SUGGESTIONS: Mutex<SuggestionCache> = Mutex::new(SuggestionCache::new());
+
+/// Suggestions by paragraph hash, most recent first.
+struct SuggestionCache {
+    entries: VecDeque<(u64, Suggestion)>,
+}
+
+impl SuggestionCache {
+    const fn new() -> Self {
+        Self { entries: VecDeque::new() }
+    }
+
+    fn get(&self, hash: u64) -> Option<Suggestion> {
+        self.entries
+            .iter()
+            .find(|(h, _)| *h == hash)
+            .map(|(_, suggestion)| suggestion.clone())
+    }
+
+    /// Add or replace the suggestion for `hash`, evicting the oldest past
+    /// [`CACHE_SIZE`].
+    fn insert(&mut self, hash: u64, suggestion: Suggestion) {
+        self.entries.retain(|(h, _)| *h != hash);
+        self.entries.push_front((hash, suggestion));
+        self.entries.truncate(CACHE_SIZE);
+    }
+}
+
+/// FNV-1a of the trimmed paragraph.
+fn paragraph_hash(text: &str) -> u64 {
+    text.trim().bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Suggestion for `text`, if it was checked.
+pub fn suggestion_for(text: &str) -> Option<Suggestion> {
+    SUGGESTIONS.lock().unwrap().get(paragraph_hash(text))
+}
+
+/// Let a paragraph that was not checked be queued again.
+fn forget_queued(hash: u64) {
+    let mut last = LAST_QUEUED.lock().unwrap();
+    if *last == Some(hash) {
+        *last = None;
+    }
+}
+
+/// Queue `text` (the paragraph under the caret in the app with bundle id
+/// `app_id` and name `app`) for a check once typing pauses. Replaces
+/// whatever was queued before.
+pub fn request(text: &str, app_id: Option<&str>, app: Option<&str>) {
+    if !settings::with(|s| s.background_check.enabled) {
+        return;
+    }
+    let len = text.trim().chars().count();
+    if !(MIN_CHARS..=MAX_CHARS).contains(&len) {
+        return;
+    }
+    let hash = paragraph_hash(text);
+    {
+        let mut last = LAST_QUEUED.lock().unwrap();
+        if *last == Some(hash) {
+            return;
+        }
+        *last = Some(hash);
+    }
+    if suggestion_for(text).is_some() {
+        return;
+    }
+    if let Some(queue) = QUEUE.lock().unwrap().as_ref() {
+        let _ = queue.send(Job {
+            hash,
+            text: text.to_string(),
+            app_id: app_id.map(str::to_string),
+            app: app.map(str::to_string),
+        });
+    }
+}
+
+/// Requests sent in the last minute.
+struct Budget {
+    sent: VecDeque<Instant>,
+}
+
+impl Budget {
+    /// How long until another request may be sent.
+    fn wait(&mut self, max_per_minute: u32) -> Duration {
+        while self.sent.front().is_some_and(|at| at.elapsed() >= BUDGET_WINDOW) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() < max_per_minute as usize {
+            return Duration::ZERO;
+        }
+        self.sent
+            .front()
+            .map_or(Duration::ZERO, |at| BUDGET_WINDOW.saturating_sub(at.elapsed()))
+    }
+}
+
+async fn check(text: &str, app_id: Option<&str>, app: Option<&str>) -> Result<Suggestion, String> {
+    let profile = profiles::profile_for(app_id, app);
+    let provider_id = settings::with(|s| s.background_check.provider_id.clone())
+        .or_else(|| profile.as_ref().and_then(|p| p.provider_id.clone()));
+    let provider = providers::resolve(provider_id.as_deref())?;
+    let instruction = profile.as_ref().and_then(|p| p.instruction.as_deref());
+    let mut messages = prompts::messages(IMPROVE_PROMPT, text, instruction);
+    llm::apply_glossary(&mut messages);
+    let raw = llm::chat_stream(&provider, &messages, |_| Ok(())).await?;
+    let (explanation, polished) = prompts::parse_response(&raw);
+    if polished.is_empty() {
+        return Err("empty_result".to_string());
+    }
+    let mut segments = diff::diff(text, &polished, &DiffOptions::default());
+    let issues = diff::group_hunks(&mut segments).len();
+    Ok(Suggestion {
+        explanation,
+        text: polished,
+        issues,
+    })
+}
+
+/// Start the checker thread. Results wake the trigger scheduler, which
+/// picks them up on its next refresh.
+pub fn start() {
+    let (tx, rx) = mpsc::channel::<Job>();
+    *QUEUE.lock().unwrap() = Some(tx);
+
+    std::thread::spawn(move || {
+        let mut pending: Option<Job> = None;
+        let mut budget = Budget { sent: VecDeque::new() };
+
+        loop {
+            let next = match pending {
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(_) => {
+                    let (debounce_ms, max_per_minute) =
+                        settings::with(|s| (s.background_check.debounce_ms, s.background_check.max_per_minute));
+                    let wait = Duration::from_millis(debounce_ms).max(budget.wait(max_per_minute));
+                    rx.recv_timeout(wait)
+                }
+            };
+            match next {
+                // Still typing: start the pause over with the newer text.
+                Ok(job) => {
+                    pending = Some(job);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let max_per_minute = settings::with(|s| s.background_check.max_per_minute);
+            if !budget.wait(max_per_minute).is_zero() {
+                continue;
+            }
+            let Some(job) = pending.take() else { continue; };
+            if !settings::with(|s| s.background_check.enabled) {
+                forget_queued(job.hash);
+                continue;
+            }
+
+            budget.sent.push_back(Instant::now());
+            match tauri::async_runtime::block_on(check(&job.text, job.app_id.as_deref(), job.app.as_deref())) {
+                Ok(suggestion) => {
+                    println!("[Polishr] Background check found {} issue(s)", suggestion.issues);
+                    SUGGESTIONS.lock().unwrap().insert(job.hash, suggestion);
+                    trigger_scheduler::wake();
+                }
+                Err(err) => {
+                    println!("[Polishr] Background check failed: {}", err);
+                    forget_queued(job.hash);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(text: &str) -> Suggestion {
+        Suggestion {
+            explanation: String::new(),
+            text: text.to_string(),
+            issues: 1,
+        }
+    }
+
+    #[test]
+    fn budget_waits_for_the_oldest_request_to_expire() {
+        let now = Instant::now();
+        let mut budget = Budget {
+            sent: VecDeque::from([now - Duration::from_secs(90), now - Duration::from_secs(45), now]),
+        };
+        // The request from 90 seconds ago no longer counts.
+        assert_eq!(budget.wait(3), Duration::ZERO);
+        assert_eq!(budget.sent.len(), 2);
+
+        let wait = budget.wait(2);
+        assert!(wait > Duration::from_secs(14) && wait <= Duration::from_secs(15), "{:?}", wait);
+        assert!(budget.wait(1) > Duration::from_secs(14));
+        assert_eq!(Budget { sent: VecDeque::new() }.wait(1), Duration::ZERO);
+    }
+
+    #[test]
+    fn paragraphs_are_cached_by_trimmed_content() {
+        assert_eq!(paragraph_hash("Some paragraph.\n"), paragraph_hash("  Some paragraph."));
+        assert_ne!(paragraph_hash("Some paragraph."), paragraph_hash("Some paragraph!"));
+
+        let mut cache = SuggestionCache::new();
+        cache.insert(paragraph_hash("first draft"), suggestion("First draft."));
+        cache.insert(paragraph_hash("first draft"), suggestion("First draft!"));
+        assert_eq!(cache.get(paragraph_hash(" first draft ")).unwrap().text, "First draft!");
+        assert_eq!(cache.entries.len(), 1);
+
+        for i in 0..CACHE_SIZE {
+            cache.insert(paragraph_hash(&format!("paragraph {}", i)), suggestion(&i.to_string()));
+        }
+        assert!(cache.get(paragraph_hash("first draft")).is_none(), "oldest entry is evicted");
+        assert_eq!(cache.get(paragraph_hash("paragraph 0")).unwrap().text, "0");
+        assert_eq!(cache.entries.len(), CACHE_SIZE);
+    }
+}
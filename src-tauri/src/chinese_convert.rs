@@ -0,0 +1,530 @@
+//! Offline Simplified ⇄ Traditional Chinese conversion.
+//!
+//! Works like OpenCC: text is matched longest entry first against phrase
+//! and character tables, so a character with several traditional forms
+//! (发 → 發/髮, 干 → 幹/乾) is resolved by the word it appears in. Taiwan and
+//! Hong Kong targets add their character variants and, if enabled, regional
+//! vocabulary (软件 → 軟體). Glossary terms are matched before any table.
+//!
+//! The built-in character tables cover the characters only one script uses
+//! (generated from ICU data, see `data/zh/NOTICE`); the phrase tables are
+//! partial and cover common words whose characters have several
+//! counterparts. OpenCC `.txt` dictionaries dropped into the app's `opencc`
+//! folder are merged over them, with the same file names (`STPhrases.txt`,
+//! `TWPhrases*.txt`, ...).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::diff::{self, DiffHunk, DiffOptions, DiffSegment, Granularity};
+use crate::readability::{self, Readability};
+use crate::settings::{self, GlossaryEntry};
+
+/// OpenCC dictionaries shipped with the app, by file stem.
+const BUILTIN: [(&str, &str); 9] = [
+    ("STCharacters", include_str!("../data/zh/STCharacters.txt")),
+    ("STPhrases", include_str!("../data/zh/STPhrases.txt")),
+    ("TSCharacters", include_str!("../data/zh/TSCharacters.txt")),
+    ("TSPhrases", include_str!("../data/zh/TSPhrases.txt")),
+    ("TWVariants", include_str!("../data/zh/TWVariants.txt")),
+    ("TWVariantsRevPhrases", include_str!("../data/zh/TWVariantsRevPhrases.txt")),
+    ("TWPhrases", include_str!("../data/zh/TWPhrases.txt")),
+    ("HKVariants", include_str!("../data/zh/HKVariants.txt")),
+    ("HKPhrases", include_str!("../data/zh/HKPhrases.txt")),
+];
+
+/// Dictionary names read from the user folder. `TWPhrasesIT.txt` and the
+/// like count as `TWPhrases`; reverse tables must match exactly.
+const TABLE_NAMES: [&str; 9] = [
+    "STCharacters",
+    "STPhrases",
+    "TSCharacters",
+    "TSPhrases",
+    "TWVariants",
+    "TWVariantsRevPhrases",
+    "TWPhrases",
+    "HKVariants",
+    "HKPhrases",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChineseVariant {
+    Simplified,
+    /// OpenCC's standard traditional forms, without regional variants.
+    Traditional,
+    #[default]
+    Taiwan,
+    HongKong,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChineseConversionSettings {
+    pub target: ChineseVariant,
+    /// Also swap regional vocabulary (软件 → 軟體 for Taiwan), not just
+    /// characters.
+    pub regional_phrases: bool,
+}
+
+impl Default for ChineseConversionSettings {
+    fn default() -> Self {
+        Self {
+            target: ChineseVariant::default(),
+            regional_phrases: true,
+        }
+    }
+}
+
+/// Converted text with the diff from the input.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConversionResult {
+    pub text: String,
+    pub segments: Vec<DiffSegment>,
+    pub hunks: Vec<DiffHunk>,
+    pub metrics: Readability,
+}
+
+/// Where user dictionaries go and which ones were found.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConversionDictionaryList {
+    /// Folder to drop OpenCC `.txt` dictionaries into.
+    pub folder: String,
+    pub files: Vec<String>,
+}
+
+// --- Tables ---
+
+/// One OpenCC dictionary: key → candidates, the first being the default.
+#[derive(Debug, Default, Clone)]
+struct Table {
+    map: HashMap<String, Vec<String>>,
+    /// Longest key, in chars.
+    max_len: usize,
+}
+
+impl Table {
+    /// Add the `key<TAB>value value...` lines of `text`; later keys win.
+    fn parse_into(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, values)) = line.split_once('\t') else {
+                continue;
+            };
+            let values: Vec<String> = values.split_whitespace().map(str::to_string).collect();
+            if !key.is_empty() && !values.is_empty() {
+                self.insert(key.to_string(), values);
+            }
+        }
+    }
+
+    fn insert(&mut self, key: String, values: Vec<String>) {
+        self.max_len = self.max_len.max(key.chars().count());
+        self.map.insert(key, values);
+    }
+
+    /// `other` on top of this table.
+    fn merge(&mut self, other: &Table) {
+        for (key, values) in &other.map {
+            self.insert(key.clone(), values.clone());
+        }
+    }
+
+    /// Every candidate mapped back to its key. When several keys share a
+    /// candidate the first key in sort order wins, so results are stable.
+    fn inverted(&self) -> Table {
+        let mut keys: Vec<&String> = self.map.keys().collect();
+        keys.sort();
+        let mut inverse = Table::default();
+        for key in keys {
+            for value in &self.map[key] {
+                if value != key && !inverse.map.contains_key(value) {
+                    inverse.insert(value.clone(), vec![key.clone()]);
+                }
+            }
+        }
+        inverse
+    }
+
+    /// True if `c` is a key this table changes.
+    fn changes(&self, c: char) -> bool {
+        let mut buf = [0; 4];
+        let key: &str = c.encode_utf8(&mut buf);
+        self.map.get(key).is_some_and(|values| values[0] != key)
+    }
+
+    /// Replace the longest matching key at each position.
+    fn convert(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut key = String::new();
+        let mut i = 0;
+        'outer: while i < chars.len() {
+            for len in (1..=self.max_len.min(chars.len() - i)).rev() {
+                key.clear();
+                key.extend(&chars[i..i + len]);
+                if let Some(values) = self.map.get(&key) {
+                    out.push_str(&values[0]);
+                    i += len;
+                    continue 'outer;
+                }
+            }
+            out.push(chars[i]);
+            i += 1;
+        }
+        out
+    }
+}
+
+/// Conversion steps built from the built-in and user dictionaries.
+#[derive(Debug, Default)]
+struct Tables {
+    st: Table,
+    ts: Table,
+    tw: Table,
+    tw_rev: Table,
+    tw_phrases: Table,
+    tw_phrases_rev: Table,
+    hk: Table,
+    hk_rev: Table,
+    hk_phrases: Table,
+    hk_phrases_rev: Table,
+}
+
+impl Tables {
+    /// Build from `(name, contents)` pairs; user files come after the
+    /// built-in ones and override their entries.
+    fn build<'a>(sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Tables {
+        let mut raw: HashMap<&str, Table> = HashMap::new();
+        for (name, text) in sources {
+            raw.entry(name).or_default().parse_into(text);
+        }
+        let get = |name: &str| raw.get(name).cloned().unwrap_or_default();
+
+        // Characters first so phrases override them in the merged table.
+        let mut st = get("STCharacters");
+        st.merge(&get("STPhrases"));
+        let mut ts = get("STCharacters").inverted();
+        ts.merge(&get("STPhrases").inverted());
+        ts.merge(&get("TSCharacters"));
+        ts.merge(&get("TSPhrases"));
+
+        let tw = get("TWVariants");
+        let mut tw_rev = tw.inverted();
+        tw_rev.merge(&get("TWVariantsRevPhrases"));
+        let tw_phrases = get("TWPhrases");
+        let hk = get("HKVariants");
+        let hk_phrases = get("HKPhrases");
+
+        Tables {
+            st,
+            ts,
+            tw_rev,
+            tw_phrases_rev: tw_phrases.inverted(),
+            hk_rev: hk.inverted(),
+            hk_phrases_rev: hk_phrases.inverted(),
+            tw,
+            tw_phrases,
+            hk,
+            hk_phrases,
+        }
+    }
+
+    /// More characters that only simplified text uses than ones that only
+    /// traditional text uses.
+    fn looks_simplified(&self, text: &str) -> bool {
+        let (mut simplified, mut traditional) = (0, 0);
+        for c in text.chars() {
+            if self.st.changes(c) {
+                simplified += 1;
+            }
+            if self.ts.changes(c) {
+                traditional += 1;
+            }
+        }
+        simplified > traditional
+    }
+
+    /// Tables to run, in order, to bring `text` to the configured target.
+    fn steps(&self, text: &str, settings: &ChineseConversionSettings) -> Vec<&Table> {
+        let phrases = settings.regional_phrases;
+        // Regional input is brought back to standard traditional first.
+        let mut to_standard = Vec::new();
+        if phrases {
+            to_standard.extend([&self.tw_phrases_rev, &self.hk_phrases_rev]);
+        }
+        to_standard.extend([&self.tw_rev, &self.hk_rev]);
+
+        let mut steps = match settings.target {
+            ChineseVariant::Simplified => {
+                to_standard.push(&self.ts);
+                return to_standard;
+            }
+            _ if self.looks_simplified(text) => vec![&self.st],
+            _ => to_standard,
+        };
+        match settings.target {
+            ChineseVariant::Taiwan => {
+                if phrases {
+                    steps.push(&self.tw_phrases);
+                }
+                steps.push(&self.tw);
+            }
+            ChineseVariant::HongKong => {
+                if phrases {
+                    steps.push(&self.hk_phrases);
+                }
+                steps.push(&self.hk);
+            }
+            _ => {}
+        }
+        steps
+    }
+
+    /// Convert `text`, writing glossary terms as configured and leaving
+    /// them out of every table.
+    fn convert(&self, text: &str, settings: &ChineseConversionSettings, glossary: &[GlossaryEntry]) -> String {
+        let steps = self.steps(text, settings);
+        split_glossary(text, glossary)
+            .into_iter()
+            .map(|(run, locked)| match locked {
+                Some(replacement) => replacement,
+                None => steps.iter().fold(run, |run, table| table.convert(&run)),
+            })
+            .collect()
+    }
+}
+
+/// `text` split into runs; glossary matches carry the text to write. The
+/// longest term wins where several start at the same place.
+fn split_glossary(text: &str, glossary: &[GlossaryEntry]) -> Vec<(String, Option<String>)> {
+    let mut terms: Vec<&GlossaryEntry> = glossary.iter().filter(|e| !e.term.is_empty()).collect();
+    terms.sort_by_key(|e| std::cmp::Reverse(e.term.len()));
+
+    let mut runs = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match terms.iter().find(|e| rest.starts_with(e.term.as_str())) {
+            Some(entry) => {
+                if !plain.is_empty() {
+                    runs.push((std::mem::take(&mut plain), None));
+                }
+                let written = if entry.replacement.is_empty() { &entry.term } else { &entry.replacement };
+                runs.push((entry.term.clone(), Some(written.clone())));
+                rest = &rest[entry.term.len()..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        runs.push((plain, None));
+    }
+    runs
+}
+
+/// Convert `text` and diff the result against it.
+fn convert_with(tables: &Tables, text: &str, settings: &ChineseConversionSettings, glossary: &[GlossaryEntry]) -> ConversionResult {
+    let converted = tables.convert(text, settings, glossary);
+    let options = DiffOptions {
+        granularity: Granularity::Word,
+        ignore_whitespace: false,
+        detect_moves: false,
+    };
+    let mut segments = diff::diff(text, &converted, &options);
+    let hunks = diff::group_hunks(&mut segments);
+    ConversionResult {
+        metrics: readability::compare(text, &converted),
+        text: converted,
+        segments,
+        hunks,
+    }
+}
+
+// --- Loading ---
+
+/// Folder in the app data directory read for user dictionaries.
+pub(crate) const DICTIONARY_FOLDER: &str = "opencc";
+
+/// Tables built so far; dropped when the dictionary list is refreshed.
+static LOADED: Mutex<Option<Arc<Tables>>> = Mutex::new(None);
+
+fn user_folder(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(DICTIONARY_FOLDER))
+}
+
+/// Table a user file feeds, from its file name.
+fn table_name(path: &Path) -> Option<&'static str> {
+    if path.extension()? != "txt" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    TABLE_NAMES
+        .iter()
+        .find(|name| stem == **name || (!stem.contains("Rev") && stem.starts_with(**name)))
+        .copied()
+}
+
+/// User dictionaries in `folder` as `(table, path)`, sorted by file name.
+fn user_files(folder: &Path) -> Vec<(&'static str, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(&'static str, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            Some((table_name(&path)?, path))
+        })
+        .collect();
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+/// Built-in tables with the dictionaries in `folder` merged over them.
+fn load_tables(folder: Option<&Path>) -> Result<Tables, String> {
+    let mut user: Vec<(&str, String)> = Vec::new();
+    for (name, path) in folder.map(user_files).unwrap_or_default() {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("dictionary_unreadable:{}:{}", path.display(), e))?;
+        user.push((name, text));
+    }
+    let sources = BUILTIN.iter().copied().chain(user.iter().map(|(name, text)| (*name, text.as_str())));
+    Ok(Tables::build(sources))
+}
+
+fn tables(app: &AppHandle) -> Arc<Tables> {
+    let mut loaded = LOADED.lock().unwrap();
+    if let Some(tables) = loaded.as_ref() {
+        return tables.clone();
+    }
+    let tables = load_tables(user_folder(app).as_deref()).unwrap_or_else(|err| {
+        println!("[Polishr] Using the built-in conversion tables: {}", err);
+        Tables::build(BUILTIN)
+    });
+    let tables = Arc::new(tables);
+    *loaded = Some(tables.clone());
+    tables
+}
+
+/// Convert `text` as configured, with the user dictionaries in `folder`.
+/// For the command line, which has no app to find the folder with.
+pub fn convert_text(text: &str, folder: Option<&Path>) -> Result<ConversionResult, String> {
+    let (conversion, glossary) = settings::with(|s| (s.chinese_conversion.clone(), s.glossary.clone()));
+    Ok(convert_with(&load_tables(folder)?, text, &conversion, &glossary))
+}
+
+// --- Commands ---
+
+/// Convert `text` to the configured Chinese variant.
+#[tauri::command]
+pub async fn convert_chinese(app: AppHandle, text: String) -> ConversionResult {
+    let (conversion, glossary) = settings::with(|s| (s.chinese_conversion.clone(), s.glossary.clone()));
+    convert_with(&tables(&app), &text, &conversion, &glossary)
+}
+
+/// User dictionaries found; they are reloaded on the next conversion.
+#[tauri::command]
+pub async fn list_conversion_dictionaries(app: AppHandle) -> Result<ConversionDictionaryList, String> {
+    let folder = user_folder(&app).ok_or_else(|| "no_data_dir".to_string())?;
+    *LOADED.lock().unwrap() = None;
+    let files = user_files(&folder)
+        .into_iter()
+        .filter_map(|(_, path)| Some(path.file_name()?.to_str()?.to_string()))
+        .collect();
+    Ok(ConversionDictionaryList {
+        folder: folder.to_string_lossy().into_owned(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin() -> Tables {
+        Tables::build(BUILTIN)
+    }
+
+    fn to(target: ChineseVariant, text: &str) -> String {
+        let settings = ChineseConversionSettings {
+            target,
+            regional_phrases: true,
+        };
+        builtin().convert(text, &settings, &[])
+    }
+
+    #[test]
+    fn phrases_pick_the_right_character() {
+        assert_eq!(to(ChineseVariant::Traditional, "头发很干净"), "頭髮很乾淨");
+        assert_eq!(to(ChineseVariant::Traditional, "发现问题"), "發現問題");
+        assert_eq!(to(ChineseVariant::Traditional, "皇后"), "皇后");
+    }
+
+    #[test]
+    fn every_script_specific_character_converts() {
+        assert_eq!(to(ChineseVariant::Traditional, "缠馋舱滨惫蔼"), "纏饞艙濱憊藹");
+        assert_eq!(to(ChineseVariant::Simplified, "纏饞艙濱憊藹"), "缠馋舱滨惫蔼");
+        assert_eq!(to(ChineseVariant::Simplified, "佈置一個傢"), "布置一个家");
+        // Characters both scripts use are left to the phrase tables.
+        assert_eq!(to(ChineseVariant::Simplified, "俱乐部"), "俱乐部");
+        assert_eq!(to(ChineseVariant::Traditional, "沉默"), "沉默");
+    }
+
+    #[test]
+    fn regional_targets_use_their_variants_and_vocabulary() {
+        assert_eq!(to(ChineseVariant::Taiwan, "软件"), "軟體");
+        assert_eq!(to(ChineseVariant::HongKong, "坐出租车"), "坐的士");
+        let characters_only = ChineseConversionSettings {
+            target: ChineseVariant::Taiwan,
+            regional_phrases: false,
+        };
+        assert_eq!(builtin().convert("软件", &characters_only, &[]), "軟件");
+    }
+
+    #[test]
+    fn traditional_input_converts_between_regions_and_back() {
+        assert_eq!(to(ChineseVariant::Simplified, "頭髮很乾淨"), "头发很干净");
+        assert_eq!(to(ChineseVariant::Simplified, "軟體"), "软件");
+        assert_eq!(to(ChineseVariant::HongKong, "軟體"), "軟件");
+    }
+
+    #[test]
+    fn glossary_terms_are_kept_or_replaced() {
+        let settings = ChineseConversionSettings::default();
+        let glossary = [
+            GlossaryEntry {
+                term: "软件".to_string(),
+                replacement: String::new(),
+            },
+            GlossaryEntry {
+                term: "数据".to_string(),
+                replacement: "數據".to_string(),
+            },
+        ];
+        assert_eq!(builtin().convert("软件数据", &settings, &glossary), "软件數據");
+    }
+
+    #[test]
+    fn user_dictionaries_override_builtin_entries() {
+        let tables = Tables::build(BUILTIN.iter().copied().chain([("TWPhrases", "軟件\t軟件\n")]));
+        let settings = ChineseConversionSettings::default();
+        assert_eq!(tables.convert("软件", &settings, &[]), "軟件");
+    }
+
+    #[test]
+    fn user_files_map_to_tables() {
+        assert_eq!(table_name(Path::new("TWPhrasesIT.txt")), Some("TWPhrases"));
+        assert_eq!(table_name(Path::new("TWVariantsRevPhrases.txt")), Some("TWVariantsRevPhrases"));
+        assert_eq!(table_name(Path::new("TWPhrases.ocd2")), None);
+    }
+}
@@ -0,0 +1,497 @@
+//! Command-line interface: `polishr improve < draft.md`.
+//!
+//! Runs instead of the GUI when the app is started with a mode as its first
+//! argument. Requests use the same providers, prompts, glossary and
+//! typography settings as the panel, read from the app's settings store
+//! (never written) with the config file and policy applied.
+//!
+//! Exit codes follow `diff`: 0 when the text is unchanged, 1 when it
+//! changed, 2 on errors.
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::chinese_convert::{self, DICTIONARY_FOLDER};
+use crate::config;
+use crate::diff::{self, DiffHunk, DiffOptions, Granularity};
+use crate::llm;
+use crate::markdown;
+use crate::prompts;
+use crate::providers;
+use crate::readability::Readability;
+use crate::settings::{self, PolishMode, STORE_PATH};
+use crate::typography;
+
+/// Matches `identifier` in `tauri.conf.json`; names the app data folder.
+const APP_IDENTIFIER: &str = "com.polishr.app";
+const MAX_ALTERNATIVES: usize = 10;
+/// Markdown blocks polished at the same time; more risks rate limits.
+const MAX_CONCURRENT_BLOCKS: usize = 4;
+
+const EXIT_UNCHANGED: i32 = 0;
+const EXIT_CHANGED: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+const USAGE: &str = "\
+Usage: polishr <mode> [options] [file]
+
+Modes:
+  improve       Fix grammar and improve clarity
+  rephrase      Say the same thing differently
+  translate     Translate Chinese to English, anything else to Chinese
+  typography    Apply the CJK typography rules (offline)
+  convert       Convert between Simplified and Traditional Chinese (offline)
+
+Reads the file, or standard input when none is given or it is `-`.
+
+Options:
+  --to <language>          Target language for translate, e.g. en, zh, ja
+  -n, --alternatives <n>   Number of rephrasings to write (default 1)
+  --provider <id>          Provider to use instead of the active one
+  --instruction <text>     Extra instruction for the model
+  --diff                   Print a unified diff instead of the result
+  --json                   Print the results, hunks and metrics as JSON
+  -h, --help               Show this help
+
+Exit status is 0 if the text is unchanged, 1 if it changed, 2 on errors.
+Providers and other settings are configured in the Polishr app.
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Diff,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    mode: PolishMode,
+    /// `None` reads standard input.
+    file: Option<PathBuf>,
+    /// Language name to translate into; detected from the text when unset.
+    to: Option<String>,
+    alternatives: usize,
+    provider: Option<String>,
+    instruction: Option<String>,
+    output: Output,
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    Polish(Args),
+}
+
+/// One result, as printed by `--json`.
+#[derive(Debug, Serialize)]
+struct PolishResult {
+    explanation: String,
+    text: String,
+    changed: bool,
+    hunks: Vec<DiffHunk>,
+    metrics: Readability,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    mode: PolishMode,
+    changed: bool,
+    results: &'a [PolishResult],
+}
+
+fn parse_mode(name: &str) -> Option<PolishMode> {
+    match name {
+        "improve" => Some(PolishMode::Improve),
+        "rephrase" => Some(PolishMode::Rephrase),
+        "translate" => Some(PolishMode::Translate),
+        "typography" => Some(PolishMode::Typography),
+        "convert" => Some(PolishMode::Convert),
+        _ => None,
+    }
+}
+
+/// Whether `args` (without the program name) ask for the command line
+/// rather than the GUI.
+pub fn is_command(args: &[String]) -> bool {
+    args.first()
+        .is_some_and(|first| parse_mode(first).is_some() || matches!(first.as_str(), "help" | "-h" | "--help"))
+}
+
+/// Language name for a `--to` value; names and unknown codes pass through.
+fn language_name(code: &str) -> String {
+    let name = match code.to_lowercase().as_str() {
+        "en" => "English",
+        "zh" | "zh-cn" | "zh-hans" => "Simplified Chinese",
+        "zh-tw" | "zh-hk" | "zh-hant" => "Traditional Chinese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        "it" => "Italian",
+        "pt" => "Portuguese",
+        "ru" => "Russian",
+        _ => return code.to_string(),
+    };
+    name.to_string()
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let mode = match args.next().map(String::as_str) {
+        Some("help" | "-h" | "--help") => return Ok(Command::Help),
+        Some(name) => parse_mode(name).ok_or_else(|| format!("unknown mode '{}'", name))?,
+        None => return Err("missing mode".to_string()),
+    };
+    let mut parsed = Args {
+        mode,
+        file: None,
+        to: None,
+        alternatives: 1,
+        provider: None,
+        instruction: None,
+        output: Output::Text,
+    };
+    let mut only_files = false;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "-h" | "--help" if !only_files => return Ok(Command::Help),
+            "--to" if !only_files => parsed.to = Some(language_name(&value(arg)?)),
+            "-n" | "--alternatives" if !only_files => {
+                let count = value(arg)?;
+                parsed.alternatives = count
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=MAX_ALTERNATIVES).contains(n))
+                    .ok_or_else(|| format!("{} must be a number from 1 to {}", arg, MAX_ALTERNATIVES))?;
+            }
+            "--provider" if !only_files => parsed.provider = Some(value(arg)?),
+            "--instruction" if !only_files => parsed.instruction = Some(value(arg)?),
+            "--diff" if !only_files => parsed.output = Output::Diff,
+            "--json" if !only_files => parsed.output = Output::Json,
+            "--" if !only_files => only_files = true,
+            option if !only_files && option.starts_with('-') && option != "-" => {
+                return Err(format!("unknown option '{}'", option));
+            }
+            file => {
+                if parsed.file.is_some() {
+                    return Err("only one file can be given".to_string());
+                }
+                parsed.file = (file != "-").then(|| PathBuf::from(file));
+            }
+        }
+    }
+    if parsed.to.is_some() && mode != PolishMode::Translate {
+        return Err("--to only applies to translate".to_string());
+    }
+    if parsed.alternatives > 1 && mode != PolishMode::Rephrase {
+        return Err("--alternatives only applies to rephrase".to_string());
+    }
+    let local = matches!(mode, PolishMode::Typography | PolishMode::Convert);
+    if local && (parsed.provider.is_some() || parsed.instruction.is_some()) {
+        return Err("--provider and --instruction only apply to modes that use a model".to_string());
+    }
+    Ok(Command::Polish(parsed))
+}
+
+/// Readable form of the error codes shared with the panel.
+fn describe_error(code: &str) -> String {
+    let (kind, detail) = code.split_once(':').unwrap_or((code, ""));
+    match kind {
+        "no_provider" => "no provider is set up; add one in Polishr's Providers page".to_string(),
+        "missing_api_key" => "the provider has no API key; add one in Polishr's Providers page".to_string(),
+        "invalid_api_key" => "the provider rejected the API key".to_string(),
+        "rate_limited" => "the provider is rate limiting requests; try again later".to_string(),
+        "model_not_found" => format!("the provider does not know the model '{}'", detail),
+        "network_error" => format!("could not reach the provider: {}", detail),
+        "empty_result" => "the model returned no text".to_string(),
+        "settings_unreadable" => format!("could not read the Polishr settings: {}", detail),
+        "dictionary_unreadable" => format!("could not read the conversion dictionary {}", detail),
+        _ => code.to_string(),
+    }
+}
+
+fn read_input(file: Option<&PathBuf>) -> Result<String, String> {
+    match file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("standard input: {}", e))?;
+            Ok(text)
+        }
+    }
+}
+
+/// Send `text` with `system` and return the explanation and the result.
+async fn request(
+    system: &str,
+    text: &str,
+    provider: Option<&str>,
+    instruction: Option<&str>,
+) -> Result<(String, String), String> {
+    let provider = providers::resolve(provider)?;
+    let mut messages = prompts::messages(system, text, instruction);
+    llm::apply_glossary(&mut messages);
+    let raw = llm::chat_stream(&provider, &messages, |_| Ok(())).await?;
+    let (explanation, polished) = prompts::parse_response(&raw);
+    if polished.is_empty() {
+        return Err("empty_result".to_string());
+    }
+    Ok((explanation, polished))
+}
+
+/// Polish `text` with a model. Markdown is polished block by block so code
+/// and structure survive, as in the panel, with a few blocks in flight at
+/// once.
+async fn polish_with_model(system: &str, text: &str, args: &Args) -> Result<(String, String), String> {
+    if !markdown::looks_like_markdown(text) {
+        // Responses are trimmed; keep the input's final newline so an
+        // unchanged text compares equal.
+        let (explanation, polished) =
+            request(system, text, args.provider.as_deref(), args.instruction.as_deref()).await?;
+        let ending = &text[text.trim_end().len()..];
+        return Ok((explanation, format!("{}{}", polished, ending)));
+    }
+    let document = markdown::segment(text);
+    let mut blocks = document
+        .blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, block)| block.prose.clone().map(|prose| (i, prose)));
+    let mut explanations = vec![String::new(); document.blocks.len()];
+    let mut polished = vec![None; document.blocks.len()];
+    let mut running = VecDeque::new();
+    loop {
+        while running.len() < MAX_CONCURRENT_BLOCKS {
+            let Some((i, prose)) = blocks.next() else { break; };
+            let (system, provider, instruction) = (system.to_string(), args.provider.clone(), args.instruction.clone());
+            let handle = tauri::async_runtime::spawn(async move {
+                request(&system, &prose, provider.as_deref(), instruction.as_deref()).await
+            });
+            running.push_back((i, handle));
+        }
+        let Some((i, handle)) = running.pop_front() else { break; };
+        match handle.await.map_err(|e| e.to_string()).and_then(|result| result) {
+            Ok((block_explanation, block_text)) => {
+                explanations[i] = block_explanation;
+                polished[i] = Some(block_text);
+            }
+            Err(err) => {
+                for (_, handle) in running {
+                    handle.abort();
+                }
+                return Err(err);
+            }
+        }
+    }
+    let explanation = explanations.into_iter().find(|e| !e.is_empty()).unwrap_or_default();
+    Ok((explanation, markdown::render(&document, &polished)))
+}
+
+/// Results for `text`, diffed against it.
+async fn polish(text: &str, args: &Args) -> Result<Vec<PolishResult>, String> {
+    let (typography, after_improve, after_translate) = settings::with(|s| {
+        (s.typography.clone(), s.typography.after_improve, s.typography.after_translate)
+    });
+    let result = |explanation: String, revised: String, hunks: Vec<DiffHunk>, metrics: Readability| PolishResult {
+        changed: revised != text,
+        explanation,
+        text: revised,
+        hunks,
+        metrics,
+    };
+
+    let system = match args.mode {
+        PolishMode::Typography => {
+            let normalized = typography::normalize(text, &typography);
+            return Ok(vec![result(String::new(), normalized.text, normalized.hunks, normalized.metrics)]);
+        }
+        PolishMode::Convert => {
+            let folder = dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(DICTIONARY_FOLDER));
+            let converted = chinese_convert::convert_text(text, folder.as_deref())?;
+            return Ok(vec![result(String::new(), converted.text, converted.hunks, converted.metrics)]);
+        }
+        mode => {
+            let target = args.to.as_deref().unwrap_or_else(|| prompts::default_translate_target(text));
+            prompts::system_prompt(mode, target).unwrap_or_default()
+        }
+    };
+    let with_typography = match args.mode {
+        PolishMode::Improve => after_improve,
+        PolishMode::Translate => after_translate,
+        _ => false,
+    };
+
+    let mut results = Vec::with_capacity(args.alternatives);
+    for _ in 0..args.alternatives {
+        let (explanation, polished) = polish_with_model(&system, text, args).await?;
+        if with_typography {
+            let normalized = typography::normalize_revision(text, &polished, &typography);
+            results.push(result(explanation, normalized.text, normalized.hunks, normalized.metrics));
+        } else {
+            let options = DiffOptions {
+                granularity: Granularity::Word,
+                ignore_whitespace: false,
+                detect_moves: true,
+            };
+            let compared = diff::compare(text, &polished, &options);
+            results.push(result(explanation, polished, compared.hunks, compared.metrics));
+        }
+    }
+    Ok(results)
+}
+
+/// `+++` label of a diff: the file name and the mode, plus the number of
+/// the alternative when there are several.
+fn diff_label(name: &str, mode: PolishMode, alternative: Option<usize>) -> String {
+    let mode = format!("{:?}", mode).to_lowercase();
+    match alternative {
+        Some(n) => format!("{} ({} {})", name, mode, n),
+        None => format!("{} ({})", name, mode),
+    }
+}
+
+fn print(results: &[PolishResult], original: &str, args: &Args) -> Result<(), String> {
+    let changed = results.iter().any(|r| r.changed);
+    match args.output {
+        Output::Json => {
+            let output = JsonOutput {
+                mode: args.mode,
+                changed,
+                results,
+            };
+            println!("{}", serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?);
+        }
+        Output::Diff => {
+            let name = args
+                .file
+                .as_ref()
+                .map_or_else(|| "stdin".to_string(), |path| path.display().to_string());
+            for (i, result) in results.iter().enumerate() {
+                let alternative = (results.len() > 1).then_some(i + 1);
+                let label = diff_label(&name, args.mode, alternative);
+                print!("{}", diff::unified(original, &result.text, &name, &label));
+            }
+        }
+        Output::Text => {
+            for (i, result) in results.iter().enumerate() {
+                // Explanations go to stderr so stdout can be piped as is.
+                if !result.explanation.is_empty() {
+                    eprintln!("{}", result.explanation);
+                }
+                if i > 0 {
+                    println!("\n---\n");
+                }
+                print!("{}", result.text);
+                if !result.text.ends_with('\n') {
+                    println!();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the command line with `args` (without the program name) and return
+/// the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let args = match parse_args(args) {
+        Ok(Command::Help) => {
+            print!("{}", USAGE);
+            return EXIT_UNCHANGED;
+        }
+        Ok(Command::Polish(args)) => args,
+        Err(err) => {
+            eprintln!("polishr: {}\nTry 'polishr --help' for more information.", err);
+            return EXIT_ERROR;
+        }
+    };
+
+    config::load();
+    let store = dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(STORE_PATH));
+    if let Some(Err(err)) = store.map(|path| settings::load_read_only(&path)) {
+        eprintln!("polishr: {}", describe_error(&err));
+        return EXIT_ERROR;
+    }
+    if let Some(managed) = settings::with(|s| s.managed.clone()) {
+        for (name, error) in [("config", managed.config_error), ("policy", managed.policy_error)] {
+            if let Some(error) = error {
+                eprintln!("polishr: ignoring the {} file: {}", name, error);
+            }
+        }
+    }
+
+    let outcome = read_input(args.file.as_ref()).and_then(|text| {
+        let results = tauri::async_runtime::block_on(polish(&text, &args)).map_err(|e| describe_error(&e))?;
+        print(&results, &text, &args)?;
+        Ok(results.iter().any(|r| r.changed))
+    });
+    match outcome {
+        Ok(true) => EXIT_CHANGED,
+        Ok(false) => EXIT_UNCHANGED,
+        Err(err) => {
+            eprintln!("polishr: {}", err);
+            EXIT_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn only_modes_and_help_start_the_command_line() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(is_command(&args(&["improve"])));
+        assert!(is_command(&args(&["--help"])));
+        assert!(!is_command(&args(&[])));
+        assert!(!is_command(&args(&["-psn_0_12345"])));
+    }
+
+    #[test]
+    fn parses_modes_options_and_file() {
+        let Ok(Command::Polish(args)) = parse(&["translate", "--to", "en", "--json", "notes.txt"]) else {
+            panic!("expected a polish command");
+        };
+        assert_eq!(args.mode, PolishMode::Translate);
+        assert_eq!(args.to.as_deref(), Some("English"));
+        assert_eq!(args.output, Output::Json);
+        assert_eq!(args.file, Some(PathBuf::from("notes.txt")));
+
+        let Ok(Command::Polish(args)) = parse(&["rephrase", "-n", "3", "-"]) else {
+            panic!("expected a polish command");
+        };
+        assert_eq!(args.alternatives, 3);
+        assert_eq!(args.file, None);
+        assert_eq!(parse(&["improve", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn rejects_options_that_do_not_apply() {
+        assert!(parse(&["polish"]).is_err());
+        assert!(parse(&["improve", "--to", "en"]).is_err());
+        assert!(parse(&["improve", "--alternatives", "2"]).is_err());
+        assert!(parse(&["rephrase", "--alternatives", "0"]).is_err());
+        assert!(parse(&["typography", "--provider", "openai"]).is_err());
+        assert!(parse(&["improve", "a.txt", "b.txt"]).is_err());
+        assert!(parse(&["improve", "--bogus"]).is_err());
+    }
+
+    #[test]
+    fn diff_labels_keep_the_file_name_case() {
+        assert_eq!(diff_label("README.md", PolishMode::Improve, None), "README.md (improve)");
+        assert_eq!(diff_label("stdin", PolishMode::Rephrase, Some(2)), "stdin (rephrase 2)");
+    }
+}
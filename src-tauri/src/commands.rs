@@ -1,13 +1,35 @@
 #[cfg(target_os = "macos")]
 use crate::ax_text;
+use crate::background_check::{self, Suggestion};
+use crate::placement::{self, Rect, Side};
+use crate::profiles::{self, AppProfile};
+use crate::shortcuts::ShortcutBinding;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
 use serde::Serialize;
+use tauri::menu::{MenuBuilder, MenuEvent, MenuItemBuilder};
 use tauri::{AppHandle, Emitter, Manager};
 
+/// Menu id of the trigger's "Disable in <app>" entry.
+const DISABLE_IN_APP_MENU_ID: &str = "trigger_disable_app";
+
 static CACHED_CAPTURE: Mutex<Option<CaptureResult>> = Mutex::new(None);
 static CACHED_PARAGRAPH: Mutex<Option<ParagraphInfo>> = Mutex::new(None);
+/// App the open trigger menu refers to: `(bundle id, name)`.
+static MENU_APP: Mutex<Option<(Option<String>, Option<String>)>> = Mutex::new(None);
+
+/// The focused element a capture was taken from, to tell whether focus moved
+/// before pasting back into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusTarget {
+    pub pid: Option<i32>,
+    /// `CFHash` of the focused element.
+    pub element: usize,
+    /// Process name activated before pasting.
+    pub app_name: Option<String>,
+}
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CaptureResult {
@@ -17,6 +39,19 @@ pub struct CaptureResult {
     pub width: f64,
     pub height: f64,
     pub line_start_x: Option<f64>,
+    /// Application the text was captured from, if known.
+    pub app: Option<String>,
+    /// Bundle identifier of `app`, if known.
+    pub app_id: Option<String>,
+    /// Profile resolved for `app` when the capture is handed to the panel.
+    pub profile: Option<AppProfile>,
+    /// Element the text was captured from; `None` for a selected paragraph.
+    #[serde(skip)]
+    pub target: Option<FocusTarget>,
+    /// Shortcut that started the capture; its action picks the mode.
+    pub shortcut: Option<ShortcutBinding>,
+    /// Improve result already computed by the background check.
+    pub suggestion: Option<Suggestion>,
 }
 
 /// Cached paragraph info for the "select paragraph and open" flow.
@@ -28,8 +63,12 @@ pub struct ParagraphInfo {
     pub width: f64,
     pub height: f64,
     pub line_start_x: Option<f64>,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
     pub range_location: isize,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
     pub range_length: isize,
+    pub app: Option<String>,
+    pub app_id: Option<String>,
 }
 
 pub fn cache_capture(result: Option<CaptureResult>) {
@@ -52,30 +91,93 @@ fn get_cached_paragraph() -> Option<ParagraphInfo> {
     cached.clone()
 }
 
+/// Space between the selection and the panel.
+const PANEL_GAP: f64 = 8.0;
+/// Space kept between the panel and the screen edge.
+const PANEL_MARGIN: f64 = 8.0;
+/// Where the panel goes, most preferred first.
+const PANEL_SIDES: [Side; 4] = [Side::Above, Side::Below, Side::Right, Side::Left];
+/// Height the panel opens at, before it reports its content size.
+const PANEL_MIN_HEIGHT: f64 = 120.0;
+/// Tallest the panel may grow, as a share of the monitor's work area.
+const PANEL_MAX_HEIGHT_RATIO: f64 = 0.6;
+
+/// Selection the panel is shown for and the side it was put on. The side
+/// is kept while the panel is open so it does not jump while results
+/// stream in.
+static PANEL_ANCHOR: Mutex<Option<(Rect, Side)>> = Mutex::new(None);
+
+/// Work areas of all monitors in logical coordinates, for [`placement`].
+pub fn screen_monitors(app: &AppHandle) -> Vec<placement::Monitor> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            let area = monitor.work_area();
+            placement::Monitor {
+                work_area: Rect::new(
+                    area.position.x as f64 / scale,
+                    area.position.y as f64 / scale,
+                    area.size.width as f64 / scale,
+                    area.size.height as f64 / scale,
+                ),
+                scale_factor: scale,
+            }
+        })
+        .collect()
+}
+
+fn panel_request(anchor: Rect, width: f64, height: f64, sides: &[Side]) -> placement::Request<'_> {
+    placement::Request {
+        anchor,
+        width,
+        height,
+        sides,
+        gap: PANEL_GAP,
+        margin: PANEL_MARGIN,
+    }
+}
+
 pub fn show_main_panel(app: &AppHandle, result: &CaptureResult) {
     if let Some(trigger_window) = app.get_webview_window("trigger") {
         let _ = trigger_window.hide();
     }
 
     if let Some(main_window) = app.get_webview_window("main") {
-        let panel_height = 197.0_f64; // matches tauri.conf.json
-        let panel_x = result.x;
-        // Place above: selection top - panel height - gap
-        let panel_y = result.y - panel_height - 8.0;
-        // If that would go off the top of the screen, place below instead
-        let panel_y = if panel_y < 0.0 {
-            result.y + result.height + 8.0
-        } else {
-            panel_y
+        let scale = main_window.scale_factor().unwrap_or(1.0);
+        let Ok(size) = main_window.outer_size() else { return; };
+        let anchor = Rect::new(result.x, result.y, result.width, result.height);
+        let request = panel_request(anchor, size.width as f64 / scale, PANEL_MIN_HEIGHT, &PANEL_SIDES);
+        // Without monitor information, fall back to above the selection.
+        let rect = match placement::place(&request, &screen_monitors(app)) {
+            Some(placed) => {
+                *PANEL_ANCHOR.lock().unwrap() = Some((anchor, placed.side));
+                placed.rect
+            }
+            None => {
+                *PANEL_ANCHOR.lock().unwrap() = None;
+                Rect::new(result.x, result.y - PANEL_MIN_HEIGHT - PANEL_GAP, request.width, PANEL_MIN_HEIGHT)
+            }
         };
 
-        let pos = tauri::LogicalPosition::new(panel_x, panel_y);
+        PANEL_ANIM_GEN.fetch_add(1, Ordering::SeqCst);
+        let size = tauri::LogicalSize::new(rect.width, rect.height);
+        let _ = main_window.set_size(tauri::Size::Logical(size));
+        let pos = tauri::LogicalPosition::new(rect.x, rect.y);
         let _ = main_window.set_position(tauri::Position::Logical(pos));
         let _ = main_window.show();
         let _ = main_window.set_focus();
     }
 }
 
+/// Resolve the app profile, show the panel and hand the capture to it.
+pub fn present_capture(app: &AppHandle, mut result: CaptureResult) {
+    profiles::apply_to_capture(app, &mut result);
+    show_main_panel(app, &result);
+    let _ = app.emit("selection-captured", result);
+}
+
 /// Check if the app has accessibility permission (macOS only).
 #[tauri::command]
 pub async fn check_accessibility_permission() -> bool {
@@ -101,6 +203,7 @@ pub fn capture_and_locate_sync() -> Result<CaptureResult, String> {
     }
 }
 
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
 pub fn peek_and_locate_sync() -> Result<CaptureResult, String> {
     #[cfg(target_os = "macos")]
     {
@@ -140,9 +243,20 @@ fn normalize_capture(
         width: w,
         height: h,
         line_start_x: result.line_start_x,
+        app: ax_text::focused_app_name(),
+        app_id: ax_text::focused_app_bundle_id(),
+        profile: None,
+        target: Some(FocusTarget {
+            pid: result.pid,
+            element: result.element,
+            app_name: result.app_name,
+        }),
+        shortcut: None,
+        suggestion: None,
     })
 }
 
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
 pub fn peek_paragraph_sync() -> Result<ParagraphInfo, String> {
     #[cfg(target_os = "macos")]
     {
@@ -161,6 +275,8 @@ pub fn peek_paragraph_sync() -> Result<ParagraphInfo, String> {
             line_start_x: result.line_start_x,
             range_location: result.range_location,
             range_length: result.range_length,
+            app: ax_text::focused_app_name(),
+            app_id: ax_text::focused_app_bundle_id(),
         })
     }
     #[cfg(not(target_os = "macos"))]
@@ -189,17 +305,22 @@ pub async fn select_paragraph_and_open(app: AppHandle) -> Result<(), String> {
 
     // Build a CaptureResult from the paragraph info
     let capture = CaptureResult {
-        text: para.text,
         x: para.x,
         y: para.y,
         width: para.width,
         height: para.height,
         line_start_x: para.line_start_x,
+        app: para.app,
+        app_id: para.app_id,
+        profile: None,
+        target: None,
+        shortcut: None,
+        suggestion: background_check::suggestion_for(&para.text),
+        text: para.text,
     };
 
     cache_capture(Some(capture.clone()));
-    show_main_panel(&app, &capture);
-    let _ = app.emit("selection-captured", capture);
+    present_capture(&app, capture);
     Ok(())
 }
 
@@ -224,8 +345,7 @@ pub async fn open_main_from_cached_selection(app: AppHandle) -> Result<(), Strin
     let result = get_cached_capture()
         .ok_or_else(|| "no_cached_selection".to_string())?;
 
-    show_main_panel(&app, &result);
-    let _ = app.emit("selection-captured", result);
+    present_capture(&app, result);
     Ok(())
 }
 
@@ -250,47 +370,122 @@ pub async fn replace_text(text: String) -> Result<(), String> {
     }
 }
 
-/// Generation counter: bumped each time a new animation starts, so stale animations stop.
-static TRIGGER_ANIM_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+/// Paste `text` over the selection `target` was captured from. Blocks while
+/// the app is activated.
+pub fn replace_text_in(target: &FocusTarget, text: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let app_name = target.app_name.as_deref().ok_or_else(|| "no_target_app".to_string())?;
+        ax_text::paste_into(app_name, text)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (target, text);
+        Err("Not implemented on this platform".to_string())
+    }
+}
 
-/// Animate the trigger window width from current to target, expanding/shrinking from center.
-fn animate_trigger_width(app: &AppHandle, target_w: f64) {
+/// Generation counters: bumped each time a new animation starts, so stale animations stop.
+static TRIGGER_ANIM_GEN: AtomicU64 = AtomicU64::new(0);
+static PANEL_ANIM_GEN: AtomicU64 = AtomicU64::new(0);
+
+/// Animate a window from its current rect to the one `target` returns for
+/// it, with an ease-out. A newer animation on the same counter stops it.
+fn animate_window(
+    app: &AppHandle,
+    label: &'static str,
+    generation: &'static AtomicU64,
+    target: impl FnOnce(Rect) -> Rect + Send + 'static,
+) {
     // Bump generation so any running animation stops at its next iteration.
-    let gen = TRIGGER_ANIM_GEN.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    let gen = generation.fetch_add(1, Ordering::SeqCst) + 1;
 
     let app = app.clone();
     std::thread::spawn(move || {
         const STEPS: usize = 8;
         const STEP_MS: u64 = 30;
 
-        let Some(win) = app.get_webview_window("trigger") else { return; };
+        let Some(win) = app.get_webview_window(label) else { return; };
         let Ok(size) = win.inner_size() else { return; };
+        let Ok(pos) = win.outer_position() else { return; };
         let scale = win.scale_factor().unwrap_or(1.0);
-        let start_w = size.width as f64 / scale;
-        let logical_h = size.height as f64 / scale;
-        let Ok(start_pos) = win.outer_position() else { return; };
-        let start_x = start_pos.x as f64 / scale;
-        let start_y = start_pos.y as f64 / scale;
+        let start = Rect::new(
+            pos.x as f64 / scale,
+            pos.y as f64 / scale,
+            size.width as f64 / scale,
+            size.height as f64 / scale,
+        );
+        let end = target(start);
 
         for i in 1..=STEPS {
             // If a newer animation started, abort this one.
-            if TRIGGER_ANIM_GEN.load(std::sync::atomic::Ordering::SeqCst) != gen {
+            if generation.load(Ordering::SeqCst) != gen {
                 return;
             }
             let t = i as f64 / STEPS as f64;
             // Ease-out: t' = 1 - (1 - t)^2
             let t_ease = 1.0 - (1.0 - t) * (1.0 - t);
-            let w = start_w + (target_w - start_w) * t_ease;
-            let x = start_x - (w - start_w) / 2.0;
+            let lerp = |from: f64, to: f64| from + (to - from) * t_ease;
 
-            let _ = win.set_size(tauri::Size::Logical(tauri::LogicalSize::new(w, logical_h)));
-            let _ = win.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, start_y)));
+            let _ = win.set_size(tauri::Size::Logical(tauri::LogicalSize::new(
+                lerp(start.width, end.width),
+                lerp(start.height, end.height),
+            )));
+            let _ = win.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(
+                lerp(start.x, end.x),
+                lerp(start.y, end.y),
+            )));
 
             std::thread::sleep(std::time::Duration::from_millis(STEP_MS));
         }
     });
 }
 
+/// Animate the trigger window width from current to target, expanding/shrinking from center.
+fn animate_trigger_width(app: &AppHandle, target_w: f64) {
+    animate_window(app, "trigger", &TRIGGER_ANIM_GEN, move |start| {
+        let x = start.x - (target_w - start.width) / 2.0;
+        Rect::new(x, start.y, target_w, start.height)
+    });
+}
+
+/// Resize the panel to fit content `height` tall (logical pixels), keeping
+/// it on the side of the selection it opened on. The height is capped by
+/// the room on that side and a share of the monitor; longer content scrolls.
+#[tauri::command]
+pub async fn resize_panel(app: AppHandle, height: f64) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "no_main_window".to_string())?;
+    if !window.is_visible().unwrap_or(false) {
+        return Ok(());
+    }
+    let Some((anchor, side)) = *PANEL_ANCHOR.lock().unwrap() else {
+        return Ok(());
+    };
+    let monitors = screen_monitors(&app);
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let width = window.outer_size().map_err(|e| e.to_string())?.width as f64 / scale;
+
+    let sides = [side];
+    let request = panel_request(anchor, width, height, &sides);
+    let Some(monitor) = placement::monitor_for(&anchor, &monitors) else {
+        return Ok(());
+    };
+    let mut max_height = monitor.work_area.height * PANEL_MAX_HEIGHT_RATIO;
+    if matches!(side, Side::Above | Side::Below) {
+        max_height = max_height.min(placement::room(&request, side, &monitor));
+    }
+    let height = height.min(max_height).max(PANEL_MIN_HEIGHT);
+
+    let request = panel_request(anchor, width, height, &sides);
+    let Some(placed) = placement::place(&request, &monitors) else {
+        return Ok(());
+    };
+    animate_window(&app, "main", &PANEL_ANIM_GEN, move |_| placed.rect);
+    Ok(())
+}
+
 /// Expand the trigger window width (paragraph hover).
 #[tauri::command]
 pub async fn expand_trigger(app: AppHandle) {
@@ -303,6 +498,48 @@ pub async fn shrink_trigger(app: AppHandle) {
     animate_trigger_width(&app, 4.0);
 }
 
+/// Show the trigger's context menu for the app it is shown in.
+#[tauri::command]
+pub async fn show_trigger_menu(app: AppHandle) -> Result<(), String> {
+    let (app_id, app_name) = get_cached_capture()
+        .map(|c| (c.app_id, c.app))
+        .or_else(|| get_cached_paragraph().map(|p| (p.app_id, p.app)))
+        .filter(|(id, name)| id.is_some() || name.is_some())
+        .ok_or_else(|| "unknown_app".to_string())?;
+    let label = format!(
+        "Disable in {}",
+        app_name.as_deref().or(app_id.as_deref()).unwrap_or_default()
+    );
+    let item = MenuItemBuilder::with_id(DISABLE_IN_APP_MENU_ID, label)
+        .build(&app)
+        .map_err(|e| e.to_string())?;
+    let menu = MenuBuilder::new(&app).item(&item).build().map_err(|e| e.to_string())?;
+    let window = app
+        .get_webview_window("trigger")
+        .ok_or_else(|| "no_trigger_window".to_string())?;
+    *MENU_APP.lock().unwrap() = Some((app_id, app_name));
+    window.popup_menu(&menu).map_err(|e| e.to_string())
+}
+
+/// Global menu handler for the trigger's context menu.
+pub fn handle_trigger_menu(app: &AppHandle, event: MenuEvent) {
+    if event.id().as_ref() != DISABLE_IN_APP_MENU_ID {
+        return;
+    }
+    let Some((app_id, app_name)) = MENU_APP.lock().unwrap().take() else {
+        return;
+    };
+    if let Err(err) = profiles::disable_trigger_for(app, app_id.as_deref(), app_name.as_deref()) {
+        println!("[Polishr] Failed to disable trigger: {}", err);
+        return;
+    }
+    cache_capture(None);
+    cache_paragraph(None);
+    if let Some(trigger_window) = app.get_webview_window("trigger") {
+        let _ = trigger_window.hide();
+    }
+}
+
 /// Clear the stored element reference (called on dismiss).
 #[tauri::command]
 pub async fn dismiss() {
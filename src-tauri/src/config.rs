@@ -0,0 +1,475 @@
+//! Declarative config file and organization policy.
+//!
+//! Two optional TOML files are layered over the user's settings:
+//!
+//! 1. `config.toml` in the XDG config dir (`$XDG_CONFIG_HOME/polishr`, or
+//!    `~/.config/polishr`), for settings provisioned as a file.
+//! 2. A read-only organization policy in a system location, which can
+//!    restrict providers and endpoints, force a glossary, disable history
+//!    and pin the default mode.
+//!
+//! Precedence is policy > config file > user settings. Fields set by either
+//! file are reported as managed so the UI can lock them. Both files are
+//! watched and changes apply without a restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::providers::Provider;
+use crate::retention::{ExcludedApp, RetentionMode};
+use crate::settings::{self, GlossaryEntry, PolishMode, Settings};
+
+const CONFIG_FILE: &str = "config.toml";
+const POLICY_FILE: &str = "policy.toml";
+/// Overrides the policy location, mainly for testing deployments.
+const POLICY_ENV: &str = "POLISHR_POLICY_FILE";
+/// Editors often write a file in several steps; wait for them to settle.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Settings provisioned through `config.toml`. Every field is optional and
+/// only the ones present override the user's settings.
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    default_mode: Option<PolishMode>,
+    active_provider: Option<String>,
+    #[serde(default)]
+    providers: Vec<ProviderConfig>,
+    history: Option<HistoryConfig>,
+    #[serde(default)]
+    glossary: Vec<GlossaryEntry>,
+}
+
+/// A provider defined in the config file. API keys are never read from
+/// files; they are entered in the app and kept in the keyring.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderConfig {
+    id: String,
+    name: Option<String>,
+    endpoint: String,
+    model: String,
+    temperature: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HistoryConfig {
+    retention: Option<RetentionMode>,
+    retention_days: Option<u32>,
+    retention_records: Option<u32>,
+    excluded_apps: Option<Vec<String>>,
+    scrub_pii: Option<bool>,
+}
+
+/// Organization policy. Anything it sets cannot be changed by the user.
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyFile {
+    default_mode: Option<PolishMode>,
+    /// Provider ids that may be used; others are hidden.
+    allowed_providers: Option<Vec<String>>,
+    /// Endpoint URL prefixes that may be used.
+    allowed_endpoints: Option<Vec<String>>,
+    #[serde(default)]
+    disable_history: bool,
+    /// Terms always sent with every request.
+    #[serde(default)]
+    glossary: Vec<GlossaryEntry>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagedSource {
+    Config,
+    Policy,
+}
+
+/// A setting the user cannot change in the app, e.g. `defaultMode`,
+/// `providers.openai` or `glossary.Polishr`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ManagedField {
+    pub field: String,
+    pub source: ManagedSource,
+}
+
+/// Where managed values come from, reported with the effective settings.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedState {
+    pub config_path: Option<String>,
+    pub policy_path: Option<String>,
+    /// Parse or validation error; the file is ignored until it is fixed.
+    pub config_error: Option<String>,
+    pub policy_error: Option<String>,
+    pub fields: Vec<ManagedField>,
+    /// Provider ids allowed by policy; `None` when unrestricted.
+    pub allowed_providers: Option<Vec<String>>,
+}
+
+#[derive(Default)]
+struct Layers {
+    config: Option<ConfigFile>,
+    policy: Option<PolicyFile>,
+    config_error: Option<String>,
+    policy_error: Option<String>,
+}
+
+static LAYERS: Mutex<Option<Layers>> = Mutex::new(None);
+
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("polishr").join(CONFIG_FILE))
+}
+
+pub fn policy_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(POLICY_ENV) {
+        return PathBuf::from(path);
+    }
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/Polishr");
+    #[cfg(target_os = "windows")]
+    let dir = std::env::var_os("ProgramData")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+        .join("Polishr");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let dir = PathBuf::from("/etc/polishr");
+    dir.join(POLICY_FILE)
+}
+
+/// Parse a TOML file. A missing file is not an error.
+fn read_toml<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => toml::from_str(&text).map(Some).map_err(|e| e.to_string()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn load_layers() -> Layers {
+    let mut layers = Layers::default();
+    if let Some(path) = config_path() {
+        match read_toml::<ConfigFile>(&path) {
+            Ok(config) => layers.config = config,
+            Err(err) => layers.config_error = Some(err),
+        }
+    }
+    match read_toml::<PolicyFile>(&policy_path()) {
+        Ok(policy) => layers.policy = policy,
+        Err(err) => layers.policy_error = Some(err),
+    }
+    layers
+}
+
+/// Read both files, log what is wrong with them, and use them from now on.
+fn install_layers() {
+    let layers = load_layers();
+    for (name, error) in [("config", &layers.config_error), ("policy", &layers.policy_error)] {
+        if let Some(error) = error {
+            println!("[Polishr] Ignoring {} file: {}", name, error);
+        }
+    }
+    *LAYERS.lock().unwrap() = Some(layers);
+}
+
+/// Read both files once, without logging or watching them. For the command
+/// line, which reports errors itself.
+pub fn load() {
+    *LAYERS.lock().unwrap() = Some(load_layers());
+}
+
+fn endpoint_allowed(endpoint: &str, allowed: &[String]) -> bool {
+    let endpoint = endpoint.trim_end_matches('/');
+    allowed.iter().any(|prefix| {
+        let prefix = prefix.trim_end_matches('/');
+        endpoint == prefix || endpoint.starts_with(&format!("{}/", prefix))
+    })
+}
+
+fn upsert_glossary(glossary: &mut Vec<GlossaryEntry>, entry: &GlossaryEntry) {
+    match glossary.iter_mut().find(|g| g.term == entry.term) {
+        Some(existing) => *existing = entry.clone(),
+        None => glossary.push(entry.clone()),
+    }
+}
+
+/// Layer the config file and policy over `user`.
+fn apply_layers(
+    user: &Settings,
+    config: Option<&ConfigFile>,
+    policy: Option<&PolicyFile>,
+) -> (Settings, Vec<ManagedField>) {
+    let mut settings = user.clone();
+    let mut fields: Vec<ManagedField> = Vec::new();
+    let mut manage = |field: String, source: ManagedSource| {
+        fields.retain(|f| f.field != field);
+        fields.push(ManagedField { field, source });
+    };
+
+    if let Some(config) = config {
+        for entry in &config.providers {
+            let provider = match settings.providers.iter_mut().find(|p| p.id == entry.id) {
+                Some(existing) => existing,
+                None => {
+                    settings.providers.push(Provider {
+                        id: entry.id.clone(),
+                        name: entry.id.clone(),
+                        endpoint: String::new(),
+                        model: String::new(),
+                        temperature: 0.3,
+                        api_key_ref: None,
+                        api_key: None,
+                    });
+                    settings.providers.last_mut().unwrap()
+                }
+            };
+            if let Some(name) = &entry.name {
+                provider.name = name.clone();
+            }
+            provider.endpoint = entry.endpoint.clone();
+            provider.model = entry.model.clone();
+            if let Some(temperature) = entry.temperature {
+                provider.temperature = temperature;
+            }
+            manage(format!("providers.{}", entry.id), ManagedSource::Config);
+        }
+        if let Some(id) = &config.active_provider {
+            settings.active_provider_id = id.clone();
+            manage("activeProviderId".to_string(), ManagedSource::Config);
+        }
+        if let Some(mode) = config.default_mode {
+            settings.default_mode = mode;
+            manage("defaultMode".to_string(), ManagedSource::Config);
+        }
+        if let Some(history) = &config.history {
+            let policy = &mut settings.history_policy;
+            if let Some(retention) = history.retention {
+                policy.retention = retention;
+                manage("historyPolicy.retention".to_string(), ManagedSource::Config);
+            }
+            if let Some(days) = history.retention_days {
+                policy.retention_days = days;
+                manage("historyPolicy.retentionDays".to_string(), ManagedSource::Config);
+            }
+            if let Some(records) = history.retention_records {
+                policy.retention_records = records;
+                manage("historyPolicy.retentionRecords".to_string(), ManagedSource::Config);
+            }
+            if let Some(apps) = &history.excluded_apps {
+                policy.excluded_apps = apps.iter().cloned().map(ExcludedApp::from).collect();
+                manage("historyPolicy.excludedApps".to_string(), ManagedSource::Config);
+            }
+            if let Some(scrub) = history.scrub_pii {
+                policy.scrub_pii = scrub;
+                manage("historyPolicy.scrubPii".to_string(), ManagedSource::Config);
+            }
+        }
+        for entry in &config.glossary {
+            upsert_glossary(&mut settings.glossary, entry);
+            manage(format!("glossary.{}", entry.term), ManagedSource::Config);
+        }
+    }
+
+    if let Some(policy) = policy {
+        if let Some(allowed) = &policy.allowed_providers {
+            settings.providers.retain(|p| allowed.contains(&p.id));
+            manage("allowedProviders".to_string(), ManagedSource::Policy);
+        }
+        if let Some(allowed) = &policy.allowed_endpoints {
+            settings.providers.retain(|p| endpoint_allowed(&p.endpoint, allowed));
+            manage("allowedProviders".to_string(), ManagedSource::Policy);
+        }
+        let ids: Vec<String> = settings.providers.iter().map(|p| p.id.clone()).collect();
+        if !ids.contains(&settings.active_provider_id) {
+            settings.active_provider_id = ids.first().cloned().unwrap_or_default();
+        }
+        for profile in settings.app_profiles.iter_mut() {
+            if profile.provider_id.as_ref().is_some_and(|id| !ids.contains(id)) {
+                profile.provider_id = None;
+            }
+        }
+        if policy.disable_history {
+            settings.history_policy.retention = RetentionMode::Never;
+            manage("historyPolicy.retention".to_string(), ManagedSource::Policy);
+        }
+        if let Some(mode) = policy.default_mode {
+            settings.default_mode = mode;
+            manage("defaultMode".to_string(), ManagedSource::Policy);
+        }
+        for entry in &policy.glossary {
+            upsert_glossary(&mut settings.glossary, entry);
+            manage(format!("glossary.{}", entry.term), ManagedSource::Policy);
+        }
+    }
+
+    (settings, fields)
+}
+
+/// Compute the effective settings: `user` with the config file and policy
+/// layered on top, annotated with what is managed. Does not change the
+/// loaded files, so it is safe to call on a candidate that may be rejected.
+pub fn apply(user: &Settings) -> Settings {
+    let guard = LAYERS.lock().unwrap();
+    match guard.as_ref() {
+        Some(layers) => effective(user, layers),
+        None => effective(user, &Layers::default()),
+    }
+}
+
+fn effective(user: &Settings, layers: &Layers) -> Settings {
+    let (mut settings, mut fields) = apply_layers(user, layers.config.as_ref(), layers.policy.as_ref());
+    let mut config_error = layers.config_error.clone();
+    // A config file that makes otherwise valid settings invalid is left out
+    // of the result while the conflict lasts. Errors in the user's own
+    // settings are left for the caller to report.
+    if layers.config.is_some() {
+        if let Err(err) = settings.validate() {
+            let (without, without_fields) = apply_layers(user, None, layers.policy.as_ref());
+            if without.validate().is_ok() {
+                config_error = Some(err);
+                (settings, fields) = (without, without_fields);
+            }
+        }
+    }
+
+    settings.managed = Some(ManagedState {
+        config_path: config_path().map(|p| p.display().to_string()),
+        policy_path: Some(policy_path().display().to_string()),
+        config_error,
+        policy_error: layers.policy_error.clone(),
+        fields,
+        allowed_providers: layers.policy.as_ref().and_then(|p| p.allowed_providers.clone()),
+    });
+    settings
+}
+
+/// Log a config file conflict that `next` introduces. Parse errors are
+/// logged when the file is read.
+pub fn log_conflict(previous: Option<&Settings>, next: &Settings) {
+    let error = |s: &Settings| s.managed.as_ref().and_then(|m| m.config_error.clone());
+    let Some(err) = error(next) else {
+        return;
+    };
+    let parse_error = LAYERS.lock().unwrap().as_ref().and_then(|l| l.config_error.clone());
+    if previous.and_then(error).as_ref() != Some(&err) && parse_error.as_ref() != Some(&err) {
+        println!("[Polishr] Ignoring config file: {}", err);
+    }
+}
+
+/// Reject providers the policy does not allow.
+pub fn check_provider(provider: &Provider) -> Result<(), String> {
+    let guard = LAYERS.lock().unwrap();
+    let Some(policy) = guard.as_ref().and_then(|l| l.policy.as_ref()) else {
+        return Ok(());
+    };
+    if policy.allowed_providers.as_ref().is_some_and(|allowed| !allowed.contains(&provider.id)) {
+        return Err("provider_not_allowed".to_string());
+    }
+    if policy
+        .allowed_endpoints
+        .as_ref()
+        .is_some_and(|allowed| !endpoint_allowed(&provider.endpoint, allowed))
+    {
+        return Err("endpoint_not_allowed".to_string());
+    }
+    Ok(())
+}
+
+/// Reload both files and push the new effective settings to every window.
+fn reload<R: Runtime>(app: &AppHandle<R>) {
+    install_layers();
+    settings::refresh(app);
+    println!("[Polishr] Reloaded config and policy files");
+}
+
+/// Load the config and policy files and watch them for changes. Must run
+/// before [`settings::init`].
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    install_layers();
+
+    let config = config_path();
+    if let Some(dir) = config.as_ref().and_then(|p| p.parent()) {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let files: Vec<PathBuf> = config.into_iter().chain([policy_path()]).collect();
+
+    let (tx, rx) = mpsc::channel::<()>();
+    let watched = files.clone();
+    let mut watcher = match notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if event.paths.iter().any(|p| watched.contains(p)) {
+                let _ = tx.send(());
+            }
+        }
+    }) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            println!("[Polishr] Config files will not be watched: {}", err);
+            return;
+        }
+    };
+    // Watch the directories: editors and provisioning tools usually replace
+    // files rather than writing them in place.
+    for dir in files.iter().filter_map(|p| p.parent()) {
+        if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            println!("[Polishr] Not watching {}: {}", dir.display(), err);
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let _watcher = watcher;
+        while rx.recv().is_ok() {
+            while rx.recv_timeout(RELOAD_DEBOUNCE).is_ok() {}
+            reload(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_with_provider(id: &str) -> Settings {
+        let mut settings = Settings::default();
+        settings.providers.push(Provider {
+            id: id.to_string(),
+            name: id.to_string(),
+            endpoint: "https://llm.example.com/v1".to_string(),
+            model: "model".to_string(),
+            temperature: 0.3,
+            api_key_ref: None,
+            api_key: None,
+        });
+        settings
+    }
+
+    #[test]
+    fn conflicting_candidate_does_not_drop_the_config_file() {
+        let layers = Layers {
+            config: Some(toml::from_str(r#"active_provider = "team""#).unwrap()),
+            ..Layers::default()
+        };
+
+        // Without a "team" provider the config file cannot apply.
+        let fallback = effective(&Settings::default(), &layers);
+        assert_eq!(fallback.active_provider_id, "");
+        assert!(fallback.managed.unwrap().config_error.is_some());
+
+        // The rejected candidate left the layer loaded for the next one.
+        assert!(layers.config.is_some());
+        let applied = effective(&user_with_provider("team"), &layers);
+        assert_eq!(applied.active_provider_id, "team");
+        assert_eq!(applied.managed.unwrap().config_error, None);
+    }
+}
@@ -0,0 +1,805 @@
+//! Token-level diff between the original and polished text.
+//!
+//! Text is tokenized at the requested granularity (characters, words or
+//! sentences; CJK runs are segmented into words with jieba), diffed with
+//! Myers' algorithm, and merged into segments that carry UTF-16 offsets into
+//! both texts so edits can be mapped back to AX ranges.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use jieba_rs::Jieba;
+use serde::{Deserialize, Serialize};
+
+use crate::readability::{self, Readability};
+
+static JIEBA: OnceLock<Jieba> = OnceLock::new();
+
+/// Deleted spans shorter than this (in chars) are never reported as moves.
+const MIN_MOVE_CHARS: usize = 8;
+/// Time budget for a diff before the rest is reported as one replacement.
+const DIFF_TIMEOUT: Duration = Duration::from_secs(1);
+/// Unchanged lines shown around each hunk of a unified diff.
+const UNIFIED_CONTEXT: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Char,
+    #[default]
+    Word,
+    Sentence,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct DiffOptions {
+    pub granularity: Granularity,
+    /// Treat any run of whitespace as equal to any other run.
+    pub ignore_whitespace: bool,
+    /// Pair deleted spans with identical inserted spans elsewhere.
+    pub detect_moves: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Half-open range of UTF-16 code units.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct TextRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffSegment {
+    #[serde(rename = "type")]
+    pub kind: SegmentKind,
+    /// Text of the segment: target text for insertions, source text otherwise.
+    pub text: String,
+    /// Covered range in the original text (empty for insertions).
+    pub source: TextRange,
+    /// Covered range in the revised text (empty for deletions).
+    pub target: TextRange,
+    /// Shared by a deletion and the insertion it was moved to.
+    pub move_id: Option<u32>,
+    /// Hunk this segment belongs to (`None` for equal segments).
+    pub hunk_id: Option<String>,
+}
+
+/// A contiguous change that can be accepted or rejected on its own.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffHunk {
+    /// Stable id derived from the hunk's position and content.
+    pub id: String,
+    /// Range in the original text replaced by this hunk.
+    pub source: TextRange,
+    /// Range in the revised text produced by this hunk.
+    pub target: TextRange,
+    pub deleted: String,
+    pub inserted: String,
+    pub move_id: Option<u32>,
+    /// Typography rules that made this change, comma-separated.
+    #[serde(default)]
+    pub rule: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffResult {
+    pub segments: Vec<DiffSegment>,
+    pub hunks: Vec<DiffHunk>,
+    pub metrics: Readability,
+}
+
+struct Token<'a> {
+    text: &'a str,
+    /// Comparison key; differs from `text` only when whitespace is ignored.
+    key: &'a str,
+    start: usize,
+    end: usize,
+}
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '…' | '\n')
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum CharClass {
+    Word,
+    Cjk,
+    Space,
+    Other,
+}
+
+fn classify(c: char) -> CharClass {
+    if readability::is_unspaced(c) {
+        CharClass::Cjk
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Other
+    }
+}
+
+/// Split `text` into byte ranges at the requested granularity.
+fn split(text: &str, granularity: Granularity) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    match granularity {
+        Granularity::Char => {
+            for (i, c) in text.char_indices() {
+                spans.push((i, i + c.len_utf8()));
+            }
+        }
+        Granularity::Sentence => {
+            let mut start = 0;
+            let mut chars = text.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let at_boundary = is_sentence_end(c)
+                    && chars.peek().is_none_or(|&(_, next)| {
+                        next.is_whitespace() || !next.is_ascii() || c == '\n'
+                    });
+                if at_boundary {
+                    // Keep trailing whitespace with the sentence it follows.
+                    let mut end = i + c.len_utf8();
+                    while let Some(&(j, ws)) = chars.peek() {
+                        if !ws.is_whitespace() || (ws == '\n' && c != '\n') {
+                            break;
+                        }
+                        end = j + ws.len_utf8();
+                        chars.next();
+                    }
+                    spans.push((start, end));
+                    start = end;
+                }
+            }
+            if start < text.len() {
+                spans.push((start, text.len()));
+            }
+        }
+        Granularity::Word => {
+            let mut chars = text.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let class = classify(c);
+                let mut end = i + c.len_utf8();
+                if class != CharClass::Other {
+                    while let Some(&(j, next)) = chars.peek() {
+                        let next_class = classify(next);
+                        // Keep contractions such as "it's" as a single word.
+                        let apostrophe = class == CharClass::Word
+                            && (next == '\'' || next == '’')
+                            && text[j + next.len_utf8()..]
+                                .chars()
+                                .next()
+                                .is_some_and(|after| classify(after) == CharClass::Word);
+                        if next_class != class && !apostrophe {
+                            break;
+                        }
+                        end = j + next.len_utf8();
+                        chars.next();
+                    }
+                }
+                if class == CharClass::Cjk {
+                    let jieba = JIEBA.get_or_init(Jieba::new);
+                    let mut offset = i;
+                    for word in jieba.cut(&text[i..end], false) {
+                        spans.push((offset, offset + word.len()));
+                        offset += word.len();
+                    }
+                } else {
+                    spans.push((i, end));
+                }
+            }
+        }
+    }
+    spans
+}
+
+fn tokenize<'a>(text: &'a str, options: &DiffOptions) -> Vec<Token<'a>> {
+    let mut utf16_pos = 0;
+    split(text, options.granularity)
+        .into_iter()
+        .map(|(start, end)| {
+            let piece = &text[start..end];
+            let len16 = piece.encode_utf16().count();
+            let key = if options.ignore_whitespace && piece.chars().all(char::is_whitespace) {
+                " "
+            } else if options.ignore_whitespace && options.granularity == Granularity::Sentence {
+                piece.trim()
+            } else {
+                piece
+            };
+            let token = Token {
+                text: piece,
+                key,
+                start: utf16_pos,
+                end: utf16_pos + len16,
+            };
+            utf16_pos += len16;
+            token
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Equal(usize, usize),
+    Insert(usize),
+    Delete(usize),
+}
+
+/// Myers' O(ND) diff over token keys in linear space, splitting at the
+/// middle snake. Returns edit operations in order, referencing indices into
+/// `a` and `b`. Past [`DIFF_TIMEOUT`] the remaining ranges are reported as a
+/// plain deletion and insertion, as diff-match-patch does.
+fn myers(a: &[Token], b: &[Token]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    let deadline = Instant::now() + DIFF_TIMEOUT;
+    diff_range(a, b, (0, a.len()), (0, b.len()), deadline, &mut ops);
+    ops
+}
+
+/// Diff `a[a_lo..a_hi]` against `b[b_lo..b_hi]`, appending to `ops`.
+fn diff_range(
+    a: &[Token],
+    b: &[Token],
+    (mut a_lo, mut a_hi): (usize, usize),
+    (mut b_lo, mut b_hi): (usize, usize),
+    deadline: Instant,
+    ops: &mut Vec<Op>,
+) {
+    while a_lo < a_hi && b_lo < b_hi && a[a_lo].key == b[b_lo].key {
+        ops.push(Op::Equal(a_lo, b_lo));
+        a_lo += 1;
+        b_lo += 1;
+    }
+    let mut suffix = 0;
+    while a_lo < a_hi && b_lo < b_hi && a[a_hi - 1].key == b[b_hi - 1].key {
+        a_hi -= 1;
+        b_hi -= 1;
+        suffix += 1;
+    }
+
+    if a_lo == a_hi || b_lo == b_hi || Instant::now() >= deadline {
+        ops.extend((a_lo..a_hi).map(Op::Delete));
+        ops.extend((b_lo..b_hi).map(Op::Insert));
+    } else {
+        match middle_snake(&a[a_lo..a_hi], &b[b_lo..b_hi], deadline) {
+            Some((x, y)) => {
+                diff_range(a, b, (a_lo, a_lo + x), (b_lo, b_lo + y), deadline, ops);
+                diff_range(a, b, (a_lo + x, a_hi), (b_lo + y, b_hi), deadline, ops);
+            }
+            None => {
+                ops.extend((a_lo..a_hi).map(Op::Delete));
+                ops.extend((b_lo..b_hi).map(Op::Insert));
+            }
+        }
+    }
+    ops.extend((0..suffix).map(|i| Op::Equal(a_hi + i, b_hi + i)));
+}
+
+/// Point where the forward and backward searches meet, splitting the
+/// problem in two. `None` when the deadline passes first.
+fn middle_snake(a: &[Token], b: &[Token], deadline: Instant) -> Option<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let len = 2 * max_d as usize + 2;
+    let mut forward = vec![-1isize; len];
+    let mut backward = vec![-1isize; len];
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+    let delta = n - m;
+    // With an odd delta the paths meet on a forward step, otherwise backward.
+    let check_forward = delta % 2 != 0;
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+    let pick = |v: &[isize], k: isize, d: isize| {
+        let i = (offset + k) as usize;
+        if k == -d || (k != d && v[i - 1] < v[i + 1]) { v[i + 1] } else { v[i - 1] + 1 }
+    };
+
+    for d in 0..max_d {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let mut x1 = pick(&forward, k1, d);
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize].key == b[y1 as usize].key {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[(offset + k1) as usize] = x1;
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if check_forward {
+                let k2 = offset + delta - k1;
+                if (0..len as isize).contains(&k2) && backward[k2 as usize] != -1 && x1 >= n - backward[k2 as usize] {
+                    return Some((x1 as usize, y1 as usize));
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let mut x2 = pick(&backward, k2, d);
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize].key == b[(m - y2 - 1) as usize].key {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[(offset + k2) as usize] = x2;
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !check_forward {
+                let k1 = offset + delta - k2;
+                if (0..len as isize).contains(&k1) && forward[k1 as usize] != -1 {
+                    let x1 = forward[k1 as usize];
+                    let y1 = offset + x1 - k1;
+                    if x1 >= n - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+    None
+}
+
+/// Merge consecutive operations of the same kind into segments.
+fn build_segments(a: &[Token], b: &[Token], ops: &[Op]) -> Vec<DiffSegment> {
+    let mut segments: Vec<DiffSegment> = Vec::new();
+    let mut src_pos = 0;
+    let mut dst_pos = 0;
+
+    for op in ops {
+        let (kind, text, source, target) = match *op {
+            // The original's text: with `ignore_whitespace` the two sides
+            // may differ in whitespace, and that is not a change to show.
+            Op::Equal(i, j) => (
+                SegmentKind::Equal,
+                a[i].text,
+                TextRange { start: a[i].start, end: a[i].end },
+                TextRange { start: b[j].start, end: b[j].end },
+            ),
+            Op::Delete(i) => (
+                SegmentKind::Delete,
+                a[i].text,
+                TextRange { start: a[i].start, end: a[i].end },
+                TextRange { start: dst_pos, end: dst_pos },
+            ),
+            Op::Insert(j) => (
+                SegmentKind::Insert,
+                b[j].text,
+                TextRange { start: src_pos, end: src_pos },
+                TextRange { start: b[j].start, end: b[j].end },
+            ),
+        };
+        src_pos = source.end;
+        dst_pos = target.end;
+
+        match segments.last_mut() {
+            Some(last) if last.kind == kind => {
+                last.text.push_str(text);
+                last.source.end = source.end;
+                last.target.end = target.end;
+            }
+            _ => segments.push(DiffSegment {
+                kind,
+                text: text.to_string(),
+                source,
+                target,
+                move_id: None,
+                hunk_id: None,
+            }),
+        }
+    }
+    segments
+}
+
+/// Absorb whitespace-only equalities that sit between two changes, so that
+/// "a b" → "x y" reads as one replacement instead of two fragments. Each
+/// resulting change region is emitted as one deletion followed by one insertion.
+fn cleanup(segments: Vec<DiffSegment>) -> Vec<DiffSegment> {
+    let is_anchor = |s: &DiffSegment| {
+        s.kind == SegmentKind::Equal && !s.text.chars().all(char::is_whitespace)
+    };
+
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < segments.len() {
+        if is_anchor(&segments[i]) {
+            out.push(segments[i].clone());
+            i += 1;
+            continue;
+        }
+
+        let mut j = i;
+        while j < segments.len() && !is_anchor(&segments[j]) {
+            j += 1;
+        }
+        let region = &segments[i..j];
+        if region.iter().all(|s| s.kind == SegmentKind::Equal) {
+            out.extend_from_slice(region);
+            i = j;
+            continue;
+        }
+
+        // Leading and trailing whitespace equalities stay outside the change.
+        let lead = region.iter().take_while(|s| s.kind == SegmentKind::Equal).count();
+        let trail = region[lead..]
+            .iter()
+            .rev()
+            .take_while(|s| s.kind == SegmentKind::Equal)
+            .count();
+        let core = &region[lead..region.len() - trail];
+        out.extend_from_slice(&region[..lead]);
+
+        let first = &core[0];
+        let last = &core[core.len() - 1];
+        let mut deleted = String::new();
+        let mut inserted = String::new();
+        for s in core {
+            match s.kind {
+                SegmentKind::Equal => {
+                    deleted.push_str(&s.text);
+                    inserted.push_str(&s.text);
+                }
+                SegmentKind::Delete => deleted.push_str(&s.text),
+                SegmentKind::Insert => inserted.push_str(&s.text),
+            }
+        }
+        let source = TextRange { start: first.source.start, end: last.source.end };
+        let target = TextRange { start: first.target.start, end: last.target.end };
+        if !deleted.is_empty() {
+            out.push(DiffSegment {
+                kind: SegmentKind::Delete,
+                text: deleted,
+                source,
+                target: TextRange { start: target.start, end: target.start },
+                move_id: None,
+                hunk_id: None,
+            });
+        }
+        if !inserted.is_empty() {
+            out.push(DiffSegment {
+                kind: SegmentKind::Insert,
+                text: inserted,
+                source: TextRange { start: source.end, end: source.end },
+                target,
+                move_id: None,
+                hunk_id: None,
+            });
+        }
+
+        out.extend_from_slice(&region[region.len() - trail..]);
+        i = j;
+    }
+    out
+}
+
+/// Pair each sufficiently long deletion with an identical insertion that is
+/// not its direct replacement, marking both with a shared move id.
+fn detect_moves(segments: &mut [DiffSegment]) {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut next_id = 0u32;
+
+    for i in 0..segments.len() {
+        if segments[i].kind != SegmentKind::Delete || segments[i].move_id.is_some() {
+            continue;
+        }
+        let key = normalize(&segments[i].text);
+        if key.chars().count() < MIN_MOVE_CHARS {
+            continue;
+        }
+        let partner = (0..segments.len()).find(|&j| {
+            segments[j].kind == SegmentKind::Insert
+                && segments[j].move_id.is_none()
+                && j != i + 1
+                && j + 1 != i
+                && normalize(&segments[j].text) == key
+        });
+        if let Some(j) = partner {
+            segments[i].move_id = Some(next_id);
+            segments[j].move_id = Some(next_id);
+            next_id += 1;
+        }
+    }
+}
+
+/// Diff `original` against `revised`.
+pub fn diff(original: &str, revised: &str, options: &DiffOptions) -> Vec<DiffSegment> {
+    let a = tokenize(original, options);
+    let b = tokenize(revised, options);
+    let ops = myers(&a, &b);
+    let mut segments = cleanup(build_segments(&a, &b, &ops));
+    if options.detect_moves {
+        detect_moves(&mut segments);
+    }
+    segments
+}
+
+/// FNV-1a, used for hunk ids that stay stable across recomputation.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Group runs of consecutive changes into hunks and tag their segments.
+pub fn group_hunks(segments: &mut [DiffSegment]) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut in_hunk = false;
+
+    for segment in segments.iter_mut() {
+        if segment.kind == SegmentKind::Equal {
+            in_hunk = false;
+            continue;
+        }
+        if !in_hunk {
+            hunks.push(DiffHunk {
+                id: String::new(),
+                source: TextRange { start: segment.source.start, end: segment.source.start },
+                target: TextRange { start: segment.target.start, end: segment.target.start },
+                deleted: String::new(),
+                inserted: String::new(),
+                move_id: None,
+                rule: None,
+            });
+            in_hunk = true;
+        }
+        let hunk = hunks.last_mut().expect("hunk was just pushed");
+        match segment.kind {
+            SegmentKind::Delete => hunk.deleted.push_str(&segment.text),
+            SegmentKind::Insert => hunk.inserted.push_str(&segment.text),
+            SegmentKind::Equal => {}
+        }
+        hunk.source.end = hunk.source.end.max(segment.source.end);
+        hunk.target.end = hunk.target.end.max(segment.target.end);
+        hunk.move_id = hunk.move_id.or(segment.move_id);
+    }
+
+    for hunk in &mut hunks {
+        let key = format!("{}\u{0}{}\u{0}{}", hunk.source.start, hunk.deleted, hunk.inserted);
+        hunk.id = format!("h{:016x}", fnv1a(key.as_bytes()));
+    }
+
+    // Tag segments with the id of the hunk they belong to.
+    let mut index = 0;
+    let mut in_hunk = false;
+    for segment in segments.iter_mut() {
+        if segment.kind == SegmentKind::Equal {
+            if in_hunk {
+                index += 1;
+            }
+            in_hunk = false;
+            continue;
+        }
+        in_hunk = true;
+        segment.hunk_id = Some(hunks[index].id.clone());
+    }
+    hunks
+}
+
+/// Diff `original` and `revised`, grouped into hunks, with the readability
+/// of both.
+pub fn compare(original: &str, revised: &str, options: &DiffOptions) -> DiffResult {
+    let mut segments = diff(original, revised, options);
+    let hunks = group_hunks(&mut segments);
+    let metrics = readability::compare(original, revised);
+    DiffResult { segments, hunks, metrics }
+}
+
+/// Line diff in the format of `diff -u`. Empty when the texts are equal.
+pub fn unified(original: &str, revised: &str, original_label: &str, revised_label: &str) -> String {
+    fn lines(text: &str) -> Vec<Token<'_>> {
+        text.split_inclusive('\n')
+            .map(|line| Token { text: line, key: line, start: 0, end: 0 })
+            .collect()
+    }
+    let (a, b) = (lines(original), lines(revised));
+    let ops = myers(&a, &b);
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| !matches!(ops[i], Op::Equal(..))).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+    // Lines of each side consumed before op `i`.
+    let mut before = vec![(0, 0); ops.len() + 1];
+    for (i, op) in ops.iter().enumerate() {
+        let (x, y) = before[i];
+        before[i + 1] = match op {
+            Op::Equal(..) => (x + 1, y + 1),
+            Op::Delete(_) => (x + 1, y),
+            Op::Insert(_) => (x, y + 1),
+        };
+    }
+    let range = |start: usize, count: usize| match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    };
+    let push_line = |out: &mut String, marker: char, line: &str| {
+        out.push(marker);
+        out.push_str(line);
+        if !line.ends_with('\n') {
+            out.push_str("\n\\ No newline at end of file\n");
+        }
+    };
+
+    let mut out = format!("--- {}\n+++ {}\n", original_label, revised_label);
+    let mut i = 0;
+    while i < changes.len() {
+        let first = changes[i];
+        while i + 1 < changes.len() && changes[i + 1] - changes[i] <= 2 * UNIFIED_CONTEXT + 1 {
+            i += 1;
+        }
+        let start = first.saturating_sub(UNIFIED_CONTEXT);
+        let end = (changes[i] + UNIFIED_CONTEXT + 1).min(ops.len());
+        let ((x0, y0), (x1, y1)) = (before[start], before[end]);
+        out.push_str(&format!("@@ -{} +{} @@\n", range(x0, x1 - x0), range(y0, y1 - y0)));
+        for op in &ops[start..end] {
+            match *op {
+                Op::Equal(x, _) => push_line(&mut out, ' ', a[x].text),
+                Op::Delete(x) => push_line(&mut out, '-', a[x].text),
+                Op::Insert(y) => push_line(&mut out, '+', b[y].text),
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Build the final text from the original, applying only accepted hunks.
+pub fn compose(original: &str, hunks: &[DiffHunk], accepted_ids: &[String]) -> String {
+    let utf16: Vec<u16> = original.encode_utf16().collect();
+    let mut ordered: Vec<&DiffHunk> = hunks.iter().collect();
+    ordered.sort_by_key(|h| (h.source.start, h.source.end));
+
+    let mut out = String::with_capacity(original.len());
+    let mut pos = 0usize;
+    for hunk in ordered {
+        let start = hunk.source.start.clamp(pos, utf16.len());
+        let end = hunk.source.end.clamp(start, utf16.len());
+        out.push_str(&String::from_utf16_lossy(&utf16[pos..start]));
+        if accepted_ids.contains(&hunk.id) {
+            out.push_str(&hunk.inserted);
+        } else {
+            out.push_str(&String::from_utf16_lossy(&utf16[start..end]));
+        }
+        pos = end;
+    }
+    out.push_str(&String::from_utf16_lossy(&utf16[pos..]));
+    out
+}
+
+/// Compute a typed diff between the original and polished text,
+/// grouped into hunks that can be accepted individually.
+#[tauri::command]
+pub async fn compute_diff(
+    original: String,
+    revised: String,
+    options: Option<DiffOptions>,
+) -> DiffResult {
+    compare(&original, &revised, &options.unwrap_or_default())
+}
+
+/// Build the text to paste from only the accepted hunks.
+#[tauri::command]
+pub async fn compose_result(
+    original: String,
+    hunks: Vec<DiffHunk>,
+    accepted_ids: Vec<String>,
+) -> String {
+    compose(&original, &hunks, &accepted_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic text of `len` words drawn from a small vocabulary.
+    fn words(seed: u64, len: usize) -> String {
+        let vocabulary = [
+            "the", "cat", "sat", "on", "a", "mat", "我们", "明天", "去", "北京", ".", ",", "\n",
+        ];
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                vocabulary[(state >> 33) as usize % vocabulary.len()]
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Both texts rebuilt from the segments.
+    fn sides(segments: &[DiffSegment]) -> (String, String) {
+        let side = |skip: SegmentKind| {
+            segments.iter().filter(|s| s.kind != skip).map(|s| s.text.as_str()).collect::<String>()
+        };
+        (side(SegmentKind::Insert), side(SegmentKind::Delete))
+    }
+
+    #[test]
+    fn segments_reproduce_both_texts() {
+        for granularity in [Granularity::Char, Granularity::Word, Granularity::Sentence] {
+            let options = DiffOptions { granularity, ignore_whitespace: false, detect_moves: true };
+            for seed in 0..20 {
+                let original = words(seed, 40);
+                let revised = words(seed + 100, 35);
+                let result = compare(&original, &revised, &options);
+                assert_eq!(sides(&result.segments), (original.clone(), revised.clone()));
+                let all: Vec<String> = result.hunks.iter().map(|h| h.id.clone()).collect();
+                assert_eq!(compose(&original, &result.hunks, &all), revised);
+                assert_eq!(compose(&original, &result.hunks, &[]), original);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_the_minimal_edit() {
+        let options = DiffOptions { granularity: Granularity::Char, ..Default::default() };
+        let segments = diff("abcabba", "cbabac", &options);
+        let edits: usize = segments
+            .iter()
+            .filter(|s| s.kind != SegmentKind::Equal)
+            .map(|s| s.text.chars().count())
+            .sum();
+        // Cleanup merges changes around short equalities, so the count may
+        // only grow; the raw script is the textbook D = 5.
+        assert!(edits >= 5);
+        let a = tokenize("abcabba", &options);
+        let b = tokenize("cbabac", &options);
+        let ops = myers(&a, &b);
+        assert_eq!(ops.iter().filter(|op| !matches!(op, Op::Equal(..))).count(), 5);
+    }
+
+    #[test]
+    fn large_unrelated_inputs_stay_bounded() {
+        let original: String = (0..8000).map(|i| char::from(b'a' + (i % 13) as u8)).collect();
+        let revised: String = (0..8000).map(|i| char::from(b'n' + (i % 11) as u8)).collect();
+        let options = DiffOptions { granularity: Granularity::Char, ..Default::default() };
+        let started = Instant::now();
+        let segments = diff(&original, &revised, &options);
+        assert!(started.elapsed() < DIFF_TIMEOUT * 3);
+        assert_eq!(sides(&segments), (original, revised));
+    }
+
+    #[test]
+    fn ignored_whitespace_keeps_the_original_text() {
+        let options = DiffOptions {
+            granularity: Granularity::Sentence,
+            ignore_whitespace: true,
+            detect_moves: false,
+        };
+        let segments = diff("One.  Two.\nThree.", "One. Two.\nFour.", &options);
+        let equal: String = segments
+            .iter()
+            .filter(|s| s.kind == SegmentKind::Equal)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(equal, "One.  Two.\n");
+    }
+
+    #[test]
+    fn unified_diff_has_hunk_headers() {
+        assert_eq!(unified("a\nb\n", "a\nb\n", "x", "y"), "");
+        assert_eq!(
+            unified("a\nb\nc\n", "a\nB\nc", "x", "y"),
+            "--- x\n+++ y\n@@ -1,3 +1,3 @@\n a\n-b\n-c\n+B\n+c\n\\ No newline at end of file\n"
+        );
+    }
+}
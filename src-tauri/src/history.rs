@@ -0,0 +1,1210 @@
+//! Polish history stored in SQLite with full-text search.
+//!
+//! Records live in `history.sqlite3` in the app data dir. An external-content
+//! FTS5 table (trigram tokenizer, so substring and CJK search work) indexes
+//! input and result text and is kept in sync by triggers. On first start the
+//! legacy `history.json` written by tauri-plugin-store is imported once and
+//! renamed out of the way.
+//!
+//! History can optionally be encrypted at rest (see `history_crypto`). Input,
+//! result and hunk columns are then sealed per row and left out of the FTS
+//! index, and text search falls back to decrypting and filtering in memory.
+//! Each value is bound to its record id and column, so sealed values cannot
+//! be swapped between rows without failing to open.
+//! The key is loaded on first use; while it is unavailable every read and
+//! write fails with `history_locked` instead of touching plaintext.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::types::ToSql;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::history_crypto::{self, Cipher, KeySource};
+use crate::retention;
+
+const DB_FILE: &str = "history.sqlite3";
+const LEGACY_STORE_FILE: &str = "history.json";
+const SCHEMA_VERSION: i32 = 3;
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// The trigram tokenizer cannot match queries shorter than three characters.
+const MIN_FTS_QUERY_CHARS: usize = 3;
+
+const META_ENCRYPTION: &str = "encryption";
+const META_KDF_SALT: &str = "kdf_salt";
+const META_KEY_CHECK: &str = "key_check";
+
+struct Db {
+    conn: Connection,
+    dir: PathBuf,
+    /// Set when history is encrypted.
+    key_source: Option<KeySource>,
+    /// Present once the key has been loaded or the passphrase entered.
+    cipher: Option<Cipher>,
+}
+
+static DB: Mutex<Option<Db>> = Mutex::new(None);
+
+/// A suggested change and whether the user kept it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryHunk {
+    pub id: String,
+    pub deleted: String,
+    pub inserted: String,
+    pub accepted: bool,
+}
+
+/// A saved polish session. Field names match the frontend `HistoryRecord`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    pub id: String,
+    /// Unix timestamp in milliseconds.
+    pub timestamp: i64,
+    pub mode: String,
+    pub input_text: String,
+    pub result_text: String,
+    #[serde(default)]
+    pub provider: String,
+    #[serde(default)]
+    pub app: Option<String>,
+    /// Bundle identifier of `app`, if known.
+    #[serde(default)]
+    pub app_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub hunks: Option<Vec<HistoryHunk>>,
+}
+
+/// Search text, filters and pagination for `history_query`.
+/// Empty filter lists match everything.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub text: Option<String>,
+    pub modes: Vec<String>,
+    pub providers: Vec<String>,
+    pub apps: Vec<String>,
+    pub languages: Vec<String>,
+    /// Inclusive lower bound, Unix ms.
+    pub from: Option<i64>,
+    /// Exclusive upper bound, Unix ms.
+    pub to: Option<i64>,
+    pub offset: u32,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryPage {
+    pub records: Vec<HistoryRecord>,
+    /// Number of records matching the query, ignoring pagination.
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionStatus {
+    /// `None` when history is stored in plaintext.
+    pub source: Option<KeySource>,
+    pub unlocked: bool,
+}
+
+/// Distinct values present in history, for filter dropdowns.
+#[derive(Debug, Serialize)]
+pub struct HistoryFacets {
+    pub modes: Vec<String>,
+    pub providers: Vec<String>,
+    pub apps: Vec<String>,
+    pub languages: Vec<String>,
+}
+
+// --- Connection management ---
+
+/// Schema changes, one per version. Step `i` upgrades version `i` to `i + 1`.
+const MIGRATIONS: [&str; SCHEMA_VERSION as usize] = [
+    "
+        CREATE TABLE IF NOT EXISTS records (
+            id          TEXT PRIMARY KEY,
+            timestamp   INTEGER NOT NULL,
+            mode        TEXT NOT NULL,
+            provider    TEXT NOT NULL DEFAULT '',
+            app         TEXT,
+            language    TEXT,
+            input_text  TEXT NOT NULL,
+            result_text TEXT NOT NULL,
+            hunks       TEXT
+        );
+        CREATE INDEX IF NOT EXISTS records_timestamp ON records(timestamp DESC);
+        CREATE INDEX IF NOT EXISTS records_app ON records(app);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS records_fts USING fts5(
+            input_text, result_text,
+            content = 'records', content_rowid = 'rowid',
+            tokenize = 'trigram'
+        );
+        CREATE TRIGGER IF NOT EXISTS records_ai AFTER INSERT ON records BEGIN
+            INSERT INTO records_fts(rowid, input_text, result_text)
+            VALUES (new.rowid, new.input_text, new.result_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS records_ad AFTER DELETE ON records BEGIN
+            INSERT INTO records_fts(records_fts, rowid, input_text, result_text)
+            VALUES ('delete', old.rowid, old.input_text, old.result_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS records_au AFTER UPDATE OF input_text, result_text ON records BEGIN
+            INSERT INTO records_fts(records_fts, rowid, input_text, result_text)
+            VALUES ('delete', old.rowid, old.input_text, old.result_text);
+            INSERT INTO records_fts(rowid, input_text, result_text)
+            VALUES (new.rowid, new.input_text, new.result_text);
+        END;
+    ",
+    // Encrypted rows must never reach the FTS index.
+    "
+        ALTER TABLE records ADD COLUMN encrypted INTEGER NOT NULL DEFAULT 0;
+        CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+
+        DROP TRIGGER IF EXISTS records_ai;
+        DROP TRIGGER IF EXISTS records_ad;
+        DROP TRIGGER IF EXISTS records_au;
+        CREATE TRIGGER records_ai AFTER INSERT ON records WHEN new.encrypted = 0 BEGIN
+            INSERT INTO records_fts(rowid, input_text, result_text)
+            VALUES (new.rowid, new.input_text, new.result_text);
+        END;
+        CREATE TRIGGER records_ad AFTER DELETE ON records WHEN old.encrypted = 0 BEGIN
+            INSERT INTO records_fts(records_fts, rowid, input_text, result_text)
+            VALUES ('delete', old.rowid, old.input_text, old.result_text);
+        END;
+        CREATE TRIGGER records_au_old AFTER UPDATE ON records WHEN old.encrypted = 0 BEGIN
+            INSERT INTO records_fts(records_fts, rowid, input_text, result_text)
+            VALUES ('delete', old.rowid, old.input_text, old.result_text);
+        END;
+        CREATE TRIGGER records_au_new AFTER UPDATE ON records WHEN new.encrypted = 0 BEGIN
+            INSERT INTO records_fts(rowid, input_text, result_text)
+            VALUES (new.rowid, new.input_text, new.result_text);
+        END;
+    ",
+    // Bundle id of the app, so exclusions and purges survive a renamed app.
+    "ALTER TABLE records ADD COLUMN app_id TEXT;",
+];
+
+/// Apply the migrations the database has not seen. Each step commits
+/// together with its version, so a failed step leaves the previous schema.
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (step, sql) in MIGRATIONS.iter().enumerate().skip(version.max(0) as usize) {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", step as i32 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Import `history.json` (tauri-plugin-store format: `{"records": [...]}`)
+/// and rename it so the import runs only once. Records go through the
+/// retention policy as new ones do.
+fn import_legacy_store(conn: &Connection, path: &Path) -> Result<usize, String> {
+    #[derive(Deserialize)]
+    struct LegacyStore {
+        #[serde(default)]
+        records: Vec<HistoryRecord>,
+    }
+
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let legacy: LegacyStore = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut imported = 0;
+    for record in legacy.records.into_iter().filter_map(retention::prepare_record) {
+        if insert(&tx, None, &record, true).map_err(|e| e.to_string())? {
+            imported += 1;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    std::fs::rename(path, path.with_extension("json.imported")).map_err(|e| e.to_string())?;
+    Ok(imported)
+}
+
+fn data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Open the history database, apply migrations and import legacy history.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let dir = data_dir(app)?;
+    let conn = Connection::open(dir.join(DB_FILE)).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "journal_mode", "WAL").map_err(|e| e.to_string())?;
+    // Overwrite deleted content so plaintext does not linger in free pages.
+    conn.pragma_update(None, "secure_delete", true).map_err(|e| e.to_string())?;
+    migrate(&conn).map_err(|e| e.to_string())?;
+
+    let key_source = get_meta(&conn, META_ENCRYPTION)
+        .map_err(|e| e.to_string())?
+        .and_then(|value| KeySource::parse(&value));
+
+    let legacy = dir.join(LEGACY_STORE_FILE);
+    // The legacy store predates encryption, so it is only imported into a
+    // plaintext database.
+    let mut imported = 0;
+    if legacy.exists() && key_source.is_none() {
+        match import_legacy_store(&conn, &legacy) {
+            Ok(count) => {
+                println!("[Polishr] Imported {} records from {}", count, LEGACY_STORE_FILE);
+                imported = count;
+            }
+            Err(err) => println!("[Polishr] Failed to import {}: {}", LEGACY_STORE_FILE, err),
+        }
+    }
+
+    *DB.lock().unwrap() = Some(Db {
+        conn,
+        dir,
+        key_source,
+        cipher: None,
+    });
+    if imported > 0 {
+        if let Err(err) = retention::enforce() {
+            println!("[Polishr] Retention failed: {}", err);
+        }
+    }
+    Ok(())
+}
+
+/// Load the key if history is encrypted and still locked. Keyring keys load
+/// without interaction; passphrase keys need `history_unlock`.
+fn ensure_unlocked(db: &mut Db) -> Result<(), String> {
+    match db.key_source {
+        None => Ok(()),
+        Some(_) if db.cipher.is_some() => Ok(()),
+        Some(KeySource::Passphrase) => Err("history_locked".to_string()),
+        Some(KeySource::Keyring) => {
+            let cipher = load_keyring_key(&db.conn).map_err(|err| {
+                println!("[Polishr] History key unavailable: {}", err);
+                "history_locked".to_string()
+            })?;
+            db.cipher = Some(cipher);
+            Ok(())
+        }
+    }
+}
+
+/// Load the keyring key that matches the stored key check. A rotation that
+/// committed but stopped before promoting its new key is finished here;
+/// one that never committed leaves a pending key that is discarded.
+fn load_keyring_key(conn: &Connection) -> Result<Cipher, String> {
+    if let Some(cipher) = history_crypto::cipher_from_keyring()? {
+        if verify_key(conn, &cipher).is_ok() {
+            history_crypto::delete_pending_keyring_key()?;
+            return Ok(cipher);
+        }
+    }
+    let cipher = history_crypto::cipher_from_pending_keyring()?
+        .ok_or_else(|| "history_locked".to_string())?;
+    verify_key(conn, &cipher)?;
+    history_crypto::promote_pending_keyring_key()?;
+    Ok(cipher)
+}
+
+fn verify_key(conn: &Connection, cipher: &Cipher) -> Result<(), String> {
+    let check = get_meta(conn, META_KEY_CHECK)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "history_locked".to_string())?;
+    if cipher.verify(&check) {
+        Ok(())
+    } else {
+        Err("wrong_key".to_string())
+    }
+}
+
+/// Run `f` against the open database, for operations that never read or
+/// write record text.
+pub(crate) fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let guard = DB.lock().unwrap();
+    let db = guard.as_ref().ok_or_else(|| "history_unavailable".to_string())?;
+    f(&db.conn).map_err(|e| e.to_string())
+}
+
+/// Run `f` with the cipher to seal and open record text (`None` when history
+/// is not encrypted). Fails with `history_locked` if the key is unavailable.
+pub(crate) fn with_records<T>(
+    f: impl FnOnce(&Connection, Option<&Cipher>) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let mut guard = DB.lock().unwrap();
+    let db = guard.as_mut().ok_or_else(|| "history_unavailable".to_string())?;
+    ensure_unlocked(db)?;
+    f(&db.conn, db.cipher.as_ref()).map_err(|e| e.to_string())
+}
+
+fn get_meta(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| row.get(0))
+        .optional()
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+fn delete_meta(conn: &Connection, key: &str) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM meta WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+fn crypto_error(err: String) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(err.into())
+}
+
+/// Associated data binding a sealed value to its record and column.
+fn aad(id: &str, column: &str) -> String {
+    format!("{id}/{column}")
+}
+
+fn seal(cipher: Option<&Cipher>, id: &str, column: &str, text: &str) -> rusqlite::Result<String> {
+    match cipher {
+        Some(cipher) => cipher.seal(text, &aad(id, column)).map_err(crypto_error),
+        None => Ok(text.to_string()),
+    }
+}
+
+/// Open a stored value, which must have been sealed for this record and column.
+fn open(
+    cipher: Option<&Cipher>,
+    id: &str,
+    column: &str,
+    stored: String,
+    encrypted: bool,
+) -> rusqlite::Result<String> {
+    match (encrypted, cipher) {
+        (false, _) => Ok(stored),
+        (true, Some(cipher)) => cipher.open(&stored, &aad(id, column)).map_err(crypto_error),
+        (true, None) => Err(crypto_error("history_locked".to_string())),
+    }
+}
+
+// --- Queries ---
+
+const RECORD_COLUMNS: &str =
+    "id, timestamp, mode, provider, app, language, input_text, result_text, hunks, encrypted, app_id";
+
+fn row_to_record(row: &rusqlite::Row, cipher: Option<&Cipher>) -> rusqlite::Result<HistoryRecord> {
+    let id: String = row.get(0)?;
+    let encrypted: bool = row.get(9)?;
+    let hunks = row
+        .get::<_, Option<String>>(8)?
+        .map(|stored| open(cipher, &id, "hunks", stored, encrypted))
+        .transpose()?;
+    Ok(HistoryRecord {
+        timestamp: row.get(1)?,
+        mode: row.get(2)?,
+        provider: row.get(3)?,
+        app: row.get(4)?,
+        app_id: row.get(10)?,
+        language: row.get(5)?,
+        input_text: open(cipher, &id, "input_text", row.get(6)?, encrypted)?,
+        result_text: open(cipher, &id, "result_text", row.get(7)?, encrypted)?,
+        hunks: hunks.and_then(|json| serde_json::from_str(&json).ok()),
+        id,
+    })
+}
+
+/// Insert a record. With `ignore_existing`, an existing id is left untouched
+/// and `false` is returned; otherwise it is replaced.
+/// Text is sealed when `cipher` is given.
+pub(crate) fn insert(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    record: &HistoryRecord,
+    ignore_existing: bool,
+) -> rusqlite::Result<bool> {
+    let hunks = record
+        .hunks
+        .as_ref()
+        .and_then(|h| serde_json::to_string(h).ok())
+        .map(|json| seal(cipher, &record.id, "hunks", &json))
+        .transpose()?;
+    let verb = if ignore_existing { "INSERT OR IGNORE" } else { "INSERT OR REPLACE" };
+    let changed = conn.execute(
+        &format!(
+            "{verb} INTO records ({RECORD_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+        ),
+        params![
+            record.id,
+            record.timestamp,
+            record.mode,
+            record.provider,
+            record.app,
+            record.language,
+            seal(cipher, &record.id, "input_text", &record.input_text)?,
+            seal(cipher, &record.id, "result_text", &record.result_text)?,
+            hunks,
+            cipher.is_some(),
+            record.app_id,
+        ],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Append `column IN (?, ?, ...)` for a non-empty filter list.
+fn push_in_filter(
+    clauses: &mut Vec<String>,
+    args: &mut Vec<Box<dyn ToSql>>,
+    column: &str,
+    values: &[String],
+) {
+    if values.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; values.len()].join(", ");
+    clauses.push(format!("{column} IN ({placeholders})"));
+    args.extend(values.iter().map(|v| Box::new(v.clone()) as Box<dyn ToSql>));
+}
+
+fn search_text(query: &HistoryQuery) -> Option<&str> {
+    query.text.as_deref().map(str::trim).filter(|t| !t.is_empty())
+}
+
+/// Build the WHERE clause and its arguments for a query. With
+/// `match_text` false the text search is left to the caller.
+fn where_clause(query: &HistoryQuery, match_text: bool) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut args: Vec<Box<dyn ToSql>> = Vec::new();
+
+    if let Some(text) = search_text(query).filter(|_| match_text) {
+        if text.chars().count() >= MIN_FTS_QUERY_CHARS {
+            // A quoted FTS5 string is a phrase; with trigrams that is a substring match.
+            clauses.push(
+                "rowid IN (SELECT rowid FROM records_fts WHERE records_fts MATCH ?)".to_string(),
+            );
+            args.push(Box::new(format!("\"{}\"", text.replace('"', "\"\""))));
+        } else {
+            let pattern = format!(
+                "%{}%",
+                text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            );
+            clauses.push(
+                "(input_text LIKE ? ESCAPE '\\' OR result_text LIKE ? ESCAPE '\\')".to_string(),
+            );
+            args.push(Box::new(pattern.clone()));
+            args.push(Box::new(pattern));
+        }
+    }
+
+    push_in_filter(&mut clauses, &mut args, "mode", &query.modes);
+    push_in_filter(&mut clauses, &mut args, "provider", &query.providers);
+    push_in_filter(&mut clauses, &mut args, "app", &query.apps);
+    push_in_filter(&mut clauses, &mut args, "language", &query.languages);
+
+    if let Some(from) = query.from {
+        clauses.push("timestamp >= ?".to_string());
+        args.push(Box::new(from));
+    }
+    if let Some(to) = query.to {
+        clauses.push("timestamp < ?".to_string());
+        args.push(Box::new(to));
+    }
+
+    let sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    (sql, args)
+}
+
+/// Records matching `query`, newest first, and the total number of matches.
+/// With `paginate` the query's offset and limit apply; otherwise every match
+/// is returned.
+fn select(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    query: &HistoryQuery,
+    paginate: bool,
+) -> rusqlite::Result<(Vec<HistoryRecord>, u64)> {
+    // Encrypted text is not indexed, so search it after decrypting.
+    let filter_in_memory = cipher.is_some() && search_text(query).is_some();
+    let (where_sql, mut args) = where_clause(query, !filter_in_memory);
+
+    let total: i64 = {
+        let refs: Vec<&dyn ToSql> = args.iter().map(|a| a.as_ref()).collect();
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM records {where_sql}"),
+            refs.as_slice(),
+            |row| row.get(0),
+        )?
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    let mut sql = format!("SELECT {RECORD_COLUMNS} FROM records {where_sql} ORDER BY timestamp DESC");
+    if paginate && !filter_in_memory {
+        sql.push_str(" LIMIT ? OFFSET ?");
+        args.push(Box::new(limit as u32));
+        args.push(Box::new(query.offset));
+    }
+    let refs: Vec<&dyn ToSql> = args.iter().map(|a| a.as_ref()).collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let records = stmt
+        .query_map(refs.as_slice(), |row| row_to_record(row, cipher))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    if !filter_in_memory {
+        return Ok((records, total as u64));
+    }
+    let needle = search_text(query).unwrap_or_default().to_lowercase();
+    let matches: Vec<HistoryRecord> = records
+        .into_iter()
+        .filter(|r| {
+            r.input_text.to_lowercase().contains(&needle)
+                || r.result_text.to_lowercase().contains(&needle)
+        })
+        .collect();
+    let total = matches.len() as u64;
+    let records = if paginate {
+        matches.into_iter().skip(query.offset as usize).take(limit).collect()
+    } else {
+        matches
+    };
+    Ok((records, total))
+}
+
+/// Every record matching `query`, newest first, ignoring pagination.
+pub(crate) fn select_records(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    query: &HistoryQuery,
+) -> rusqlite::Result<Vec<HistoryRecord>> {
+    select(conn, cipher, query, false).map(|(records, _)| records)
+}
+
+/// Records with the given ids, newest first.
+pub(crate) fn select_by_ids(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    ids: &[String],
+) -> rusqlite::Result<Vec<HistoryRecord>> {
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        "SELECT {RECORD_COLUMNS} FROM records WHERE id IN ({placeholders}) ORDER BY timestamp DESC"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let records = stmt
+        .query_map(rusqlite::params_from_iter(ids), |row| row_to_record(row, cipher))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(records)
+}
+
+fn distinct(conn: &Connection, column: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT {column} FROM records WHERE {column} IS NOT NULL AND {column} != '' ORDER BY {column}"
+    ))?;
+    let values = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(values)
+}
+
+/// Delete records older than `cutoff` (Unix ms). Returns the number removed.
+pub(crate) fn delete_older_than(conn: &Connection, cutoff: i64) -> rusqlite::Result<usize> {
+    conn.execute("DELETE FROM records WHERE timestamp < ?1", params![cutoff])
+}
+
+/// Keep only the newest `count` records. Returns the number removed.
+pub(crate) fn keep_newest(conn: &Connection, count: u32) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM records WHERE id NOT IN
+            (SELECT id FROM records ORDER BY timestamp DESC LIMIT ?1)",
+        params![count],
+    )
+}
+
+/// Delete every record captured from `app_name`. Returns the number removed.
+/// Delete the records of an app: by bundle id when both the record and the
+/// caller know it, else by name.
+pub(crate) fn delete_app(conn: &Connection, app_id: Option<&str>, app_name: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM records WHERE
+            CASE WHEN app_id IS NOT NULL AND ?1 IS NOT NULL THEN app_id = ?1 ELSE app = ?2 END",
+        params![app_id, app_name],
+    )
+}
+
+// --- Encryption ---
+
+/// Rewrite every record's text from `old` to `new` (either may be `None`
+/// for plaintext). Runs inside the caller's transaction.
+fn reseal_all(conn: &Connection, old: Option<&Cipher>, new: Option<&Cipher>) -> rusqlite::Result<()> {
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, input_text, result_text, hunks, encrypted FROM records",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let encrypted: bool = row.get(4)?;
+                Ok((
+                    open(old, &id, "input_text", row.get(1)?, encrypted)?,
+                    open(old, &id, "result_text", row.get(2)?, encrypted)?,
+                    row.get::<_, Option<String>>(3)?
+                        .map(|stored| open(old, &id, "hunks", stored, encrypted))
+                        .transpose()?,
+                    id,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+
+    let mut update = conn.prepare(
+        "UPDATE records SET input_text = ?1, result_text = ?2, hunks = ?3, encrypted = ?4 WHERE id = ?5",
+    )?;
+    for (input, result, hunks, id) in rows {
+        update.execute(params![
+            seal(new, &id, "input_text", &input)?,
+            seal(new, &id, "result_text", &result)?,
+            hunks.map(|h| seal(new, &id, "hunks", &h)).transpose()?,
+            new.is_some(),
+            id,
+        ])?;
+    }
+    Ok(())
+}
+
+/// Flush the WAL and rebuild the file so no superseded plaintext remains.
+fn compact(conn: &Connection) {
+    let result = conn
+        .execute_batch(
+            "INSERT INTO records_fts(records_fts) VALUES ('optimize');
+             PRAGMA wal_checkpoint(TRUNCATE);
+             VACUUM;",
+        );
+    if let Err(err) = result {
+        println!("[Polishr] Failed to compact history database: {}", err);
+    }
+}
+
+/// Re-encrypt all history under a new key from `source`, replacing the
+/// current key (or encrypting plaintext history for the first time).
+fn install_key(db: &mut Db, source: KeySource, passphrase: Option<&str>) -> Result<(), String> {
+    ensure_unlocked(db)?;
+
+    let (cipher, salt, keyring_key) = match source {
+        KeySource::Keyring => {
+            let (cipher, encoded) = history_crypto::new_random_key()?;
+            (cipher, None, Some(encoded))
+        }
+        KeySource::Passphrase => {
+            let salt = history_crypto::new_salt();
+            let cipher = history_crypto::cipher_from_passphrase(passphrase.unwrap_or_default(), &salt)?;
+            (cipher, Some(salt), None)
+        }
+    };
+
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    reseal_all(&tx, db.cipher.as_ref(), Some(&cipher)).map_err(|e| e.to_string())?;
+    set_meta(&tx, META_ENCRYPTION, source.as_str()).map_err(|e| e.to_string())?;
+    set_meta(&tx, META_KEY_CHECK, &cipher.key_check()?).map_err(|e| e.to_string())?;
+    match &salt {
+        Some(salt) => set_meta(&tx, META_KDF_SALT, salt),
+        None => delete_meta(&tx, META_KDF_SALT),
+    }
+    .map_err(|e| e.to_string())?;
+    // Keep the new key aside until the data commits: a keyring failure leaves
+    // the old key and data untouched, and a crash in between is resolved by
+    // `load_keyring_key` using whichever key matches the stored check.
+    if let Some(encoded) = &keyring_key {
+        history_crypto::store_pending_keyring_key(encoded)?;
+    }
+    if let Err(err) = tx.commit() {
+        if keyring_key.is_some() {
+            let _ = history_crypto::delete_pending_keyring_key();
+        }
+        return Err(err.to_string());
+    }
+    if keyring_key.is_some() {
+        // The data is already sealed with the new key; if promotion fails
+        // the pending key is picked up on next unlock.
+        if let Err(err) = history_crypto::promote_pending_keyring_key() {
+            println!("[Polishr] Failed to promote new history key: {}", err);
+        }
+    }
+
+    if db.key_source == Some(KeySource::Keyring) && source == KeySource::Passphrase {
+        if let Err(err) = history_crypto::delete_keyring_key() {
+            println!("[Polishr] Failed to remove old history key: {}", err);
+        }
+    }
+    db.key_source = Some(source);
+    db.cipher = Some(cipher);
+
+    // The imported legacy store is a plaintext copy of old history.
+    let _ = std::fs::remove_file(db.dir.join(LEGACY_STORE_FILE).with_extension("json.imported"));
+    compact(&db.conn);
+    Ok(())
+}
+
+/// Decrypt all history back to plaintext and forget the key.
+fn remove_key(db: &mut Db) -> Result<(), String> {
+    let Some(source) = db.key_source else {
+        return Ok(());
+    };
+    ensure_unlocked(db)?;
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    reseal_all(&tx, db.cipher.as_ref(), None).map_err(|e| e.to_string())?;
+    for key in [META_ENCRYPTION, META_KEY_CHECK, META_KDF_SALT] {
+        delete_meta(&tx, key).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    if source == KeySource::Keyring {
+        if let Err(err) = history_crypto::delete_keyring_key() {
+            println!("[Polishr] Failed to remove history key: {}", err);
+        }
+    }
+    db.key_source = None;
+    db.cipher = None;
+    compact(&db.conn);
+    Ok(())
+}
+
+fn with_db_mut<T>(f: impl FnOnce(&mut Db) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = DB.lock().unwrap();
+    let db = guard.as_mut().ok_or_else(|| "history_unavailable".to_string())?;
+    f(db)
+}
+
+pub(crate) fn notify_changed<R: Runtime>(app: &AppHandle<R>) {
+    let _ = app.emit("history-changed", ());
+}
+
+// --- Commands ---
+
+/// Save a polish session, subject to the retention policy: nothing is saved
+/// when history is off or the app is excluded, and PII is scrubbed first
+/// when enabled.
+#[tauri::command]
+pub async fn history_add(app: AppHandle, record: HistoryRecord) -> Result<(), String> {
+    let Some(record) = retention::prepare_record(record) else {
+        return Ok(());
+    };
+    with_records(|conn, cipher| insert(conn, cipher, &record, false))?;
+    if let Err(err) = retention::enforce() {
+        println!("[Polishr] Retention failed: {}", err);
+    }
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Record which hunks of a session were accepted.
+#[tauri::command]
+pub async fn history_update_hunks(
+    app: AppHandle,
+    id: String,
+    hunks: Vec<HistoryHunk>,
+) -> Result<(), String> {
+    let hunks = retention::prepare_hunks(hunks);
+    let json = serde_json::to_string(&hunks).map_err(|e| e.to_string())?;
+    with_records(|conn, cipher| {
+        conn.execute(
+            "UPDATE records SET hunks = ?1 WHERE id = ?2",
+            params![seal(cipher, &id, "hunks", &json)?, id],
+        )
+    })?;
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Search and filter history, newest first, one page at a time.
+#[tauri::command]
+pub async fn history_query(query: HistoryQuery) -> Result<HistoryPage, String> {
+    with_records(|conn, cipher| {
+        let (records, total) = select(conn, cipher, &query, true)?;
+        Ok(HistoryPage { records, total })
+    })
+}
+
+/// Fetch a single record by id.
+#[tauri::command]
+pub async fn history_get(id: String) -> Result<Option<HistoryRecord>, String> {
+    with_records(|conn, cipher| {
+        conn.query_row(
+            &format!("SELECT {RECORD_COLUMNS} FROM records WHERE id = ?1"),
+            params![id],
+            |row| row_to_record(row, cipher),
+        )
+        .optional()
+    })
+}
+
+/// Distinct modes, providers, apps and languages present in history.
+#[tauri::command]
+pub async fn history_facets() -> Result<HistoryFacets, String> {
+    with_db(|conn| {
+        Ok(HistoryFacets {
+            modes: distinct(conn, "mode")?,
+            providers: distinct(conn, "provider")?,
+            apps: distinct(conn, "app")?,
+            languages: distinct(conn, "language")?,
+        })
+    })
+}
+
+/// Delete specific records.
+#[tauri::command]
+pub async fn history_delete(app: AppHandle, ids: Vec<String>) -> Result<(), String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare("DELETE FROM records WHERE id = ?1")?;
+        for id in &ids {
+            stmt.execute(params![id])?;
+        }
+        Ok(())
+    })?;
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Delete all history.
+#[tauri::command]
+pub async fn history_clear(app: AppHandle) -> Result<(), String> {
+    with_db(|conn| conn.execute("DELETE FROM records", []))?;
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Whether history is encrypted and whether the key is loaded.
+#[tauri::command]
+pub async fn history_encryption_status() -> Result<EncryptionStatus, String> {
+    with_db_mut(|db| {
+        // Keyring keys unlock on first use without a prompt.
+        let _ = ensure_unlocked(db);
+        Ok(EncryptionStatus {
+            source: db.key_source,
+            unlocked: db.key_source.is_none() || db.cipher.is_some(),
+        })
+    })
+}
+
+/// Encrypt history with a key from the OS keyring or a passphrase.
+#[tauri::command]
+pub async fn history_enable_encryption(
+    app: AppHandle,
+    source: KeySource,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    with_db_mut(|db| {
+        if db.key_source.is_some() {
+            return Err("already_encrypted".to_string());
+        }
+        install_key(db, source, passphrase.as_deref())
+    })?;
+    println!("[Polishr] History encryption enabled ({})", source.as_str());
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Re-encrypt history under a fresh key, optionally switching key source.
+#[tauri::command]
+pub async fn history_rotate_key(
+    app: AppHandle,
+    source: KeySource,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    with_db_mut(|db| {
+        if db.key_source.is_none() {
+            return Err("not_encrypted".to_string());
+        }
+        install_key(db, source, passphrase.as_deref())
+    })?;
+    println!("[Polishr] History key rotated ({})", source.as_str());
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Decrypt history back to plaintext and forget the key.
+#[tauri::command]
+pub async fn history_disable_encryption(app: AppHandle) -> Result<(), String> {
+    with_db_mut(remove_key)?;
+    println!("[Polishr] History encryption disabled");
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Unlock passphrase-encrypted history for this session.
+#[tauri::command]
+pub async fn history_unlock(app: AppHandle, passphrase: String) -> Result<(), String> {
+    with_db_mut(|db| {
+        if db.key_source != Some(KeySource::Passphrase) {
+            return ensure_unlocked(db);
+        }
+        let salt = get_meta(&db.conn, META_KDF_SALT)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "invalid_salt".to_string())?;
+        let cipher = history_crypto::cipher_from_passphrase(&passphrase, &salt)?;
+        verify_key(&db.conn, &cipher).map_err(|_| "wrong_passphrase".to_string())?;
+        db.cipher = Some(cipher);
+        Ok(())
+    })?;
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Forget the loaded key until the next unlock.
+#[tauri::command]
+pub async fn history_lock(app: AppHandle) -> Result<(), String> {
+    with_db_mut(|db| {
+        db.cipher = None;
+        Ok(())
+    })?;
+    notify_changed(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        conn
+    }
+
+    fn record(id: &str, timestamp: i64, mode: &str, input_text: &str) -> HistoryRecord {
+        HistoryRecord {
+            id: id.to_string(),
+            timestamp,
+            mode: mode.to_string(),
+            input_text: input_text.to_string(),
+            result_text: format!("{} (polished)", input_text),
+            provider: "openai".to_string(),
+            app: None,
+            app_id: None,
+            language: None,
+            hunks: None,
+        }
+    }
+
+    fn user_version(conn: &Connection) -> i32 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+    }
+
+    fn ids(records: &[HistoryRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    fn search(conn: &Connection, cipher: Option<&Cipher>, text: &str) -> Vec<String> {
+        let query = HistoryQuery {
+            text: Some(text.to_string()),
+            ..Default::default()
+        };
+        select_records(conn, cipher, &query).unwrap().into_iter().map(|r| r.id).collect()
+    }
+
+    fn fts_rows(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM records_fts WHERE records_fts MATCH '\"kitten\"'", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    /// A unique folder that is never created.
+    fn scratch_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("polishr-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn migrations_upgrade_each_version_once() {
+        let conn = database();
+        assert_eq!(user_version(&conn), SCHEMA_VERSION);
+        migrate(&conn).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO records (id, timestamp, mode, input_text, result_text) VALUES ('old', 1, 'fix', 'a kitten', 'b')",
+            [],
+        )
+        .unwrap();
+        migrate(&conn).unwrap();
+        assert_eq!(user_version(&conn), SCHEMA_VERSION);
+        let encrypted: bool = conn.query_row("SELECT encrypted FROM records", [], |row| row.get(0)).unwrap();
+        assert!(!encrypted);
+        assert_eq!(search(&conn, None, "kitten"), ["old"]);
+    }
+
+    #[test]
+    fn failed_migration_keeps_the_previous_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        // Makes the last statement of the second step fail.
+        conn.execute_batch("CREATE TRIGGER records_au_new AFTER UPDATE ON records BEGIN SELECT 1; END;")
+            .unwrap();
+
+        assert!(migrate(&conn).is_err());
+        assert_eq!(user_version(&conn), 1);
+        assert!(conn.prepare("SELECT encrypted FROM records").is_err());
+        assert!(get_meta(&conn, META_ENCRYPTION).is_err(), "meta table was rolled back");
+    }
+
+    #[test]
+    fn imports_the_legacy_store_once() {
+        let dir = scratch_dir("legacy");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LEGACY_STORE_FILE);
+        let store = serde_json::json!({
+            "records": [
+                { "id": "r1", "timestamp": 1, "mode": "fix", "inputText": "teh", "resultText": "the" },
+                { "id": "r2", "timestamp": 2, "mode": "improve", "inputText": "a", "resultText": "b", "app": "Mail" },
+            ]
+        });
+        std::fs::write(&path, store.to_string()).unwrap();
+
+        let conn = database();
+        assert_eq!(import_legacy_store(&conn, &path), Ok(2));
+        assert!(!path.exists());
+        assert!(path.with_extension("json.imported").exists());
+        let records = select_records(&conn, None, &HistoryQuery::default()).unwrap();
+        assert_eq!(ids(&records), ["r2", "r1"]);
+        assert_eq!(records[0].app.as_deref(), Some("Mail"));
+
+        // Ids already in history are left alone.
+        std::fs::write(&path, store.to_string()).unwrap();
+        assert_eq!(import_legacy_store(&conn, &path), Ok(0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn searches_text_and_filters() {
+        let conn = database();
+        for record in [
+            record("zh", 3, "translate", "我们明天去北京"),
+            record("en", 2, "fix", "The Quick brown kitten"),
+            record("pct", 1, "fix", "Save 100% now"),
+        ] {
+            insert(&conn, None, &record, false).unwrap();
+        }
+
+        // Three characters or more go through the trigram index, shorter ones through LIKE.
+        assert_eq!(search(&conn, None, "去北京"), ["zh"]);
+        assert_eq!(search(&conn, None, "北京"), ["zh"]);
+        assert_eq!(search(&conn, None, "quick"), ["en"]);
+        assert_eq!(search(&conn, None, "0%"), ["pct"]);
+        assert_eq!(search(&conn, None, "_"), Vec::<String>::new());
+        assert_eq!(search(&conn, None, "say \"hi\""), Vec::<String>::new());
+        assert_eq!(search(&conn, None, "polished"), ["zh", "en", "pct"]);
+
+        let fixes = HistoryQuery {
+            modes: vec!["fix".to_string()],
+            limit: Some(1),
+            offset: 1,
+            ..Default::default()
+        };
+        let (page, total) = select(&conn, None, &fixes, true).unwrap();
+        assert_eq!((ids(&page), total), (vec!["pct"], 2));
+        let recent = HistoryQuery {
+            from: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(ids(&select_records(&conn, None, &recent).unwrap()), ["zh", "en"]);
+    }
+
+    #[test]
+    fn retention_deletes_the_oldest_records() {
+        let conn = database();
+        for timestamp in 1..=5 {
+            insert(&conn, None, &record(&timestamp.to_string(), timestamp, "fix", "kitten"), false).unwrap();
+        }
+        assert_eq!(keep_newest(&conn, 3), Ok(2));
+        assert_eq!(delete_older_than(&conn, 4), Ok(1));
+        let left = select_records(&conn, None, &HistoryQuery::default()).unwrap();
+        assert_eq!(ids(&left), ["5", "4"]);
+        // The index follows deletions.
+        assert_eq!(fts_rows(&conn), 2);
+    }
+
+    #[test]
+    fn purges_an_app_by_bundle_id_then_name() {
+        let conn = database();
+        for (id, app, app_id) in [
+            ("renamed", "Code - Insiders", Some("com.microsoft.VSCode")),
+            ("other", "Code", Some("com.vscodium")),
+            ("legacy", "Code", None),
+            ("mail", "Mail", None),
+        ] {
+            let mut record = record(id, 1, "fix", "kitten");
+            record.app = Some(app.to_string());
+            record.app_id = app_id.map(str::to_string);
+            insert(&conn, None, &record, false).unwrap();
+        }
+        assert_eq!(delete_app(&conn, Some("com.microsoft.VSCode"), "Code"), Ok(2));
+        let left = select_records(&conn, None, &HistoryQuery::default()).unwrap();
+        assert_eq!(ids(&left), ["other", "mail"]);
+        assert_eq!(left[0].app_id.as_deref(), Some("com.vscodium"));
+    }
+
+    #[test]
+    fn encrypts_rotates_and_decrypts_history() {
+        let mut db = Db {
+            conn: database(),
+            dir: scratch_dir("crypto"),
+            key_source: None,
+            cipher: None,
+        };
+        let mut first = record("a", 1, "fix", "a kitten");
+        first.hunks = Some(vec![HistoryHunk {
+            id: "h1".to_string(),
+            deleted: "a".to_string(),
+            inserted: "the".to_string(),
+            accepted: true,
+        }]);
+        insert(&db.conn, None, &first, false).unwrap();
+        insert(&db.conn, None, &record("b", 2, "fix", "a puppy"), false).unwrap();
+
+        install_key(&mut db, KeySource::Passphrase, Some("first")).unwrap();
+        let raw: String = db.conn.query_row("SELECT input_text FROM records WHERE id = 'a'", [], |row| row.get(0)).unwrap();
+        assert!(!raw.contains("kitten"));
+        assert_eq!(fts_rows(&db.conn), 0);
+        assert_eq!(search(&db.conn, db.cipher.as_ref(), "kitten"), ["a"]);
+        assert!(select_records(&db.conn, None, &HistoryQuery::default()).is_err());
+
+        let first_salt = get_meta(&db.conn, META_KDF_SALT).unwrap().unwrap();
+        install_key(&mut db, KeySource::Passphrase, Some("second")).unwrap();
+        let salt = get_meta(&db.conn, META_KDF_SALT).unwrap().unwrap();
+        assert_ne!(salt, first_salt);
+        let old = history_crypto::cipher_from_passphrase("first", &salt).unwrap();
+        assert!(verify_key(&db.conn, &old).is_err());
+        let new = history_crypto::cipher_from_passphrase("second", &salt).unwrap();
+        assert!(verify_key(&db.conn, &new).is_ok());
+        let records = select_records(&db.conn, Some(&new), &HistoryQuery::default()).unwrap();
+        assert_eq!(records[1].input_text, "a kitten");
+        assert_eq!(records[1].hunks.as_ref().unwrap()[0].inserted, "the");
+
+        remove_key(&mut db).unwrap();
+        assert_eq!(db.key_source, None);
+        assert_eq!(get_meta(&db.conn, META_KEY_CHECK), Ok(None));
+        assert_eq!(fts_rows(&db.conn), 1);
+        assert_eq!(search(&db.conn, None, "kitten"), ["a"]);
+    }
+
+    #[test]
+    fn sealed_values_do_not_open_in_another_row() {
+        let (cipher, _) = history_crypto::new_random_key().unwrap();
+        let conn = database();
+        insert(&conn, Some(&cipher), &record("a", 1, "fix", "a kitten"), false).unwrap();
+        insert(&conn, Some(&cipher), &record("b", 2, "fix", "a puppy"), false).unwrap();
+        conn.execute(
+            "UPDATE records SET input_text = (SELECT input_text FROM records WHERE id = 'b') WHERE id = 'a'",
+            [],
+        )
+        .unwrap();
+        let a = HistoryQuery {
+            from: Some(0),
+            to: Some(2),
+            ..Default::default()
+        };
+        assert!(select_records(&conn, Some(&cipher), &a).is_err());
+    }
+}
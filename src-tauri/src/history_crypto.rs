@@ -0,0 +1,211 @@
+//! Encryption of history text at rest.
+//!
+//! Text columns are sealed with XChaCha20-Poly1305 under a 256-bit key that
+//! is either random and kept in the OS keyring, or derived from a passphrase
+//! with Argon2id. Stored values are base64 of `nonce || ciphertext`, sealed
+//! with the record id and column as associated data so a value cannot be
+//! moved to another row or column without failing to open.
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+
+use crate::secrets;
+
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+/// Keyring entry holding the base64 history key.
+const KEYRING_ENTRY: &str = "history-key";
+/// Holds a new key while history is re-encrypted, until the change commits.
+const PENDING_KEYRING_ENTRY: &str = "history-key-pending";
+/// Known plaintext sealed with the key so a wrong passphrase is detected.
+const KEY_CHECK_PLAINTEXT: &str = "polishr-history";
+const KEY_CHECK_AAD: &str = "key_check";
+
+/// Where the history key comes from.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    Keyring,
+    Passphrase,
+}
+
+impl KeySource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeySource::Keyring => "keyring",
+            KeySource::Passphrase => "passphrase",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keyring" => Some(KeySource::Keyring),
+            "passphrase" => Some(KeySource::Passphrase),
+            _ => None,
+        }
+    }
+}
+
+pub struct Cipher {
+    aead: XChaCha20Poly1305,
+}
+
+impl Cipher {
+    fn from_key(key: &[u8]) -> Result<Self, String> {
+        let aead = XChaCha20Poly1305::new_from_slice(key).map_err(|_| "invalid_key".to_string())?;
+        Ok(Self { aead })
+    }
+
+    pub fn seal(&self, plaintext: &str, aad: &str) -> Result<String, String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: plaintext.as_bytes(),
+            aad: aad.as_bytes(),
+        };
+        let ciphertext = self
+            .aead
+            .encrypt(&nonce, payload)
+            .map_err(|_| "encrypt_failed".to_string())?;
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&ciphertext);
+        Ok(BASE64.encode(out))
+    }
+
+    /// Open a value sealed with the same `aad`.
+    pub fn open(&self, sealed: &str, aad: &str) -> Result<String, String> {
+        let bytes = BASE64.decode(sealed).map_err(|_| "decrypt_failed".to_string())?;
+        if bytes.len() < NONCE_LEN {
+            return Err("decrypt_failed".to_string());
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: aad.as_bytes(),
+        };
+        let plaintext = self
+            .aead
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| "decrypt_failed".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "decrypt_failed".to_string())
+    }
+
+    /// Value stored next to the data to verify this key later.
+    pub fn key_check(&self) -> Result<String, String> {
+        self.seal(KEY_CHECK_PLAINTEXT, KEY_CHECK_AAD)
+    }
+
+    pub fn verify(&self, key_check: &str) -> bool {
+        self.open(key_check, KEY_CHECK_AAD).is_ok_and(|text| text == KEY_CHECK_PLAINTEXT)
+    }
+}
+
+pub fn new_salt() -> String {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    BASE64.encode(salt)
+}
+
+/// Derive a key from a passphrase with Argon2id.
+pub fn cipher_from_passphrase(passphrase: &str, salt: &str) -> Result<Cipher, String> {
+    if passphrase.is_empty() {
+        return Err("passphrase_required".to_string());
+    }
+    let salt = BASE64.decode(salt).map_err(|_| "invalid_salt".to_string())?;
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Cipher::from_key(&key)
+}
+
+/// Generate a random key. Returns the cipher and the encoded key to store.
+pub fn new_random_key() -> Result<(Cipher, String), String> {
+    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+    Ok((Cipher::from_key(&key)?, BASE64.encode(key)))
+}
+
+fn cipher_from_entry(entry: &str) -> Result<Option<Cipher>, String> {
+    let Some(encoded) = secrets::get_secret(entry)? else {
+        return Ok(None);
+    };
+    let key = BASE64.decode(encoded).map_err(|_| "invalid_key".to_string())?;
+    Cipher::from_key(&key).map(Some)
+}
+
+/// Load the key from the OS keyring. `Ok(None)` if no key is stored.
+pub fn cipher_from_keyring() -> Result<Option<Cipher>, String> {
+    cipher_from_entry(KEYRING_ENTRY)
+}
+
+/// Load a new key left pending by an interrupted rotation.
+pub fn cipher_from_pending_keyring() -> Result<Option<Cipher>, String> {
+    cipher_from_entry(PENDING_KEYRING_ENTRY)
+}
+
+/// Keep a new key aside until the data sealed with it is committed.
+pub fn store_pending_keyring_key(encoded: &str) -> Result<(), String> {
+    secrets::set_secret(PENDING_KEYRING_ENTRY, encoded)
+}
+
+/// Make the pending key the history key.
+pub fn promote_pending_keyring_key() -> Result<(), String> {
+    let encoded = secrets::get_secret(PENDING_KEYRING_ENTRY)?
+        .ok_or_else(|| "invalid_key".to_string())?;
+    secrets::set_secret(KEYRING_ENTRY, &encoded)?;
+    secrets::delete_secret(PENDING_KEYRING_ENTRY)
+}
+
+pub fn delete_pending_keyring_key() -> Result<(), String> {
+    secrets::delete_secret(PENDING_KEYRING_ENTRY)
+}
+
+pub fn delete_keyring_key() -> Result<(), String> {
+    secrets::delete_secret(KEYRING_ENTRY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_text_opens_with_the_same_key_and_row() {
+        let (cipher, _) = new_random_key().unwrap();
+        let sealed = cipher.seal("hello 世界", "r1/input_text").unwrap();
+        assert_ne!(sealed, cipher.seal("hello 世界", "r1/input_text").unwrap(), "nonces must differ");
+        assert_eq!(cipher.open(&sealed, "r1/input_text").unwrap(), "hello 世界");
+        // Moved to another row or column.
+        assert!(cipher.open(&sealed, "r2/input_text").is_err());
+        assert!(cipher.open(&sealed, "r1/result_text").is_err());
+        assert!(cipher.open(&sealed, "").is_err());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_detected() {
+        let salt = new_salt();
+        let right = cipher_from_passphrase("correct horse", &salt).unwrap();
+        let wrong = cipher_from_passphrase("battery staple", &salt).unwrap();
+        let check = right.key_check().unwrap();
+        assert!(right.verify(&check));
+        assert!(!wrong.verify(&check));
+        let sealed = right.seal("secret", "r1/input_text").unwrap();
+        assert!(wrong.open(&sealed, "r1/input_text").is_err());
+        assert_eq!(cipher_from_passphrase("", &salt).err().as_deref(), Some("passphrase_required"));
+    }
+
+    #[test]
+    fn tampered_values_do_not_open() {
+        let (cipher, _) = new_random_key().unwrap();
+        let sealed = cipher.seal("hello", "r1/input_text").unwrap();
+        let mut bytes = BASE64.decode(&sealed).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(cipher.open(&BASE64.encode(&bytes), "r1/input_text").is_err());
+        assert!(cipher.open(&BASE64.encode(&bytes[..NONCE_LEN - 1]), "r1/input_text").is_err());
+        assert!(cipher.open("not base64!", "r1/input_text").is_err());
+    }
+}
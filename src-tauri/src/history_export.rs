@@ -0,0 +1,483 @@
+//! History export and import.
+//!
+//! JSON is lossless and is the format for moving history between machines.
+//! CSV is for spreadsheets and Markdown is a report for reviewers, with each
+//! session's changes rendered as ~~deleted~~ / **inserted** text. Import
+//! accepts JSON and CSV and skips records whose id already exists.
+
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::diff::{self, DiffOptions, SegmentKind};
+use crate::history::{self, HistoryQuery, HistoryRecord};
+use crate::retention;
+
+const EXPORT_VERSION: u32 = 1;
+const CSV_COLUMNS: [&str; 9] = [
+    "id",
+    "timestamp",
+    "date",
+    "mode",
+    "provider",
+    "app",
+    "language",
+    "input_text",
+    "result_text",
+];
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Envelope written by JSON export.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryExport {
+    version: u32,
+    exported_at: i64,
+    records: Vec<HistoryRecord>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportResult {
+    pub path: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    /// Records whose id was already in history.
+    pub duplicates: usize,
+    /// Records left out by the retention policy: excluded apps, or history off.
+    pub excluded: usize,
+    /// CSV rows without a readable timestamp.
+    pub invalid: usize,
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+fn format_time(ts: i64) -> String {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.format(DATE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+// --- JSON ---
+
+fn to_json(records: Vec<HistoryRecord>) -> Result<String, String> {
+    let export = HistoryExport {
+        version: EXPORT_VERSION,
+        exported_at: Local::now().timestamp_millis(),
+        records,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
+}
+
+/// Accepts the export envelope, the legacy `{"records": [...]}` store file
+/// or a bare array of records.
+fn from_json(content: &str) -> Result<Vec<HistoryRecord>, String> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Input {
+        Envelope { records: Vec<HistoryRecord> },
+        Records(Vec<HistoryRecord>),
+    }
+
+    match serde_json::from_str::<Input>(content).map_err(|_| "invalid_history_json".to_string())? {
+        Input::Envelope { records } | Input::Records(records) => Ok(records),
+    }
+}
+
+// --- CSV ---
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(records: &[HistoryRecord]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for record in records {
+        let fields = [
+            record.id.clone(),
+            record.timestamp.to_string(),
+            format_time(record.timestamp),
+            record.mode.clone(),
+            record.provider.clone(),
+            record.app.clone().unwrap_or_default(),
+            record.language.clone().unwrap_or_default(),
+            record.input_text.clone(),
+            record.result_text.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Parse RFC 4180 CSV into rows of fields.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Unix ms from the `timestamp` column, or from `date` when a spreadsheet
+/// reformatted the number.
+fn parse_timestamp(timestamp: &str, date: &str) -> Option<i64> {
+    timestamp.trim().parse().ok().or_else(|| {
+        let date = NaiveDateTime::parse_from_str(date.trim(), DATE_FORMAT).ok()?;
+        Local.from_local_datetime(&date).earliest().map(|t| t.timestamp_millis())
+    })
+}
+
+/// Records in `content`, and the number of rows skipped because their
+/// timestamp could not be read.
+fn from_csv(content: &str) -> Result<(Vec<HistoryRecord>, usize), String> {
+    let mut rows = parse_csv(content.trim_start_matches('\u{feff}')).into_iter();
+    let header = rows.next().ok_or_else(|| "invalid_history_csv".to_string())?;
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let (Some(id), Some(timestamp), Some(mode), Some(input), Some(result)) = (
+        column("id"),
+        column("timestamp"),
+        column("mode"),
+        column("input_text"),
+        column("result_text"),
+    ) else {
+        return Err("invalid_history_csv".to_string());
+    };
+    let (date, provider, app, language) = (column("date"), column("provider"), column("app"), column("language"));
+
+    let mut records = Vec::new();
+    let mut invalid = 0;
+    for row in rows {
+        let get = |i: usize| row.get(i).cloned().unwrap_or_default();
+        let optional = |i: Option<usize>| i.map(get).filter(|v| !v.is_empty());
+        if get(id).is_empty() {
+            continue;
+        }
+        let Some(timestamp) = parse_timestamp(&get(timestamp), &optional(date).unwrap_or_default()) else {
+            invalid += 1;
+            continue;
+        };
+        records.push(HistoryRecord {
+            id: get(id),
+            timestamp,
+            mode: get(mode),
+            input_text: get(input),
+            result_text: get(result),
+            provider: optional(provider).unwrap_or_default(),
+            app: optional(app),
+            app_id: None,
+            language: optional(language),
+            hunks: None,
+        });
+    }
+    Ok((records, invalid))
+}
+
+// --- Markdown ---
+
+/// Wrap `text` in `marker`, keeping surrounding whitespace outside so the
+/// emphasis still parses (`** foo**` is not bold).
+fn emphasize(out: &mut String, text: &str, marker: &str) {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        out.push_str(text);
+        return;
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = start + trimmed.len();
+    out.push_str(&text[..start]);
+    out.push_str(marker);
+    out.push_str(trimmed);
+    out.push_str(marker);
+    out.push_str(&text[end..]);
+}
+
+fn render_changes(record: &HistoryRecord) -> String {
+    let segments = diff::diff(&record.input_text, &record.result_text, &DiffOptions::default());
+    let mut out = String::new();
+    for segment in &segments {
+        match segment.kind {
+            SegmentKind::Equal => out.push_str(&segment.text),
+            SegmentKind::Delete => emphasize(&mut out, &segment.text, "~~"),
+            SegmentKind::Insert => emphasize(&mut out, &segment.text, "**"),
+        }
+    }
+    out
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {line}") })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn to_markdown(records: &[HistoryRecord]) -> String {
+    let mut out = format!(
+        "# Polishr history\n\nExported {} · {} record{}\n",
+        format_time(Local::now().timestamp_millis()),
+        records.len(),
+        if records.len() == 1 { "" } else { "s" },
+    );
+    for record in records {
+        let mut heading = vec![format_time(record.timestamp), capitalize(&record.mode)];
+        heading.extend(record.app.clone());
+        out.push_str(&format!("\n## {}\n\n", heading.join(" · ")));
+        out.push_str(&format!("**Changes**\n\n{}\n\n", quote(&render_changes(record))));
+        out.push_str(&format!("**Result**\n\n{}\n", quote(&record.result_text)));
+    }
+    out
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// --- Export / import ---
+
+pub(crate) fn render(format: ExportFormat, records: Vec<HistoryRecord>) -> Result<String, String> {
+    match format {
+        ExportFormat::Json => to_json(records),
+        ExportFormat::Csv => Ok(to_csv(&records)),
+        ExportFormat::Markdown => Ok(to_markdown(&records)),
+    }
+}
+
+fn export_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .download_dir()
+        .or_else(|_| app.path().app_data_dir())
+        .map_err(|e| e.to_string())
+}
+
+/// Write matching records to a timestamped file in the Downloads folder.
+pub(crate) fn export_to_file<R: Runtime>(
+    app: &AppHandle<R>,
+    format: ExportFormat,
+    ids: Option<Vec<String>>,
+    query: HistoryQuery,
+) -> Result<ExportResult, String> {
+    let records = history::with_records(|conn, cipher| match &ids {
+        Some(ids) => history::select_by_ids(conn, cipher, ids),
+        None => history::select_records(conn, cipher, &query),
+    })?;
+    let count = records.len();
+    let content = render(format, records)?;
+
+    let dir = export_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "polishr-history-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    println!("[Polishr] Exported {} history records to {}", count, path.display());
+
+    Ok(ExportResult {
+        path: path.to_string_lossy().into_owned(),
+        count,
+    })
+}
+
+// --- Commands ---
+
+/// Export selected records (`ids`) or everything matching `query`.
+#[tauri::command]
+pub async fn history_export(
+    app: AppHandle,
+    format: ExportFormat,
+    ids: Option<Vec<String>>,
+    query: Option<HistoryQuery>,
+) -> Result<ExportResult, String> {
+    export_to_file(&app, format, ids, query.unwrap_or_default())
+}
+
+/// Merge exported history into the store, de-duplicating by id. Records go
+/// through the retention policy like new ones: excluded apps are skipped,
+/// PII is scrubbed, and nothing is imported while history is off.
+#[tauri::command]
+pub async fn history_import(
+    app: AppHandle,
+    content: String,
+    format: ExportFormat,
+) -> Result<ImportResult, String> {
+    let (records, invalid) = match format {
+        ExportFormat::Json => (from_json(&content)?, 0),
+        ExportFormat::Csv => from_csv(&content)?,
+        ExportFormat::Markdown => return Err("unsupported_import_format".to_string()),
+    };
+
+    let total = records.len();
+    let allowed: Vec<HistoryRecord> = records.into_iter().filter_map(retention::prepare_record).collect();
+
+    let imported = history::with_records(|conn, cipher| {
+        let tx = conn.unchecked_transaction()?;
+        let mut imported = 0;
+        for record in &allowed {
+            if history::insert(&tx, cipher, record, true)? {
+                imported += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(imported)
+    })?;
+    if let Err(err) = retention::enforce() {
+        println!("[Polishr] Retention failed: {}", err);
+    }
+    history::notify_changed(&app);
+
+    Ok(ImportResult {
+        imported,
+        duplicates: allowed.len() - imported,
+        excluded: total - allowed.len(),
+        invalid,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, input_text: &str) -> HistoryRecord {
+        HistoryRecord {
+            id: id.to_string(),
+            timestamp: 1_700_000_000_000,
+            mode: "improve".to_string(),
+            input_text: input_text.to_string(),
+            result_text: "Fixed.".to_string(),
+            provider: "openai".to_string(),
+            app: Some("Mail".to_string()),
+            app_id: None,
+            language: None,
+            hunks: None,
+        }
+    }
+
+    #[test]
+    fn csv_round_trips_quotes_commas_and_newlines() {
+        let records = [
+            record("a", "One, two, three"),
+            record("b", "She said \"hi\"\nthen left\r\n"),
+            record("c", "\"\""),
+        ];
+        let csv = to_csv(&records);
+        assert!(csv.contains("\"She said \"\"hi\"\"\nthen left\r\n\""));
+
+        let (parsed, invalid) = from_csv(&format!("\u{feff}{}", csv)).unwrap();
+        assert_eq!(invalid, 0);
+        assert_eq!(parsed.len(), 3);
+        for (parsed, original) in parsed.iter().zip(&records) {
+            assert_eq!(parsed.id, original.id);
+            assert_eq!(parsed.timestamp, original.timestamp);
+            assert_eq!(parsed.input_text, original.input_text);
+            assert_eq!(parsed.result_text, original.result_text);
+            assert_eq!(parsed.provider, original.provider);
+            assert_eq!(parsed.app, original.app);
+            assert_eq!(parsed.language, None);
+        }
+    }
+
+    #[test]
+    fn parses_rfc_4180_fields() {
+        assert_eq!(
+            parse_csv("a,\"b,c\",\"d\"\"e\"\r\n,\"\",x\nlast"),
+            [vec!["a", "b,c", "d\"e"], vec!["", "", "x"], vec!["last"]]
+        );
+        assert!(parse_csv("").is_empty());
+    }
+
+    #[test]
+    fn csv_import_needs_only_the_core_columns() {
+        let csv = "mode,id,input_text,timestamp,result_text\nfix,r1,teh,1700000000000,the\n,,,,\n";
+        let (records, _) = from_csv(csv).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "r1");
+        assert_eq!(records[0].mode, "fix");
+        assert_eq!(records[0].provider, "");
+        assert_eq!(records[0].app, None);
+
+        assert_eq!(from_csv("id,mode\nr1,fix\n").err().as_deref(), Some("invalid_history_csv"));
+        assert_eq!(from_csv("").err().as_deref(), Some("invalid_history_csv"));
+    }
+
+    #[test]
+    fn unreadable_timestamps_skip_only_their_row() {
+        let date = format_time(1_700_000_000_000);
+        let csv = format!(
+            "id,timestamp,date,mode,input_text,result_text\n\
+             r1,1.7E+12,{date},fix,a,b\n\
+             r2,yesterday,,fix,a,b\n\
+             r3,1700000000000,,fix,a,b\n"
+        );
+        let (records, invalid) = from_csv(&csv).unwrap();
+        assert_eq!(invalid, 1);
+        let ids: Vec<&str> = records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["r1", "r3"]);
+        // The date column only keeps minutes.
+        assert_eq!(records[0].timestamp, 1_700_000_000_000 - 1_700_000_000_000 % 60_000);
+    }
+}
@@ -0,0 +1,394 @@
+//! Writing statistics and recurring-mistake insights from history.
+//!
+//! Sessions are aggregated by mode, language, app and day. Each suggested
+//! change (the hunks saved with a record, or recomputed from its input and
+//! result) is classified into an error category with lightweight heuristics,
+//! and identical corrections are grouped to find the ones a writer repeats.
+//! Translations are counted but not mined for corrections.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{self, DiffOptions};
+use crate::history::{self, HistoryHunk, HistoryQuery, HistoryRecord};
+use crate::readability;
+
+const TOP_CORRECTIONS: usize = 20;
+/// Corrections spanning more words than this are treated as rephrasing.
+const MAX_CORRECTION_WORDS: usize = 3;
+
+const ARTICLES: &[&str] = &["a", "an", "the"];
+const PREPOSITIONS: &[&str] = &[
+    "about", "at", "by", "for", "from", "in", "into", "of", "on", "onto", "over", "to", "under",
+    "upon", "with", "within",
+];
+/// Groups of words that are commonly confused with each other.
+const CONFUSABLES: &[&[&str]] = &[
+    &["its", "it's"],
+    &["their", "there", "they're"],
+    &["your", "you're"],
+    &["whose", "who's"],
+    &["then", "than"],
+    &["affect", "effect"],
+    &["loose", "lose"],
+    &["to", "too", "two"],
+    &["were", "where", "we're"],
+    &["accept", "except"],
+    &["fewer", "less"],
+    &["who", "whom"],
+    &["的", "得", "地"],
+    &["在", "再"],
+    &["做", "作"],
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Spelling,
+    Punctuation,
+    Capitalization,
+    Spacing,
+    Article,
+    Preposition,
+    ConfusedWords,
+    Grammar,
+    WordChoice,
+    Rephrasing,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct InsightsRange {
+    /// Inclusive lower bound, Unix ms.
+    pub from: Option<i64>,
+    /// Exclusive upper bound, Unix ms.
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct Breakdown {
+    pub key: String,
+    pub sessions: u32,
+    pub words: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryCount {
+    pub category: ErrorCategory,
+    pub count: u32,
+}
+
+/// A correction the writer received more than once.
+#[derive(Debug, Serialize)]
+pub struct RecurringCorrection {
+    pub category: ErrorCategory,
+    pub deleted: String,
+    pub inserted: String,
+    pub count: u32,
+    /// How often the suggestion was accepted, when hunk decisions are known.
+    pub accepted: u32,
+    pub last_seen: i64,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct DayStats {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub sessions: u32,
+    pub words: u32,
+    pub corrections: u32,
+    pub by_category: BTreeMap<ErrorCategory, u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Insights {
+    pub sessions: u32,
+    pub words: u32,
+    pub corrections: u32,
+    pub by_mode: Vec<Breakdown>,
+    pub by_language: Vec<Breakdown>,
+    pub by_app: Vec<Breakdown>,
+    pub categories: Vec<CategoryCount>,
+    pub top_corrections: Vec<RecurringCorrection>,
+    /// One entry per day with activity, oldest first.
+    pub trend: Vec<DayStats>,
+}
+
+// --- Classification ---
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || "，。！？、；：“”‘’（）《》【】「」…—·".contains(c)
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(is_punctuation).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn strip(text: &str, drop: impl Fn(char) -> bool) -> String {
+    text.chars().filter(|c| !drop(*c)).collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+fn confusable(a: &str, b: &str) -> bool {
+    let a = a.replace('’', "'");
+    let b = b.replace('’', "'");
+    CONFUSABLES
+        .iter()
+        .any(|group| group.contains(&a.as_str()) && group.contains(&b.as_str()))
+}
+
+/// Share a stem and differ only in an inflection (e.g. go / goes, make / making).
+fn inflection(a: &str, b: &str) -> bool {
+    const SUFFIXES: &[&str] = &["", "e", "s", "es", "d", "ed", "ing", "er", "est", "ly", "y", "ies", "ied"];
+    let common: usize = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    common >= 2 && a != b && SUFFIXES.contains(&&a[common..]) && SUFFIXES.contains(&&b[common..])
+}
+
+/// Put a suggested change into an error category.
+pub fn classify(deleted: &str, inserted: &str) -> ErrorCategory {
+    if strip(deleted, char::is_whitespace) == strip(inserted, char::is_whitespace) {
+        return ErrorCategory::Spacing;
+    }
+    if deleted.to_lowercase() == inserted.to_lowercase() {
+        return ErrorCategory::Capitalization;
+    }
+    let is_apostrophe = |c: char| c == '\'' || c == '’';
+    let plain = |text: &str| strip(text, |c| is_punctuation(c) || c.is_whitespace()).to_lowercase();
+    if plain(deleted) == plain(inserted) {
+        // its / it's differ only by an apostrophe but are different words.
+        let apostrophe_only = strip(deleted, is_apostrophe) == strip(inserted, is_apostrophe);
+        return if apostrophe_only && confusable(&deleted.trim().to_lowercase(), &inserted.trim().to_lowercase()) {
+            ErrorCategory::ConfusedWords
+        } else {
+            ErrorCategory::Punctuation
+        };
+    }
+
+    // Han and kana characters count as words, so unspaced text can be rephrasing too.
+    let longest = readability::count_words(deleted).max(readability::count_words(inserted));
+    if longest > MAX_CORRECTION_WORDS {
+        return ErrorCategory::Rephrasing;
+    }
+    let old = words(deleted);
+    let new = words(inserted);
+    let all_in = |ws: &[String], set: &[&str]| !ws.is_empty() && ws.iter().all(|w| set.contains(&w.as_str()));
+    let only_articles = |ws: &[String]| ws.is_empty() || all_in(ws, ARTICLES);
+    if only_articles(&old) && only_articles(&new) {
+        return ErrorCategory::Article;
+    }
+    let only_prepositions = |ws: &[String]| ws.is_empty() || all_in(ws, PREPOSITIONS);
+    if only_prepositions(&old) && only_prepositions(&new) {
+        return ErrorCategory::Preposition;
+    }
+
+    if let ([a], [b]) = (old.as_slice(), new.as_slice()) {
+        if confusable(a, b) {
+            return ErrorCategory::ConfusedWords;
+        }
+        if inflection(a, b) {
+            return ErrorCategory::Grammar;
+        }
+        let longest = a.chars().count().max(b.chars().count());
+        if longest >= 3 && levenshtein(a, b) <= 2 {
+            return ErrorCategory::Spelling;
+        }
+    }
+    ErrorCategory::WordChoice
+}
+
+// --- Aggregation ---
+
+fn day_of(ts: i64) -> String {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Saved hunk decisions, or hunks recomputed from input and result.
+fn hunks_of(record: &HistoryRecord) -> Vec<HistoryHunk> {
+    if let Some(hunks) = &record.hunks {
+        return hunks.clone();
+    }
+    let mut segments = diff::diff(&record.input_text, &record.result_text, &DiffOptions::default());
+    diff::group_hunks(&mut segments)
+        .into_iter()
+        .map(|h| HistoryHunk {
+            id: h.id,
+            deleted: h.deleted,
+            inserted: h.inserted,
+            // Unknown: count recomputed suggestions as kept.
+            accepted: true,
+        })
+        .collect()
+}
+
+fn bump(map: &mut HashMap<String, Breakdown>, key: &str, words: u32) {
+    let entry = map.entry(key.to_string()).or_insert_with(|| Breakdown {
+        key: key.to_string(),
+        ..Default::default()
+    });
+    entry.sessions += 1;
+    entry.words += words;
+}
+
+fn sorted(map: HashMap<String, Breakdown>) -> Vec<Breakdown> {
+    let mut list: Vec<Breakdown> = map.into_values().collect();
+    list.sort_by(|a, b| b.sessions.cmp(&a.sessions).then_with(|| a.key.cmp(&b.key)));
+    list
+}
+
+pub fn analyze(records: &[HistoryRecord]) -> Insights {
+    let mut by_mode = HashMap::new();
+    let mut by_language = HashMap::new();
+    let mut by_app = HashMap::new();
+    let mut categories: HashMap<ErrorCategory, u32> = HashMap::new();
+    let mut recurring: HashMap<(String, String), RecurringCorrection> = HashMap::new();
+    let mut days: BTreeMap<String, DayStats> = BTreeMap::new();
+    let (mut total_words, mut total_corrections) = (0, 0);
+
+    for record in records {
+        let words = readability::count_words(&record.input_text) as u32;
+        total_words += words;
+        bump(&mut by_mode, &record.mode, words);
+        bump(&mut by_language, record.language.as_deref().unwrap_or("unknown"), words);
+        bump(&mut by_app, record.app.as_deref().unwrap_or("unknown"), words);
+
+        let date = day_of(record.timestamp);
+        let day = days.entry(date.clone()).or_insert_with(|| DayStats {
+            date,
+            ..Default::default()
+        });
+        day.sessions += 1;
+        day.words += words;
+
+        if record.mode == "translate" {
+            continue;
+        }
+        for hunk in hunks_of(record) {
+            let category = classify(&hunk.deleted, &hunk.inserted);
+            total_corrections += 1;
+            day.corrections += 1;
+            *day.by_category.entry(category).or_default() += 1;
+            *categories.entry(category).or_default() += 1;
+
+            if category == ErrorCategory::Rephrasing {
+                continue;
+            }
+            let key = (hunk.deleted.trim().to_lowercase(), hunk.inserted.trim().to_lowercase());
+            let entry = recurring.entry(key).or_insert_with(|| RecurringCorrection {
+                category,
+                deleted: hunk.deleted.trim().to_string(),
+                inserted: hunk.inserted.trim().to_string(),
+                count: 0,
+                accepted: 0,
+                last_seen: 0,
+            });
+            entry.count += 1;
+            entry.accepted += u32::from(hunk.accepted);
+            entry.last_seen = entry.last_seen.max(record.timestamp);
+        }
+    }
+
+    let mut categories: Vec<CategoryCount> = categories
+        .into_iter()
+        .map(|(category, count)| CategoryCount { category, count })
+        .collect();
+    categories.sort_by(|a, b| b.count.cmp(&a.count).then(a.category.cmp(&b.category)));
+
+    let mut top_corrections: Vec<RecurringCorrection> =
+        recurring.into_values().filter(|c| c.count > 1).collect();
+    top_corrections.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_seen.cmp(&a.last_seen)));
+    top_corrections.truncate(TOP_CORRECTIONS);
+
+    Insights {
+        sessions: records.len() as u32,
+        words: total_words,
+        corrections: total_corrections,
+        by_mode: sorted(by_mode),
+        by_language: sorted(by_language),
+        by_app: sorted(by_app),
+        categories,
+        top_corrections,
+        trend: days.into_values().collect(),
+    }
+}
+
+// --- Commands ---
+
+/// Statistics and recurring corrections for history in `range`.
+#[tauri::command]
+pub async fn get_insights(range: Option<InsightsRange>) -> Result<Insights, String> {
+    let range = range.unwrap_or_default();
+    let query = HistoryQuery {
+        from: range.from,
+        to: range.to,
+        ..Default::default()
+    };
+    let records = history::with_records(|conn, cipher| history::select_records(conn, cipher, &query))?;
+    Ok(analyze(&records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_each_category() {
+        use ErrorCategory::*;
+        for (deleted, inserted, category) in [
+            ("recieve", "receive", Spelling),
+            ("teh", "the", Spelling),
+            ("Hello", "Hello!", Punctuation),
+            ("However", "However,", Punctuation),
+            ("paris", "Paris", Capitalization),
+            ("hello,world", "hello, world", Spacing),
+            ("a", "an", Article),
+            ("", "the", Article),
+            ("in", "on", Preposition),
+            ("at", "", Preposition),
+            ("its", "it's", ConfusedWords),
+            ("their", "there", ConfusedWords),
+            ("then", "than", ConfusedWords),
+            ("go", "goes", Grammar),
+            ("make", "making", Grammar),
+            ("big", "large", WordChoice),
+            ("the results was good", "the outcome proved better", Rephrasing),
+            // Chinese and Japanese.
+            ("中文English", "中文 English", Spacing),
+            ("你好,", "你好，", Punctuation),
+            ("好吗?", "好吗？", Punctuation),
+            ("的", "得", ConfusedWords),
+            ("在", "再", ConfusedWords),
+            ("很好", "不错", WordChoice),
+            ("这个方法不太好用", "这种做法的效果比较一般", Rephrasing),
+            ("ありがとう", "どうもありがとうございます", Rephrasing),
+        ] {
+            assert_eq!(classify(deleted, inserted), category, "{:?} -> {:?}", deleted, inserted);
+        }
+    }
+}
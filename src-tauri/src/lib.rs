@@ -1,183 +1,221 @@
 #[cfg(target_os = "macos")]
 mod ax_text;
+mod background_check;
+mod chinese_convert;
+mod cli;
 mod commands;
+mod config;
+mod diff;
+mod history;
+mod history_crypto;
+mod history_export;
+mod insights;
+mod llm;
+mod markdown;
+mod placement;
+mod profiles;
+mod prompts;
+mod providers;
+mod quick_replace;
+mod readability;
+mod retention;
+mod secrets;
+mod settings;
+mod shortcuts;
+mod spellcheck;
 mod tray;
+mod trigger_scheduler;
+mod trigger_state;
+mod typography;
 
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "macos")]
 use tauri::{Emitter, Manager};
-use tauri_plugin_global_shortcut::ShortcutState;
+#[cfg(target_os = "macos")]
+use trigger_state::{Anchor, Transition, TriggerEvent, TriggerMachine, TriggerState};
 
 /// Suppresses the next Reopen event (set when floating panel hides programmatically).
 static SUPPRESS_REOPEN: AtomicBool = AtomicBool::new(false);
 
+#[cfg(target_os = "macos")]
 const TRIGGER_SELECTION_WIDTH: f64 = 16.0;
+#[cfg(target_os = "macos")]
 const TRIGGER_PARAGRAPH_WIDTH: f64 = 4.0;
+#[cfg(target_os = "macos")]
 const TRIGGER_MIN_HEIGHT: f64 = 16.0;
+#[cfg(target_os = "macos")]
 const TRIGGER_WINDOW_GAP: f64 = 12.0;
+#[cfg(target_os = "macos")]
 const SCREEN_EDGE_PADDING: f64 = 4.0;
 
-/// The two modes the trigger window can be in.
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum TriggerMode {
-    /// Blue button — user has selected text.
-    Selection,
-    /// Gray line — cursor is in a paragraph but no selection.
-    Paragraph,
+/// Trigger window rect left of the line starting at `line_x`, vertically
+/// centered on the line.
+#[cfg(target_os = "macos")]
+fn trigger_rect(app: &tauri::AppHandle, line_x: f64, y: f64, height: f64, width: f64) -> placement::Rect {
+    let trigger_height = height.max(TRIGGER_MIN_HEIGHT);
+    let request = placement::Request {
+        anchor: placement::Rect::new(line_x, y, 0.0, height),
+        width,
+        height: trigger_height,
+        sides: &[placement::Side::Left],
+        gap: TRIGGER_WINDOW_GAP,
+        margin: SCREEN_EDGE_PADDING,
+    };
+    match placement::place(&request, &commands::screen_monitors(app)) {
+        Some(placed) => placed.rect,
+        None => placement::Rect::new(
+            (line_x - width - TRIGGER_WINDOW_GAP).max(SCREEN_EDGE_PADDING),
+            (y + (height - trigger_height) / 2.0).max(SCREEN_EDGE_PADDING),
+            width,
+            trigger_height,
+        ),
+    }
 }
 
+/// Trigger window state between scheduler wake-ups.
 #[cfg(target_os = "macos")]
-fn start_selection_trigger_poller(app: tauri::AppHandle) {
-    std::thread::spawn(move || {
-        let mut pinned_selection: Option<(usize, f64, f64, f64, f64, f64)> = None;
-        let mut pinned_button: Option<(f64, f64, f64)> = None; // (x, y, height)
-        let mut current_mode: Option<TriggerMode> = None;
-
-        loop {
-            std::thread::sleep(std::time::Duration::from_millis(350));
-
-            let Some(trigger_window) = app.get_webview_window("trigger") else {
-                continue;
-            };
-
-            let main_is_visible = app
-                .get_webview_window("main")
-                .and_then(|window| window.is_visible().ok())
-                .unwrap_or(false);
-            let settings_is_focused = app
-                .get_webview_window("settings")
-                .and_then(|window| window.is_focused().ok())
-                .unwrap_or(false);
-            let trigger_is_focused = trigger_window.is_focused().unwrap_or(false);
+#[derive(Default)]
+struct TriggerTracker {
+    machine: TriggerMachine,
+}
 
-            if main_is_visible || settings_is_focused {
-                let _ = trigger_window.hide();
-                pinned_selection = None;
-                pinned_button = None;
-                current_mode = None;
-                continue;
+#[cfg(target_os = "macos")]
+impl TriggerTracker {
+    /// Run `event` through the state machine, then send the new state to the
+    /// trigger window and move, show or hide it as needed.
+    fn apply(&mut self, app: &tauri::AppHandle, trigger_window: &tauri::WebviewWindow, event: TriggerEvent) -> Transition {
+        let transition = self.machine.handle(event);
+        let state = self.machine.state();
+        if transition.state {
+            let _ = app.emit_to("trigger", "trigger-state", state);
+        }
+        match self.machine.anchor() {
+            Some(anchor) if state.is_visible() => {
+                if transition.changed() {
+                    let width = match state {
+                        TriggerState::Paragraph => TRIGGER_PARAGRAPH_WIDTH,
+                        _ => TRIGGER_SELECTION_WIDTH,
+                    };
+                    let rect = trigger_rect(app, anchor.line_x, anchor.rect.y, anchor.rect.height, width);
+                    let size = tauri::LogicalSize::new(rect.width, rect.height);
+                    let _ = trigger_window.set_size(tauri::Size::Logical(size));
+                    let pos = tauri::LogicalPosition::new(rect.x, rect.y);
+                    let _ = trigger_window.set_position(tauri::Position::Logical(pos));
+                }
+                let _ = trigger_window.show();
             }
-            if trigger_is_focused {
-                continue;
+            _ => {
+                if transition.changed() {
+                    let _ = trigger_window.hide();
+                }
             }
+        }
+        transition
+    }
+}
 
-            // --- Try selection mode first ---
-            match commands::peek_and_locate_sync() {
-                Ok(result) => {
-                    commands::cache_capture(Some(result.clone()));
-                    commands::cache_paragraph(None);
-
-                    let trigger_width = TRIGGER_SELECTION_WIDTH;
-                    let line_anchor_x = result.line_start_x.unwrap_or(result.x);
-                    let button_x =
-                        (line_anchor_x - trigger_width - TRIGGER_WINDOW_GAP).max(SCREEN_EDGE_PADDING);
-                    let trigger_height = result.height.max(TRIGGER_MIN_HEIGHT);
-                    let button_y =
-                        (result.y + (result.height - trigger_height) / 2.0).max(SCREEN_EDGE_PADDING);
+#[cfg(target_os = "macos")]
+fn text_anchor(text: &str, x: f64, y: f64, width: f64, height: f64, line_start_x: Option<f64>) -> Anchor {
+    Anchor {
+        text_len: text.len(),
+        rect: placement::Rect::new(x, y, width, height),
+        line_x: line_start_x.unwrap_or(x),
+    }
+}
 
-                    let selection_changed = match pinned_selection {
-                        Some((text_len, x, y, width, height, anchor_x)) => {
-                            text_len != result.text.len()
-                                || (x - result.x).abs() > 0.5
-                                || (y - result.y).abs() > 0.5
-                                || (width - result.width).abs() > 0.5
-                                || (height - result.height).abs() > 0.5
-                                || (anchor_x - line_anchor_x).abs() > 0.5
-                        }
-                        None => true,
-                    };
+#[cfg(target_os = "macos")]
+impl trigger_scheduler::TriggerProbe for TriggerTracker {
+    fn refresh(&mut self, app: &tauri::AppHandle) -> bool {
+        let Some(trigger_window) = app.get_webview_window("trigger") else {
+            return false;
+        };
 
-                    if selection_changed {
-                        if let Err(err) = ax_text::cache_frontmost_app_for_replace() {
-                            println!("[Polishr] Failed to cache frontmost app for replace: {}", err);
-                        }
-                        pinned_selection = Some((
-                            result.text.len(),
-                            result.x,
-                            result.y,
-                            result.width,
-                            result.height,
-                            line_anchor_x,
-                        ));
-                    }
+        let main_is_visible = app
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        let settings_is_focused = app
+            .get_webview_window("settings")
+            .and_then(|window| window.is_focused().ok())
+            .unwrap_or(false);
+        let trigger_is_focused = trigger_window.is_focused().unwrap_or(false);
 
-                    // Emit mode change to frontend
-                    if current_mode != Some(TriggerMode::Selection) {
-                        current_mode = Some(TriggerMode::Selection);
-                        let _ = app.emit_to("trigger", "trigger-mode", "selection");
-                    }
+        if main_is_visible || settings_is_focused {
+            return self.apply(app, &trigger_window, TriggerEvent::Cleared).changed();
+        }
+        if trigger_is_focused {
+            return false;
+        }
 
-                    let button_changed = match pinned_button {
-                        Some((x, y, h)) => {
-                            (x - button_x).abs() > 0.5
-                                || (y - button_y).abs() > 0.5
-                                || (h - trigger_height).abs() > 0.5
-                        }
-                        None => true,
-                    };
+        // Issues the background check found in the paragraph, if checked.
+        let mut issues = None;
 
-                    if button_changed {
-                        let size = tauri::LogicalSize::new(trigger_width, trigger_height);
-                        let _ = trigger_window.set_size(tauri::Size::Logical(size));
-                        let pos = tauri::LogicalPosition::new(button_x, button_y);
-                        let _ = trigger_window.set_position(tauri::Position::Logical(pos));
-                        pinned_button = Some((button_x, button_y, trigger_height));
-                    }
-                    let _ = trigger_window.show();
+        // --- Try selection mode first ---
+        let event = match commands::peek_and_locate_sync() {
+            Ok(result) if profiles::is_trigger_enabled(result.app_id.as_deref(), result.app.as_deref()) => {
+                commands::cache_capture(Some(result.clone()));
+                commands::cache_paragraph(None);
+                TriggerEvent::SelectionFocused {
+                    anchor: text_anchor(&result.text, result.x, result.y, result.width, result.height, result.line_start_x),
+                    busy: quick_replace::is_polishing(&result.text),
                 }
-                Err(_err) => {
-                    commands::cache_capture(None);
-                    pinned_selection = None;
-
-                    // --- Fallback: try paragraph mode ---
-                    match commands::peek_paragraph_sync() {
-                        Ok(para) => {
-                            commands::cache_paragraph(Some(para.clone()));
-
-                            // Paragraph mode: thin 4px line
-                            let trigger_width = TRIGGER_PARAGRAPH_WIDTH;
-                            let line_anchor_x = para.line_start_x.unwrap_or(para.x);
-                            let button_x =
-                                (line_anchor_x - trigger_width - TRIGGER_WINDOW_GAP).max(SCREEN_EDGE_PADDING);
-                            let trigger_height = para.height.max(TRIGGER_MIN_HEIGHT);
-                            let button_y =
-                                (para.y + (para.height - trigger_height) / 2.0).max(SCREEN_EDGE_PADDING);
-
-                            // Emit mode change to frontend
-                            if current_mode != Some(TriggerMode::Paragraph) {
-                                current_mode = Some(TriggerMode::Paragraph);
-                                let _ = app.emit_to("trigger", "trigger-mode", "paragraph");
-                            }
-
-                            let button_changed = match pinned_button {
-                                Some((x, y, h)) => {
-                                    (x - button_x).abs() > 0.5
-                                        || (y - button_y).abs() > 0.5
-                                        || (h - trigger_height).abs() > 0.5
-                                }
-                                None => true,
-                            };
+            }
+            Ok(_) => {
+                commands::cache_capture(None);
+                commands::cache_paragraph(None);
+                TriggerEvent::AppExcluded
+            }
+            Err(_) => {
+                commands::cache_capture(None);
 
-                            if button_changed {
-                                let size = tauri::LogicalSize::new(trigger_width, trigger_height);
-                                let _ = trigger_window.set_size(tauri::Size::Logical(size));
-                                let pos = tauri::LogicalPosition::new(button_x, button_y);
-                                let _ = trigger_window.set_position(tauri::Position::Logical(pos));
-                                pinned_button = Some((button_x, button_y, trigger_height));
-                            }
-                            let _ = trigger_window.show();
-                        }
-                        Err(_) => {
-                            // No selection and no paragraph — hide everything
-                            commands::cache_paragraph(None);
-                            pinned_button = None;
-                            current_mode = None;
-                            let _ = trigger_window.hide();
-                        }
+                // --- Fallback: try paragraph mode ---
+                match commands::peek_paragraph_sync() {
+                    Ok(para) if profiles::is_trigger_enabled(para.app_id.as_deref(), para.app.as_deref()) => {
+                        let anchor = text_anchor(&para.text, para.x, para.y, para.width, para.height, para.line_start_x);
+                        background_check::request(&para.text, para.app_id.as_deref(), para.app.as_deref());
+                        issues = background_check::suggestion_for(&para.text).map(|s| s.issues);
+                        commands::cache_paragraph(Some(para));
+                        TriggerEvent::ParagraphFocused { anchor }
+                    }
+                    Ok(_) => {
+                        commands::cache_paragraph(None);
+                        TriggerEvent::AppExcluded
+                    }
+                    Err(_) => {
+                        // No selection and no paragraph — hide everything
+                        commands::cache_paragraph(None);
+                        TriggerEvent::Cleared
                     }
                 }
             }
+        };
+
+        let mut transition = self.apply(app, &trigger_window, event);
+        if transition.anchor && self.machine.state().is_selection() {
+            if let Err(err) = ax_text::cache_frontmost_app_for_replace() {
+                println!("[Polishr] Failed to cache frontmost app for replace: {}", err);
+            }
+        }
+        if let Some(count) = issues {
+            let checked = self.apply(app, &trigger_window, TriggerEvent::IssuesChecked { count });
+            transition.state |= checked.state;
+        }
+        transition.changed()
+    }
+
+    fn pause(&mut self, app: &tauri::AppHandle) {
+        commands::cache_capture(None);
+        commands::cache_paragraph(None);
+        if let Some(trigger_window) = app.get_webview_window("trigger") {
+            self.apply(app, &trigger_window, TriggerEvent::Paused);
         }
-    });
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn start_selection_trigger_poller(app: tauri::AppHandle) {
+    background_check::start();
+    trigger_scheduler::start(app, TriggerTracker::default());
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -185,49 +223,36 @@ fn start_selection_trigger_poller(_app: tauri::AppHandle) {}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // `polishr improve < draft.md` and friends run without a window.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_command(&args) {
+        std::process::exit(cli::run(&args));
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_shortcuts(["alt+super+KeyP"])
-                .expect("Failed to register shortcuts")
-                .with_handler(|app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        println!("[Polishr] Cmd+Option+P pressed!");
-                        let handle = app.clone();
-                        tauri::async_runtime::spawn(async move {
-                            match commands::capture_and_locate().await {
-                                Ok(result) => {
-                                    println!(
-                                        "[Polishr] Captured {} chars at ({}, {})",
-                                        result.text.len(),
-                                        result.x,
-                                        result.y
-                                    );
-                                    commands::show_main_panel(&handle, &result);
-                                    let _ = handle.emit("selection-captured", result);
-                                }
-                                Err(err) => {
-                                    println!("[Polishr] Capture failed: {}", err);
-                                    let _ = handle.emit("capture-error", err.clone());
-                                    // Still show the window at a reasonable position
-                                    if let Some(window) = handle.get_webview_window("main") {
-                                        let _ = window.center();
-                                        let _ = window.show();
-                                    }
-                                }
-                            }
-                        });
-                    }
-                })
+                .with_handler(shortcuts::handle)
                 .build(),
         )
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
-            println!("[Polishr] App started. Global shortcut: Cmd+Option+P");
+            println!("[Polishr] App started");
+
+            config::init(app.handle());
+            settings::init(app.handle());
+            profiles::init(app.handle());
+            providers::init(app.handle());
+            shortcuts::init(app.handle());
+            if let Err(err) = history::init(app.handle()) {
+                println!("[Polishr] History database unavailable: {}", err);
+            }
+            retention::init(app.handle());
 
             // Create system tray
             tray::create_tray(app.handle())?;
+            app.on_menu_event(commands::handle_trigger_menu);
             start_selection_trigger_poller(app.handle().clone());
 
             Ok(())
@@ -238,9 +263,53 @@ pub fn run() {
             commands::select_paragraph_and_open,
             commands::expand_trigger,
             commands::shrink_trigger,
+            commands::show_trigger_menu,
+            commands::resize_panel,
             commands::replace_text,
             commands::dismiss,
             commands::check_accessibility_permission,
+            diff::compute_diff,
+            diff::compose_result,
+            history::history_add,
+            history::history_update_hunks,
+            history::history_query,
+            history::history_get,
+            history::history_facets,
+            history::history_delete,
+            history::history_clear,
+            history::history_encryption_status,
+            history::history_enable_encryption,
+            history::history_rotate_key,
+            history::history_disable_encryption,
+            history::history_unlock,
+            history::history_lock,
+            history_export::history_export,
+            history_export::history_import,
+            insights::get_insights,
+            llm::llm_chat_stream,
+            llm::llm_cancel,
+            markdown::segment_markdown,
+            markdown::render_markdown,
+            profiles::list_recent_apps,
+            profiles::get_app_profiles,
+            profiles::save_app_profile,
+            profiles::delete_app_profile,
+            prompts::prompt_messages,
+            prompts::parse_polish_response,
+            providers::save_provider,
+            retention::get_history_policy,
+            retention::save_history_policy,
+            retention::history_purge_app,
+            settings::get_settings,
+            settings::update_settings,
+            shortcuts::get_shortcut_status,
+            spellcheck::check_spelling,
+            spellcheck::add_personal_word,
+            spellcheck::list_dictionaries,
+            trigger_scheduler::get_trigger_scheduler_state,
+            typography::normalize_typography,
+            chinese_convert::convert_chinese,
+            chinese_convert::list_conversion_dictionaries,
         ])
         .on_window_event(|window, event| {
             let label = window.label();
@@ -261,6 +330,11 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building Polishr")
         .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                profiles::save_recent_apps(app);
+            }
+            // Reopen (Dock icon clicked) only exists on macOS.
+            #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { .. } = event {
                 // If suppressed (floating panel just hid), skip this one
                 if SUPPRESS_REOPEN.swap(false, Ordering::SeqCst) {
@@ -0,0 +1,192 @@
+//! Streaming chat completions against OpenAI-compatible providers.
+//!
+//! Requests are made here rather than from the webview so API keys never
+//! leave the backend. Tokens are streamed to the caller through a channel;
+//! a running request can be cancelled by id.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::ipc::Channel;
+use tokio::task::AbortHandle;
+
+use crate::providers::{self, ResolvedProvider};
+use crate::settings::{self, GlossaryEntry};
+
+/// Longest error body passed through to the UI.
+const MAX_ERROR_BODY: usize = 300;
+
+static ACTIVE_REQUESTS: Mutex<Option<HashMap<String, AbortHandle>>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Events sent to the webview while a request streams.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Token(String),
+    Done,
+}
+
+fn error_for_status(status: u16, model: &str, body: &str) -> String {
+    match status {
+        401 => "invalid_api_key".to_string(),
+        429 => "rate_limited".to_string(),
+        404 => format!("model_not_found:{}", model),
+        _ => {
+            let body: String = body.trim().chars().take(MAX_ERROR_BODY).collect();
+            format!("api_error:{}:{}", status, body)
+        }
+    }
+}
+
+/// Instruction listing glossary terms, or `None` when there are none.
+fn glossary_instruction(glossary: &[GlossaryEntry]) -> Option<String> {
+    if glossary.is_empty() {
+        return None;
+    }
+    let rules: Vec<String> = glossary
+        .iter()
+        .map(|entry| match entry.replacement.as_str() {
+            "" => format!("- Keep \"{}\" exactly as written.", entry.term),
+            replacement => format!("- Always write \"{}\" as \"{}\".", entry.term, replacement),
+        })
+        .collect();
+    Some(format!("Follow this glossary:\n{}", rules.join("\n")))
+}
+
+/// Add the glossary to the system message, or as one if there is none.
+pub fn apply_glossary(messages: &mut Vec<ChatMessage>) {
+    let Some(instruction) = settings::with(|s| glossary_instruction(&s.glossary)) else {
+        return;
+    };
+    match messages.iter_mut().find(|m| m.role == "system") {
+        Some(system) => {
+            system.content.push_str("\n\n");
+            system.content.push_str(&instruction);
+        }
+        None => messages.insert(
+            0,
+            ChatMessage {
+                role: "system".to_string(),
+                content: instruction,
+            },
+        ),
+    }
+}
+
+/// Content token from one SSE `data:` payload, if it carries one.
+fn parse_data_line(data: &str) -> Option<String> {
+    let chunk: serde_json::Value = serde_json::from_str(data).ok()?;
+    let content = chunk["choices"][0]["delta"]["content"].as_str()?;
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// Send a chat request and feed content tokens to `on_token` as they
+/// arrive. Returns the full response text.
+pub async fn chat_stream(
+    provider: &ResolvedProvider,
+    messages: &[ChatMessage],
+    mut on_token: impl FnMut(String) -> Result<(), String>,
+) -> Result<String, String> {
+    let url = format!("{}/chat/completions", provider.endpoint.trim_end_matches('/'));
+    let mut response = reqwest::Client::new()
+        .post(url)
+        .bearer_auth(&provider.api_key)
+        .json(&json!({
+            "model": provider.model,
+            "messages": messages,
+            "stream": true,
+            "temperature": provider.temperature,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("network_error:{}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(error_for_status(status.as_u16(), &provider.model, &body));
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut full = String::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("network_error:{}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(full);
+            }
+            if let Some(token) = parse_data_line(data) {
+                full.push_str(&token);
+                on_token(token)?;
+            }
+        }
+    }
+    Ok(full)
+}
+
+// --- Commands ---
+
+/// Stream a chat completion from a configured provider (the active one when
+/// `provider_id` is omitted), following the glossary. Fails with `cancelled` if [`llm_cancel`] is
+/// called with the same `request_id`.
+#[tauri::command]
+pub async fn llm_chat_stream(
+    request_id: String,
+    provider_id: Option<String>,
+    mut messages: Vec<ChatMessage>,
+    on_event: Channel<StreamEvent>,
+) -> Result<(), String> {
+    let provider = providers::resolve(provider_id.as_deref())?;
+    apply_glossary(&mut messages);
+    let events = on_event.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        chat_stream(&provider, &messages, |token| {
+            events.send(StreamEvent::Token(token)).map_err(|e| e.to_string())
+        })
+        .await
+    });
+
+    ACTIVE_REQUESTS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(request_id.clone(), task.inner().abort_handle());
+    let result = task.await;
+    if let Some(active) = ACTIVE_REQUESTS.lock().unwrap().as_mut() {
+        active.remove(&request_id);
+    }
+
+    result.map_err(|_| "cancelled".to_string())??;
+    on_event.send(StreamEvent::Done).map_err(|e| e.to_string())
+}
+
+/// Cancel a request started with [`llm_chat_stream`].
+#[tauri::command]
+pub async fn llm_cancel(request_id: String) {
+    if let Some(handle) = ACTIVE_REQUESTS
+        .lock()
+        .unwrap()
+        .as_mut()
+        .and_then(|active| active.remove(&request_id))
+    {
+        handle.abort();
+    }
+}
@@ -0,0 +1,818 @@
+//! Markdown-aware segmentation for polishing.
+//!
+//! Splits a Markdown selection into blocks so that only prose is sent to the
+//! LLM. Code, tables, front matter and raw HTML pass through verbatim, and
+//! polished prose is re-emitted with the original markers and wrap width.
+
+use serde::{Deserialize, Serialize};
+
+use crate::readability;
+
+/// Kind of a top-level Markdown block.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    FrontMatter,
+    CodeBlock,
+    Table,
+    Html,
+    Rule,
+    Blank,
+    Heading,
+    ListItem,
+    BlockQuote,
+    Paragraph,
+}
+
+/// One block of a segmented Markdown document.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkdownBlock {
+    pub kind: BlockKind,
+    /// Original source of the block with its inner line endings, without
+    /// the line ending that follows it.
+    pub raw: String,
+    /// Line ending after the block (`"\n"`, `"\r\n"`, or empty at the end).
+    pub line_ending: String,
+    /// Prose to polish, with markers stripped and soft breaks joined.
+    /// `None` for blocks that must be kept verbatim.
+    pub prose: Option<String>,
+    /// Marker written before the first line, e.g. `"## "`, `"- "`, `"> "`.
+    pub first_prefix: String,
+    /// Prefix written before wrapped continuation lines.
+    pub continuation_prefix: String,
+    /// Widest original line in display columns, if the block was hard-wrapped.
+    pub wrap_width: Option<usize>,
+    /// Marker that ended hard-broken lines (two spaces or `\`); prose keeps
+    /// the breaks as `\n`.
+    pub hard_break: Option<String>,
+    /// Setext underline character (`=` or `-`) for underlined headings.
+    pub underline: Option<char>,
+}
+
+/// A Markdown selection split into blocks.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkdownDocument {
+    pub blocks: Vec<MarkdownBlock>,
+}
+
+/// Source lines without their endings, and the endings kept aside so
+/// untouched blocks are written back byte for byte.
+struct SourceLines<'a> {
+    lines: Vec<&'a str>,
+    endings: Vec<&'a str>,
+}
+
+impl<'a> SourceLines<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut endings = Vec::new();
+        for piece in text.split_inclusive('\n') {
+            let line = piece.strip_suffix('\n').map_or(piece, |l| l.strip_suffix('\r').unwrap_or(l));
+            lines.push(line);
+            endings.push(&piece[line.len()..]);
+        }
+        Self { lines, endings }
+    }
+
+    /// Source of lines `start..end` and the line ending after them.
+    fn raw(&self, start: usize, end: usize) -> (String, String) {
+        let mut raw = String::new();
+        for i in start..end {
+            raw.push_str(self.lines[i]);
+            if i + 1 < end {
+                raw.push_str(self.endings[i]);
+            }
+        }
+        (raw, self.endings[end - 1].to_string())
+    }
+}
+
+/// Trailing hard-break marker of a line that is not the last of its block.
+fn hard_break_marker(line: &str) -> Option<&str> {
+    if line.ends_with('\\') {
+        return Some("\\");
+    }
+    let trailing = &line[line.trim_end_matches(' ').len()..];
+    (trailing.len() >= 2).then_some(trailing)
+}
+
+impl MarkdownBlock {
+    fn verbatim(kind: BlockKind, source: &SourceLines, start: usize, end: usize) -> Self {
+        let (raw, line_ending) = source.raw(start, end);
+        Self {
+            kind,
+            raw,
+            line_ending,
+            prose: None,
+            first_prefix: String::new(),
+            continuation_prefix: String::new(),
+            wrap_width: None,
+            hard_break: None,
+            underline: None,
+        }
+    }
+
+    fn prose(
+        kind: BlockKind,
+        source: &SourceLines,
+        (start, end): (usize, usize),
+        first_prefix: String,
+        continuation_prefix: String,
+        contents: &[&str],
+    ) -> Self {
+        let (raw, line_ending) = source.raw(start, end);
+        let hard_break = contents
+            .iter()
+            .take(contents.len().saturating_sub(1))
+            .find_map(|line| hard_break_marker(line))
+            .map(str::to_string);
+
+        let prose = if hard_break.is_some() {
+            contents
+                .iter()
+                .enumerate()
+                .map(|(n, line)| {
+                    let line = if n + 1 < contents.len() { line.strip_suffix('\\').unwrap_or(line) } else { line };
+                    line.trim()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            join_soft_lines(contents)
+        };
+
+        let wrap_width = if contents.len() > 1 && hard_break.is_none() {
+            source.lines[start..end].iter().map(|line| display_width(line)).max()
+        } else {
+            None
+        };
+
+        Self {
+            kind,
+            raw,
+            line_ending,
+            prose: if prose.trim().is_empty() { None } else { Some(prose) },
+            first_prefix,
+            continuation_prefix,
+            wrap_width,
+            hard_break,
+            underline: None,
+        }
+    }
+
+    /// Line ending used inside the block when it is rendered again.
+    fn newline(&self) -> &str {
+        if self.raw.contains("\r\n") || (!self.raw.contains('\n') && self.line_ending == "\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+}
+
+/// Heuristic check for whether a selection is Markdown rather than plain prose.
+/// Requires multiple lines and at least one structural marker.
+pub fn looks_like_markdown(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 2 {
+        return false;
+    }
+
+    let mut list_items = 0usize;
+    for (i, line) in lines.iter().enumerate() {
+        if (i == 0 && is_front_matter_fence(line))
+            || heading_prefix(line).is_some()
+            || fence_marker(line).is_some()
+            || (i + 1 < lines.len() && line.contains('|') && is_table_separator(lines[i + 1]))
+        {
+            return true;
+        }
+        if list_prefix(line).is_some() {
+            list_items += 1;
+        }
+    }
+    list_items >= 2
+}
+
+/// Split Markdown text into blocks. Never fails: unrecognised input becomes
+/// paragraphs, so rendering without changes reproduces the original text.
+pub fn segment(text: &str) -> MarkdownDocument {
+    let source = SourceLines::new(text);
+    let lines = &source.lines;
+    let mut blocks = Vec::new();
+    let mut i = 0usize;
+
+    // --- Front matter: only valid on the very first line ---
+    if lines.first().is_some_and(|line| is_front_matter_fence(line)) {
+        let fence = lines[0].trim_end();
+        if let Some(end) = lines[1..].iter().position(|line| line.trim_end() == fence) {
+            blocks.push(MarkdownBlock::verbatim(BlockKind::FrontMatter, &source, 0, end + 2));
+            i = end + 2;
+        }
+    }
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if line.trim().is_empty() {
+            blocks.push(MarkdownBlock::verbatim(BlockKind::Blank, &source, i, i + 1));
+            i += 1;
+            continue;
+        }
+
+        // --- Fenced code ---
+        if let Some((fence_char, fence_len)) = fence_marker(line) {
+            let mut end = i + 1;
+            while end < lines.len() {
+                if let Some((c, len)) = fence_marker(lines[end]) {
+                    let rest = lines[end].trim().trim_start_matches(c);
+                    if c == fence_char && len >= fence_len && rest.trim().is_empty() {
+                        end += 1;
+                        break;
+                    }
+                }
+                end += 1;
+            }
+            blocks.push(MarkdownBlock::verbatim(BlockKind::CodeBlock, &source, i, end));
+            i = end;
+            continue;
+        }
+
+        // --- Indented code (must follow a blank line or start the document) ---
+        let after_blank = blocks
+            .last()
+            .is_none_or(|b| b.kind == BlockKind::Blank);
+        if after_blank && indent_width(line) >= 4 {
+            let mut end = i + 1;
+            while end < lines.len()
+                && (indent_width(lines[end]) >= 4 || lines[end].trim().is_empty())
+            {
+                end += 1;
+            }
+            // Trailing blank lines belong to the surrounding document.
+            while end > i + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            blocks.push(MarkdownBlock::verbatim(BlockKind::CodeBlock, &source, i, end));
+            i = end;
+            continue;
+        }
+
+        // --- Tables ---
+        if line.contains('|') && i + 1 < lines.len() && is_table_separator(lines[i + 1]) {
+            let mut end = i + 2;
+            while end < lines.len() && lines[end].contains('|') && !lines[end].trim().is_empty() {
+                end += 1;
+            }
+            blocks.push(MarkdownBlock::verbatim(BlockKind::Table, &source, i, end));
+            i = end;
+            continue;
+        }
+
+        // --- Raw HTML ---
+        if is_html_start(line) {
+            let end = block_end(lines, i);
+            blocks.push(MarkdownBlock::verbatim(BlockKind::Html, &source, i, end));
+            i = end;
+            continue;
+        }
+
+        // --- Thematic break ---
+        if is_thematic_break(line) {
+            blocks.push(MarkdownBlock::verbatim(BlockKind::Rule, &source, i, i + 1));
+            i += 1;
+            continue;
+        }
+
+        // --- ATX heading ---
+        if let Some(prefix_len) = heading_prefix(line) {
+            let (prefix, content) = line.split_at(prefix_len);
+            blocks.push(MarkdownBlock::prose(
+                BlockKind::Heading,
+                &source,
+                (i, i + 1),
+                prefix.to_string(),
+                String::new(),
+                &[content],
+            ));
+            i += 1;
+            continue;
+        }
+
+        // --- Block quote ---
+        if line.trim_start().starts_with('>') {
+            let mut end = i + 1;
+            while end < lines.len() && lines[end].trim_start().starts_with('>') {
+                end += 1;
+            }
+            let contents: Vec<&str> = lines[i..end].iter().map(|l| strip_quote(l)).collect();
+            let nested = contents.iter().any(|c| {
+                c.trim().is_empty()
+                    || heading_prefix(c).is_some()
+                    || list_prefix(c).is_some()
+                    || fence_marker(c).is_some()
+                    || c.trim_start().starts_with('>')
+            });
+            if nested {
+                blocks.push(MarkdownBlock::verbatim(BlockKind::BlockQuote, &source, i, end));
+            } else {
+                let marker = |l: &str| l[..l.len() - strip_quote(l).len()].to_string();
+                let continuation = lines.get(i + 1).filter(|_| end > i + 1).map_or_else(|| marker(line), |l| marker(l));
+                blocks.push(MarkdownBlock::prose(
+                    BlockKind::BlockQuote,
+                    &source,
+                    (i, end),
+                    marker(line),
+                    continuation,
+                    &contents,
+                ));
+            }
+            i = end;
+            continue;
+        }
+
+        // --- List item ---
+        if let Some(prefix_len) = list_prefix(line) {
+            let mut end = i + 1;
+            while end < lines.len() {
+                let next = lines[end];
+                if next.trim().is_empty()
+                    || list_prefix(next).is_some()
+                    || starts_new_block(next)
+                {
+                    break;
+                }
+                end += 1;
+            }
+            let mut contents = vec![&line[prefix_len..]];
+            contents.extend(lines[i + 1..end].iter().map(|l| l.trim_start()));
+            // Continuation lines keep the indent they were written with.
+            let continuation = match lines.get(i + 1).filter(|_| end > i + 1) {
+                Some(next) => leading_whitespace(next).to_string(),
+                None => " ".repeat(display_width(&line[..prefix_len])),
+            };
+            blocks.push(MarkdownBlock::prose(
+                BlockKind::ListItem,
+                &source,
+                (i, end),
+                line[..prefix_len].to_string(),
+                continuation,
+                &contents,
+            ));
+            i = end;
+            continue;
+        }
+
+        // --- Paragraph (possibly a setext heading) ---
+        let mut end = i + 1;
+        let mut underline = None;
+        while end < lines.len() {
+            let next = lines[end];
+            if let Some(c) = setext_underline(next) {
+                underline = Some(c);
+                break;
+            }
+            if next.trim().is_empty() || starts_new_block(next) || list_prefix(next).is_some() {
+                break;
+            }
+            end += 1;
+        }
+        let contents: Vec<&str> = lines[i..end].iter().map(|l| l.trim_start()).collect();
+        // An indented paragraph (e.g. a list item's second paragraph) stays indented.
+        let indent = leading_whitespace(line).to_string();
+        let continuation = match lines.get(i + 1).filter(|_| end > i + 1) {
+            Some(next) => leading_whitespace(next).to_string(),
+            None => indent.clone(),
+        };
+        match underline {
+            Some(c) => {
+                let mut block = MarkdownBlock::prose(
+                    BlockKind::Heading,
+                    &source,
+                    (i, end + 1),
+                    indent,
+                    continuation,
+                    &contents,
+                );
+                block.underline = Some(c);
+                block.wrap_width = None;
+                blocks.push(block);
+                i = end + 1;
+            }
+            None => {
+                blocks.push(MarkdownBlock::prose(
+                    BlockKind::Paragraph,
+                    &source,
+                    (i, end),
+                    indent,
+                    continuation,
+                    &contents,
+                ));
+                i = end;
+            }
+        }
+    }
+
+    MarkdownDocument { blocks }
+}
+
+/// Re-emit a segmented document. `polished[i]` replaces the prose of block `i`;
+/// `None` (or a verbatim block) keeps the original source.
+pub fn render(document: &MarkdownDocument, polished: &[Option<String>]) -> String {
+    let mut out = String::new();
+
+    for (i, block) in document.blocks.iter().enumerate() {
+        let replacement = polished
+            .get(i)
+            .and_then(|p| p.as_deref())
+            .filter(|p| block.prose.is_some() && !p.trim().is_empty());
+
+        let Some(text) = replacement else {
+            out.push_str(&block.raw);
+            out.push_str(&block.line_ending);
+            continue;
+        };
+
+        let body_lines: Vec<String> = match &block.hard_break {
+            Some(marker) => {
+                let lines: Vec<&str> = text.trim().lines().map(str::trim).collect();
+                let last = lines.len().saturating_sub(1);
+                lines
+                    .iter()
+                    .enumerate()
+                    .map(|(n, line)| if n < last { format!("{}{}", line, marker) } else { line.to_string() })
+                    .collect()
+            }
+            None => {
+                let single = join_soft_lines(&text.lines().collect::<Vec<_>>());
+                match block.wrap_width {
+                    Some(width) => {
+                        let prefix_width = display_width(&block.first_prefix)
+                            .max(display_width(&block.continuation_prefix));
+                        wrap(&single, width.saturating_sub(prefix_width).max(1))
+                    }
+                    None => vec![single],
+                }
+            }
+        };
+
+        let newline = block.newline();
+        for (n, body) in body_lines.iter().enumerate() {
+            if n > 0 {
+                out.push_str(newline);
+                out.push_str(&block.continuation_prefix);
+            } else {
+                out.push_str(&block.first_prefix);
+            }
+            out.push_str(body);
+        }
+        if let Some(c) = block.underline {
+            let width = body_lines.iter().map(|l| display_width(l)).max().unwrap_or(3);
+            out.push_str(newline);
+            out.push_str(&c.to_string().repeat(width.max(3)));
+        }
+        out.push_str(&block.line_ending);
+    }
+
+    out
+}
+
+// --- Line classification helpers ---
+
+fn indent_width(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += 4 - (width % 4),
+            _ => break,
+        }
+    }
+    width
+}
+
+fn is_front_matter_fence(line: &str) -> bool {
+    matches!(line.trim_end(), "---" | "+++")
+}
+
+/// Opening/closing code fence: returns the fence character and run length.
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    if indent_width(line) > 3 {
+        return None;
+    }
+    let trimmed = line.trim_start();
+    let c = trimmed.chars().next()?;
+    if c != '`' && c != '~' {
+        return None;
+    }
+    let len = trimmed.chars().take_while(|&ch| ch == c).count();
+    if len < 3 {
+        return None;
+    }
+    // Backtick fences may not contain backticks in the info string.
+    if c == '`' && trimmed[len..].contains('`') {
+        return None;
+    }
+    Some((c, len))
+}
+
+/// Byte length of an ATX heading marker (`"## "`), including leading indent.
+fn heading_prefix(line: &str) -> Option<usize> {
+    if indent_width(line) > 3 {
+        return None;
+    }
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = &line[indent..];
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if hashes == 0 || hashes > 6 {
+        return None;
+    }
+    let rest = &trimmed[hashes..];
+    if rest.is_empty() {
+        return Some(line.len());
+    }
+    if !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    let spaces = rest.len() - rest.trim_start().len();
+    Some(indent + hashes + spaces)
+}
+
+/// Byte length of a list marker including indent, optional task box and the
+/// following space (`"  - [ ] "`, `"1. "`).
+fn list_prefix(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = &line[indent..];
+    let marker_len = if trimmed.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || digits > 9 || !trimmed[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    let rest = &trimmed[marker_len..];
+    if !rest.starts_with(' ') || rest.trim().is_empty() || is_thematic_break(line) {
+        return None;
+    }
+    let mut len = indent + marker_len + (rest.len() - rest.trim_start().len());
+    let after = &line[len..];
+    for task in ["[ ] ", "[x] ", "[X] "] {
+        if after.starts_with(task) {
+            len += task.len();
+            break;
+        }
+    }
+    Some(len)
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    if !trimmed.contains('-') {
+        return false;
+    }
+    let cells: Vec<&str> = trimmed.trim_matches('|').split('|').collect();
+    (trimmed.contains('|') || cells.len() > 1)
+        && cells.iter().all(|cell| {
+            let cell = cell.trim();
+            !cell.is_empty()
+                && cell.trim_matches(':').chars().all(|c| c == '-')
+                && cell.contains('-')
+        })
+}
+
+fn is_html_start(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let mut chars = trimmed.chars();
+    chars.next() == Some('<')
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    if indent_width(line) > 3 {
+        return false;
+    }
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&m| compact.chars().all(|c| c == m))
+}
+
+fn setext_underline(line: &str) -> Option<char> {
+    let trimmed = line.trim();
+    if indent_width(line) > 3 || trimmed.is_empty() {
+        return None;
+    }
+    ['=', '-']
+        .into_iter()
+        .find(|&c| trimmed.chars().all(|ch| ch == c))
+}
+
+/// Lines that interrupt a paragraph or list item continuation.
+fn starts_new_block(line: &str) -> bool {
+    heading_prefix(line).is_some()
+        || fence_marker(line).is_some()
+        || is_thematic_break(line)
+        || is_html_start(line)
+        || line.trim_start().starts_with('>')
+}
+
+/// End (exclusive) of a block that runs until the next blank line.
+fn block_end(lines: &[&str], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < lines.len() && !lines[end].trim().is_empty() {
+        end += 1;
+    }
+    end
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn strip_quote(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix('>').unwrap_or(trimmed);
+    rest.strip_prefix(' ').unwrap_or(rest)
+}
+
+// --- Text width and wrapping ---
+
+/// CJK punctuation and full-width forms.
+fn is_fullwidth_symbol(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+/// Written without spaces: lines join and wrap between any two of these.
+fn is_unspaced(c: char) -> bool {
+    readability::is_unspaced(c) || is_fullwidth_symbol(c)
+}
+
+/// Width in terminal columns; CJK characters count as two.
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if readability::is_cjk(c) || is_fullwidth_symbol(c) { 2 } else { 1 })
+        .sum()
+}
+
+/// Join soft-wrapped lines. CJK lines are joined without a space, matching
+/// how Markdown renderers treat line breaks between CJK characters.
+fn join_soft_lines(lines: &[&str]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let needs_space = match (out.chars().last(), line.chars().next()) {
+            (Some(prev), Some(next)) => !(is_unspaced(prev) && is_unspaced(next)),
+            _ => false,
+        };
+        if needs_space {
+            out.push(' ');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Greedy wrap at `width` columns. CJK text may break between any characters.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0usize;
+
+    for token in wrap_tokens(text) {
+        let token_width = display_width(token);
+        let is_space = token == " ";
+        if current_width + token_width > width && current_width > 0 && !is_space {
+            lines.push(current.trim_end().to_string());
+            current.clear();
+            current_width = 0;
+        }
+        if is_space && current_width == 0 {
+            continue;
+        }
+        current.push_str(token);
+        current_width += token_width;
+    }
+    if !current.trim().is_empty() {
+        lines.push(current.trim_end().to_string());
+    }
+    lines
+}
+
+/// Split into words, single spaces and individual CJK characters.
+fn wrap_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, c) in text.char_indices() {
+        if c == ' ' || is_unspaced(c) {
+            if let Some(s) = start.take() {
+                tokens.push(&text[s..idx]);
+            }
+            tokens.push(&text[idx..idx + c.len_utf8()]);
+        } else if start.is_none() {
+            start = Some(idx);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+/// Split a selection into Markdown blocks if it looks like Markdown.
+/// Returns `None` for plain prose, which should be polished as a whole.
+#[tauri::command]
+pub async fn segment_markdown(text: String) -> Option<MarkdownDocument> {
+    if looks_like_markdown(&text) {
+        Some(segment(&text))
+    } else {
+        None
+    }
+}
+
+/// Rebuild the document with polished prose for each block.
+#[tauri::command]
+pub async fn render_markdown(document: MarkdownDocument, polished: Vec<Option<String>>) -> String {
+    render(&document, &polished)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render with every prose block replaced by `f(prose)`.
+    fn polish_with(text: &str, f: impl Fn(&str) -> String) -> String {
+        let document = segment(text);
+        let polished: Vec<Option<String>> =
+            document.blocks.iter().map(|b| b.prose.as_deref().map(&f)).collect();
+        render(&document, &polished)
+    }
+
+    #[test]
+    fn unchanged_documents_round_trip() {
+        for text in [
+            "- one\n- two\n  continued\n\n  second paragraph\n",
+            "> quoted line\n> and more\n\n> - nested list\n",
+            "Intro\n\n```rust\nfn main() {}\n```\n",
+            "| a | b |\n|---|---|\n| 1 | 2 |\nAfter the table",
+            "Line with hard break  \nnext line\\\nlast\n",
+            "# Title\r\n\r\n- item\r\n- item two\r\n",
+            "---\ntitle: x\n---\nBody\n\n\n",
+            "Setext\n======\n    code\n",
+        ] {
+            assert_eq!(render(&segment(text), &[]), text, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn hard_breaks_keep_their_markers() {
+        let text = "Line with hard break  \nnext line\n";
+        assert_eq!(segment(text).blocks[0].prose.as_deref(), Some("Line with hard break\nnext line"));
+        assert_eq!(polish_with(text, |p| p.replace("Line", "A line")), "A line with hard break  \nnext line\n");
+
+        let text = "first\\\nsecond\\\nthird";
+        assert_eq!(segment(text).blocks[0].prose.as_deref(), Some("first\nsecond\nthird"));
+        assert_eq!(polish_with(text, |p| p.to_uppercase()), "FIRST\\\nSECOND\\\nTHIRD");
+    }
+
+    #[test]
+    fn indents_and_line_endings_are_kept() {
+        let text = "- item\n\n  continued paragraph\n";
+        assert_eq!(polish_with(text, |p| p.replace("item", "entry")), "- entry\n\n  continued paragraph\n");
+
+        let text = "- item that wraps\n    onto a deeper line\n";
+        let polished = polish_with(text, |p| p.to_string());
+        assert_eq!(polished, "- item that wraps\n    onto a deeper line\n");
+
+        let text = "# Title\r\n\r\nSome text\r\nwrapped here\r\n";
+        let polished = polish_with(text, |p| p.to_string());
+        assert_eq!(polished, "# Title\r\n\r\nSome text\r\nwrapped here\r\n");
+    }
+
+    #[test]
+    fn line_breaks_are_only_dropped_between_cjk() {
+        let text = "# 标题\n\n正文\n";
+        assert_eq!(polish_with(text, |_| "我们用 Rust\nand Go 写\n代码".to_string()), "# 我们用 Rust and Go 写代码\n\n我们用 Rust and Go 写代码\n");
+    }
+
+    #[test]
+    fn korean_soft_breaks_keep_their_space() {
+        assert_eq!(join_soft_lines(&["오늘은 날씨가", "좋습니다"]), "오늘은 날씨가 좋습니다");
+        assert_eq!(join_soft_lines(&["今天", "天气好。", "明天"]), "今天天气好。明天");
+        assert_eq!(display_width("한국어"), 6);
+    }
+
+    #[test]
+    fn narrow_blocks_wrap_at_the_source_width() {
+        let text = "> one two\n> three\n";
+        let polished = polish_with(text, |_| "one two three four".to_string());
+        assert_eq!(polished, "> one two\n> three\n> four\n");
+    }
+}
@@ -0,0 +1,479 @@
+//! Where to put a window next to something on screen.
+//!
+//! Pure geometry in logical coordinates (points on macOS, the space
+//! accessibility bounds are reported in). Given the rect to stay next to,
+//! the work area of every monitor and the window size, [`place`] picks the
+//! first side with room, keeps the window inside the work area of the
+//! monitor the anchor is on, and snaps it to that monitor's device pixels.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self { x, y, width, height }
+    }
+
+    fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    fn overlap(&self, other: &Rect) -> f64 {
+        let w = self.right().min(other.right()) - self.x.max(other.x);
+        let h = self.bottom().min(other.bottom()) - self.y.max(other.y);
+        w.max(0.0) * h.max(0.0)
+    }
+
+    /// Distance from this rect's center to the nearest point of `other`.
+    fn distance_to(&self, other: &Rect) -> f64 {
+        let cx = self.x + self.width / 2.0;
+        let cy = self.y + self.height / 2.0;
+        let dx = (other.x - cx).max(cx - other.right()).max(0.0);
+        let dy = (other.y - cy).max(cy - other.bottom()).max(0.0);
+        dx.hypot(dy)
+    }
+}
+
+/// A monitor's usable area (without menu bar or dock) and its scale factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Monitor {
+    pub work_area: Rect,
+    pub scale_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Above,
+    Below,
+    Left,
+    Right,
+}
+
+/// What to place and where.
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    /// Rect the window stays next to, e.g. the selection.
+    pub anchor: Rect,
+    pub width: f64,
+    pub height: f64,
+    /// Sides to try, most preferred first.
+    pub sides: &'a [Side],
+    /// Space between the anchor and the window.
+    pub gap: f64,
+    /// Minimum space between the window and the work area edge.
+    pub margin: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub side: Side,
+    /// Final window rect; smaller than requested if the work area is.
+    pub rect: Rect,
+    pub scale_factor: f64,
+}
+
+/// The monitor showing most of `anchor`, or the nearest one when the anchor
+/// is off every monitor.
+pub fn monitor_for(anchor: &Rect, monitors: &[Monitor]) -> Option<Monitor> {
+    let overlapping = monitors
+        .iter()
+        .map(|m| (m, m.work_area.overlap(anchor)))
+        .filter(|(_, overlap)| *overlap > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| *m);
+    overlapping.or_else(|| {
+        monitors
+            .iter()
+            .min_by(|a, b| anchor.distance_to(&a.work_area).total_cmp(&anchor.distance_to(&b.work_area)))
+            .copied()
+    })
+}
+
+/// Window rect on `side` of the anchor. Above and below are aligned to the
+/// anchor's left edge; left and right are centered on it vertically.
+fn beside(request: &Request, side: Side, width: f64, height: f64) -> Rect {
+    let a = request.anchor;
+    let centered_y = a.y + (a.height - height) / 2.0;
+    match side {
+        Side::Above => Rect::new(a.x, a.y - request.gap - height, width, height),
+        Side::Below => Rect::new(a.x, a.bottom() + request.gap, width, height),
+        Side::Left => Rect::new(a.x - request.gap - width, centered_y, width, height),
+        Side::Right => Rect::new(a.right() + request.gap, centered_y, width, height),
+    }
+}
+
+/// Room between the anchor and the work area edge on `side`, minus what
+/// the window needs there. Negative when the window does not fit.
+fn spare_room(request: &Request, side: Side, area: &Rect, width: f64, height: f64) -> f64 {
+    let a = request.anchor;
+    let (room, needed) = match side {
+        Side::Above => (a.y - area.y, height),
+        Side::Below => (area.bottom() - a.bottom(), height),
+        Side::Left => (a.x - area.x, width),
+        Side::Right => (area.right() - a.right(), width),
+    };
+    room - request.margin - request.gap - needed
+}
+
+/// How large the window may be on `side` of the anchor without covering
+/// it: its height above or below, or its width left or right.
+pub fn room(request: &Request, side: Side, monitor: &Monitor) -> f64 {
+    spare_room(request, side, &monitor.work_area, 0.0, 0.0).max(0.0)
+}
+
+fn clamp_into(rect: Rect, area: &Rect) -> Rect {
+    let x = rect.x.min(area.right() - rect.width).max(area.x);
+    let y = rect.y.min(area.bottom() - rect.height).max(area.y);
+    Rect::new(x, y, rect.width, rect.height)
+}
+
+fn snap(value: f64, scale_factor: f64) -> f64 {
+    (value * scale_factor).round() / scale_factor
+}
+
+/// Place a window next to `request.anchor`. Uses the first of
+/// `request.sides` with room; when none has room, the one with the most,
+/// overlapping the anchor if it must. `None` without monitors.
+pub fn place(request: &Request, monitors: &[Monitor]) -> Option<Placement> {
+    let monitor = monitor_for(&request.anchor, monitors)?;
+    let scale_factor = if monitor.scale_factor > 0.0 { monitor.scale_factor } else { 1.0 };
+    let wa = monitor.work_area;
+    let area = Rect::new(
+        wa.x + request.margin,
+        wa.y + request.margin,
+        (wa.width - 2.0 * request.margin).max(0.0),
+        (wa.height - 2.0 * request.margin).max(0.0),
+    );
+    let width = request.width.min(area.width);
+    let height = request.height.min(area.height);
+
+    let spare = |side: Side| spare_room(request, side, &wa, width, height);
+    let side = request
+        .sides
+        .iter()
+        .copied()
+        .find(|side| spare(*side) >= 0.0)
+        .or_else(|| request.sides.iter().copied().max_by(|a, b| spare(*a).total_cmp(&spare(*b))))?;
+
+    let rect = clamp_into(beside(request, side, width, height), &area);
+    let rect = Rect::new(
+        snap(rect.x, scale_factor),
+        snap(rect.y, scale_factor),
+        snap(rect.width, scale_factor),
+        snap(rect.height, scale_factor),
+    );
+    Some(Placement { side, rect, scale_factor })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Side; 4] = [Side::Above, Side::Below, Side::Left, Side::Right];
+
+    /// 1440x900 screen with a 25pt menu bar.
+    fn laptop() -> Monitor {
+        Monitor {
+            work_area: Rect::new(0.0, 25.0, 1440.0, 875.0),
+            scale_factor: 2.0,
+        }
+    }
+
+    /// 1920x1080 monitor to the left of the laptop, scale 1.
+    fn left_external() -> Monitor {
+        Monitor {
+            work_area: Rect::new(-1920.0, 0.0, 1920.0, 1080.0),
+            scale_factor: 1.0,
+        }
+    }
+
+    fn request(anchor: Rect, width: f64, height: f64) -> Request<'static> {
+        Request {
+            anchor,
+            width,
+            height,
+            sides: &ALL,
+            gap: 8.0,
+            margin: 0.0,
+        }
+    }
+
+    fn place_on(request: &Request, monitors: &[Monitor]) -> Placement {
+        place(request, monitors).expect("placement")
+    }
+
+    #[test]
+    fn above_when_there_is_room() {
+        let p = place_on(&request(Rect::new(100.0, 400.0, 200.0, 20.0), 594.0, 197.0), &[laptop()]);
+        assert_eq!(p.side, Side::Above);
+        assert_eq!(p.rect, Rect::new(100.0, 400.0 - 8.0 - 197.0, 594.0, 197.0));
+    }
+
+    #[test]
+    fn below_when_the_menu_bar_is_in_the_way() {
+        // Room above the screen top but not above the work area.
+        let p = place_on(&request(Rect::new(100.0, 210.0, 200.0, 20.0), 594.0, 197.0), &[laptop()]);
+        assert_eq!(p.side, Side::Below);
+        assert_eq!(p.rect.y, 238.0);
+    }
+
+    #[test]
+    fn exactly_enough_room_above_counts() {
+        let p = place_on(&request(Rect::new(100.0, 25.0 + 197.0 + 8.0, 200.0, 20.0), 594.0, 197.0), &[laptop()]);
+        assert_eq!(p.side, Side::Above);
+        assert_eq!(p.rect.y, 25.0);
+    }
+
+    #[test]
+    fn sideways_when_neither_above_nor_below_fits() {
+        let p = place_on(&request(Rect::new(100.0, 400.0, 200.0, 20.0), 300.0, 600.0), &[laptop()]);
+        assert_eq!(p.side, Side::Right);
+        assert_eq!(p.rect.x, 308.0);
+        // Vertically centered on the anchor, then pushed into the work area.
+        assert_eq!(p.rect.y, 110.0);
+    }
+
+    #[test]
+    fn left_when_the_right_is_full() {
+        let p = place_on(&request(Rect::new(1000.0, 400.0, 300.0, 20.0), 300.0, 600.0), &[laptop()]);
+        assert_eq!(p.side, Side::Left);
+        assert_eq!(p.rect.x, 692.0);
+    }
+
+    #[test]
+    fn most_room_wins_when_no_side_fits() {
+        // Tall and wide window; more room below than anywhere else.
+        let p = place_on(&request(Rect::new(600.0, 100.0, 200.0, 20.0), 1000.0, 800.0), &[laptop()]);
+        assert_eq!(p.side, Side::Below);
+        // Clamped into the work area, overlapping the anchor.
+        assert_eq!(p.rect, Rect::new(440.0, 100.0, 1000.0, 800.0));
+    }
+
+    #[test]
+    fn clamped_at_the_right_edge() {
+        let p = place_on(&request(Rect::new(1300.0, 400.0, 100.0, 20.0), 594.0, 197.0), &[laptop()]);
+        assert_eq!(p.side, Side::Above);
+        assert_eq!(p.rect.x, 1440.0 - 594.0);
+    }
+
+    #[test]
+    fn clamped_at_the_left_edge() {
+        let p = place_on(&request(Rect::new(-50.0, 400.0, 100.0, 20.0), 594.0, 197.0), &[laptop()]);
+        assert_eq!(p.rect.x, 0.0);
+    }
+
+    #[test]
+    fn below_is_clamped_at_the_bottom_edge() {
+        let sides = [Side::Below];
+        let r = Request {
+            sides: &sides,
+            ..request(Rect::new(100.0, 850.0, 100.0, 20.0), 594.0, 197.0)
+        };
+        let p = place_on(&r, &[laptop()]);
+        assert_eq!(p.side, Side::Below);
+        assert_eq!(p.rect.y, 900.0 - 197.0);
+    }
+
+    #[test]
+    fn preferred_order_is_respected() {
+        let sides = [Side::Below, Side::Above];
+        let r = Request {
+            sides: &sides,
+            ..request(Rect::new(100.0, 400.0, 200.0, 20.0), 594.0, 197.0)
+        };
+        assert_eq!(place_on(&r, &[laptop()]).side, Side::Below);
+    }
+
+    #[test]
+    fn only_listed_sides_are_used() {
+        let sides = [Side::Above];
+        let r = Request {
+            sides: &sides,
+            ..request(Rect::new(100.0, 30.0, 200.0, 20.0), 594.0, 197.0)
+        };
+        let p = place_on(&r, &[laptop()]);
+        assert_eq!(p.side, Side::Above);
+        assert_eq!(p.rect.y, 25.0);
+    }
+
+    #[test]
+    fn margin_keeps_distance_from_the_edges() {
+        let r = Request {
+            margin: 4.0,
+            ..request(Rect::new(1400.0, 400.0, 20.0, 20.0), 594.0, 197.0)
+        };
+        let p = place_on(&r, &[laptop()]);
+        assert_eq!(p.rect.x, 1440.0 - 4.0 - 594.0);
+
+        // The margin also counts when checking for room.
+        let r = Request {
+            margin: 4.0,
+            ..request(Rect::new(100.0, 25.0 + 197.0 + 8.0, 200.0, 20.0), 594.0, 197.0)
+        };
+        assert_eq!(place_on(&r, &[laptop()]).side, Side::Below);
+    }
+
+    #[test]
+    fn window_larger_than_the_work_area_is_shrunk() {
+        let p = place_on(&request(Rect::new(100.0, 400.0, 200.0, 20.0), 2000.0, 1000.0), &[laptop()]);
+        assert_eq!(p.rect.width, 1440.0);
+        assert_eq!(p.rect.height, 875.0);
+        assert_eq!(p.rect.x, 0.0);
+        assert_eq!(p.rect.y, 25.0);
+    }
+
+    #[test]
+    fn uses_the_monitor_the_anchor_is_on() {
+        let monitors = [laptop(), left_external()];
+        let p = place_on(&request(Rect::new(-500.0, 10.0, 200.0, 20.0), 594.0, 197.0), &monitors);
+        // No room above on the external monitor; stays on it rather than
+        // jumping to the laptop, and is pushed back from the shared edge.
+        assert_eq!(p.side, Side::Below);
+        assert_eq!(p.rect.x, -594.0);
+        assert_eq!(p.scale_factor, 1.0);
+    }
+
+    #[test]
+    fn window_never_spills_onto_the_next_monitor() {
+        let monitors = [laptop(), left_external()];
+        let p = place_on(&request(Rect::new(-100.0, 500.0, 50.0, 20.0), 594.0, 197.0), &monitors);
+        assert_eq!(p.rect.x + p.rect.width, 0.0);
+    }
+
+    #[test]
+    fn anchor_across_monitors_uses_the_larger_part() {
+        let monitors = [laptop(), left_external()];
+        let mostly_left = Rect::new(-300.0, 500.0, 400.0, 20.0);
+        assert_eq!(place_on(&request(mostly_left, 100.0, 100.0), &monitors).scale_factor, 1.0);
+        let mostly_right = Rect::new(-100.0, 500.0, 400.0, 20.0);
+        assert_eq!(place_on(&request(mostly_right, 100.0, 100.0), &monitors).scale_factor, 2.0);
+    }
+
+    #[test]
+    fn anchor_off_every_monitor_uses_the_nearest() {
+        let monitors = [laptop(), left_external()];
+        let p = place_on(&request(Rect::new(2000.0, 400.0, 10.0, 10.0), 100.0, 100.0), &monitors);
+        assert_eq!(p.scale_factor, 2.0);
+        assert_eq!(p.rect.x, 1340.0);
+        let p = place_on(&request(Rect::new(-3000.0, 400.0, 10.0, 10.0), 100.0, 100.0), &monitors);
+        assert_eq!(p.scale_factor, 1.0);
+        assert_eq!(p.rect.x, -1920.0);
+    }
+
+    #[test]
+    fn monitor_above_the_primary() {
+        let top = Monitor {
+            work_area: Rect::new(0.0, -1080.0, 1920.0, 1080.0),
+            scale_factor: 1.0,
+        };
+        let p = place_on(&request(Rect::new(100.0, -1070.0, 200.0, 20.0), 594.0, 197.0), &[laptop(), top]);
+        assert_eq!(p.side, Side::Below);
+        assert_eq!(p.rect.y, -1070.0 + 20.0 + 8.0);
+    }
+
+    #[test]
+    fn snaps_to_device_pixels() {
+        let retina = Monitor {
+            work_area: Rect::new(0.0, 0.0, 1000.0, 1000.0),
+            scale_factor: 2.0,
+        };
+        let p = place_on(&request(Rect::new(10.3, 500.2, 50.0, 20.0), 100.0, 100.0), &[retina]);
+        assert_eq!(p.rect.x, 10.5);
+        assert_eq!(p.rect.y, 392.0);
+
+        let fractional = Monitor {
+            scale_factor: 1.5,
+            ..retina
+        };
+        let p = place_on(&request(Rect::new(10.3, 500.0, 50.0, 20.0), 100.0, 100.0), &[fractional]);
+        assert!(((p.rect.x * 1.5) - (p.rect.x * 1.5).round()).abs() < 1e-9);
+        assert!((p.rect.x - 10.3).abs() <= 1.0 / 3.0);
+    }
+
+    #[test]
+    fn bad_scale_factor_is_treated_as_one() {
+        let broken = Monitor {
+            work_area: Rect::new(0.0, 0.0, 1000.0, 1000.0),
+            scale_factor: 0.0,
+        };
+        let p = place_on(&request(Rect::new(10.4, 500.0, 50.0, 20.0), 100.0, 100.0), &[broken]);
+        assert_eq!(p.rect.x, 10.0);
+        assert_eq!(p.scale_factor, 1.0);
+    }
+
+    #[test]
+    fn trigger_sits_left_of_the_line_centered() {
+        let sides = [Side::Left];
+        let r = Request {
+            anchor: Rect::new(300.0, 400.0, 200.0, 30.0),
+            width: 16.0,
+            height: 16.0,
+            sides: &sides,
+            gap: 12.0,
+            margin: 4.0,
+        };
+        let p = place_on(&r, &[laptop()]);
+        assert_eq!(p.side, Side::Left);
+        assert_eq!(p.rect, Rect::new(272.0, 407.0, 16.0, 16.0));
+    }
+
+    #[test]
+    fn trigger_at_the_screen_edge_stays_inside() {
+        let sides = [Side::Left];
+        let r = Request {
+            anchor: Rect::new(5.0, 30.0, 200.0, 10.0),
+            width: 16.0,
+            height: 16.0,
+            sides: &sides,
+            gap: 12.0,
+            margin: 4.0,
+        };
+        let p = place_on(&r, &[laptop()]);
+        assert_eq!(p.rect.x, 4.0);
+        assert_eq!(p.rect.y, 29.0);
+    }
+
+    #[test]
+    fn room_on_each_side() {
+        let r = Request {
+            margin: 4.0,
+            ..request(Rect::new(100.0, 400.0, 200.0, 20.0), 594.0, 197.0)
+        };
+        let m = laptop();
+        assert_eq!(room(&r, Side::Above, &m), 400.0 - 25.0 - 4.0 - 8.0);
+        assert_eq!(room(&r, Side::Below, &m), 900.0 - 420.0 - 4.0 - 8.0);
+        assert_eq!(room(&r, Side::Left, &m), 100.0 - 4.0 - 8.0);
+        assert_eq!(room(&r, Side::Right, &m), 1440.0 - 300.0 - 4.0 - 8.0);
+        // Never negative, even for an anchor hugging the edge.
+        let r = request(Rect::new(0.0, 25.0, 10.0, 10.0), 10.0, 10.0);
+        assert_eq!(room(&r, Side::Above, &m), 0.0);
+    }
+
+    #[test]
+    fn monitor_for_empty_list() {
+        assert!(monitor_for(&Rect::new(0.0, 0.0, 1.0, 1.0), &[]).is_none());
+    }
+
+    #[test]
+    fn no_monitors_no_placement() {
+        assert!(place(&request(Rect::new(0.0, 0.0, 10.0, 10.0), 100.0, 100.0), &[]).is_none());
+    }
+
+    #[test]
+    fn no_sides_no_placement() {
+        let r = Request {
+            sides: &[],
+            ..request(Rect::new(0.0, 0.0, 10.0, 10.0), 100.0, 100.0)
+        };
+        assert!(place(&r, &[laptop()]).is_none());
+    }
+}
@@ -0,0 +1,343 @@
+//! Per-application profiles.
+//!
+//! A profile overrides the panel's default mode, provider and instruction for
+//! one application, and can turn the passive trigger off for it. Profiles and
+//! the trigger's allow/deny list are part of [`crate::settings`]; the list of
+//! recently seen apps is runtime state kept in the same store file and cached
+//! here.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::CaptureResult;
+use crate::settings::{self, PolishMode};
+
+const STORE_PATH: &str = "settings.json";
+const RECENT_APPS_KEY: &str = "recentApps";
+const MAX_RECENT_APPS: usize = 20;
+/// Recent apps are written this long after a capture, so a burst of
+/// captures rewrites the store once.
+const RECENT_APPS_SAVE_DELAY: Duration = Duration::from_secs(30);
+
+static RECENT_APPS: Mutex<Vec<RecentApp>> = Mutex::new(Vec::new());
+/// Recent apps changed since they were last written.
+static RECENT_APPS_DIRTY: AtomicBool = AtomicBool::new(false);
+
+/// Overrides applied when text is captured from a specific application.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppProfile {
+    /// Application name as reported by the accessibility API.
+    pub app: String,
+    /// Bundle identifier; profiles saved before it was recorded only have
+    /// the name.
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Default polish mode; `None` keeps the global default.
+    #[serde(default)]
+    pub mode: Option<PolishMode>,
+    /// Provider id; `None` keeps the active provider.
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    /// Extra instruction sent with every request from this app.
+    #[serde(default)]
+    pub instruction: Option<String>,
+    /// Whether the passive trigger button appears in this app.
+    #[serde(default = "default_true")]
+    pub trigger_enabled: bool,
+}
+
+impl AppProfile {
+    /// Matches by bundle id when both sides know it, else by name.
+    pub(crate) fn matches(&self, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+        match (self.app_id.as_deref(), app_id) {
+            (Some(id), Some(other)) => id == other,
+            _ => Some(self.app.as_str()) == app_name,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Apps the trigger stays out of on new installs: terminals and password
+/// managers. `(bundle id, name)`.
+pub const DEFAULT_DENIED_APPS: [(&str, &str); 9] = [
+    ("com.apple.Terminal", "Terminal"),
+    ("com.googlecode.iterm2", "iTerm2"),
+    ("com.mitchellh.ghostty", "Ghostty"),
+    ("dev.warp.Warp-Stable", "Warp"),
+    ("com.apple.keychainaccess", "Keychain Access"),
+    ("com.apple.Passwords", "Passwords"),
+    ("com.1password.1password", "1Password"),
+    ("com.agilebits.onepassword7", "1Password 7"),
+    ("com.bitwarden.desktop", "Bitwarden"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerAppsMode {
+    /// The trigger appears everywhere except in the listed apps.
+    #[default]
+    Deny,
+    /// The trigger only appears in the listed apps.
+    Allow,
+}
+
+/// An app in the trigger's allow/deny list.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerApp {
+    /// Bundle identifier, or the app name when the bundle id is unknown.
+    pub id: String,
+    /// Name shown in settings.
+    #[serde(default)]
+    pub name: String,
+}
+
+impl TriggerApp {
+    fn matches(&self, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+        Some(self.id.as_str()) == app_id || Some(self.id.as_str()) == app_name
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TriggerApps {
+    pub mode: TriggerAppsMode,
+    pub apps: Vec<TriggerApp>,
+}
+
+impl TriggerApps {
+    /// Whether the list lets the trigger appear in an app. An app that
+    /// cannot be identified is only allowed in deny mode.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn allows(&self, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+        let listed = self.apps.iter().any(|a| a.matches(app_id, app_name));
+        match self.mode {
+            TriggerAppsMode::Deny => !listed,
+            TriggerAppsMode::Allow => listed,
+        }
+    }
+}
+
+/// An application we captured text from recently.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentApp {
+    pub app: String,
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub last_seen: u64,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn read_key<R: Runtime, T: for<'de> Deserialize<'de>>(app: &AppHandle<R>, key: &str) -> Option<T> {
+    let store = app.store(STORE_PATH).ok()?;
+    serde_json::from_value(store.get(key)?).ok()
+}
+
+fn write_key<R: Runtime, T: Serialize>(app: &AppHandle<R>, key: &str, value: &T) -> Result<(), String> {
+    let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
+    let json = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    store.set(key, json);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Load recent apps from the settings store into memory.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let recent: Vec<RecentApp> = read_key(app, RECENT_APPS_KEY).unwrap_or_default();
+    let profiles = settings::with(|s| s.app_profiles.len());
+    println!("[Polishr] Loaded {} app profiles", profiles);
+    *RECENT_APPS.lock().unwrap() = recent;
+}
+
+/// Look up the profile for an application by bundle id and name, if one is
+/// configured.
+pub fn profile_for(app_id: Option<&str>, app_name: Option<&str>) -> Option<AppProfile> {
+    settings::with(|s| s.app_profiles.iter().find(|p| p.matches(app_id, app_name)).cloned())
+}
+
+/// Whether the passive trigger may appear for the given application, by
+/// bundle id and name: the allow/deny list must allow it and its profile,
+/// if any, must not turn the trigger off.
+#[cfg(target_os = "macos")]
+pub fn is_trigger_enabled(app_id: Option<&str>, app_name: Option<&str>) -> bool {
+    settings::with(|s| s.trigger_apps.allows(app_id, app_name))
+        && profile_for(app_id, app_name).is_none_or(|p| p.trigger_enabled)
+}
+
+/// Keep the trigger out of an app from now on: add it to the deny list, or
+/// take it off the allow list.
+pub fn disable_trigger_for<R: Runtime>(
+    app: &AppHandle<R>,
+    app_id: Option<&str>,
+    app_name: Option<&str>,
+) -> Result<(), String> {
+    let id = app_id.or(app_name).ok_or_else(|| "unknown_app".to_string())?;
+    settings::update(app, |settings| {
+        let list = &mut settings.trigger_apps;
+        match list.mode {
+            TriggerAppsMode::Deny => {
+                if !list.apps.iter().any(|a| a.matches(app_id, app_name)) {
+                    list.apps.push(TriggerApp {
+                        id: id.to_string(),
+                        name: app_name.unwrap_or(id).to_string(),
+                    });
+                }
+            }
+            TriggerAppsMode::Allow => list.apps.retain(|a| !a.matches(app_id, app_name)),
+        }
+        Ok(())
+    })?;
+    println!("[Polishr] Trigger disabled in {}", id);
+    Ok(())
+}
+
+impl RecentApp {
+    /// Same rule as [`AppProfile::matches`].
+    fn matches(&self, app_id: Option<&str>, app_name: &str) -> bool {
+        match (self.app_id.as_deref(), app_id) {
+            (Some(id), Some(other)) => id == other,
+            _ => self.app == app_name,
+        }
+    }
+}
+
+/// Remember that text was captured from `app_name`, newest first. The store
+/// is written a little later, see [`save_recent_apps`].
+fn record_recent_app<R: Runtime>(app: &AppHandle<R>, app_id: Option<&str>, app_name: &str) {
+    {
+        let mut recent = RECENT_APPS.lock().unwrap();
+        recent.retain(|r| !r.matches(app_id, app_name));
+        recent.insert(
+            0,
+            RecentApp {
+                app: app_name.to_string(),
+                app_id: app_id.map(str::to_string),
+                last_seen: now_ms(),
+            },
+        );
+        recent.truncate(MAX_RECENT_APPS);
+    }
+    if !RECENT_APPS_DIRTY.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(RECENT_APPS_SAVE_DELAY);
+            save_recent_apps(&app);
+        });
+    }
+}
+
+/// Write recent apps to the store if they changed since the last write.
+/// Also called on exit.
+pub fn save_recent_apps<R: Runtime>(app: &AppHandle<R>) {
+    if !RECENT_APPS_DIRTY.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let snapshot = RECENT_APPS.lock().unwrap().clone();
+    if let Err(err) = write_key(app, RECENT_APPS_KEY, &snapshot) {
+        println!("[Polishr] Failed to save recent apps: {}", err);
+    }
+}
+
+/// Resolve the profile for a capture right before it is sent to the panel.
+pub fn apply_to_capture<R: Runtime>(app: &AppHandle<R>, capture: &mut CaptureResult) {
+    let Some(app_name) = capture.app.clone() else {
+        return;
+    };
+    record_recent_app(app, capture.app_id.as_deref(), &app_name);
+    capture.profile = profile_for(capture.app_id.as_deref(), Some(&app_name));
+}
+
+/// List applications text was recently captured from, newest first.
+#[tauri::command]
+pub async fn list_recent_apps() -> Vec<RecentApp> {
+    RECENT_APPS.lock().unwrap().clone()
+}
+
+/// List all configured app profiles.
+#[tauri::command]
+pub async fn get_app_profiles() -> Vec<AppProfile> {
+    settings::get().app_profiles
+}
+
+/// Create or replace the profile for `profile.app_id`, or `profile.app` when
+/// the bundle id is unknown.
+#[tauri::command]
+pub async fn save_app_profile(app: AppHandle, window: WebviewWindow, profile: AppProfile) -> Result<(), String> {
+    settings::require_settings_window(&window)?;
+    if profile.app.trim().is_empty() {
+        return Err("empty_app_name".to_string());
+    }
+    settings::update(&app, |settings| {
+        let profiles = &mut settings.app_profiles;
+        let (app_id, app_name) = (profile.app_id.as_deref(), Some(profile.app.as_str()));
+        match profiles.iter_mut().find(|p| p.matches(app_id, app_name)) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Remove the profile for an application.
+#[tauri::command]
+pub async fn delete_app_profile(
+    app: AppHandle,
+    window: WebviewWindow,
+    app_id: Option<String>,
+    app_name: String,
+) -> Result<(), String> {
+    settings::require_settings_window(&window)?;
+    settings::update(&app, |settings| {
+        settings
+            .app_profiles
+            .retain(|p| !p.matches(app_id.as_deref(), Some(&app_name)));
+        Ok(())
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(app: &str, app_id: Option<&str>) -> AppProfile {
+        AppProfile {
+            app: app.to_string(),
+            app_id: app_id.map(str::to_string),
+            mode: None,
+            provider_id: None,
+            instruction: None,
+            trigger_enabled: true,
+        }
+    }
+
+    #[test]
+    fn matches_by_bundle_id_then_name() {
+        let code = profile("Code", Some("com.microsoft.VSCode"));
+        assert!(code.matches(Some("com.microsoft.VSCode"), Some("Visual Studio Code")));
+        assert!(!code.matches(Some("com.example.Code"), Some("Code")));
+        assert!(code.matches(None, Some("Code")));
+
+        let legacy = profile("Notes", None);
+        assert!(legacy.matches(Some("com.apple.Notes"), Some("Notes")));
+        assert!(!legacy.matches(Some("com.apple.Notes"), Some("Mail")));
+        assert!(!legacy.matches(None, None));
+    }
+}
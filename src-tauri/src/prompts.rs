@@ -0,0 +1,189 @@
+//! Prompts and response parsing for every model request.
+//!
+//! The panel fetches its messages through `prompt_messages` and parses
+//! results with `parse_polish_response`, so the CLI, quick fix, background
+//! check and panel all share this one copy.
+
+use serde::Serialize;
+
+use crate::llm::ChatMessage;
+use crate::readability;
+use crate::settings::PolishMode;
+
+pub const IMPROVE_PROMPT: &str = r#"You are a professional editor and proofreader. Your task is to polish the given text IN THE SAME LANGUAGE as the input.
+
+Rules:
+1. Fix all grammar, spelling, and punctuation errors.
+2. Improve clarity, conciseness, and readability.
+3. Maintain the original meaning and tone.
+4. Preserve technical terms and proper nouns as-is.
+5. The output language MUST match the input language exactly.
+
+Output format:
+- First line: a SHORT explanation (under 8 words) of what you changed, e.g. "Refined phrasing for conciseness" or "Fixed subject-verb agreement"
+- Second line: empty
+- Third line onwards: the polished text only
+
+If the text is already perfect, use "Looks good!" as the explanation and return the original text.
+
+Example output:
+Improved clarity and fixed grammar
+
+The corrected sentence goes here."#;
+
+pub const REPHRASE_PROMPT: &str = r#"You are a professional writer. Your task is to rephrase the given text using different words and sentence structures while preserving the original meaning. Keep the SAME LANGUAGE as the input.
+
+Rules:
+1. Rewrite the text with alternative phrasing and vocabulary.
+2. Maintain the original meaning, tone, and intent.
+3. Make the rephrased version sound natural and fluent.
+4. Preserve technical terms and proper nouns as-is.
+5. The result should be noticeably different from the original, not just minor word swaps.
+6. The output language MUST match the input language exactly.
+
+Output format:
+- First line: a SHORT explanation (under 8 words) of how you rephrased it, e.g. "Restructured for variety" or "Used more concise phrasing"
+- Second line: empty
+- Third line onwards: the rephrased text only
+
+If the text cannot be meaningfully rephrased, use "Looks good as is!" as the explanation and return the original text.
+
+Example output:
+Restructured with alternative phrasing
+
+The rephrased sentence goes here."#;
+
+/// Translate prompt for `target`, a language name such as "English".
+pub fn translate_prompt(target: &str) -> String {
+    format!(
+        r#"You are a professional translator. Your task is to translate the given text into {target}.
+
+Rules:
+1. Translate into fluent, idiomatic {target}.
+2. Ensure the translation reads naturally to a native {target} speaker.
+3. Preserve the original meaning, tone, and intent.
+4. Keep technical terms, proper nouns, and brand names accurate.
+5. Do NOT provide a literal word-by-word translation; aim for natural expression.
+
+Output format:
+- First line: a SHORT explanation (under 8 words) of the translation approach, e.g. "Translated to natural {target}"
+- Second line: empty
+- Third line onwards: the translated text only
+
+Example output:
+Translated with natural phrasing
+
+The translated sentence goes here."#
+    )
+}
+
+/// Language the panel translates into from `lang` (`"zh"` or `"en"`):
+/// Chinese to English, anything else to Chinese.
+pub fn translate_target(lang: &str) -> &'static str {
+    if lang == "zh" {
+        "English"
+    } else {
+        "Chinese"
+    }
+}
+
+/// Language the panel translates `text` into.
+pub fn default_translate_target(text: &str) -> &'static str {
+    translate_target(readability::detect_language(text))
+}
+
+/// System prompt for `mode`, or `None` for modes that run locally.
+pub fn system_prompt(mode: PolishMode, target: &str) -> Option<String> {
+    match mode {
+        PolishMode::Improve => Some(IMPROVE_PROMPT.to_string()),
+        PolishMode::Rephrase => Some(REPHRASE_PROMPT.to_string()),
+        PolishMode::Translate => Some(translate_prompt(target)),
+        PolishMode::Typography | PolishMode::Convert => None,
+    }
+}
+
+/// Longer first paragraphs are treated as part of the text, not as an
+/// explanation.
+const MAX_EXPLANATION_CHARS: usize = 60;
+
+/// System and user messages for a request, with an optional extra
+/// instruction appended to the text as the panel does.
+pub fn messages(system: &str, text: &str, instruction: Option<&str>) -> Vec<ChatMessage> {
+    let content = match instruction.filter(|i| !i.trim().is_empty()) {
+        Some(instruction) => format!("{}\n\n[Additional instruction from user: {}]", text, instruction),
+        None => text.to_string(),
+    };
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system.to_string(),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content,
+        },
+    ]
+}
+
+/// Split a response into its explanation line and the polished text.
+pub fn parse_response(raw: &str) -> (String, String) {
+    match raw.split_once("\n\n") {
+        Some((explanation, text)) if explanation.trim().chars().count() <= MAX_EXPLANATION_CHARS => {
+            (explanation.trim().to_string(), text.trim().to_string())
+        }
+        _ => (String::new(), raw.trim().to_string()),
+    }
+}
+
+/// A response split into its explanation line and the polished text.
+#[derive(Debug, Serialize)]
+pub struct ParsedResponse {
+    pub explanation: String,
+    pub text: String,
+}
+
+/// Messages for a panel request. `lang` is the language detected for the
+/// whole selection, which decides the translation direction even when
+/// `text` is one block of it.
+#[tauri::command]
+pub async fn prompt_messages(
+    mode: PolishMode,
+    text: String,
+    lang: String,
+    instruction: Option<String>,
+) -> Result<Vec<ChatMessage>, String> {
+    let system = system_prompt(mode, translate_target(&lang)).ok_or_else(|| "local_mode".to_string())?;
+    Ok(messages(&system, &text, instruction.as_deref()))
+}
+
+#[tauri::command]
+pub async fn parse_polish_response(raw: String) -> ParsedResponse {
+    let (explanation, text) = parse_response(&raw);
+    ParsedResponse { explanation, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_explanation_from_the_text() {
+        let (explanation, text) = parse_response("Fixed grammar\n\nThe corrected text.\n\nSecond paragraph.");
+        assert_eq!(explanation, "Fixed grammar");
+        assert_eq!(text, "The corrected text.\n\nSecond paragraph.");
+
+        let (explanation, text) = parse_response("Just the text.");
+        assert_eq!((explanation.as_str(), text.as_str()), ("", "Just the text."));
+
+        let long = format!("{}\n\nrest", "a".repeat(MAX_EXPLANATION_CHARS + 1));
+        assert_eq!(parse_response(&long).1, long);
+    }
+
+    #[test]
+    fn translates_between_chinese_and_english() {
+        assert_eq!(default_translate_target("我们明天去北京。"), "English");
+        assert_eq!(default_translate_target("See you tomorrow."), "Chinese");
+        assert!(system_prompt(PolishMode::Translate, "English").unwrap().contains("into English"));
+        assert!(system_prompt(PolishMode::Typography, "English").is_none());
+    }
+}
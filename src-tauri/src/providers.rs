@@ -0,0 +1,264 @@
+//! LLM provider settings and their API keys.
+//!
+//! Providers are part of [`crate::settings`], but their API keys are kept in
+//! the OS keyring and settings only hold a reference to the keyring entry.
+//! The webview never sees a raw key: requests are sent by [`crate::llm`],
+//! which resolves the key here.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime, WebviewWindow};
+
+use crate::config;
+use crate::secrets;
+use crate::settings;
+
+const KEYRING_REF_PREFIX: &str = "keyring:";
+
+/// A configured OpenAI-compatible provider, as stored in settings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Provider {
+    pub id: String,
+    pub name: String,
+    pub endpoint: String,
+    pub model: String,
+    pub temperature: f64,
+    /// Keyring entry holding the API key, e.g. `keyring:provider:openai`.
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
+    /// Plaintext key from older versions, kept only until it has been moved
+    /// to the keyring. Never sent to the webview.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) api_key: Option<String>,
+}
+
+/// Everything needed to send a request to a provider.
+pub struct ResolvedProvider {
+    pub endpoint: String,
+    pub model: String,
+    pub temperature: f64,
+    pub api_key: String,
+}
+
+fn entry_name(provider_id: &str) -> String {
+    format!("provider:{}", provider_id)
+}
+
+fn key_ref(provider_id: &str) -> String {
+    format!("{}{}", KEYRING_REF_PREFIX, entry_name(provider_id))
+}
+
+fn same_endpoint(a: &str, b: &str) -> bool {
+    a.trim().trim_end_matches('/') == b.trim().trim_end_matches('/')
+}
+
+/// Carry the stored key over from `existing` only while the provider keeps
+/// its endpoint, so a changed endpoint never receives the old key. Returns
+/// `true` when a stored key was dropped.
+pub(crate) fn carry_key(existing: Option<&Provider>, provider: &mut Provider) -> bool {
+    match existing {
+        Some(existing) if same_endpoint(&existing.endpoint, &provider.endpoint) => {
+            provider.api_key_ref = existing.api_key_ref.clone();
+            provider.api_key = existing.api_key.clone();
+            false
+        }
+        _ => {
+            provider.api_key_ref = None;
+            provider.api_key = None;
+            existing.is_some_and(|p| p.api_key_ref.is_some() || p.api_key.is_some())
+        }
+    }
+}
+
+/// Remove a provider's key from the keyring after settings stopped
+/// referring to it.
+pub(crate) fn forget_key(provider_id: &str) {
+    if let Err(err) = secrets::delete_secret(&entry_name(provider_id)) {
+        println!("[Polishr] Failed to remove {} API key: {}", provider_id, err);
+    }
+}
+
+/// Put back the keyring entry a rejected save changed.
+fn restore_key(entry: &str, key: Option<&str>) {
+    let restored = match key {
+        Some(key) => secrets::set_secret(entry, key),
+        None => secrets::delete_secret(entry),
+    };
+    if let Err(err) = restored {
+        println!("[Polishr] Failed to restore {}: {}", entry, err);
+    }
+}
+
+/// Move a plaintext key into the keyring. On failure the key is kept where
+/// it was so it is not lost, and migration is retried on the next launch.
+fn move_key_to_keyring(provider: &mut Provider) -> bool {
+    let Some(key) = provider.api_key.take().filter(|k| !k.is_empty()) else {
+        return false;
+    };
+    match secrets::set_secret(&entry_name(&provider.id), &key) {
+        Ok(()) => {
+            provider.api_key_ref = Some(key_ref(&provider.id));
+            true
+        }
+        Err(err) => {
+            println!("[Polishr] Failed to move {} API key to keyring: {}", provider.id, err);
+            provider.api_key = Some(key);
+            false
+        }
+    }
+}
+
+/// Move plaintext API keys left by older versions into the keyring. Runs
+/// after [`settings::init`], which turns the legacy flat keys into a provider.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let pending = settings::with(|s| s.providers.iter().any(|p| p.api_key.is_some()));
+    if !pending {
+        return;
+    }
+    let result = settings::update(app, |settings| {
+        let migrated = settings
+            .providers
+            .iter_mut()
+            .map(move_key_to_keyring)
+            .filter(|moved| *moved)
+            .count();
+        println!("[Polishr] Moved {} API keys to the keyring", migrated);
+        Ok(())
+    });
+    if let Err(err) = result {
+        println!("[Polishr] Failed to save migrated providers: {}", err);
+    }
+}
+
+/// Look up a provider and its API key. `None` picks the active provider.
+pub fn resolve(provider_id: Option<&str>) -> Result<ResolvedProvider, String> {
+    let provider = settings::with(|s| {
+        let id = provider_id.unwrap_or(&s.active_provider_id);
+        s.providers.iter().find(|p| p.id == id).cloned()
+    })
+    .ok_or("no_provider")?;
+
+    let api_key = match provider.api_key_ref.as_deref() {
+        Some(reference) => {
+            let entry = reference.strip_prefix(KEYRING_REF_PREFIX).ok_or("missing_api_key")?;
+            secrets::get_secret(entry)?
+        }
+        // Not yet migrated because the keyring was unavailable.
+        None => provider.api_key.clone(),
+    }
+    .filter(|k| !k.is_empty())
+    .ok_or("missing_api_key")?;
+
+    Ok(ResolvedProvider {
+        endpoint: provider.endpoint,
+        model: provider.model,
+        temperature: provider.temperature,
+        api_key,
+    })
+}
+
+// --- Commands ---
+
+/// Create or update a provider. `api_key` replaces the stored key when
+/// given; an empty string removes it. Without one the stored key is kept
+/// only if the endpoint is unchanged. The keyring is only changed once the
+/// provider is known to be valid. Settings window only. Fails with
+/// `provider_not_allowed` or `endpoint_not_allowed` when the organization
+/// policy forbids it. Returns the updated provider list.
+#[tauri::command]
+pub async fn save_provider(
+    app: AppHandle,
+    window: WebviewWindow,
+    mut provider: Provider,
+    api_key: Option<String>,
+) -> Result<Vec<Provider>, String> {
+    settings::require_settings_window(&window)?;
+    if provider.id.trim().is_empty() {
+        return Err("empty_provider_id".to_string());
+    }
+    config::check_provider(&provider)?;
+    let existing = settings::with(|s| s.providers.iter().find(|p| p.id == provider.id).cloned());
+
+    let replace = |settings: &mut settings::Settings, provider: Provider| {
+        match settings.providers.iter_mut().find(|p| p.id == provider.id) {
+            Some(existing) => *existing = provider,
+            None => settings.providers.push(provider),
+        }
+        Ok(())
+    };
+    // Check the provider before the keyring changes.
+    settings::check(|settings| replace(settings, provider.clone()))?;
+
+    let entry = entry_name(&provider.id);
+    // The key the keyring held before, to put back if the update still
+    // fails, e.g. when saving the store does; `None` when the keyring is
+    // left alone.
+    let mut previous_key = None;
+    let mut dropped = false;
+    match api_key.as_deref() {
+        Some("") => {
+            previous_key = Some(secrets::get_secret(&entry)?);
+            secrets::delete_secret(&entry)?;
+            (provider.api_key_ref, provider.api_key) = (None, None);
+        }
+        Some(key) => {
+            previous_key = Some(secrets::get_secret(&entry)?);
+            secrets::set_secret(&entry, key)?;
+            (provider.api_key_ref, provider.api_key) = (Some(key_ref(&provider.id)), None);
+        }
+        None => dropped = carry_key(existing.as_ref(), &mut provider),
+    }
+    let provider_id = provider.id.clone();
+
+    let updated = settings::update(&app, |settings| replace(settings, provider));
+    let updated = match updated {
+        Ok(updated) => updated,
+        Err(err) => {
+            if let Some(previous) = previous_key {
+                restore_key(&entry, previous.as_deref());
+            }
+            return Err(err);
+        }
+    };
+    if dropped {
+        forget_key(&provider_id);
+    }
+    Ok(updated.redacted().providers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(endpoint: &str) -> Provider {
+        Provider {
+            id: "openai".to_string(),
+            name: "OpenAI".to_string(),
+            endpoint: endpoint.to_string(),
+            model: "gpt-4o".to_string(),
+            temperature: 0.3,
+            api_key_ref: None,
+            api_key: None,
+        }
+    }
+
+    #[test]
+    fn stored_key_follows_only_an_unchanged_endpoint() {
+        let mut existing = provider("https://api.openai.com/v1");
+        existing.api_key_ref = Some(key_ref("openai"));
+
+        let mut same = provider("https://api.openai.com/v1/");
+        assert!(!carry_key(Some(&existing), &mut same));
+        assert_eq!(same.api_key_ref, existing.api_key_ref);
+
+        let mut moved = provider("https://attacker.example/v1");
+        moved.api_key_ref = existing.api_key_ref.clone();
+        assert!(carry_key(Some(&existing), &mut moved));
+        assert_eq!(moved.api_key_ref, None);
+
+        let mut new = provider("https://api.openai.com/v1");
+        new.api_key_ref = Some(key_ref("openai"));
+        assert!(!carry_key(None, &mut new));
+        assert_eq!(new.api_key_ref, None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Headless "polish and replace": capture the selection, run Improve in the
+//! backend and paste the result straight back without opening the panel.
+//!
+//! Progress and errors are shown on the tray icon. Nothing is pasted when
+//! the selection is longer than the configured limit, or when focus or the
+//! selection changed while the request was running.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::{self, CaptureResult};
+use crate::history::{self, HistoryRecord};
+use crate::llm;
+use crate::profiles;
+use crate::prompts::{self, IMPROVE_PROMPT};
+use crate::providers;
+use crate::readability;
+use crate::settings;
+use crate::tray;
+
+const DEFAULT_MAX_CHARS: u32 = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct QuickReplaceSettings {
+    /// Longest selection, in characters, replaced without review.
+    pub max_chars: u32,
+}
+
+impl Default for QuickReplaceSettings {
+    fn default() -> Self {
+        Self {
+            max_chars: DEFAULT_MAX_CHARS,
+        }
+    }
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Text the running quick fix is polishing.
+static POLISHING: Mutex<Option<String>> = Mutex::new(None);
+
+/// Whether a quick fix is running for `text`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn is_polishing(text: &str) -> bool {
+    POLISHING.lock().unwrap().as_deref() == Some(text)
+}
+
+/// Short tray message for an error code.
+fn describe(err: &str) -> String {
+    let (code, detail) = err.split_once(':').unwrap_or((err, ""));
+    match code {
+        "no_selection" => "Nothing selected".to_string(),
+        "secure_field" => "Password fields are never read".to_string(),
+        "selection_too_long" => format!("Selection over {} characters; use the panel", detail),
+        "focus_changed" | "selection_changed" => "Selection changed; nothing replaced".to_string(),
+        "missing_api_key" | "no_provider" => "No API key configured".to_string(),
+        "invalid_api_key" => "Invalid API key".to_string(),
+        "rate_limited" => "Rate limited; try again".to_string(),
+        _ => "Quick fix failed".to_string(),
+    }
+}
+
+/// Clears the running state however the quick fix ends, panics included.
+struct RunGuard;
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if let Ok(mut polishing) = POLISHING.lock() {
+            *polishing = None;
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Capture the selection off the async runtime; AX calls block.
+async fn capture_selection() -> Result<CaptureResult, String> {
+    tauri::async_runtime::spawn_blocking(commands::capture_and_locate_sync)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+async fn polish_and_replace(app: &AppHandle) -> Result<(), String> {
+    let capture = capture_selection().await?;
+    if capture.text.trim().is_empty() {
+        return Err("no_selection".to_string());
+    }
+    let max_chars = settings::with(|s| s.quick_replace.max_chars);
+    if capture.text.chars().count() > max_chars as usize {
+        return Err(format!("selection_too_long:{}", max_chars));
+    }
+    *POLISHING.lock().unwrap() = Some(capture.text.clone());
+
+    let profile = profiles::profile_for(capture.app_id.as_deref(), capture.app.as_deref());
+    let provider_id = profile.as_ref().and_then(|p| p.provider_id.clone());
+    let provider = providers::resolve(provider_id.as_deref())?;
+    let instruction = profile.as_ref().and_then(|p| p.instruction.as_deref());
+    let mut messages = prompts::messages(IMPROVE_PROMPT, &capture.text, instruction);
+    llm::apply_glossary(&mut messages);
+    let raw = llm::chat_stream(&provider, &messages, |_| Ok(())).await?;
+    let (_, polished) = prompts::parse_response(&raw);
+    if polished.is_empty() {
+        return Err("empty_result".to_string());
+    }
+
+    // Only paste over the exact text that was polished, in the same field.
+    let current = capture_selection().await.map_err(|_| "selection_changed".to_string())?;
+    if current.app_id != capture.app_id || current.target != capture.target {
+        return Err("focus_changed".to_string());
+    }
+    if current.text != capture.text {
+        return Err("selection_changed".to_string());
+    }
+    if polished != capture.text {
+        // Paste into this capture's app, not whatever the trigger stored since.
+        let target = capture.target.clone().ok_or_else(|| "focus_changed".to_string())?;
+        let text = polished.clone();
+        tauri::async_runtime::spawn_blocking(move || commands::replace_text_in(&target, &text))
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+
+    let timestamp = now_ms();
+    let language = readability::detect_language(&capture.text).to_string();
+    let record = HistoryRecord {
+        id: format!("quick-{}", timestamp),
+        timestamp,
+        mode: "improve".to_string(),
+        input_text: capture.text,
+        result_text: polished,
+        provider: provider_id.unwrap_or_else(|| settings::with(|s| s.active_provider_id.clone())),
+        app: capture.app,
+        app_id: capture.app_id,
+        language: Some(language),
+        hunks: None,
+    };
+    if let Err(err) = history::history_add(app.clone(), record).await {
+        println!("[Polishr] Failed to record quick fix: {}", err);
+    }
+    Ok(())
+}
+
+/// Polish the selection and replace it in place. Ignored while a previous
+/// run is still in flight.
+pub fn run(app: &AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        println!("[Polishr] Quick fix already running");
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let guard = RunGuard;
+        tray::set_status(&app, Some("Polishing…"));
+        let result = polish_and_replace(&app).await;
+        drop(guard);
+        match result {
+            Ok(()) => {
+                println!("[Polishr] Quick fix replaced the selection");
+                tray::set_status(&app, None);
+            }
+            Err(err) => {
+                println!("[Polishr] Quick fix aborted: {}", err);
+                tray::flash_status(&app, &describe(&err));
+            }
+        }
+    });
+}
@@ -0,0 +1,321 @@
+//! Length and readability statistics for the original and polished text.
+//!
+//! Counting is CJK-aware: every Han or kana character counts as a word, and
+//! other words (Latin, Hangul, Cyrillic, ...) are runs of letters and
+//! digits. Flesch reading ease and the Flesch-Kincaid grade level only mean
+//! something for English, so they are left out unless most words are
+//! Latin-script.
+
+use serde::Serialize;
+
+/// Average silent reading speeds for English words and Chinese characters.
+/// Other space-separated words are read at the English rate.
+const LATIN_WORDS_PER_MINUTE: f64 = 238.0;
+const CJK_CHARS_PER_MINUTE: f64 = 260.0;
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct TextMetrics {
+    /// Space-separated words plus Han and kana characters.
+    pub words: usize,
+    /// Characters other than whitespace.
+    pub characters: usize,
+    pub sentences: usize,
+    /// Words per sentence.
+    pub avg_sentence_length: f64,
+    /// Flesch reading ease, higher is easier; `None` for non-Latin text.
+    pub reading_ease: Option<f64>,
+    /// Flesch-Kincaid grade level; `None` for non-Latin text.
+    pub grade_level: Option<f64>,
+    pub reading_time_secs: u32,
+}
+
+/// Metrics of the text before and after a change.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Readability {
+    pub before: TextMetrics,
+    pub after: TextMetrics,
+}
+
+// Script tests shared by every module that treats CJK text specially.
+
+pub fn is_han(c: char) -> bool {
+    matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F)
+}
+
+pub fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x31F0..=0x31FF)
+}
+
+pub fn is_hangul(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF)
+}
+
+/// Han, kana and Hangul.
+pub fn is_cjk(c: char) -> bool {
+    is_unspaced(c) || is_hangul(c)
+}
+
+/// Han and kana, written without spaces between words. Hangul is not:
+/// Korean separates words with spaces.
+pub fn is_unspaced(c: char) -> bool {
+    is_han(c) || is_kana(c)
+}
+
+/// `"zh"` when more than 30% of the characters are CJK ideographs, else
+/// `"en"`. The same rule as `detectLanguage` in `src/core/lang/detect.ts`,
+/// so records saved from Rust are tagged like those saved by the panel.
+pub fn detect_language(text: &str) -> &'static str {
+    let total = text.encode_utf16().count();
+    let cjk = text.chars().filter(|&c| matches!(c as u32, 0x4E00..=0x9FFF)).count();
+    if text.trim().is_empty() || cjk as f64 / total as f64 <= 0.3 {
+        "en"
+    } else {
+        "zh"
+    }
+}
+
+/// Letters of the Latin script (Basic Latin through Latin Extended-B).
+fn is_latin_letter(c: char) -> bool {
+    c.is_alphabetic() && (c as u32) < 0x0250
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_unspaced(c)
+}
+
+/// Closes a sentence wherever it appears.
+fn is_cjk_terminator(c: char) -> bool {
+    matches!(c, '。' | '！' | '？' | '…')
+}
+
+/// Closing quotes and brackets that may follow a terminator.
+fn is_closer(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '”' | '’' | '」' | '』' | '）')
+}
+
+/// English syllables by vowel groups, dropping a silent final `e` and
+/// the `-ed` of `jumped`. Good enough for Flesch, which was fitted the
+/// same way.
+fn syllables(word: &str) -> usize {
+    let word: Vec<char> = word.to_lowercase().chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    if word.len() <= 3 {
+        return 1;
+    }
+    let is_vowel = |i: usize| matches!(word[i], 'a' | 'e' | 'i' | 'o' | 'u') || (word[i] == 'y' && i > 0);
+    let mut count = (0..word.len()).filter(|&i| is_vowel(i) && (i == 0 || !is_vowel(i - 1))).count();
+    let n = word.len();
+    // "make" but not "table".
+    let silent_e = word[n - 1] == 'e' && (word[n - 2] != 'l' || is_vowel(n - 3));
+    let silent_ed = word[n - 2..] == ['e', 'd'] && !matches!(word[n - 3], 't' | 'd');
+    if silent_e || silent_ed {
+        count = count.saturating_sub(1);
+    }
+    count.max(1)
+}
+
+/// Statistics of one text.
+pub fn measure(text: &str) -> TextMetrics {
+    let chars: Vec<char> = text.chars().collect();
+    let mut latin_words = 0;
+    // Space-separated words in other scripts, e.g. Hangul or Cyrillic.
+    let mut other_words = 0;
+    let mut cjk_chars = 0;
+    let mut syllable_count = 0;
+    let mut sentences = 0;
+    // Words since the last sentence end.
+    let mut pending = false;
+    let mut newlines = 0;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            newlines += 1;
+        } else if !c.is_whitespace() {
+            newlines = 0;
+        }
+        // A blank line ends a heading or list item without punctuation.
+        if newlines >= 2 && pending {
+            sentences += 1;
+            pending = false;
+        }
+
+        if is_unspaced(c) {
+            cjk_chars += 1;
+            pending = true;
+        } else if is_word_char(c) {
+            // Apostrophes and hyphens inside a word, separators inside a number.
+            let joins = |i: usize| {
+                let (prev, next) = (chars[i - 1], chars.get(i + 1).copied());
+                match chars[i] {
+                    '\'' | '’' | '-' => next.is_some_and(is_word_char),
+                    '.' | ',' => prev.is_ascii_digit() && next.is_some_and(|n| n.is_ascii_digit()),
+                    c => is_word_char(c),
+                }
+            };
+            let start = i;
+            while i + 1 < chars.len() && joins(i + 1) {
+                i += 1;
+            }
+            let word = &chars[start..=i];
+            if word.iter().any(|&c| c.is_alphabetic() && !is_latin_letter(c)) {
+                other_words += 1;
+            } else {
+                latin_words += 1;
+                syllable_count += syllables(&word.iter().collect::<String>());
+            }
+            pending = true;
+        } else if is_cjk_terminator(c) && pending {
+            sentences += 1;
+            pending = false;
+        } else if matches!(c, '.' | '!' | '?') && pending {
+            let mut next = i + 1;
+            while next < chars.len() && (is_closer(chars[next]) || matches!(chars[next], '.' | '!' | '?')) {
+                next += 1;
+            }
+            let at_break = next == chars.len() || chars[next].is_whitespace();
+            // "e.g. this" and "J. Smith" don't end a sentence.
+            let continues = c == '.'
+                && (chars[next..].iter().find(|c| !c.is_whitespace()).is_some_and(|c| c.is_lowercase())
+                    || (i >= 1 && chars[i - 1].is_uppercase() && (i < 2 || !is_word_char(chars[i - 2]))));
+            if at_break && !continues {
+                sentences += 1;
+                pending = false;
+                i = next - 1;
+            }
+        }
+        i += 1;
+    }
+    if pending {
+        sentences += 1;
+    }
+
+    let words = latin_words + other_words + cjk_chars;
+    let per_sentence = |n: usize| if sentences == 0 { 0.0 } else { n as f64 / sentences as f64 };
+    let avg_sentence_length = per_sentence(words);
+    let (reading_ease, grade_level) = if latin_words > 0 && latin_words >= other_words + cjk_chars {
+        let words_per_sentence = per_sentence(latin_words);
+        let syllables_per_word = syllable_count as f64 / latin_words as f64;
+        (
+            Some(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word),
+            Some((0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59).max(0.0)),
+        )
+    } else {
+        (None, None)
+    };
+    let minutes =
+        (latin_words + other_words) as f64 / LATIN_WORDS_PER_MINUTE + cjk_chars as f64 / CJK_CHARS_PER_MINUTE;
+
+    TextMetrics {
+        words,
+        characters: chars.iter().filter(|c| !c.is_whitespace()).count(),
+        sentences,
+        avg_sentence_length,
+        reading_ease,
+        grade_level,
+        reading_time_secs: (minutes * 60.0).ceil() as u32,
+    }
+}
+
+/// Words as reported everywhere: space-separated words plus Han and kana
+/// characters.
+pub fn count_words(text: &str) -> usize {
+    measure(text).words
+}
+
+pub fn compare(before: &str, after: &str) -> Readability {
+    Readability {
+        before: measure(before),
+        after: measure(after),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_cjk_characters_as_words() {
+        let metrics = measure("我们明天去北京。你呢？");
+        assert_eq!(metrics.words, 9);
+        assert_eq!(metrics.characters, 11);
+        assert_eq!(metrics.sentences, 2);
+        assert_eq!(metrics.reading_ease, None);
+
+        let mixed = measure("用 Rust 写 CLI 工具");
+        assert_eq!(mixed.words, 6);
+        assert_eq!(mixed.sentences, 1);
+    }
+
+    #[test]
+    fn scores_only_latin_script_text() {
+        let korean = measure("안녕하세요 여러분. 오늘은 날씨가 좋습니다.");
+        assert_eq!(korean.words, 5);
+        assert_eq!(korean.sentences, 2);
+        assert_eq!(korean.reading_ease, None);
+        assert_eq!(korean.grade_level, None);
+
+        for text in ["Привет всем. Сегодня хорошая погода.", "Γεια σας. Καλή μέρα.", "مرحبا بكم. يوم جميل."] {
+            let metrics = measure(text);
+            assert_eq!(metrics.sentences, 2, "{}", text);
+            assert_eq!(metrics.reading_ease, None, "{}", text);
+        }
+        assert!(measure("Café owners naïvely agreed.").reading_ease.is_some());
+        assert_eq!(measure("iPhone을 샀어요").words, 2);
+    }
+
+    #[test]
+    fn detects_language_like_the_panel() {
+        assert_eq!(detect_language("我们明天去北京。"), "zh");
+        assert_eq!(detect_language("用 Rust 写一个命令行工具"), "zh");
+        assert_eq!(detect_language("Deploy the 服务 today"), "en");
+        assert_eq!(detect_language("안녕하세요"), "en");
+        assert_eq!(detect_language("  "), "en");
+    }
+
+    #[test]
+    fn splits_english_sentences() {
+        assert_eq!(measure("It works. Does it? Yes!").sentences, 3);
+        assert_eq!(measure("He said \"stop.\" Then he left").sentences, 2);
+        assert_eq!(measure("Use tools, e.g. a linter. Ask J. Smith.").sentences, 2);
+        assert_eq!(measure("Version 1.2 is out").sentences, 1);
+        assert_eq!(measure("Version 1.2 is out").words, 4);
+        assert_eq!(measure("Heading\n\nBody text here.").sentences, 2);
+        assert_eq!(measure("").sentences, 0);
+    }
+
+    #[test]
+    fn counts_syllables() {
+        for (word, count) in [
+            ("the", 1),
+            ("make", 1),
+            ("jumped", 1),
+            ("table", 2),
+            ("wanted", 2),
+            ("beautiful", 3),
+            ("readability", 5),
+        ] {
+            assert_eq!(syllables(word), count, "{}", word);
+        }
+    }
+
+    #[test]
+    fn simpler_text_scores_easier() {
+        let hard = measure(
+            "Notwithstanding considerable organizational complexity, the implementation \
+             demonstrated remarkable operational effectiveness throughout the evaluation.",
+        );
+        let easy = measure("The plan was hard. It still worked well. We are glad.");
+        assert!(hard.grade_level.unwrap() > 12.0);
+        assert!(easy.grade_level.unwrap() < 5.0);
+        assert!(easy.reading_ease.unwrap() > hard.reading_ease.unwrap());
+        assert_eq!(easy.avg_sentence_length, 11.0 / 3.0);
+    }
+
+    #[test]
+    fn estimates_reading_time() {
+        let words = "word ".repeat(238);
+        assert_eq!(measure(&words).reading_time_secs, 60);
+        assert_eq!(measure("").reading_time_secs, 0);
+    }
+}
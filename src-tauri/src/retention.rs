@@ -0,0 +1,448 @@
+//! History retention, per-app exclusions and PII scrubbing.
+//!
+//! The policy is part of [`crate::settings`]. It is applied to every record before it is written, and a
+//! background thread prunes records that fall outside the retention window.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime, WebviewWindow};
+
+use crate::history::{self, HistoryHunk, HistoryRecord};
+use crate::settings;
+
+const ENFORCE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// Hex digests top out at 4 bits per character; random keys mix more symbols.
+const TOKEN_MIN_ENTROPY: f64 = 4.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionMode {
+    #[default]
+    KeepAll,
+    /// Keep records newer than `retention_days`.
+    Days,
+    /// Keep the newest `retention_records` records.
+    Records,
+    /// Never save new history. Existing records are kept until deleted.
+    Never,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistoryPolicy {
+    pub retention: RetentionMode,
+    pub retention_days: u32,
+    pub retention_records: u32,
+    /// Apps whose text is never recorded, e.g. password managers.
+    pub excluded_apps: Vec<ExcludedApp>,
+    /// Replace emails, phone numbers, card numbers and secrets before saving.
+    pub scrub_pii: bool,
+}
+
+impl Default for HistoryPolicy {
+    fn default() -> Self {
+        Self {
+            retention: RetentionMode::KeepAll,
+            retention_days: 30,
+            retention_records: 500,
+            excluded_apps: Vec::new(),
+            scrub_pii: false,
+        }
+    }
+}
+
+/// An app history is never recorded from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "ExcludedAppEntry", rename_all = "camelCase")]
+pub struct ExcludedApp {
+    /// Application name, or a bundle id from the config file.
+    pub app: String,
+    pub app_id: Option<String>,
+}
+
+/// Older settings and the config file list exclusions as plain strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExcludedAppEntry {
+    Name(String),
+    #[serde(rename_all = "camelCase")]
+    App {
+        app: String,
+        #[serde(default)]
+        app_id: Option<String>,
+    },
+}
+
+impl From<ExcludedAppEntry> for ExcludedApp {
+    fn from(entry: ExcludedAppEntry) -> Self {
+        match entry {
+            ExcludedAppEntry::Name(app) => app.into(),
+            ExcludedAppEntry::App { app, app_id } => Self { app, app_id },
+        }
+    }
+}
+
+impl From<String> for ExcludedApp {
+    fn from(app: String) -> Self {
+        Self { app, app_id: None }
+    }
+}
+
+impl ExcludedApp {
+    /// Matches by bundle id when both sides know it, else by name. A bare
+    /// entry also matches a bundle id, as in the trigger's app list.
+    fn matches(&self, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+        match (self.app_id.as_deref(), app_id) {
+            (Some(id), Some(other)) => id == other,
+            (Some(_), None) => app_name.is_some_and(|name| self.app.eq_ignore_ascii_case(name)),
+            (None, _) => {
+                Some(self.app.as_str()) == app_id || app_name.is_some_and(|name| self.app.eq_ignore_ascii_case(name))
+            }
+        }
+    }
+}
+
+fn policy() -> HistoryPolicy {
+    settings::with(|s| s.history_policy.clone())
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// --- PII scrubbing ---
+
+struct Scrubber {
+    email: Regex,
+    secret: Regex,
+    card: Regex,
+    phone: Regex,
+    token: Regex,
+}
+
+fn scrubber() -> &'static Scrubber {
+    static SCRUBBER: OnceLock<Scrubber> = OnceLock::new();
+    SCRUBBER.get_or_init(|| Scrubber {
+        email: Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap(),
+        // Well-known key formats: OpenAI/Anthropic-style, GitHub, AWS, Slack, Google.
+        secret: Regex::new(
+            r"\b(?:sk-[A-Za-z0-9_-]{16,}|gh[pousr]_[A-Za-z0-9]{20,}|AKIA[0-9A-Z]{16}|xox[abpr]-[A-Za-z0-9-]{10,}|AIza[0-9A-Za-z_-]{35})",
+        )
+        .unwrap(),
+        card: Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap(),
+        // International numbers, a parenthesised area code, or groups split
+        // like `555-123-4567`. Bare digit runs are order numbers and the like.
+        phone: Regex::new(concat!(
+            r"\+\d{1,3}[ .-]?(?:\(\d{1,4}\)[ .-]?)?\d{1,4}(?:[ .-]?\d{2,4}){1,4}\b",
+            r"|\(\d{2,4}\)[ .-]?\d{3,4}[ .-]?\d{3,4}\b",
+            r"|\b\d{2,4}[ .-]\d{3,4}[ .-]\d{4}\b",
+        ))
+        .unwrap(),
+        // Long mixed-character tokens that look like generated credentials.
+        token: Regex::new(r"\b[A-Za-z0-9_-]{32,}\b").unwrap(),
+    })
+}
+
+/// Luhn checksum, to tell card numbers from other long digit runs.
+fn luhn_valid(digits: &str) -> bool {
+    let digits: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Whether a phone pattern match at `start..end` of `text` is a phone
+/// number rather than part of an address, version or list of years.
+fn looks_like_phone(text: &str, start: usize, end: usize) -> bool {
+    let matched = &text[start..end];
+    let digits = matched.chars().filter(|c| c.is_ascii_digit()).count();
+    if !(9..=15).contains(&digits) {
+        return false;
+    }
+    // `192.168.100.200` or `1.2.3.4444`: a dotted number continues past the match.
+    let before = text[..start].chars().rev().take(2).collect::<Vec<_>>();
+    let after = text[end..].chars().take(2).collect::<Vec<_>>();
+    let dotted = |pair: &[char]| pair.len() == 2 && matches!(pair[0], '.' | '-') && pair[1].is_ascii_digit();
+    if dotted(&before) || dotted(&after) {
+        return false;
+    }
+    if matched.starts_with(['+', '(']) {
+        return true;
+    }
+    // `2023 2024 2025`.
+    let years = matched
+        .split([' ', '.', '-'])
+        .all(|group| group.len() == 4 && (group.starts_with("19") || group.starts_with("20")));
+    !years
+}
+
+/// Whether a long word is a generated credential: one long random run,
+/// not `kebab-case-ids`, UUIDs or hex digests.
+fn looks_like_token(candidate: &str) -> bool {
+    let longest_run = candidate.split(['-', '_']).map(str::len).max().unwrap_or(0);
+    longest_run >= 24
+        && candidate.chars().any(|c| c.is_ascii_digit())
+        && candidate.chars().any(|c| c.is_ascii_alphabetic())
+        && entropy(candidate) > TOKEN_MIN_ENTROPY
+}
+
+/// Shannon entropy in bits per character, ignoring separators.
+fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars().filter(|c| !matches!(c, '-' | '_')) {
+        *counts.entry(c).or_default() += 1;
+    }
+    let total = counts.values().sum::<usize>() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Replace PII and secrets in `text` with placeholders like `[email]`.
+pub fn scrub(text: &str) -> String {
+    let s = scrubber();
+    let text = s.secret.replace_all(text, "[secret]");
+    let text = s.email.replace_all(&text, "[email]");
+    let text = s.card.replace_all(&text, |caps: &regex::Captures| {
+        let matched = caps.get(0).unwrap();
+        // `+86 138 ...` is an international phone number.
+        let international = text[..matched.start()].ends_with('+');
+        if luhn_valid(matched.as_str()) && !international {
+            "[card]".to_string()
+        } else {
+            matched.as_str().to_string()
+        }
+    });
+    let text = s.phone.replace_all(&text, |caps: &regex::Captures| {
+        let matched = caps.get(0).unwrap();
+        if looks_like_phone(&text, matched.start(), matched.end()) {
+            "[phone]".to_string()
+        } else {
+            matched.as_str().to_string()
+        }
+    });
+    let text = s.token.replace_all(&text, |caps: &regex::Captures| {
+        let matched = &caps[0];
+        if looks_like_token(matched) { "[secret]".to_string() } else { matched.to_string() }
+    });
+    text.into_owned()
+}
+
+fn scrub_hunks(hunks: &mut [HistoryHunk]) {
+    for hunk in hunks {
+        hunk.deleted = scrub(&hunk.deleted);
+        hunk.inserted = scrub(&hunk.inserted);
+    }
+}
+
+// --- Policy application ---
+
+fn is_excluded(policy: &HistoryPolicy, app_id: Option<&str>, app_name: Option<&str>) -> bool {
+    policy.excluded_apps.iter().any(|excluded| excluded.matches(app_id, app_name))
+}
+
+/// Apply the policy to a record about to be saved. Returns `None` when it
+/// must not be saved at all.
+pub fn prepare_record(mut record: HistoryRecord) -> Option<HistoryRecord> {
+    let policy = policy();
+    if policy.retention == RetentionMode::Never || is_excluded(&policy, record.app_id.as_deref(), record.app.as_deref()) {
+        return None;
+    }
+    if policy.scrub_pii {
+        record.input_text = scrub(&record.input_text);
+        record.result_text = scrub(&record.result_text);
+        if let Some(hunks) = record.hunks.as_mut() {
+            scrub_hunks(hunks);
+        }
+    }
+    Some(record)
+}
+
+/// Apply the policy's scrubbing to hunk decisions about to be saved.
+pub fn prepare_hunks(mut hunks: Vec<HistoryHunk>) -> Vec<HistoryHunk> {
+    if policy().scrub_pii {
+        scrub_hunks(&mut hunks);
+    }
+    hunks
+}
+
+/// Delete records outside the retention window. Returns the number removed.
+pub fn enforce() -> Result<usize, String> {
+    let policy = policy();
+    history::with_db(|conn| match policy.retention {
+        RetentionMode::KeepAll | RetentionMode::Never => Ok(0),
+        RetentionMode::Days => {
+            history::delete_older_than(conn, now_ms() - policy.retention_days as i64 * DAY_MS)
+        }
+        RetentionMode::Records => history::keep_newest(conn, policy.retention_records),
+    })
+}
+
+fn enforce_and_notify<R: Runtime>(app: &AppHandle<R>) {
+    match enforce() {
+        Ok(0) => {}
+        Ok(removed) => {
+            println!("[Polishr] Retention removed {} history records", removed);
+            history::notify_changed(app);
+        }
+        Err(err) => println!("[Polishr] Retention failed: {}", err),
+    }
+}
+
+/// Start the background retention task.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        enforce_and_notify(&app);
+        std::thread::sleep(ENFORCE_INTERVAL);
+    });
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn get_history_policy() -> HistoryPolicy {
+    policy()
+}
+
+/// Save the policy and apply it to existing history right away.
+#[tauri::command]
+pub async fn save_history_policy(
+    app: AppHandle,
+    window: WebviewWindow,
+    policy: HistoryPolicy,
+) -> Result<(), String> {
+    settings::require_settings_window(&window)?;
+    settings::update(&app, |settings| {
+        settings.history_policy = policy;
+        Ok(())
+    })?;
+    enforce_and_notify(&app);
+    Ok(())
+}
+
+/// Delete every history record captured from an application, by bundle id
+/// and name. Settings window only.
+#[tauri::command]
+pub async fn history_purge_app(
+    app: AppHandle,
+    window: WebviewWindow,
+    app_id: Option<String>,
+    app_name: String,
+) -> Result<usize, String> {
+    settings::require_settings_window(&window)?;
+    let removed = history::with_db(|conn| history::delete_app(conn, app_id.as_deref(), &app_name))?;
+    println!("[Polishr] Purged {} history records from {}", removed, app_name);
+    history::notify_changed(&app);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_apps_by_bundle_id_then_name() {
+        let policy: HistoryPolicy = serde_json::from_value(serde_json::json!({
+            "excludedApps": [
+                "Bitwarden",
+                "com.1password.1password",
+                { "app": "Passwords", "appId": "com.apple.Passwords" },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(policy.excluded_apps[0], ExcludedApp::from("Bitwarden".to_string()));
+
+        assert!(is_excluded(&policy, Some("com.bitwarden.desktop"), Some("bitwarden")));
+        assert!(is_excluded(&policy, Some("com.1password.1password"), Some("1Password 8")));
+        assert!(is_excluded(&policy, Some("com.apple.Passwords"), Some("Mots de passe")));
+        assert!(is_excluded(&policy, None, Some("Passwords")));
+        assert!(!is_excluded(&policy, Some("com.example.Passwords"), Some("Passwords")));
+        assert!(!is_excluded(&policy, None, None));
+    }
+
+    #[test]
+    fn scrubs_phone_numbers() {
+        for phone in [
+            "+1 555 123 4567",
+            "+86 138 1234 5678",
+            "+8613812345678",
+            "(555) 123-4567",
+            "555-123-4567",
+            "010 1234 5678",
+        ] {
+            assert_eq!(scrub(&format!("Call {} now", phone)), "Call [phone] now", "{}", phone);
+        }
+    }
+
+    #[test]
+    fn leaves_numbers_that_are_not_phones() {
+        for text in [
+            "Ping 192.168.100.200 first",
+            "Releases 2023 2024 2025",
+            "Order #123456789 shipped",
+            "Version 10.2024.1234.5",
+            "Total 1234567890",
+        ] {
+            assert_eq!(scrub(text), text);
+        }
+    }
+
+    #[test]
+    fn leaves_ids_that_are_not_secrets() {
+        for text in [
+            "Row 550e8400-e29b-41d4-a716-446655440000 moved",
+            "See feature-flag-rollout-for-billing-v2-accounts",
+            "Column created_at_timestamp_utc_2024_migrated",
+            "Commit 9fceb02d0ae598e95dc970b74767f19372d61af8 landed",
+        ] {
+            assert_eq!(scrub(text), text);
+        }
+        assert_eq!(scrub("Token Zx9vQ2mK7pLr4TnW8yBc3HdF6jGs1AeU"), "Token [secret]");
+        assert_eq!(scrub("Token q7w2e9r4t1y8u3i6o5p0a2s7d4f9g1h3"), "Token [secret]");
+    }
+
+    #[test]
+    fn scrubs_emails_cards_and_secrets() {
+        assert_eq!(scrub("Mail a.b@example.com"), "Mail [email]");
+        assert_eq!(scrub("Card 4111 1111 1111 1111 ok"), "Card [card] ok");
+        assert_eq!(scrub("Key sk-abcdefghijklmnop1234"), "Key [secret]");
+    }
+
+    #[test]
+    fn luhn_checks_card_numbers() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("4111-1111-1111-1111"));
+        assert!(luhn_valid("5500 0000 0000 0004"));
+        assert!(!luhn_valid("4111111111111112"));
+        // Too short or too long for a card.
+        assert!(!luhn_valid("0"));
+        assert!(!luhn_valid(&"0".repeat(20)));
+    }
+}
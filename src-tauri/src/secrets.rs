@@ -0,0 +1,32 @@
+//! OS keyring access: Keychain on macOS, Credential Manager on Windows and
+//! Secret Service on Linux (cached in the kernel keyring). Entries live
+//! under the app identifier.
+
+use keyring::Entry;
+
+const SERVICE: &str = "com.polishr.app";
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, name).map_err(|e| e.to_string())
+}
+
+/// Read a secret. Returns `Ok(None)` when no entry exists.
+pub fn get_secret(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn set_secret(name: &str, value: &str) -> Result<(), String> {
+    entry(name)?.set_password(value).map_err(|e| e.to_string())
+}
+
+/// Remove a secret. Missing entries are not an error.
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
@@ -0,0 +1,894 @@
+//! Typed, versioned settings owned by the backend.
+//!
+//! Settings are kept as top-level keys of `settings.json` (the same file the
+//! store plugin has always used) with a `schemaVersion` stamp. At startup the
+//! raw store is run through an ordered migration chain, parsed into
+//! [`Settings`] and cached here. All writes go through [`update`], which
+//! validates the result, saves it and broadcasts `settings-changed` to every
+//! window.
+//!
+//! Readers see the effective settings: the user's settings with the config
+//! file and organization policy from [`crate::config`] layered on top.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Runtime, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::background_check::{self, BackgroundCheckSettings};
+use crate::chinese_convert::ChineseConversionSettings;
+use crate::config::{self, ManagedState};
+use crate::profiles::{self, AppProfile, TriggerApps};
+use crate::providers::{self, Provider};
+use crate::quick_replace::QuickReplaceSettings;
+use crate::retention::HistoryPolicy;
+use crate::shortcuts::{self, ShortcutAction, ShortcutBinding};
+use crate::spellcheck::SpellCheckSettings;
+use crate::typography::TypographySettings;
+
+pub(crate) const STORE_PATH: &str = "settings.json";
+const VERSION_KEY: &str = "schemaVersion";
+/// The only window allowed to change settings.
+const SETTINGS_WINDOW: &str = "settings";
+const MAX_TEMPERATURE: f64 = 2.0;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// Entry `i` upgrades schema version `i` to `i + 1`.
+const MIGRATIONS: [Migration; 4] = [
+    migrate_v1_providers,
+    migrate_v2_typed,
+    migrate_v3_shortcuts,
+    migrate_v4_trigger_apps,
+];
+
+/// Version written by this build.
+pub const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// What the user chose; this is what gets saved.
+static SETTINGS: Mutex<Option<Settings>> = Mutex::new(None);
+/// [`SETTINGS`] with the config file and policy applied.
+static EFFECTIVE: Mutex<Option<Settings>> = Mutex::new(None);
+/// Set when the file was written by a newer version; we then never write it.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PolishMode {
+    #[default]
+    Improve,
+    Rephrase,
+    Translate,
+    /// Rule-based CJK typography, no model involved.
+    Typography,
+    /// Offline Simplified ⇄ Traditional Chinese conversion.
+    Convert,
+}
+
+impl PolishMode {
+    const ALL: [&str; 5] = ["improve", "rephrase", "translate", "typography", "convert"];
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub schema_version: u64,
+    pub providers: Vec<Provider>,
+    /// Empty when no provider has been activated yet.
+    pub active_provider_id: String,
+    pub default_mode: PolishMode,
+    pub app_profiles: Vec<AppProfile>,
+    pub history_policy: HistoryPolicy,
+    pub glossary: Vec<GlossaryEntry>,
+    pub shortcuts: Vec<ShortcutBinding>,
+    pub quick_replace: QuickReplaceSettings,
+    /// Trigger button paused from the tray.
+    pub trigger_paused: bool,
+    /// Apps the trigger button may appear in.
+    pub trigger_apps: TriggerApps,
+    /// Opt-in grammar check of the paragraph under the caret.
+    pub background_check: BackgroundCheckSettings,
+    /// Offline spell checking and the personal word list.
+    pub spell_check: SpellCheckSettings,
+    /// CJK typography rules, also run after Improve and Translate if asked.
+    pub typography: TypographySettings,
+    /// Target variant of the Chinese conversion mode.
+    pub chinese_conversion: ChineseConversionSettings,
+    /// Fields set by the config file or policy. Only present on the
+    /// effective settings and never saved.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub managed: Option<ManagedState>,
+}
+
+/// A term the model must keep or write a specific way. An empty
+/// `replacement` means "keep the term as written".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryEntry {
+    pub term: String,
+    #[serde(default)]
+    pub replacement: String,
+}
+
+impl Settings {
+    /// Copy safe to hand to the webview.
+    pub(crate) fn redacted(&self) -> Settings {
+        let mut copy = self.clone();
+        for provider in copy.providers.iter_mut() {
+            provider.api_key = None;
+        }
+        copy
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let invalid = |field: String, reason: &str| Err(format!("invalid_setting:{}:{}", field, reason));
+
+        for (i, provider) in self.providers.iter().enumerate() {
+            let field = |name: &str| format!("providers[{}].{}", i, name);
+            if provider.id.trim().is_empty() {
+                return invalid(field("id"), "empty");
+            }
+            if self.providers[..i].iter().any(|p| p.id == provider.id) {
+                return invalid(field("id"), "duplicate");
+            }
+            if !(provider.endpoint.starts_with("https://") || provider.endpoint.starts_with("http://")) {
+                return invalid(field("endpoint"), "not_http_url");
+            }
+            if provider.model.trim().is_empty() {
+                return invalid(field("model"), "empty");
+            }
+            if !(0.0..=MAX_TEMPERATURE).contains(&provider.temperature) {
+                return invalid(field("temperature"), "out_of_range");
+            }
+        }
+        if !self.active_provider_id.is_empty()
+            && !self.providers.iter().any(|p| p.id == self.active_provider_id)
+        {
+            return invalid("activeProviderId".to_string(), "unknown_provider");
+        }
+
+        for (i, profile) in self.app_profiles.iter().enumerate() {
+            let field = |name: &str| format!("appProfiles[{}].{}", i, name);
+            if profile.app.trim().is_empty() {
+                return invalid(field("app"), "empty");
+            }
+            if self.app_profiles[..i]
+                .iter()
+                .any(|p| p.matches(profile.app_id.as_deref(), Some(&profile.app)))
+            {
+                return invalid(field("app"), "duplicate");
+            }
+            if let Some(provider_id) = profile.provider_id.as_deref() {
+                if !self.providers.iter().any(|p| p.id == provider_id) {
+                    return invalid(field("providerId"), "unknown_provider");
+                }
+            }
+        }
+
+        if self.history_policy.retention_days == 0 {
+            return invalid("historyPolicy.retentionDays".to_string(), "zero");
+        }
+        if self.history_policy.retention_records == 0 {
+            return invalid("historyPolicy.retentionRecords".to_string(), "zero");
+        }
+
+        for (i, binding) in self.shortcuts.iter().enumerate() {
+            let field = |name: &str| format!("shortcuts[{}].{}", i, name);
+            if binding.id.trim().is_empty() {
+                return invalid(field("id"), "empty");
+            }
+            if self.shortcuts[..i].iter().any(|b| b.id == binding.id) {
+                return invalid(field("id"), "duplicate");
+            }
+            if binding.accelerator.trim().is_empty() {
+                return invalid(field("accelerator"), "empty");
+            }
+            let has_instruction = binding.instruction.as_deref().is_some_and(|i| !i.trim().is_empty());
+            if binding.action == ShortcutAction::Custom && !has_instruction {
+                return invalid(field("instruction"), "empty");
+            }
+        }
+
+        for (i, app) in self.trigger_apps.apps.iter().enumerate() {
+            if app.id.trim().is_empty() {
+                return invalid(format!("triggerApps.apps[{}].id", i), "empty");
+            }
+            if self.trigger_apps.apps[..i].iter().any(|a| a.id == app.id) {
+                return invalid(format!("triggerApps.apps[{}].id", i), "duplicate");
+            }
+        }
+
+        if self.quick_replace.max_chars == 0 {
+            return invalid("quickReplace.maxChars".to_string(), "zero");
+        }
+
+        if let Some(provider_id) = self.background_check.provider_id.as_deref() {
+            if !self.providers.iter().any(|p| p.id == provider_id) {
+                return invalid("backgroundCheck.providerId".to_string(), "unknown_provider");
+            }
+        }
+        if self.background_check.debounce_ms < background_check::MIN_DEBOUNCE_MS {
+            return invalid("backgroundCheck.debounceMs".to_string(), "too_short");
+        }
+        if self.background_check.max_per_minute == 0 {
+            return invalid("backgroundCheck.maxPerMinute".to_string(), "zero");
+        }
+
+        for (i, language) in self.spell_check.languages.iter().enumerate() {
+            let field = format!("spellCheck.languages[{}]", i);
+            if language.trim().is_empty() {
+                return invalid(field, "empty");
+            }
+            if language.contains(['/', '\\', '.']) {
+                return invalid(field, "not_a_language");
+            }
+            if self.spell_check.languages[..i].contains(language) {
+                return invalid(field, "duplicate");
+            }
+        }
+        for (i, word) in self.spell_check.personal_words.iter().enumerate() {
+            if word.trim().is_empty() {
+                return invalid(format!("spellCheck.personalWords[{}]", i), "empty");
+            }
+        }
+        for (i, rule) in self.typography.rules.iter().enumerate() {
+            if self.typography.rules[..i].contains(rule) {
+                return invalid(format!("typography.rules[{}]", i), "duplicate");
+            }
+        }
+
+        for (i, entry) in self.glossary.iter().enumerate() {
+            if entry.term.trim().is_empty() {
+                return invalid(format!("glossary[{}].term", i), "empty");
+            }
+            if self.glossary[..i].iter().any(|g| g.term == entry.term) {
+                return invalid(format!("glossary[{}].term", i), "duplicate");
+            }
+        }
+        Ok(())
+    }
+}
+
+// --- Migrations ---
+
+/// Built-in presets, used to name a provider migrated from the legacy flat
+/// settings: `(endpoint, id, name)`.
+const PRESET_ENDPOINTS: [(&str, &str, &str); 4] = [
+    ("https://api.deepseek.com/v1", "deepseek", "DeepSeek"),
+    ("https://api.openai.com/v1", "openai", "OpenAI"),
+    ("https://openrouter.ai/api/v1", "openrouter", "OpenRouter"),
+    ("https://api.minimax.chat/v1", "minimax", "MiniMax"),
+];
+
+const DEFAULT_ENDPOINT: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o";
+const DEFAULT_TEMPERATURE: f64 = 0.3;
+
+/// v1: the single-provider flat keys (`apiEndpoint`, `apiKey`, `model`,
+/// `temperature`) become an entry in `providers`. The plaintext key is left
+/// on the provider for [`crate::providers::init`] to move to the keyring.
+fn migrate_v1_providers(raw: &mut Map<String, Value>) {
+    let endpoint = raw.remove("apiEndpoint");
+    let api_key = raw.remove("apiKey");
+    let model = raw.remove("model");
+    let temperature = raw.remove("temperature");
+
+    let has_providers = raw
+        .get("providers")
+        .and_then(Value::as_array)
+        .is_some_and(|p| !p.is_empty());
+    let Some(api_key) = api_key.and_then(|k| k.as_str().map(String::from)) else {
+        return;
+    };
+    if has_providers || api_key.is_empty() {
+        return;
+    }
+
+    let endpoint = endpoint
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+    let (id, name) = PRESET_ENDPOINTS
+        .iter()
+        .find(|(preset, _, _)| *preset == endpoint)
+        .map(|(_, id, name)| (*id, *name))
+        .unwrap_or(("custom", "Custom"));
+    let provider = serde_json::json!({
+        "id": id,
+        "name": name,
+        "endpoint": endpoint,
+        "apiKey": api_key,
+        "model": model.and_then(|v| v.as_str().map(String::from)).unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+        "temperature": temperature.and_then(|v| v.as_f64()).unwrap_or(DEFAULT_TEMPERATURE),
+    });
+    raw.insert("providers".to_string(), Value::Array(vec![provider]));
+    raw.insert("activeProviderId".to_string(), Value::String(id.to_string()));
+}
+
+/// v2: bring values written by the untyped frontend into the shape of
+/// [`Settings`] so a single bad field cannot make the whole file unreadable.
+fn migrate_v2_typed(raw: &mut Map<String, Value>) {
+    let is_mode = |v: &Value| v.as_str().is_some_and(|m| PolishMode::ALL.contains(&m));
+
+    if raw.get("defaultMode").is_some_and(|m| !is_mode(m)) {
+        raw.insert("defaultMode".to_string(), Value::String("improve".to_string()));
+    }
+
+    let mut provider_ids = Vec::new();
+    if let Some(Value::Array(providers)) = raw.get_mut("providers") {
+        providers.retain(|p| p["id"].as_str().is_some_and(|id| !id.is_empty()));
+        for provider in providers.iter_mut() {
+            let id = provider["id"].as_str().unwrap_or_default().to_string();
+            let endpoint = provider["endpoint"].as_str().unwrap_or(DEFAULT_ENDPOINT);
+            let endpoint = endpoint.trim_end_matches('/').to_string();
+            provider["endpoint"] = Value::String(endpoint);
+            if provider["name"].as_str().is_none() {
+                provider["name"] = Value::String(id.clone());
+            }
+            if provider["model"].as_str().is_none() {
+                provider["model"] = Value::String(DEFAULT_MODEL.to_string());
+            }
+            let temperature = provider["temperature"].as_f64().unwrap_or(DEFAULT_TEMPERATURE);
+            provider["temperature"] = serde_json::json!(temperature.clamp(0.0, MAX_TEMPERATURE));
+            provider_ids.push(id);
+        }
+    } else {
+        raw.remove("providers");
+    }
+
+    let active_is_known = raw
+        .get("activeProviderId")
+        .and_then(Value::as_str)
+        .is_some_and(|id| provider_ids.iter().any(|p| p == id));
+    if !active_is_known {
+        let fallback = provider_ids.first().cloned().unwrap_or_default();
+        raw.insert("activeProviderId".to_string(), Value::String(fallback));
+    }
+
+    if let Some(Value::Array(profiles)) = raw.get_mut("appProfiles") {
+        profiles.retain(|p| p["app"].as_str().is_some_and(|app| !app.trim().is_empty()));
+        for profile in profiles.iter_mut() {
+            if !profile["mode"].is_null() && !is_mode(&profile["mode"]) {
+                profile["mode"] = Value::Null;
+            }
+            let known_provider = profile["providerId"]
+                .as_str()
+                .is_some_and(|id| provider_ids.iter().any(|p| p == id));
+            if !profile["providerId"].is_null() && !known_provider {
+                profile["providerId"] = Value::Null;
+            }
+        }
+    } else {
+        raw.remove("appProfiles");
+    }
+
+    if raw.get("historyPolicy").is_some_and(|p| !p.is_object()) {
+        raw.remove("historyPolicy");
+    }
+}
+
+/// v3: the hard-coded panel hotkey becomes the first configurable binding.
+fn migrate_v3_shortcuts(raw: &mut Map<String, Value>) {
+    if !raw.get("shortcuts").is_some_and(Value::is_array) {
+        let binding = serde_json::json!({
+            "id": "open-panel",
+            "accelerator": shortcuts::DEFAULT_ACCELERATOR,
+            "action": "open_panel",
+        });
+        raw.insert("shortcuts".to_string(), Value::Array(vec![binding]));
+    }
+}
+
+/// v4: the trigger gets an allow/deny list, starting out with terminals and
+/// password managers denied.
+fn migrate_v4_trigger_apps(raw: &mut Map<String, Value>) {
+    if !raw.get("triggerApps").is_some_and(Value::is_object) {
+        let apps: Vec<Value> = profiles::DEFAULT_DENIED_APPS
+            .iter()
+            .map(|(id, name)| serde_json::json!({ "id": id, "name": name }))
+            .collect();
+        let list = serde_json::json!({ "mode": "deny", "apps": apps });
+        raw.insert("triggerApps".to_string(), list);
+    }
+}
+
+/// Run every migration newer than the file's version. Returns whether
+/// anything ran.
+fn migrate(raw: &mut Map<String, Value>) -> Result<bool, String> {
+    let version = raw.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        return Err("settings_from_newer_version".to_string());
+    }
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        step(raw);
+        raw.insert(VERSION_KEY.to_string(), Value::from(from as u64 + 1));
+    }
+    Ok(version < SCHEMA_VERSION)
+}
+
+// --- Storage ---
+
+fn persist<R: Runtime>(app: &AppHandle<R>, settings: &Settings) -> Result<(), String> {
+    if READ_ONLY.load(Ordering::SeqCst) {
+        return Err("settings_from_newer_version".to_string());
+    }
+    let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
+    let Value::Object(values) = serde_json::to_value(settings).map_err(|e| e.to_string())? else {
+        return Err("settings_not_an_object".to_string());
+    };
+    for (key, value) in values {
+        store.set(key, value);
+    }
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Load, migrate and cache settings. Must run before anything reads them.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let store = match app.store(STORE_PATH) {
+        Ok(store) => store,
+        Err(err) => {
+            println!("[Polishr] Settings store unavailable: {}", err);
+            return;
+        }
+    };
+    let original: Map<String, Value> = store.entries().into_iter().collect();
+    let mut raw = original.clone();
+
+    let migrated = match migrate(&mut raw) {
+        Ok(migrated) => migrated,
+        Err(err) => {
+            println!("[Polishr] Settings were written by a newer version ({}); not saving changes", err);
+            READ_ONLY.store(true, Ordering::SeqCst);
+            false
+        }
+    };
+
+    let settings = match serde_json::from_value::<Settings>(Value::Object(raw.clone())) {
+        Ok(settings) => settings,
+        Err(err) => {
+            println!("[Polishr] Failed to parse settings, using defaults: {}", err);
+            READ_ONLY.store(true, Ordering::SeqCst);
+            Settings::default()
+        }
+    };
+
+    if migrated && !READ_ONLY.load(Ordering::SeqCst) {
+        for key in original.keys().filter(|k| !raw.contains_key(*k)) {
+            store.delete(key);
+        }
+        for (key, value) in raw {
+            store.set(key, value);
+        }
+        match store.save() {
+            Ok(()) => println!("[Polishr] Migrated settings to schema v{}", SCHEMA_VERSION),
+            Err(err) => println!("[Polishr] Failed to save migrated settings: {}", err),
+        }
+    }
+    let effective = config::apply(&settings);
+    config::log_conflict(None, &effective);
+    *EFFECTIVE.lock().unwrap() = Some(effective);
+    *SETTINGS.lock().unwrap() = Some(settings);
+}
+
+/// Load and cache settings from the store file at `path` without an app,
+/// for the command line. Migrations run in memory only; the file is never
+/// written. A missing file gives the defaults.
+pub fn load_read_only(path: &Path) -> Result<(), String> {
+    let mut raw: Map<String, Value> = match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("settings_unreadable:{}", e))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Map::new(),
+        Err(err) => return Err(format!("settings_unreadable:{}", err)),
+    };
+    READ_ONLY.store(true, Ordering::SeqCst);
+    // Settings from a newer version are read as far as this one understands
+    // them, as at startup.
+    let _ = migrate(&mut raw);
+    let settings: Settings =
+        serde_json::from_value(Value::Object(raw)).map_err(|e| format!("settings_unreadable:{}", e))?;
+    *EFFECTIVE.lock().unwrap() = Some(config::apply(&settings));
+    *SETTINGS.lock().unwrap() = Some(settings);
+    Ok(())
+}
+
+/// Read the effective settings without cloning them.
+pub fn with<T>(f: impl FnOnce(&Settings) -> T) -> T {
+    let guard = EFFECTIVE.lock().unwrap();
+    match guard.as_ref() {
+        Some(settings) => f(settings),
+        None => f(&Settings::default()),
+    }
+}
+
+pub fn get() -> Settings {
+    with(Settings::clone)
+}
+
+/// Change the user's settings, validate and save them, and notify every
+/// window. Returns the new effective settings; managed fields keep their
+/// managed values whatever `change` does.
+pub fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    change: impl FnOnce(&mut Settings) -> Result<(), String>,
+) -> Result<Settings, String> {
+    let effective = {
+        let mut guard = SETTINGS.lock().unwrap();
+        let previous = guard.clone().unwrap_or_default();
+        let (next, effective) = prepare(&previous, change)?;
+        persist(app, &next)?;
+        *guard = Some(next);
+        let mut current = EFFECTIVE.lock().unwrap();
+        config::log_conflict(current.as_ref(), &effective);
+        *current = Some(effective.clone());
+        effective
+    };
+    let _ = app.emit("settings-changed", effective.redacted());
+    Ok(effective)
+}
+
+/// Whether `update` would accept `change`, without applying it.
+pub fn check(change: impl FnOnce(&mut Settings) -> Result<(), String>) -> Result<(), String> {
+    let previous = SETTINGS.lock().unwrap().clone().unwrap_or_default();
+    prepare(&previous, change).map(|_| ())
+}
+
+/// The user's settings after `change`, and the effective settings they
+/// produce, once both are valid.
+fn prepare(
+    previous: &Settings,
+    change: impl FnOnce(&mut Settings) -> Result<(), String>,
+) -> Result<(Settings, Settings), String> {
+    let mut next = previous.clone();
+    change(&mut next)?;
+    keep_managed(previous, &mut next)?;
+    next.schema_version = SCHEMA_VERSION;
+    // Validate what will be used: the user's settings may refer to
+    // providers defined in the config file.
+    let effective = config::apply(&next);
+    effective.validate()?;
+    Ok((next, effective))
+}
+
+/// Keep the user's own values for fields the config file or policy manages,
+/// so a form that echoes effective values back does not save them as the
+/// user's. Provider and glossary entries are keyed and left as they are.
+fn keep_managed(previous: &Settings, next: &mut Settings) -> Result<(), String> {
+    let fields: Vec<String> = with(|s| {
+        s.managed
+            .iter()
+            .flat_map(|m| m.fields.iter())
+            .map(|f| f.field.clone())
+            .filter(|f| !f.starts_with("providers.") && !f.starts_with("glossary."))
+            .collect()
+    });
+    if fields.is_empty() {
+        return Ok(());
+    }
+    let previous = serde_json::to_value(previous).map_err(|e| e.to_string())?;
+    let mut merged = serde_json::to_value(&*next).map_err(|e| e.to_string())?;
+    for field in fields {
+        let pointer = format!("/{}", field.replace('.', "/"));
+        if let (Some(value), Some(slot)) = (previous.pointer(&pointer), merged.pointer_mut(&pointer)) {
+            *slot = value.clone();
+        }
+    }
+    *next = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Recompute the effective settings after the config file or policy changed,
+/// and notify every window.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let effective = {
+        let guard = SETTINGS.lock().unwrap();
+        let effective = config::apply(&guard.clone().unwrap_or_default());
+        let mut current = EFFECTIVE.lock().unwrap();
+        config::log_conflict(current.as_ref(), &effective);
+        *current = Some(effective.clone());
+        effective
+    };
+    let _ = app.emit("settings-changed", effective.redacted());
+}
+
+/// Reject writes from any window but the settings window, so the panel and
+/// trigger webviews cannot repoint a provider that has a stored key.
+pub(crate) fn require_settings_window<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    if window.label() == SETTINGS_WINDOW {
+        Ok(())
+    } else {
+        Err("not_allowed".to_string())
+    }
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn get_settings() -> Settings {
+    get().redacted()
+}
+
+/// Apply a partial update, e.g. `{ "defaultMode": "rephrase" }`. Provider
+/// API keys are managed by `save_provider` and cannot be changed here; a
+/// provider whose endpoint changes loses its stored key. Settings window only.
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, window: WebviewWindow, patch: Value) -> Result<Settings, String> {
+    require_settings_window(&window)?;
+    let Value::Object(patch) = patch else {
+        return Err("invalid_setting:patch:not_an_object".to_string());
+    };
+    let mut dropped = Vec::new();
+    let updated = update(&app, |settings| {
+        let Value::Object(mut merged) = serde_json::to_value(&*settings).map_err(|e| e.to_string())? else {
+            return Err("settings_not_an_object".to_string());
+        };
+        for (key, value) in patch {
+            if key != VERSION_KEY {
+                merged.insert(key, value);
+            }
+        }
+        let mut next: Settings = serde_json::from_value(Value::Object(merged))
+            .map_err(|e| format!("invalid_setting:patch:{}", e))?;
+        for provider in next.providers.iter_mut() {
+            let existing = settings.providers.iter().find(|p| p.id == provider.id);
+            if providers::carry_key(existing, provider) {
+                dropped.push(provider.id.clone());
+            }
+        }
+        *settings = next;
+        Ok(())
+    })?;
+    for provider_id in &dropped {
+        providers::forget_key(provider_id);
+    }
+    Ok(updated.redacted())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn legacy_flat_keys_become_a_provider() {
+        let mut raw = object(json!({
+            "apiEndpoint": "https://api.deepseek.com/v1",
+            "apiKey": "sk-test",
+            "model": "deepseek-chat",
+            "temperature": 0.5,
+            "defaultMode": "rephrase",
+        }));
+        assert!(migrate(&mut raw).unwrap());
+
+        assert_eq!(raw[VERSION_KEY], json!(SCHEMA_VERSION));
+        for key in ["apiEndpoint", "apiKey", "model", "temperature"] {
+            assert!(!raw.contains_key(key), "{} should be removed", key);
+        }
+        assert_eq!(raw["activeProviderId"], json!("deepseek"));
+        let provider = &raw["providers"][0];
+        assert_eq!(provider["name"], json!("DeepSeek"));
+        assert_eq!(provider["apiKey"], json!("sk-test"));
+        assert_eq!(provider["model"], json!("deepseek-chat"));
+
+        let settings: Settings = serde_json::from_value(Value::Object(raw)).unwrap();
+        assert_eq!(settings.default_mode, PolishMode::Rephrase);
+        assert_eq!(settings.providers[0].api_key.as_deref(), Some("sk-test"));
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn unknown_endpoint_migrates_to_custom_provider() {
+        let mut raw = object(json!({ "apiEndpoint": "http://localhost:11434/v1", "apiKey": "k" }));
+        migrate(&mut raw).unwrap();
+        assert_eq!(raw["providers"][0]["id"], json!("custom"));
+        assert_eq!(raw["providers"][0]["model"], json!(DEFAULT_MODEL));
+        assert_eq!(raw["providers"][0]["temperature"], json!(DEFAULT_TEMPERATURE));
+    }
+
+    #[test]
+    fn flat_keys_are_dropped_when_providers_exist() {
+        let mut raw = object(json!({
+            "apiKey": "stale",
+            "providers": [{ "id": "openai", "name": "OpenAI", "endpoint": "https://api.openai.com/v1", "model": "gpt-4o", "temperature": 0.3 }],
+            "activeProviderId": "openai",
+        }));
+        migrate(&mut raw).unwrap();
+        assert!(!raw.contains_key("apiKey"));
+        assert_eq!(raw["providers"].as_array().unwrap().len(), 1);
+        assert!(raw["providers"][0].get("apiKey").is_none());
+    }
+
+    #[test]
+    fn empty_store_migrates_to_defaults() {
+        let mut raw = Map::new();
+        assert!(migrate(&mut raw).unwrap());
+        let settings: Settings = serde_json::from_value(Value::Object(raw)).unwrap();
+        assert!(settings.providers.is_empty());
+        assert_eq!(settings.active_provider_id, "");
+        assert_eq!(settings.schema_version, SCHEMA_VERSION);
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn v2_repairs_untyped_values() {
+        let mut raw = object(json!({
+            "schemaVersion": 1,
+            "defaultMode": "polish",
+            "providers": [
+                { "id": "openai", "endpoint": "https://api.openai.com/v1/", "temperature": 7 },
+                { "name": "no id" },
+            ],
+            "activeProviderId": "gone",
+            "appProfiles": [
+                { "app": "Mail", "mode": "shout", "providerId": "gone" },
+                { "app": "  " },
+            ],
+            "historyPolicy": "nonsense",
+        }));
+        migrate(&mut raw).unwrap();
+
+        let settings: Settings = serde_json::from_value(Value::Object(raw)).unwrap();
+        assert_eq!(settings.default_mode, PolishMode::Improve);
+        assert_eq!(settings.providers.len(), 1);
+        assert_eq!(settings.providers[0].endpoint, "https://api.openai.com/v1");
+        assert_eq!(settings.providers[0].temperature, MAX_TEMPERATURE);
+        assert_eq!(settings.active_provider_id, "openai");
+        assert_eq!(settings.app_profiles.len(), 1);
+        assert!(settings.app_profiles[0].mode.is_none());
+        assert!(settings.app_profiles[0].provider_id.is_none());
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn migrations_only_run_from_the_stored_version() {
+        // A v1 file skips the v1 flat-key migration.
+        let mut raw = object(json!({ "schemaVersion": 1, "apiKey": "not-a-v0-file", "defaultMode": "bad" }));
+        migrate(&mut raw).unwrap();
+        assert_eq!(raw["apiKey"], json!("not-a-v0-file"));
+        assert_eq!(raw["defaultMode"], json!("improve"));
+        assert!(!raw.contains_key("providers"));
+    }
+
+    #[test]
+    fn current_version_is_untouched() {
+        let original = object(json!({ "schemaVersion": SCHEMA_VERSION, "defaultMode": "translate" }));
+        let mut raw = original.clone();
+        assert!(!migrate(&mut raw).unwrap());
+        assert_eq!(raw, original);
+    }
+
+    #[test]
+    fn newer_version_is_refused() {
+        let mut raw = object(json!({ "schemaVersion": SCHEMA_VERSION + 1 }));
+        assert_eq!(migrate(&mut raw), Err("settings_from_newer_version".to_string()));
+    }
+
+    #[test]
+    fn migrating_twice_is_a_no_op() {
+        let mut raw = object(json!({ "apiKey": "sk-x", "apiEndpoint": "https://api.openai.com/v1" }));
+        migrate(&mut raw).unwrap();
+        let once = raw.clone();
+        assert!(!migrate(&mut raw).unwrap());
+        assert_eq!(raw, once);
+    }
+
+    #[test]
+    fn v3_adds_the_default_shortcut() {
+        let mut raw = object(json!({ "schemaVersion": 2 }));
+        migrate(&mut raw).unwrap();
+        let settings: Settings = serde_json::from_value(Value::Object(raw)).unwrap();
+        assert_eq!(settings.shortcuts.len(), 1);
+        assert_eq!(settings.shortcuts[0].accelerator, shortcuts::DEFAULT_ACCELERATOR);
+        assert_eq!(settings.shortcuts[0].action, ShortcutAction::OpenPanel);
+
+        // An existing list is left alone.
+        let mut raw = object(json!({ "schemaVersion": 2, "shortcuts": [] }));
+        migrate(&mut raw).unwrap();
+        assert_eq!(raw["shortcuts"], json!([]));
+    }
+
+    #[test]
+    fn v4_denies_terminals_and_password_managers() {
+        let mut raw = object(json!({ "schemaVersion": 3 }));
+        migrate(&mut raw).unwrap();
+        let settings: Settings = serde_json::from_value(Value::Object(raw)).unwrap();
+        assert_eq!(settings.trigger_apps.apps.len(), profiles::DEFAULT_DENIED_APPS.len());
+        assert!(!settings.trigger_apps.allows(Some("com.apple.Terminal"), Some("Terminal")));
+        assert!(settings.trigger_apps.allows(Some("com.apple.TextEdit"), Some("TextEdit")));
+
+        // An existing list is left alone.
+        let mut raw = object(json!({ "schemaVersion": 3, "triggerApps": { "mode": "allow", "apps": [] } }));
+        migrate(&mut raw).unwrap();
+        assert_eq!(raw["triggerApps"]["mode"], json!("allow"));
+    }
+
+    fn provider(id: &str) -> Provider {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": id,
+            "endpoint": "https://example.com/v1",
+            "model": "m",
+            "temperature": 0.3,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn validation_reports_the_failing_field() {
+        let mut settings = Settings {
+            providers: vec![provider("a"), provider("a")],
+            ..Default::default()
+        };
+        assert_eq!(settings.validate(), Err("invalid_setting:providers[1].id:duplicate".to_string()));
+
+        settings.providers = vec![provider("a")];
+        settings.providers[0].endpoint = "ftp://example.com".to_string();
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:providers[0].endpoint:not_http_url".to_string())
+        );
+
+        settings.providers = vec![provider("a")];
+        settings.active_provider_id = "b".to_string();
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:activeProviderId:unknown_provider".to_string())
+        );
+
+        settings.active_provider_id = "a".to_string();
+        settings.history_policy.retention_days = 0;
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:historyPolicy.retentionDays:zero".to_string())
+        );
+
+        settings.history_policy.retention_days = 30;
+        settings.background_check.provider_id = Some("cheap".to_string());
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:backgroundCheck.providerId:unknown_provider".to_string())
+        );
+
+        settings.background_check.provider_id = None;
+        settings.background_check.max_per_minute = 0;
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:backgroundCheck.maxPerMinute:zero".to_string())
+        );
+        settings.background_check.max_per_minute = 1;
+        let profile = |app: &str, app_id: Option<&str>| -> AppProfile {
+            serde_json::from_value(json!({ "app": app, "appId": app_id })).unwrap()
+        };
+        settings.app_profiles = vec![
+            profile("Code", Some("com.microsoft.VSCode")),
+            profile("Code", Some("com.vscodium")),
+        ];
+        assert_eq!(settings.validate(), Ok(()));
+        settings.app_profiles.push(profile("Code", None));
+        assert_eq!(
+            settings.validate(),
+            Err("invalid_setting:appProfiles[2].app:duplicate".to_string())
+        );
+        settings.app_profiles.pop();
+        assert_eq!(settings.validate(), Ok(()));
+    }
+
+    #[test]
+    fn redacted_settings_never_carry_plaintext_keys() {
+        let mut settings = Settings {
+            providers: vec![provider("a")],
+            ..Default::default()
+        };
+        settings.providers[0].api_key = Some("sk-secret".to_string());
+        let json = serde_json::to_string(&settings.redacted()).unwrap();
+        assert!(!json.contains("sk-secret"));
+    }
+}
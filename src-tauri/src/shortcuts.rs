@@ -0,0 +1,190 @@
+//! User-configurable global shortcuts.
+//!
+//! Bindings are part of [`crate::settings`] and are re-registered whenever
+//! they change. Every binding captures the selection; its action decides
+//! which mode the panel runs, or replaces the text directly through
+//! [`crate::quick_replace`]. Bindings that cannot be registered
+//! (bad accelerator, clash with another binding, or taken by another app)
+//! are reported through [`get_shortcut_status`].
+
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::commands;
+use crate::quick_replace;
+use crate::settings;
+
+/// Binding created for new installs and older settings files.
+pub const DEFAULT_ACCELERATOR: &str = "Alt+Super+KeyP";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// Open the panel in the app profile's or the default mode.
+    OpenPanel,
+    Improve,
+    Rephrase,
+    Translate,
+    /// Run the default mode with the binding's instruction.
+    Custom,
+    /// Improve and replace the selection without opening the panel.
+    QuickReplace,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub id: String,
+    /// e.g. `Alt+Super+KeyP` or `CommandOrControl+Shift+KeyR`.
+    pub accelerator: String,
+    pub action: ShortcutAction,
+    /// Instruction sent by `custom` bindings.
+    #[serde(default)]
+    pub instruction: Option<String>,
+}
+
+/// Registration outcome for one binding.
+#[derive(Debug, Serialize, Clone)]
+pub struct ShortcutStatus {
+    pub id: String,
+    /// `None` once registered. Otherwise `invalid_accelerator`,
+    /// `conflict:<other binding id>` or `unavailable:<reason>`.
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Registered {
+    bindings: Vec<ShortcutBinding>,
+    /// Hotkey id of each registered shortcut and the binding it belongs to.
+    hotkeys: Vec<(u32, ShortcutBinding)>,
+    status: Vec<ShortcutStatus>,
+}
+
+static REGISTERED: Mutex<Option<Registered>> = Mutex::new(None);
+/// Serializes re-registration.
+static SYNCING: Mutex<()> = Mutex::new(());
+
+/// Replace every registered shortcut with `bindings`.
+fn register_all<R: Runtime>(app: &AppHandle<R>, bindings: Vec<ShortcutBinding>) -> Registered {
+    let global = app.global_shortcut();
+    if let Err(err) = global.unregister_all() {
+        println!("[Polishr] Failed to unregister shortcuts: {}", err);
+    }
+
+    let mut hotkeys: Vec<(u32, ShortcutBinding)> = Vec::new();
+    let mut status = Vec::new();
+    for binding in &bindings {
+        let error = match Shortcut::from_str(&binding.accelerator) {
+            Err(_) => Some("invalid_accelerator".to_string()),
+            Ok(shortcut) => match hotkeys.iter().find(|(id, _)| *id == shortcut.id()) {
+                Some((_, other)) => Some(format!("conflict:{}", other.id)),
+                None => match global.register(shortcut) {
+                    Ok(()) => {
+                        hotkeys.push((shortcut.id(), binding.clone()));
+                        None
+                    }
+                    Err(err) => Some(format!("unavailable:{}", err)),
+                },
+            },
+        };
+        match &error {
+            Some(error) => println!("[Polishr] Shortcut {} ({}) not registered: {}", binding.id, binding.accelerator, error),
+            None => println!("[Polishr] Shortcut {} registered: {}", binding.id, binding.accelerator),
+        }
+        status.push(ShortcutStatus {
+            id: binding.id.clone(),
+            error,
+        });
+    }
+    Registered {
+        bindings,
+        hotkeys,
+        status,
+    }
+}
+
+/// Re-register shortcuts if the bindings in settings changed.
+fn sync<R: Runtime>(app: &AppHandle<R>) {
+    // REGISTERED is not held while registering: the plugin calls `handle`
+    // with its own lock held, and registering takes that lock too.
+    let _syncing = SYNCING.lock().unwrap();
+    let bindings = settings::with(|s| s.shortcuts.clone());
+    if REGISTERED.lock().unwrap().as_ref().is_some_and(|r| r.bindings == bindings) {
+        return;
+    }
+    let next = register_all(app, bindings);
+    let status = next.status.clone();
+    *REGISTERED.lock().unwrap() = Some(next);
+    let _ = app.emit("shortcuts-changed", status);
+}
+
+/// Register the shortcuts from settings and follow later changes. Runs
+/// after [`settings::init`].
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    sync(app);
+    let handle = app.clone();
+    app.listen_any("settings-changed", move |_| sync(&handle));
+}
+
+/// Global shortcut plugin handler.
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state != ShortcutState::Pressed {
+        return;
+    }
+    let binding = REGISTERED.lock().unwrap().as_ref().and_then(|r| {
+        r.hotkeys
+            .iter()
+            .find(|(id, _)| *id == shortcut.id())
+            .map(|(_, binding)| binding.clone())
+    });
+    let Some(binding) = binding else {
+        return;
+    };
+    println!("[Polishr] Shortcut {} pressed", binding.id);
+    if binding.action == ShortcutAction::QuickReplace {
+        quick_replace::run(app);
+        return;
+    }
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match commands::capture_and_locate().await {
+            Ok(mut result) => {
+                println!(
+                    "[Polishr] Captured {} chars at ({}, {})",
+                    result.text.len(),
+                    result.x,
+                    result.y
+                );
+                result.shortcut = Some(binding);
+                commands::present_capture(&handle, result);
+            }
+            Err(err) => {
+                println!("[Polishr] Capture failed: {}", err);
+                let _ = handle.emit("capture-error", err.clone());
+                // Still show the window at a reasonable position
+                if let Some(window) = handle.get_webview_window("main") {
+                    let _ = window.center();
+                    let _ = window.show();
+                }
+            }
+        }
+    });
+}
+
+// --- Commands ---
+
+/// Registration status of every binding, in settings order.
+#[tauri::command]
+pub async fn get_shortcut_status() -> Vec<ShortcutStatus> {
+    REGISTERED
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|r| r.status.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,892 @@
+//! Offline spell checking with Hunspell dictionaries.
+//!
+//! Loads `<language>.aff` / `<language>.dic` pairs from the app's
+//! `dictionaries` folder and the usual system locations. The affix support
+//! covers what checking and suggesting need: prefix and suffix rules with
+//! cross products, all flag types and aliases, NEEDAFFIX, FORBIDDENWORD and
+//! NOSUGGEST, and TRY/REP/KEY for suggestions. Compound words are not
+//! supported and are reported as misspelled.
+//!
+//! A word is correct if any configured dictionary or the personal word list
+//! accepts it. Suggestions come from single edits ranked by how likely the
+//! typo is, so checking a paragraph takes milliseconds.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::diff::TextRange;
+use crate::readability;
+use crate::settings;
+
+const DEFAULT_LANGUAGE: &str = "en_US";
+const MAX_SUGGESTIONS: usize = 5;
+/// Misspellings reported per text; the rest is left to the LLM.
+const MAX_MISSPELLINGS: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    /// Dictionary names, e.g. `en_US`, `de_DE`.
+    pub languages: Vec<String>,
+    /// Words never reported, whatever the dictionaries say.
+    pub personal_words: Vec<String>,
+}
+
+impl Default for SpellCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            languages: vec![DEFAULT_LANGUAGE.to_string()],
+            personal_words: Vec::new(),
+        }
+    }
+}
+
+/// A word no dictionary knows, with replacements, most likely first.
+#[derive(Debug, Serialize, Clone)]
+pub struct Misspelling {
+    pub word: String,
+    /// UTF-16 range of the word in the checked text.
+    pub range: TextRange,
+    pub suggestions: Vec<String>,
+}
+
+/// Where dictionaries are looked up and which ones were found.
+#[derive(Debug, Serialize, Clone)]
+pub struct DictionaryList {
+    /// Folder to drop `.aff`/`.dic` pairs into.
+    pub folder: String,
+    pub languages: Vec<String>,
+}
+
+// --- Affix file ---
+
+type Flag = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagType {
+    Short,
+    Long,
+    Num,
+    Utf8,
+}
+
+#[derive(Debug)]
+enum CondPart {
+    Any,
+    Char(char),
+    Set(Vec<char>, bool),
+}
+
+impl CondPart {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CondPart::Any => true,
+            CondPart::Char(expected) => *expected == c,
+            CondPart::Set(chars, negated) => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<CondPart> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(CondPart::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                parts.push(CondPart::Set(set, negated));
+            }
+            c => parts.push(CondPart::Char(c)),
+        }
+    }
+    parts
+}
+
+#[derive(Debug)]
+struct Affix {
+    flag: Flag,
+    cross: bool,
+    strip: String,
+    add: String,
+    condition: Vec<CondPart>,
+}
+
+impl Affix {
+    /// Whether a prefix rule may apply to `root`.
+    fn matches_start(&self, root: &str) -> bool {
+        let mut chars = root.chars();
+        self.condition.iter().all(|part| chars.next().is_some_and(|c| part.matches(c)))
+    }
+
+    /// Whether a suffix rule may apply to `root`.
+    fn matches_end(&self, root: &str) -> bool {
+        let mut chars = root.chars().rev();
+        self.condition.iter().rev().all(|part| chars.next().is_some_and(|c| part.matches(c)))
+    }
+}
+
+/// A loaded Hunspell dictionary.
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    /// Homonyms keep their own flags.
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// Rule indexes by the text the rule adds.
+    prefix_index: HashMap<String, Vec<usize>>,
+    suffix_index: HashMap<String, Vec<usize>>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    keyboard: Vec<Vec<char>>,
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    no_suggest: Option<Flag>,
+    only_in_compound: Option<Flag>,
+}
+
+/// Decode a dictionary file in the encoding named by the affix file.
+fn decode(bytes: &[u8], encoding: &str) -> String {
+    match encoding.to_ascii_uppercase().as_str() {
+        "ISO8859-1" | "ISO-8859-1" | "ISO8859-15" | "ISO-8859-15" => bytes.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn encoding_of(aff: &[u8]) -> String {
+    aff.split(|&b| b == b'\n')
+        .find_map(|line| line.strip_prefix(b"SET "))
+        .map(|name| String::from_utf8_lossy(name).trim().to_string())
+        .unwrap_or_else(|| "UTF-8".to_string())
+}
+
+struct FlagParser {
+    kind: FlagType,
+    aliases: Vec<Vec<Flag>>,
+}
+
+impl FlagParser {
+    fn parse(&self, field: &str) -> Vec<Flag> {
+        if !self.aliases.is_empty() {
+            if let Ok(index) = field.parse::<usize>() {
+                return self.aliases.get(index.wrapping_sub(1)).cloned().unwrap_or_default();
+            }
+        }
+        self.parse_raw(field)
+    }
+
+    fn parse_raw(&self, field: &str) -> Vec<Flag> {
+        match self.kind {
+            FlagType::Short | FlagType::Utf8 => field.chars().map(|c| c as Flag).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = field.chars().collect();
+                chars
+                    .chunks(2)
+                    .map(|pair| ((pair[0] as Flag) << 16) | pair.get(1).map_or(0, |&c| c as Flag))
+                    .collect()
+            }
+            FlagType::Num => field.split(',').filter_map(|n| n.trim().parse().ok()).collect(),
+        }
+    }
+
+    fn single(&self, field: &str) -> Option<Flag> {
+        self.parse_raw(field).first().copied()
+    }
+}
+
+/// `0` stands for the empty string in affix rules.
+fn affix_text(field: &str) -> String {
+    if field == "0" {
+        String::new()
+    } else {
+        field.to_string()
+    }
+}
+
+impl Dictionary {
+    pub fn load(aff_path: &Path, dic_path: &Path) -> Result<Dictionary, String> {
+        let aff_bytes = std::fs::read(aff_path).map_err(|e| format!("read_failed:{}", e))?;
+        let dic_bytes = std::fs::read(dic_path).map_err(|e| format!("read_failed:{}", e))?;
+        let encoding = encoding_of(&aff_bytes);
+        Ok(Self::parse(&decode(&aff_bytes, &encoding), &decode(&dic_bytes, &encoding)))
+    }
+
+    pub fn parse(aff: &str, dic: &str) -> Dictionary {
+        let mut dict = Dictionary::default();
+        let mut flags = FlagParser {
+            kind: FlagType::Short,
+            aliases: Vec::new(),
+        };
+        // Affix block being read: (is prefix, flag, cross product, rules left).
+        let mut block: Option<(bool, Flag, bool, usize)> = None;
+        let mut raw_flags: Vec<(&str, &str)> = Vec::new();
+        let mut af_header = false;
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(&keyword) = fields.first() else { continue; };
+            match (keyword, fields.len()) {
+                ("FLAG", 2..) => {
+                    flags.kind = match fields[1] {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Num,
+                        "UTF-8" => FlagType::Utf8,
+                        _ => FlagType::Short,
+                    }
+                }
+                // The first AF line holds the count.
+                ("AF", 2..) if !af_header => af_header = true,
+                ("AF", 2..) => {
+                    let alias = flags.parse_raw(fields[1]);
+                    flags.aliases.push(alias);
+                }
+                ("TRY", 2..) => dict.try_chars = fields[1].chars().collect(),
+                ("KEY", 2..) => dict.keyboard = fields[1].split('|').map(|row| row.chars().collect()).collect(),
+                ("REP", 3..) => dict.replacements.push((fields[1].to_string(), fields[2].replace('_', " "))),
+                ("NEEDAFFIX" | "PSEUDOROOT" | "FORBIDDENWORD" | "NOSUGGEST" | "ONLYINCOMPOUND", 2..) => {
+                    raw_flags.push((keyword, fields[1]));
+                }
+                ("PFX" | "SFX", 4..) => {
+                    let is_prefix = keyword == "PFX";
+                    let flag = flags.single(fields[1]).unwrap_or(0);
+                    match block.as_mut() {
+                        Some((prefix, current, cross, left)) if *prefix == is_prefix && *current == flag && *left > 0 => {
+                            *left -= 1;
+                            let (add, _continuation) = fields[3].split_once('/').unwrap_or((fields[3], ""));
+                            let affix = Affix {
+                                flag,
+                                cross: *cross,
+                                strip: affix_text(fields[2]),
+                                add: affix_text(add),
+                                condition: parse_condition(fields.get(4).copied().unwrap_or(".")),
+                            };
+                            if is_prefix {
+                                dict.prefixes.push(affix);
+                            } else {
+                                dict.suffixes.push(affix);
+                            }
+                        }
+                        _ => block = Some((is_prefix, flag, fields[2] == "Y", fields[3].parse().unwrap_or(0))),
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (keyword, field) in raw_flags {
+            let flag = flags.single(field);
+            match keyword {
+                "NEEDAFFIX" | "PSEUDOROOT" => dict.need_affix = flag,
+                "FORBIDDENWORD" => dict.forbidden = flag,
+                "NOSUGGEST" => dict.no_suggest = flag,
+                _ => dict.only_in_compound = flag,
+            }
+        }
+        for (i, affix) in dict.prefixes.iter().enumerate() {
+            dict.prefix_index.entry(affix.add.clone()).or_default().push(i);
+        }
+        for (i, affix) in dict.suffixes.iter().enumerate() {
+            dict.suffix_index.entry(affix.add.clone()).or_default().push(i);
+        }
+
+        // The first line holds the approximate word count.
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else { continue; };
+            if entry.starts_with('/') {
+                continue;
+            }
+            let (word, word_flags) = match entry.find('/') {
+                Some(i) if i > 0 && !entry[..i].ends_with('\\') => (&entry[..i], flags.parse(&entry[i + 1..])),
+                _ => (entry, Vec::new()),
+            };
+            let word = word.replace("\\/", "/");
+            dict.words.entry(word).or_default().push(word_flags);
+        }
+        dict
+    }
+
+    /// Whether `word` is a root that may stand on its own (`affix: None`)
+    /// or take the affix with `affix` as its flag.
+    fn has_root(&self, word: &str, affix: Option<Flag>) -> bool {
+        let Some(homonyms) = self.words.get(word) else {
+            return false;
+        };
+        homonyms.iter().any(|flags| {
+            let has = |flag: Option<Flag>| flag.is_some_and(|f| flags.contains(&f));
+            if has(self.forbidden) {
+                return false;
+            }
+            match affix {
+                Some(flag) => flags.contains(&flag),
+                None => !has(self.need_affix) && !has(self.only_in_compound),
+            }
+        })
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        let Some(forbidden) = self.forbidden else {
+            return false;
+        };
+        self.words
+            .get(word)
+            .is_some_and(|homonyms| homonyms.iter().any(|flags| flags.contains(&forbidden)))
+    }
+
+    /// Roots `word` can be derived from with one suffix rule, with the rule.
+    fn suffix_roots<'a>(&'a self, word: &'a str) -> impl Iterator<Item = (String, &'a Affix)> + 'a {
+        let ends = word.char_indices().map(|(i, _)| i).skip(1).chain(std::iter::once(word.len()));
+        ends.flat_map(move |i| {
+            let (stem, add) = word.split_at(i);
+            self.suffix_index
+                .get(add)
+                .into_iter()
+                .flatten()
+                .map(move |&rule| (stem, &self.suffixes[rule]))
+        })
+        .filter_map(|(stem, affix)| {
+            let root = format!("{}{}", stem, affix.strip);
+            affix.matches_end(&root).then_some((root, affix))
+        })
+    }
+
+    /// Roots `word` can be derived from with one prefix rule, with the rule.
+    fn prefix_roots<'a>(&'a self, word: &'a str) -> impl Iterator<Item = (String, &'a Affix)> + 'a {
+        let starts = std::iter::once(0).chain(word.char_indices().map(|(i, _)| i).skip(1));
+        starts
+            .flat_map(move |i| {
+                let (add, rest) = word.split_at(i);
+                self.prefix_index
+                    .get(add)
+                    .into_iter()
+                    .flatten()
+                    .map(move |&rule| (rest, &self.prefixes[rule]))
+            })
+            .filter_map(|(rest, affix)| {
+                let root = format!("{}{}", affix.strip, rest);
+                (!rest.is_empty() && affix.matches_start(&root)).then_some((root, affix))
+            })
+    }
+
+    /// Check `word` exactly as written.
+    fn check_exact(&self, word: &str) -> bool {
+        if self.is_forbidden(word) {
+            return false;
+        }
+        if self.has_root(word, None) {
+            return true;
+        }
+        if self.suffix_roots(word).any(|(root, sfx)| self.has_root(&root, Some(sfx.flag))) {
+            return true;
+        }
+        self.prefix_roots(word).any(|(root, pfx)| {
+            self.has_root(&root, Some(pfx.flag))
+                || (pfx.cross
+                    && self.suffix_roots(&root).any(|(inner, sfx)| {
+                        sfx.cross && self.has_root(&inner, Some(pfx.flag)) && self.has_root(&inner, Some(sfx.flag))
+                    }))
+        })
+    }
+
+    /// Check `word`, also accepting capitalized and upper-case forms of
+    /// lower-case entries.
+    pub fn check(&self, word: &str) -> bool {
+        if self.check_exact(word) {
+            return true;
+        }
+        let normalized = word.replace('’', "'");
+        if normalized != word && self.check_exact(&normalized) {
+            return true;
+        }
+        match casing(&normalized) {
+            Casing::Capitalized => self.check_exact(&normalized.to_lowercase()),
+            Casing::Upper => {
+                let lower = normalized.to_lowercase();
+                self.check_exact(&lower) || self.check_exact(&capitalize(&lower))
+            }
+            Casing::Lower | Casing::Mixed => false,
+        }
+    }
+
+    fn suggestable(&self, word: &str) -> bool {
+        let no_suggest = self.no_suggest;
+        let flagged = no_suggest.is_some_and(|flag| {
+            self.words
+                .get(word)
+                .is_some_and(|homonyms| homonyms.iter().all(|flags| flags.contains(&flag)))
+        });
+        !flagged && self.check(word)
+    }
+
+    fn are_neighbors(&self, a: char, b: char) -> bool {
+        self.keyboard.iter().any(|row| {
+            row.windows(2)
+                .any(|pair| (pair[0] == a && pair[1] == b) || (pair[0] == b && pair[1] == a))
+        })
+    }
+
+    /// Up to `limit` corrections for `word`, most likely first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let casing = casing(word);
+        let lower = if matches!(casing, Casing::Capitalized | Casing::Upper) {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        };
+        let chars: Vec<char> = lower.chars().collect();
+        let mut candidates: Vec<(u8, usize, String)> = Vec::new();
+        let mut push = |rank: u8, candidate: String| {
+            let order = candidates.len();
+            candidates.push((rank, order, candidate));
+        };
+        let rebuild = |chars: &[char]| chars.iter().collect::<String>();
+
+        // Known typo patterns first.
+        for (from, to) in &self.replacements {
+            let anchored_start = from.starts_with('^');
+            let anchored_end = from.ends_with('$') && from.len() > 1;
+            let pattern = from.trim_start_matches('^').trim_end_matches('$');
+            if pattern.is_empty() {
+                continue;
+            }
+            for (i, _) in lower.match_indices(pattern) {
+                if (anchored_start && i != 0) || (anchored_end && i + pattern.len() != lower.len()) {
+                    continue;
+                }
+                push(0, format!("{}{}{}", &lower[..i], to, &lower[i + pattern.len()..]));
+            }
+        }
+        // Swapped letters and neighboring keys.
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(i, i + 1);
+            push(1, rebuild(&swapped));
+        }
+        for i in 0..chars.len() {
+            for &c in &self.try_chars {
+                if c != chars[i] && self.are_neighbors(c, chars[i]) {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    push(1, rebuild(&replaced));
+                }
+            }
+        }
+        // Doubled letters, then any single edit.
+        for i in 0..chars.len() {
+            let doubled = (i > 0 && chars[i - 1] == chars[i]) || chars.get(i + 1) == Some(&chars[i]);
+            let mut removed = chars.clone();
+            removed.remove(i);
+            push(if doubled { 1 } else { 2 }, rebuild(&removed));
+        }
+        for i in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                push(2, rebuild(&inserted));
+            }
+        }
+        for i in 0..chars.len() {
+            for &c in &self.try_chars {
+                if c != chars[i] {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    push(2, rebuild(&replaced));
+                }
+            }
+        }
+        // Two words run together.
+        for i in 1..chars.len() {
+            push(3, format!("{} {}", rebuild(&chars[..i]), rebuild(&chars[i..])));
+        }
+
+        candidates.sort_by_key(|(rank, order, _)| (*rank, *order));
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+        for (_, _, candidate) in candidates {
+            if suggestions.len() >= limit {
+                break;
+            }
+            if candidate == lower || !seen.insert(candidate.clone()) {
+                continue;
+            }
+            if candidate.split(' ').all(|part| !part.is_empty() && self.suggestable(part)) {
+                suggestions.push(candidate);
+            }
+        }
+        suggestions
+            .into_iter()
+            .map(|s| match casing {
+                Casing::Capitalized => capitalize(&s),
+                Casing::Upper => s.to_uppercase(),
+                Casing::Lower | Casing::Mixed => s,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+fn casing(word: &str) -> Casing {
+    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return Casing::Lower;
+    };
+    let rest: Vec<char> = letters.collect();
+    let rest_lower = rest.iter().all(|c| !c.is_uppercase());
+    let rest_upper = rest.iter().all(|c| !c.is_lowercase());
+    match (first.is_uppercase(), rest_lower, rest_upper) {
+        (false, true, _) => Casing::Lower,
+        (true, _, true) if !rest.is_empty() => Casing::Upper,
+        (true, true, _) => Casing::Capitalized,
+        _ => Casing::Mixed,
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// --- Checking text ---
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() && !readability::is_cjk(c)
+}
+
+/// Words worth checking in `text`, as byte ranges. URLs, e-mail addresses,
+/// paths, code spans and words with digits are skipped.
+fn words(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut in_code = false;
+    let mut offset = 0;
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += chunk.len();
+        if chunk.contains('`') {
+            if chunk.matches('`').count() % 2 == 1 {
+                in_code = !in_code;
+            }
+            continue;
+        }
+        let token = chunk.trim_end();
+        if in_code || token.contains("://") || token.contains('@') || token.contains('/') || token.contains('\\') {
+            continue;
+        }
+
+        // Letters with apostrophes or hyphens inside; hyphenated parts are
+        // checked one by one.
+        let mut word_start: Option<usize> = None;
+        let mut has_digit = false;
+        let chars: Vec<(usize, char)> = token.char_indices().collect();
+        for (n, &(i, c)) in chars.iter().enumerate() {
+            let joins = matches!(c, '\'' | '’')
+                && word_start.is_some()
+                && chars.get(n + 1).is_some_and(|&(_, next)| is_word_char(next));
+            if is_word_char(c) || joins {
+                word_start.get_or_insert(i);
+                continue;
+            }
+            if c.is_ascii_digit() || c == '_' {
+                has_digit = true;
+                word_start.get_or_insert(i);
+                continue;
+            }
+            if let Some(s) = word_start.take() {
+                if !has_digit {
+                    spans.push((start + s, start + i));
+                }
+            }
+            has_digit = false;
+        }
+        if let Some(s) = word_start {
+            if !has_digit {
+                spans.push((start + s, start + token.len()));
+            }
+        }
+    }
+    spans
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+/// Misspelled words in `text`. A word is correct if any dictionary knows
+/// it or it is on the personal list (compared case-insensitively).
+pub fn check_text(dictionaries: &[Arc<Dictionary>], personal: &[String], text: &str) -> Vec<Misspelling> {
+    if dictionaries.is_empty() {
+        return Vec::new();
+    }
+    let personal: HashSet<String> = personal.iter().map(|w| w.to_lowercase()).collect();
+    let mut misspellings = Vec::new();
+    let mut utf16_pos = 0;
+    let mut byte_pos = 0;
+    for (start, end) in words(text) {
+        utf16_pos += utf16_len(&text[byte_pos..start]);
+        let word = &text[start..end];
+        let word_len = utf16_len(word);
+        let skip = word.chars().count() < 2
+            || personal.contains(&word.to_lowercase())
+            || dictionaries.iter().any(|dict| dict.check(word));
+        if !skip {
+            let mut suggestions: Vec<String> = Vec::new();
+            for dict in dictionaries {
+                for suggestion in dict.suggest(word, MAX_SUGGESTIONS) {
+                    if suggestions.len() < MAX_SUGGESTIONS && !suggestions.contains(&suggestion) {
+                        suggestions.push(suggestion);
+                    }
+                }
+            }
+            misspellings.push(Misspelling {
+                word: word.to_string(),
+                range: TextRange {
+                    start: utf16_pos,
+                    end: utf16_pos + word_len,
+                },
+                suggestions,
+            });
+            if misspellings.len() >= MAX_MISSPELLINGS {
+                break;
+            }
+        }
+        utf16_pos += word_len;
+        byte_pos = end;
+    }
+    misspellings
+}
+
+// --- Dictionary lookup ---
+
+/// Dictionaries loaded so far, by language.
+static LOADED: Mutex<Vec<(String, Arc<Dictionary>)>> = Mutex::new(Vec::new());
+
+fn user_folder(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join("dictionaries"))
+}
+
+/// Folders searched for dictionaries, the app's own first.
+fn search_folders(app: &AppHandle) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = user_folder(app).into_iter().collect();
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            folders.push(home.join("Library/Spelling"));
+        }
+        folders.push(PathBuf::from("/Library/Spelling"));
+    }
+    #[cfg(target_os = "linux")]
+    {
+        folders.push(PathBuf::from("/usr/share/hunspell"));
+        folders.push(PathBuf::from("/usr/share/myspell/dicts"));
+        folders.push(PathBuf::from("/usr/share/myspell"));
+    }
+    folders
+}
+
+/// `en-US` and `en_US` name the same dictionary.
+fn file_stem(language: &str) -> String {
+    language.trim().replace('-', "_")
+}
+
+fn find(app: &AppHandle, language: &str) -> Option<(PathBuf, PathBuf)> {
+    let stem = file_stem(language);
+    search_folders(app).into_iter().find_map(|folder| {
+        let aff = folder.join(format!("{}.aff", stem));
+        let dic = folder.join(format!("{}.dic", stem));
+        (aff.is_file() && dic.is_file()).then_some((aff, dic))
+    })
+}
+
+/// Dictionaries for `languages`, loading the ones not loaded yet.
+/// Languages without a dictionary are skipped.
+fn dictionaries(app: &AppHandle, languages: &[String]) -> Vec<Arc<Dictionary>> {
+    let mut loaded = LOADED.lock().unwrap();
+    let mut result = Vec::new();
+    for language in languages {
+        if let Some((_, dict)) = loaded.iter().find(|(l, _)| l == language) {
+            result.push(dict.clone());
+            continue;
+        }
+        let Some((aff, dic)) = find(app, language) else {
+            println!("[Polishr] No dictionary found for {}", language);
+            continue;
+        };
+        match Dictionary::load(&aff, &dic) {
+            Ok(dict) => {
+                println!("[Polishr] Loaded {} dictionary ({} words)", language, dict.words.len());
+                let dict = Arc::new(dict);
+                loaded.push((language.clone(), dict.clone()));
+                result.push(dict);
+            }
+            Err(err) => println!("[Polishr] Failed to load {} dictionary: {}", language, err),
+        }
+    }
+    result
+}
+
+// --- Commands ---
+
+/// Misspelled words in `text`, checked locally.
+#[tauri::command]
+pub async fn check_spelling(app: AppHandle, text: String) -> Result<Vec<Misspelling>, String> {
+    let spell_check = settings::with(|s| s.spell_check.clone());
+    if !spell_check.enabled {
+        return Ok(Vec::new());
+    }
+    let dictionaries = dictionaries(&app, &spell_check.languages);
+    if dictionaries.is_empty() {
+        return Err("no_dictionary".to_string());
+    }
+    Ok(check_text(&dictionaries, &spell_check.personal_words, &text))
+}
+
+/// Add `word` to the personal word list.
+#[tauri::command]
+pub async fn add_personal_word(app: AppHandle, word: String) -> Result<(), String> {
+    let word = word.trim().to_string();
+    if word.is_empty() {
+        return Err("word_required".to_string());
+    }
+    settings::update(&app, |settings| {
+        let words = &mut settings.spell_check.personal_words;
+        if !words.iter().any(|w| w.to_lowercase() == word.to_lowercase()) {
+            words.push(word);
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Dictionaries available on this machine.
+#[tauri::command]
+pub async fn list_dictionaries(app: AppHandle) -> Result<DictionaryList, String> {
+    let folder = user_folder(&app).ok_or_else(|| "no_data_dir".to_string())?;
+    let mut languages: Vec<String> = search_folders(&app)
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stem = path.file_stem()?.to_str()?.to_string();
+            (path.extension()? == "dic" && path.with_extension("aff").is_file()).then_some(stem)
+        })
+        .collect();
+    languages.sort();
+    languages.dedup();
+    Ok(DictionaryList {
+        folder: folder.to_string_lossy().into_owned(),
+        languages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(aff: &str, words: &[&str]) -> Dictionary {
+        let dic = format!("{}\n{}\n", words.len(), words.join("\n"));
+        Dictionary::parse(aff, &dic)
+    }
+
+    #[test]
+    fn reads_every_flag_type_and_aliases() {
+        let long = dictionary(
+            "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\nPFX Bb Y 1\nPFX Bb 0 re .",
+            &["work/AaBb", "play/Aa"],
+        );
+        assert!(long.check("works") && long.check("rework") && long.check("plays"));
+        assert!(!long.check("replay"));
+
+        let num = dictionary("FLAG num\nSFX 101 Y 1\nSFX 101 0 ed .\nSFX 7 Y 1\nSFX 7 0 er .", &["walk/101,7", "talk/7"]);
+        assert!(num.check("walked") && num.check("walker") && num.check("talker"));
+        assert!(!num.check("talked"));
+
+        let utf8 = dictionary("FLAG UTF-8\nSFX é Y 1\nSFX é 0 ing .", &["sing/é"]);
+        assert!(utf8.check("singing"));
+
+        let aliases = dictionary(
+            "AF 2\nAF AB\nAF B\nSFX A Y 1\nSFX A 0 s .\nSFX B Y 1\nSFX B 0 ed .",
+            &["jump/1", "kick/2"],
+        );
+        assert!(aliases.check("jumps") && aliases.check("jumped") && aliases.check("kicked"));
+        assert!(!aliases.check("kicks"));
+    }
+
+    #[test]
+    fn applies_conditions_and_cross_products() {
+        let dict = dictionary(
+            "SFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\n\
+             PFX U Y 1\nPFX U 0 un .\nPFX N N 1\nPFX N 0 non .\nSFX D Y 1\nSFX D 0 ed .",
+            &["city/S", "dog/S", "lock/UD", "rest/ND", "pack/U"],
+        );
+        assert!(dict.check("cities") && dict.check("dogs"));
+        assert!(!dict.check("citys") && !dict.check("dogies"));
+        assert!(dict.check("unlocked") && dict.check("nonrest") && dict.check("rested"));
+        // `N` does not combine with suffixes, and `pack` has no `D`.
+        assert!(!dict.check("nonrested"));
+        assert!(!dict.check("unpacked"));
+    }
+
+    #[test]
+    fn honors_needaffix_and_forbidden_words() {
+        let dict = dictionary(
+            "NEEDAFFIX X\nFORBIDDENWORD F\nSFX S Y 1\nSFX S 0 s .",
+            &["foo/XS", "colour/S", "colours/F"],
+        );
+        assert!(dict.check("foos"));
+        assert!(!dict.check("foo"));
+        assert!(dict.check("colour"));
+        assert!(!dict.check("colours") && !dict.check("Colours"));
+    }
+
+    #[test]
+    fn ranks_known_typos_first_and_anchors_replacements() {
+        let dict = dictionary(
+            "TRY abcdefghijklmnopqrstuvwxyz\nREP 3\nREP ^alot$ a_lot\nREP ^ph f\nREP shun$ tion",
+            &["a", "lot", "slot", "allot", "fat", "alfa", "nation", "nations"],
+        );
+        let alot = dict.suggest("alot", 5);
+        assert_eq!(alot.first().map(String::as_str), Some("a lot"));
+        assert!(alot.contains(&"slot".to_string()) && alot.contains(&"allot".to_string()));
+        assert_eq!(dict.suggest("Alot", 1), ["A lot"]);
+
+        assert_eq!(dict.suggest("phat", 5).first().map(String::as_str), Some("fat"));
+        assert!(!dict.suggest("alpha", 5).contains(&"alfa".to_string()));
+        assert_eq!(dict.suggest("nashun", 5).first().map(String::as_str), Some("nation"));
+        assert!(!dict.suggest("nashuns", 5).contains(&"nations".to_string()));
+    }
+
+    #[test]
+    fn reports_utf16_ranges_outside_code_spans() {
+        let dict = Arc::new(dictionary("", &["the", "code"]));
+        let text = "😀 the `teh code` teh Polishr.";
+        let misspellings = check_text(&[dict], &["polishr".to_string()], text);
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].word, "teh");
+        assert_eq!((misspellings[0].range.start, misspellings[0].range.end), (18, 21));
+        assert_eq!(misspellings[0].suggestions, ["the"]);
+
+        assert_eq!(words("don't x-ray v2 a@b.c `x`"), [(0, 5), (6, 7), (8, 11)]);
+    }
+}
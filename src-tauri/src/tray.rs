@@ -1,27 +1,54 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::settings;
 use tauri::{
     AppHandle,
+    Emitter,
     Manager,
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder},
+    Listener,
 };
 
+const TRAY_ID: &str = "main";
+/// How long an error stays next to the tray icon.
+const FLASH_DURATION: Duration = Duration::from_secs(4);
+
+/// Bumped on every status change so a pending flash does not clear a newer one.
+static STATUS_GEN: AtomicU64 = AtomicU64::new(0);
+
 pub fn create_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let settings_item = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
+    let export_item = MenuItemBuilder::with_id("export_history", "Export History…").build(app)?;
+    let pause_item = CheckMenuItemBuilder::with_id("pause_trigger", "Pause Trigger")
+        .checked(settings::with(|s| s.trigger_paused))
+        .build(app)?;
     let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
 
     let menu = MenuBuilder::new(app)
         .item(&settings_item)
+        .item(&export_item)
+        .item(&pause_item)
         .separator()
         .item(&quit_item)
         .build()?;
 
-    TrayIconBuilder::new()
+    TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .tooltip("Polishr")
         .on_menu_event(move |app, event| match event.id().as_ref() {
             "settings" => {
                 show_settings_window(app);
             }
+            "export_history" => {
+                show_settings_window(app);
+                // The History page picks the format and scope before writing anything.
+                let _ = app.emit_to("settings", "open-page", "history");
+            }
+            "pause_trigger" => {
+                toggle_trigger_pause(app);
+            }
             "quit" => {
                 app.exit(0);
             }
@@ -40,9 +67,45 @@ pub fn create_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
         })
         .build(app)?;
 
+    // Keep the check mark in sync with settings changed elsewhere.
+    app.listen_any("settings-changed", move |_| {
+        let _ = pause_item.set_checked(settings::with(|s| s.trigger_paused));
+    });
+
     Ok(())
 }
 
+fn apply_status(app: &AppHandle, status: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = match status {
+        Some(status) => format!("Polishr — {}", status),
+        None => "Polishr".to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+    let _ = tray.set_title(status);
+}
+
+/// Show a short status next to the tray icon, or clear it with `None`.
+pub fn set_status(app: &AppHandle, status: Option<&str>) {
+    STATUS_GEN.fetch_add(1, Ordering::SeqCst);
+    apply_status(app, status);
+}
+
+/// Show a status for a few seconds, e.g. an error from a background task.
+pub fn flash_status(app: &AppHandle, status: &str) {
+    let gen = STATUS_GEN.fetch_add(1, Ordering::SeqCst) + 1;
+    apply_status(app, Some(status));
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(FLASH_DURATION);
+        if STATUS_GEN.load(Ordering::SeqCst) == gen {
+            apply_status(&app, None);
+        }
+    });
+}
+
 /// Show the settings window, creating it if it was closed.
 fn show_settings_window(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("settings") {
@@ -51,3 +114,14 @@ fn show_settings_window(app: &AppHandle) {
         let _ = window.set_focus();
     }
 }
+
+/// Pause or resume the selection trigger.
+fn toggle_trigger_pause(app: &AppHandle) {
+    let result = settings::update(app, |settings| {
+        settings.trigger_paused = !settings.trigger_paused;
+        Ok(())
+    });
+    if let Err(err) = result {
+        println!("[Polishr] Failed to toggle trigger pause: {}", err);
+    }
+}
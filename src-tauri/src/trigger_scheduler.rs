@@ -0,0 +1,271 @@
+//! Decides when the selection trigger re-reads the focused element.
+//!
+//! Peeking at the focused element is only worth it when something may have
+//! changed. The scheduler wakes up on accessibility notifications from the
+//! focused app where the backend provides them, and after keyboard or mouse
+//! input. With neither, the polling interval backs off while nothing
+//! changes. Nothing is peeked while the screen is locked or the trigger is
+//! paused from the tray.
+//!
+//! Only macOS has a selection backend, so the scheduler is not started on
+//! other platforms; [`get_trigger_scheduler_state`] then reports it as not
+//! running.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Listener};
+
+#[cfg(target_os = "macos")]
+use crate::ax_text;
+use crate::settings;
+
+/// Interval right after input or a change, so the trigger follows the user.
+const ACTIVE_INTERVAL: Duration = Duration::from_millis(150);
+/// Longest interval while polling without notifications.
+const IDLE_INTERVAL: Duration = Duration::from_millis(2000);
+/// Longest interval while notifications cover the focused app. Kept finite
+/// because apps do not report every change (e.g. scrolling).
+const OBSERVED_IDLE_INTERVAL: Duration = Duration::from_millis(5000);
+/// How often to check whether a pause is over.
+const PAUSED_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Why the scheduler stopped peeking.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    ScreenLocked,
+    /// Paused from the tray (`triggerPaused` in settings).
+    Disabled,
+}
+
+/// What woke the scheduler up.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WakeReason {
+    /// Accessibility notification from the focused app.
+    Notification,
+    /// Keyboard or mouse input since the last wake-up.
+    Input,
+    /// Settings changed, e.g. the trigger was paused or resumed.
+    Settings,
+    /// Another part of the app has news for the trigger, e.g. a background
+    /// check finished.
+    Requested,
+    Timer,
+}
+
+/// Scheduler state for diagnostics.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SchedulerState {
+    pub running: bool,
+    pub paused: Option<PauseReason>,
+    /// Whether the focused app delivers change notifications.
+    pub observing: bool,
+    /// Current wait between wake-ups.
+    pub interval_ms: u64,
+    pub last_wake: Option<WakeReason>,
+    /// Wake-ups that peeked at the focused element.
+    pub peeks: u64,
+    /// Wake-ups that found no reason to peek.
+    pub skipped: u64,
+}
+
+static STATE: Mutex<Option<SchedulerState>> = Mutex::new(None);
+/// Sender for [`wake`], set once the scheduler runs.
+static WAKE: Mutex<Option<mpsc::Sender<Wake>>> = Mutex::new(None);
+
+/// What the scheduler drives: the trigger window.
+pub trait TriggerProbe: Send + 'static {
+    /// Re-read the focused element and update the trigger. Returns whether
+    /// anything the user can see changed.
+    fn refresh(&mut self, app: &AppHandle) -> bool;
+    /// Hide the trigger while the scheduler is paused.
+    fn pause(&mut self, app: &AppHandle);
+}
+
+enum Wake {
+    Notification,
+    Settings,
+    Requested,
+}
+
+/// Interval that grows while nothing happens and snaps back on activity.
+struct Backoff {
+    interval: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self {
+            interval: ACTIVE_INTERVAL,
+            max: IDLE_INTERVAL,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.interval = ACTIVE_INTERVAL;
+    }
+
+    fn idle(&mut self) {
+        self.interval = (self.interval * 3 / 2).min(self.max);
+    }
+}
+
+fn update_state(change: impl FnOnce(&mut SchedulerState)) {
+    let mut guard = STATE.lock().unwrap();
+    change(guard.get_or_insert_with(SchedulerState::default));
+}
+
+fn pause_reason() -> Option<PauseReason> {
+    if screen_locked() {
+        Some(PauseReason::ScreenLocked)
+    } else if settings::with(|s| s.trigger_paused) {
+        Some(PauseReason::Disabled)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn screen_locked() -> bool {
+    ax_text::screen_locked()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn screen_locked() -> bool {
+    false
+}
+
+/// Time since the last keyboard or mouse event, if the platform reports it.
+#[cfg(target_os = "macos")]
+fn input_idle() -> Option<Duration> {
+    Some(Duration::from_secs_f64(ax_text::input_idle_seconds().max(0.0)))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn input_idle() -> Option<Duration> {
+    None
+}
+
+/// Start delivering change notifications for whichever app has focus.
+#[cfg(target_os = "macos")]
+fn watch_focused_app(on_change: impl Fn() + Send + 'static) {
+    ax_text::watch_focused_app(on_change);
+}
+
+#[cfg(not(target_os = "macos"))]
+fn watch_focused_app(_on_change: impl Fn() + Send + 'static) {}
+
+#[cfg(target_os = "macos")]
+fn observing() -> bool {
+    ax_text::is_observing()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn observing() -> bool {
+    false
+}
+
+/// Refresh the trigger now instead of at the next scheduled wake-up.
+pub fn wake() {
+    if let Some(tx) = WAKE.lock().unwrap().as_ref() {
+        let _ = tx.send(Wake::Requested);
+    }
+}
+
+/// Run `probe` on a background thread for the rest of the app's life.
+pub fn start(app: AppHandle, mut probe: impl TriggerProbe) {
+    let (tx, rx) = mpsc::channel::<Wake>();
+    let notify = tx.clone();
+    *WAKE.lock().unwrap() = Some(tx.clone());
+    watch_focused_app(move || {
+        let _ = notify.send(Wake::Notification);
+    });
+    app.listen_any("settings-changed", move |_| {
+        let _ = tx.send(Wake::Settings);
+    });
+    update_state(|s| s.running = true);
+
+    std::thread::spawn(move || {
+        let mut backoff = Backoff::new();
+        let mut paused: Option<PauseReason> = None;
+        let mut last_wake = Instant::now();
+
+        loop {
+            let wait = if paused.is_some() { PAUSED_INTERVAL } else { backoff.interval };
+            let mut reason = match rx.recv_timeout(wait) {
+                Ok(Wake::Notification) => WakeReason::Notification,
+                Ok(Wake::Settings) => WakeReason::Settings,
+                Ok(Wake::Requested) => WakeReason::Requested,
+                Err(RecvTimeoutError::Timeout) => WakeReason::Timer,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            // One peek covers a burst of notifications (e.g. typing).
+            while rx.try_recv().is_ok() {}
+            let since_last = last_wake.elapsed();
+            last_wake = Instant::now();
+
+            let pause = pause_reason();
+            if pause != paused {
+                match pause {
+                    Some(pause) => {
+                        println!("[Polishr] Trigger paused: {:?}", pause);
+                        probe.pause(&app);
+                    }
+                    None => println!("[Polishr] Trigger resumed"),
+                }
+                paused = pause;
+                backoff.reset();
+            }
+            if paused.is_some() {
+                update_state(|s| {
+                    s.paused = paused;
+                    s.interval_ms = PAUSED_INTERVAL.as_millis() as u64;
+                });
+                continue;
+            }
+
+            let input = input_idle().is_some_and(|idle| idle < since_last);
+            if input && reason == WakeReason::Timer {
+                reason = WakeReason::Input;
+            }
+            let observing = observing();
+            backoff.max = if observing { OBSERVED_IDLE_INTERVAL } else { IDLE_INTERVAL };
+
+            // With notifications, a timer wake-up without input only peeks
+            // once the interval has backed off all the way.
+            let peek = reason != WakeReason::Timer || !observing || backoff.interval >= backoff.max;
+            let changed = peek && probe.refresh(&app);
+            if changed || reason != WakeReason::Timer {
+                backoff.reset();
+            } else {
+                backoff.idle();
+            }
+
+            update_state(|s| {
+                s.paused = None;
+                s.observing = observing;
+                s.interval_ms = backoff.interval.as_millis() as u64;
+                s.last_wake = Some(reason);
+                if peek {
+                    s.peeks += 1;
+                } else {
+                    s.skipped += 1;
+                }
+            });
+        }
+    });
+}
+
+// --- Commands ---
+
+/// Scheduler state for diagnostics.
+#[tauri::command]
+pub async fn get_trigger_scheduler_state() -> SchedulerState {
+    STATE.lock().unwrap().clone().unwrap_or_default()
+}
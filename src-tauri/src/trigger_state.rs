@@ -0,0 +1,356 @@
+//! What the trigger window shows, as a state machine.
+//!
+//! The poller turns each look at the focused element into a
+//! [`TriggerEvent`]; [`TriggerMachine::handle`] moves to the next
+//! [`TriggerState`] and reports whether the window has to be re-emitted or
+//! moved. The state is sent to the trigger window as-is, so the frontend
+//! renders exactly what the backend decided.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::Serialize;
+
+use crate::placement::Rect;
+
+/// Trigger state, sent to the trigger window as the `trigger-state` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum TriggerState {
+    /// Nothing to attach to, or the panel is open.
+    Hidden,
+    /// Paused from the tray, screen locked, or the app is excluded.
+    Disabled,
+    /// Blue button next to selected text.
+    Selection,
+    /// A quick fix is polishing the selected text.
+    Busy,
+    /// Gray line next to the paragraph under the caret.
+    Paragraph,
+    /// The background check found problems in the paragraph.
+    IssuesFound { count: usize },
+}
+
+impl TriggerState {
+    pub fn is_visible(self) -> bool {
+        !matches!(self, TriggerState::Hidden | TriggerState::Disabled)
+    }
+
+    /// Whether the trigger belongs to a selection rather than a paragraph.
+    pub fn is_selection(self) -> bool {
+        matches!(self, TriggerState::Selection | TriggerState::Busy)
+    }
+}
+
+/// Text the trigger is attached to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub text_len: usize,
+    /// Bounds of the text.
+    pub rect: Rect,
+    /// Left edge of the first line, where the trigger sits.
+    pub line_x: f64,
+}
+
+impl Anchor {
+    fn same_as(&self, other: &Anchor) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= 0.5;
+        self.text_len == other.text_len
+            && close(self.rect.x, other.rect.x)
+            && close(self.rect.y, other.rect.y)
+            && close(self.rect.width, other.rect.width)
+            && close(self.rect.height, other.rect.height)
+            && close(self.line_x, other.line_x)
+    }
+}
+
+/// What the poller found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerEvent {
+    /// Text is selected in an app where the trigger is allowed. `busy` when
+    /// a quick fix is running for that text.
+    SelectionFocused { anchor: Anchor, busy: bool },
+    /// No selection, but the caret is in a paragraph.
+    ParagraphFocused { anchor: Anchor },
+    /// The background check finished for the current paragraph.
+    IssuesChecked { count: usize },
+    /// The focused app is excluded from the trigger.
+    AppExcluded,
+    /// The trigger was paused (tray or locked screen).
+    Paused,
+    /// Nothing to attach to, or the panel took over.
+    Cleared,
+}
+
+/// What a transition changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Transition {
+    /// The state changed and must be sent to the trigger window.
+    pub state: bool,
+    /// The trigger is attached to different text and must be moved.
+    pub anchor: bool,
+}
+
+impl Transition {
+    pub fn changed(self) -> bool {
+        self.state || self.anchor
+    }
+}
+
+#[derive(Debug)]
+pub struct TriggerMachine {
+    state: TriggerState,
+    anchor: Option<Anchor>,
+}
+
+impl Default for TriggerMachine {
+    fn default() -> Self {
+        Self {
+            state: TriggerState::Hidden,
+            anchor: None,
+        }
+    }
+}
+
+impl TriggerMachine {
+    pub fn state(&self) -> TriggerState {
+        self.state
+    }
+
+    pub fn anchor(&self) -> Option<Anchor> {
+        self.anchor
+    }
+
+    pub fn handle(&mut self, event: TriggerEvent) -> Transition {
+        let (state, anchor) = match event {
+            TriggerEvent::SelectionFocused { anchor, busy } => {
+                let state = if busy { TriggerState::Busy } else { TriggerState::Selection };
+                (state, Some(anchor))
+            }
+            TriggerEvent::ParagraphFocused { anchor } => {
+                // Findings only hold for the text they were made on.
+                let state = match self.state {
+                    TriggerState::IssuesFound { .. } if self.is_anchored_to(&anchor) => self.state,
+                    _ => TriggerState::Paragraph,
+                };
+                (state, Some(anchor))
+            }
+            TriggerEvent::IssuesChecked { count } => match self.state {
+                TriggerState::Paragraph | TriggerState::IssuesFound { .. } => {
+                    let state = if count > 0 {
+                        TriggerState::IssuesFound { count }
+                    } else {
+                        TriggerState::Paragraph
+                    };
+                    (state, self.anchor)
+                }
+                // Too late: the caret left the paragraph.
+                _ => return Transition::default(),
+            },
+            TriggerEvent::AppExcluded | TriggerEvent::Paused => (TriggerState::Disabled, None),
+            TriggerEvent::Cleared => (TriggerState::Hidden, None),
+        };
+
+        let moved = match (self.anchor, anchor) {
+            (Some(old), Some(new)) => {
+                !old.same_as(&new) || self.state.is_selection() != state.is_selection()
+            }
+            (None, None) => false,
+            _ => true,
+        };
+        let transition = Transition {
+            state: self.state != state,
+            anchor: moved,
+        };
+        self.state = state;
+        self.anchor = anchor;
+        transition
+    }
+
+    fn is_anchored_to(&self, anchor: &Anchor) -> bool {
+        self.anchor.is_some_and(|current| current.same_as(anchor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor(text_len: usize, y: f64) -> Anchor {
+        Anchor {
+            text_len,
+            rect: Rect::new(100.0, y, 300.0, 18.0),
+            line_x: 100.0,
+        }
+    }
+
+    fn selection(text_len: usize, y: f64) -> TriggerEvent {
+        TriggerEvent::SelectionFocused {
+            anchor: anchor(text_len, y),
+            busy: false,
+        }
+    }
+
+    fn paragraph(text_len: usize, y: f64) -> TriggerEvent {
+        TriggerEvent::ParagraphFocused {
+            anchor: anchor(text_len, y),
+        }
+    }
+
+    fn moved() -> Transition {
+        Transition { state: true, anchor: true }
+    }
+
+    #[test]
+    fn starts_hidden() {
+        let machine = TriggerMachine::default();
+        assert_eq!(machine.state(), TriggerState::Hidden);
+        assert!(!machine.state().is_visible());
+        assert_eq!(machine.anchor(), None);
+    }
+
+    #[test]
+    fn selection_shows_the_button() {
+        let mut machine = TriggerMachine::default();
+        assert_eq!(machine.handle(selection(5, 40.0)), moved());
+        assert_eq!(machine.state(), TriggerState::Selection);
+        assert_eq!(machine.anchor(), Some(anchor(5, 40.0)));
+    }
+
+    #[test]
+    fn same_selection_changes_nothing() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        assert!(!machine.handle(selection(5, 40.2)).changed());
+    }
+
+    #[test]
+    fn selection_that_moves_keeps_the_state() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        let transition = machine.handle(selection(5, 80.0));
+        assert_eq!(transition, Transition { state: false, anchor: true });
+        assert_eq!(machine.anchor(), Some(anchor(5, 80.0)));
+    }
+
+    #[test]
+    fn longer_selection_counts_as_new() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        assert!(machine.handle(selection(6, 40.0)).anchor);
+    }
+
+    #[test]
+    fn busy_while_quick_fix_runs() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        let busy = TriggerEvent::SelectionFocused {
+            anchor: anchor(5, 40.0),
+            busy: true,
+        };
+        assert_eq!(machine.handle(busy), Transition { state: true, anchor: false });
+        assert_eq!(machine.state(), TriggerState::Busy);
+        assert_eq!(machine.handle(selection(5, 40.0)), Transition { state: true, anchor: false });
+        assert_eq!(machine.state(), TriggerState::Selection);
+    }
+
+    #[test]
+    fn paragraph_after_selection_moves_the_trigger() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        // Same bounds, but a different kind of trigger.
+        assert_eq!(machine.handle(paragraph(5, 40.0)), moved());
+        assert_eq!(machine.state(), TriggerState::Paragraph);
+    }
+
+    #[test]
+    fn issues_found_in_paragraph() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(paragraph(120, 40.0));
+        let transition = machine.handle(TriggerEvent::IssuesChecked { count: 3 });
+        assert_eq!(transition, Transition { state: true, anchor: false });
+        assert_eq!(machine.state(), TriggerState::IssuesFound { count: 3 });
+
+        // Still on the same text: the findings stay.
+        assert!(!machine.handle(paragraph(120, 40.0)).changed());
+        assert_eq!(machine.state(), TriggerState::IssuesFound { count: 3 });
+    }
+
+    #[test]
+    fn new_count_replaces_the_old_one() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(paragraph(120, 40.0));
+        machine.handle(TriggerEvent::IssuesChecked { count: 3 });
+        assert!(machine.handle(TriggerEvent::IssuesChecked { count: 1 }).state);
+        assert_eq!(machine.state(), TriggerState::IssuesFound { count: 1 });
+        machine.handle(TriggerEvent::IssuesChecked { count: 0 });
+        assert_eq!(machine.state(), TriggerState::Paragraph);
+    }
+
+    #[test]
+    fn typing_drops_the_findings() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(paragraph(120, 40.0));
+        machine.handle(TriggerEvent::IssuesChecked { count: 3 });
+        assert_eq!(machine.handle(paragraph(121, 40.0)), moved());
+        assert_eq!(machine.state(), TriggerState::Paragraph);
+    }
+
+    #[test]
+    fn issues_ignored_outside_paragraphs() {
+        let mut machine = TriggerMachine::default();
+        assert!(!machine.handle(TriggerEvent::IssuesChecked { count: 2 }).changed());
+        assert_eq!(machine.state(), TriggerState::Hidden);
+
+        machine.handle(selection(5, 40.0));
+        assert!(!machine.handle(TriggerEvent::IssuesChecked { count: 2 }).changed());
+        assert_eq!(machine.state(), TriggerState::Selection);
+    }
+
+    #[test]
+    fn excluded_app_disables() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        assert_eq!(machine.handle(TriggerEvent::AppExcluded), moved());
+        assert_eq!(machine.state(), TriggerState::Disabled);
+        assert!(!machine.state().is_visible());
+        assert_eq!(machine.anchor(), None);
+        assert!(!machine.handle(TriggerEvent::AppExcluded).changed());
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(paragraph(120, 40.0));
+        machine.handle(TriggerEvent::Paused);
+        assert_eq!(machine.state(), TriggerState::Disabled);
+        // The poller only reports focus again once resumed.
+        assert_eq!(machine.handle(paragraph(120, 40.0)), moved());
+        assert_eq!(machine.state(), TriggerState::Paragraph);
+    }
+
+    #[test]
+    fn cleared_hides() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(selection(5, 40.0));
+        assert_eq!(machine.handle(TriggerEvent::Cleared), moved());
+        assert_eq!(machine.state(), TriggerState::Hidden);
+        assert!(!machine.handle(TriggerEvent::Cleared).changed());
+    }
+
+    #[test]
+    fn disabled_to_hidden_is_a_state_change_only() {
+        let mut machine = TriggerMachine::default();
+        machine.handle(TriggerEvent::Paused);
+        assert_eq!(machine.handle(TriggerEvent::Cleared), Transition { state: true, anchor: false });
+    }
+
+    #[test]
+    fn payload_is_tagged() {
+        let json = |state| serde_json::to_string(&state).unwrap();
+        assert_eq!(json(TriggerState::Selection), r#"{"state":"selection"}"#);
+        assert_eq!(json(TriggerState::Busy), r#"{"state":"busy"}"#);
+        assert_eq!(
+            json(TriggerState::IssuesFound { count: 2 }),
+            r#"{"state":"issues_found","count":2}"#
+        );
+    }
+}
@@ -0,0 +1,748 @@
+//! Rule-based typography for mixed CJK and Latin text.
+//!
+//! A deterministic pass with no model involved: a space between CJK and
+//! Latin letters or digits, full-width punctuation in Chinese sentences,
+//! half-width punctuation in English ones, no spaces around full-width
+//! punctuation, and consistent quotation marks. Code blocks and spans,
+//! Markdown link targets, URLs and e-mail addresses are left alone.
+//!
+//! It runs as the Typography mode and, when enabled, after Improve and
+//! Translate. Every hunk it reports is tagged with the rules that made it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{self, DiffHunk, DiffOptions, DiffSegment, Granularity, SegmentKind, TextRange};
+use crate::readability::{self, is_han, is_kana, is_unspaced, Readability};
+use crate::settings;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TypographyRule {
+    /// `用iPhone拍` → `用 iPhone 拍`.
+    CjkLatinSpacing,
+    /// `你好,世界` → `你好，世界` in Chinese sentences.
+    FullwidthPunctuation,
+    /// `Hello，world` → `Hello, world` in English sentences.
+    HalfwidthPunctuation,
+    /// `你好 ，世界` → `你好，世界`.
+    FullwidthSpacing,
+    /// Straight quotes in Chinese sentences become `“”` or `「」`.
+    QuotationMarks,
+}
+
+impl TypographyRule {
+    pub const ALL: [TypographyRule; 5] = [
+        TypographyRule::CjkLatinSpacing,
+        TypographyRule::FullwidthPunctuation,
+        TypographyRule::HalfwidthPunctuation,
+        TypographyRule::FullwidthSpacing,
+        TypographyRule::QuotationMarks,
+    ];
+
+    /// Name reported on hunks; the same as the serialized form.
+    fn name(self) -> &'static str {
+        match self {
+            TypographyRule::CjkLatinSpacing => "cjk_latin_spacing",
+            TypographyRule::FullwidthPunctuation => "fullwidth_punctuation",
+            TypographyRule::HalfwidthPunctuation => "halfwidth_punctuation",
+            TypographyRule::FullwidthSpacing => "fullwidth_spacing",
+            TypographyRule::QuotationMarks => "quotation_marks",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// `“”` and `‘’`, as in Simplified Chinese.
+    #[default]
+    Curly,
+    /// `「」` and `『』`, as in Traditional Chinese and Japanese.
+    Corner,
+}
+
+impl QuoteStyle {
+    /// Opening and closing double quote, then single quote.
+    fn marks(self) -> [char; 4] {
+        match self {
+            QuoteStyle::Curly => ['“', '”', '‘', '’'],
+            QuoteStyle::Corner => ['「', '」', '『', '』'],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TypographySettings {
+    pub rules: Vec<TypographyRule>,
+    pub quote_style: QuoteStyle,
+    /// Normalize the result of Improve.
+    pub after_improve: bool,
+    /// Normalize the result of Translate.
+    pub after_translate: bool,
+}
+
+impl Default for TypographySettings {
+    fn default() -> Self {
+        Self {
+            rules: TypographyRule::ALL.to_vec(),
+            quote_style: QuoteStyle::default(),
+            after_improve: false,
+            after_translate: false,
+        }
+    }
+}
+
+/// Normalized text with the diff from the text it was computed from.
+#[derive(Debug, Serialize, Clone)]
+pub struct TypographyResult {
+    pub text: String,
+    pub segments: Vec<DiffSegment>,
+    pub hunks: Vec<DiffHunk>,
+    pub metrics: Readability,
+}
+
+/// Letters and digits that get a space next to CJK.
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphanumeric() || (c.is_alphabetic() && (c as u32) < 0x0250)
+}
+
+/// Full-width punctuation that never has spaces around it.
+fn is_fullwidth_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '，' | '。' | '！' | '？' | '：' | '；' | '、' | '（' | '）' | '「' | '」' | '『' | '』' | '《' | '》' | '【' | '】'
+    )
+}
+
+/// Closing marks that belong to the sentence before them.
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | '”' | '’' | '」' | '』' | ')' | '）' | '》' | '】')
+}
+
+fn to_fullwidth(c: char) -> Option<char> {
+    Some(match c {
+        ',' => '，',
+        '.' => '。',
+        '!' => '！',
+        '?' => '？',
+        ':' => '：',
+        ';' => '；',
+        '(' => '（',
+        ')' => '）',
+        _ => return None,
+    })
+}
+
+fn to_halfwidth(c: char) -> Option<char> {
+    Some(match c {
+        '，' | '、' => ',',
+        '。' => '.',
+        '！' => '!',
+        '？' => '?',
+        '：' => ':',
+        '；' => ';',
+        '（' => '(',
+        '）' => ')',
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Script {
+    Chinese,
+    /// Punctuation is left alone: Japanese uses `、` and `。` differently.
+    Japanese,
+    Latin,
+}
+
+/// A sentence is Chinese when it has more Han characters than Latin words.
+fn classify(sentence: &[char], protected: &[bool]) -> Script {
+    let visible = || sentence.iter().zip(protected).filter(|(_, &p)| !p).map(|(&c, _)| c);
+    if visible().any(is_kana) {
+        return Script::Japanese;
+    }
+    let han = visible().filter(|&c| is_han(c)).count();
+    let mut words = 0;
+    let mut in_word = false;
+    for c in visible() {
+        let letter = c.is_alphabetic() && !is_unspaced(c);
+        if letter && !in_word {
+            words += 1;
+        }
+        in_word = letter;
+    }
+    if han > words {
+        Script::Chinese
+    } else {
+        Script::Latin
+    }
+}
+
+/// Script of the sentence each char belongs to.
+fn sentence_scripts(chars: &[char], protected: &[bool]) -> Vec<Script> {
+    let mut scripts = vec![Script::Latin; chars.len()];
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let ends = match c {
+            '\n' | '。' | '！' | '？' | '!' | '?' => true,
+            '.' => chars.get(i + 1).is_none_or(|n| n.is_whitespace()),
+            _ => false,
+        };
+        i += 1;
+        if ends && c != '\n' {
+            while i < chars.len() && is_closing(chars[i]) {
+                i += 1;
+            }
+        }
+        if ends || i == chars.len() {
+            scripts[start..i].fill(classify(&chars[start..i], &protected[start..i]));
+            start = i;
+        }
+    }
+    scripts
+}
+
+/// Protect fenced and indented code blocks and the targets of Markdown
+/// reference definitions (`[id]: docs/a.md`), line by line.
+fn protect_blocks(chars: &[char], protected: &mut [bool]) {
+    // Marker char and length of the open fence.
+    let mut fence: Option<(char, usize)> = None;
+    let mut prev_blank = true;
+    let mut in_indented = false;
+    let mut start = 0;
+    while start < chars.len() {
+        let end = chars[start..].iter().position(|&c| c == '\n').map_or(chars.len(), |p| start + p);
+        let line = &chars[start..end];
+        let blank = line.iter().all(|c| c.is_whitespace());
+        let indent = line.iter().take_while(|&&c| c == ' ').count();
+        let marker = line.get(indent).copied().filter(|&c| c == '`' || c == '~');
+        let run = marker.map_or(0, |m| line[indent..].iter().take_while(|&&c| c == m).count());
+
+        let code = if let Some((open, len)) = fence {
+            let closes = marker == Some(open)
+                && run >= len
+                && indent < 4
+                && line[indent + run..].iter().all(|c| c.is_whitespace());
+            if closes {
+                fence = None;
+            }
+            true
+        } else if let Some(open) = marker.filter(|_| indent < 4 && run >= 3) {
+            fence = Some((open, run));
+            in_indented = false;
+            true
+        } else {
+            // Indented code cannot interrupt a paragraph.
+            let indented = indent >= 4 || line.first() == Some(&'\t');
+            in_indented = !blank && indented && (prev_blank || in_indented);
+            in_indented
+        };
+
+        if code {
+            protected[start..end].fill(true);
+        } else if indent < 4 && line.get(indent) == Some(&'[') {
+            if let Some(colon) = line.windows(2).position(|w| w == [']', ':']) {
+                protected[start + colon + 2..end].fill(true);
+            }
+        }
+        prev_blank = blank;
+        start = end + 1;
+    }
+}
+
+/// Protect the `(…)` target of inline links and images, `[text](target)`,
+/// and the `!` that makes a link an image.
+fn protect_link_targets(chars: &[char], protected: &mut [bool]) {
+    for i in 1..chars.len() {
+        if chars[i] != '(' || chars[i - 1] != ']' || protected[i] {
+            continue;
+        }
+        let open = chars[..i - 1].iter().rposition(|&c| c == '[' || c == '\n');
+        if let Some(open) = open.filter(|&k| k > 0 && chars[k] == '[' && chars[k - 1] == '!') {
+            protected[open - 1] = true;
+        }
+        let mut depth = 0;
+        for (k, &c) in chars.iter().enumerate().skip(i) {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        protected[i..=k].fill(true);
+                        break;
+                    }
+                }
+                '\n' => break,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Chars no rule may touch: code blocks and spans, Markdown link targets,
+/// URLs and e-mail addresses.
+fn protected_chars(chars: &[char]) -> Vec<bool> {
+    let mut protected = vec![false; chars.len()];
+    protect_blocks(chars, &mut protected);
+
+    let mut open = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '`' && !protected[i] {
+            match open.take() {
+                Some(start) => protected[start..=i].fill(true),
+                None => open = Some(i),
+            }
+        }
+    }
+
+    let url_end = |mut end: usize| {
+        while end < chars.len() && chars[end].is_ascii_graphic() {
+            end += 1;
+        }
+        end
+    };
+    for i in 0..chars.len() {
+        if chars[i..].starts_with(&[':', '/', '/']) {
+            let mut start = i;
+            while start > 0 && chars[start - 1].is_ascii_alphabetic() {
+                start -= 1;
+            }
+            if start < i {
+                protected[start..url_end(i)].fill(true);
+            }
+        } else if chars[i..].starts_with(&['w', 'w', 'w', '.'])
+            && (i == 0 || !chars[i - 1].is_ascii_alphanumeric())
+        {
+            protected[i..url_end(i)].fill(true);
+        } else if chars[i] == '@'
+            && i > 0
+            && chars[i - 1].is_ascii_alphanumeric()
+            && chars.get(i + 1).is_some_and(|c| c.is_ascii_alphanumeric())
+        {
+            let mut start = i;
+            while start > 0 && (chars[start - 1].is_ascii_alphanumeric() || "._%+-".contains(chars[start - 1])) {
+                start -= 1;
+            }
+            let mut end = i + 1;
+            while end < chars.len() && (chars[end].is_ascii_alphanumeric() || ".-".contains(chars[end])) {
+                end += 1;
+            }
+            protected[start..end].fill(true);
+        }
+    }
+    protect_link_targets(chars, &mut protected);
+    protected
+}
+
+/// One rule application: chars `start..end` of the input become
+/// `replacement`.
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement: String,
+    rule: TypographyRule,
+}
+
+struct Context<'a> {
+    chars: &'a [char],
+    scripts: Vec<Script>,
+    protected: Vec<bool>,
+    settings: &'a TypographySettings,
+}
+
+impl Context<'_> {
+    fn on(&self, rule: TypographyRule) -> bool {
+        self.settings.rules.contains(&rule)
+    }
+
+    fn prev_visible(&self, i: usize) -> Option<char> {
+        self.chars[..i].iter().rev().find(|c| !c.is_whitespace()).copied()
+    }
+
+    fn next_visible(&self, i: usize) -> Option<char> {
+        self.chars[i + 1..].iter().find(|c| !c.is_whitespace()).copied()
+    }
+
+    /// Punctuation and quote replacements, by char index.
+    fn conversions(&self) -> Vec<Option<(String, TypographyRule)>> {
+        let chars = self.chars;
+        let [open, close, single_open, single_close] = self.settings.quote_style.marks();
+        let mut out = vec![None; chars.len()];
+        // Straight double quotes alternate within a paragraph.
+        let mut quote_open = false;
+
+        for (i, &c) in chars.iter().enumerate() {
+            if c == '\n' {
+                quote_open = false;
+                continue;
+            }
+            if self.protected[i] {
+                continue;
+            }
+            let prev = self.prev_visible(i);
+            let next = chars.get(i + 1).copied();
+
+            out[i] = match self.scripts[i] {
+                Script::Chinese => {
+                    let after_cjk = prev.is_some_and(|p| is_han(p) || matches!(p, '”' | '」' | '』' | '）' | '》' | '】'));
+                    let before_han = self.next_visible(i).is_some_and(is_han);
+                    let convert = match c {
+                        ',' | '!' | '?' | ';' => after_cjk || before_han,
+                        // Not decimals, times, ellipses or abbreviations.
+                        '.' | ':' => after_cjk && !next.is_some_and(|n| n.is_ascii_alphanumeric() || n == '.'),
+                        '(' => prev.is_some_and(is_han) || before_han,
+                        ')' => prev.is_some_and(is_han) || next.is_some_and(is_han),
+                        _ => false,
+                    };
+                    if convert && self.on(TypographyRule::FullwidthPunctuation) {
+                        to_fullwidth(c).map(|f| (f.to_string(), TypographyRule::FullwidthPunctuation))
+                    } else if self.on(TypographyRule::QuotationMarks) {
+                        let mark = match c {
+                            '"' => {
+                                quote_open = !quote_open;
+                                Some(if quote_open { open } else { close })
+                            }
+                            '“' | '「' => Some(open),
+                            '”' | '」' => Some(close),
+                            '‘' | '『' => Some(single_open),
+                            '’' | '』' => Some(single_close),
+                            _ => None,
+                        };
+                        mark.filter(|&m| m != c)
+                            .map(|m| (m.to_string(), TypographyRule::QuotationMarks))
+                    } else {
+                        None
+                    }
+                }
+                Script::Latin => {
+                    if let Some(half) = to_halfwidth(c).filter(|_| self.on(TypographyRule::HalfwidthPunctuation)) {
+                        let replacement = if half == '(' {
+                            let spaced = i > 0 && !chars[i - 1].is_whitespace() && !chars[i - 1].is_ascii_punctuation();
+                            if spaced { " (".to_string() } else { "(".to_string() }
+                        } else {
+                            let spaced = next.is_some_and(|n| is_latin(n) || is_unspaced(n));
+                            if spaced { format!("{} ", half) } else { half.to_string() }
+                        };
+                        Some((replacement, TypographyRule::HalfwidthPunctuation))
+                    } else if self.on(TypographyRule::QuotationMarks) {
+                        let mark = match c {
+                            '「' => Some('“'),
+                            '」' => Some('”'),
+                            '『' => Some('‘'),
+                            '』' => Some('’'),
+                            _ => None,
+                        };
+                        mark.map(|m| (m.to_string(), TypographyRule::QuotationMarks))
+                    } else {
+                        None
+                    }
+                }
+                Script::Japanese => None,
+            };
+        }
+        out
+    }
+
+    fn edits(&self) -> Vec<Edit> {
+        let chars = self.chars;
+        let conversions = self.conversions();
+        // The char as it will read once converted.
+        let shown = |k: usize| {
+            conversions[k]
+                .as_ref()
+                .and_then(|(s, _)| s.chars().find(|c| !c.is_whitespace()))
+                .unwrap_or(chars[k])
+        };
+        let space_rule = |start: usize, end: usize| {
+            if start == 0 || end == chars.len() || chars[start - 1] == '\n' || chars[end] == '\n' {
+                return None;
+            }
+            let neighbours = [start - 1, end];
+            let neighbours = neighbours.iter().filter(|&&k| self.scripts[k] != Script::Latin);
+            let mut rule = None;
+            for &k in neighbours {
+                if matches!(conversions[k], Some((_, TypographyRule::FullwidthPunctuation))) {
+                    return Some(TypographyRule::FullwidthPunctuation);
+                }
+                let c = shown(k);
+                let curly = self.scripts[k] == Script::Chinese && matches!(c, '“' | '”' | '‘' | '’');
+                if (is_fullwidth_punctuation(c) || curly) && self.on(TypographyRule::FullwidthSpacing) {
+                    rule = Some(TypographyRule::FullwidthSpacing);
+                }
+            }
+            rule
+        };
+
+        let mut edits = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if self.protected[i] {
+                i += 1;
+                continue;
+            }
+            if chars[i] == ' ' {
+                let mut end = i;
+                while end < chars.len() && chars[end] == ' ' && !self.protected[end] {
+                    end += 1;
+                }
+                if let Some(rule) = space_rule(i, end) {
+                    edits.push(Edit { start: i, end, replacement: String::new(), rule });
+                }
+                i = end;
+                continue;
+            }
+            if let Some((replacement, rule)) = conversions[i].clone() {
+                edits.push(Edit { start: i, end: i + 1, replacement, rule });
+            }
+            if let Some(&next) = chars.get(i + 1) {
+                let (c, next_protected) = (chars[i], self.protected[i + 1]);
+                // Not Hangul: Korean particles attach to Latin words (`iPhone을`).
+                let mixed = (is_unspaced(c) && is_latin(next)) || (is_latin(c) && is_unspaced(next));
+                if mixed && !next_protected && self.on(TypographyRule::CjkLatinSpacing) {
+                    edits.push(Edit {
+                        start: i + 1,
+                        end: i + 1,
+                        replacement: " ".to_string(),
+                        rule: TypographyRule::CjkLatinSpacing,
+                    });
+                }
+            }
+            i += 1;
+        }
+        edits
+    }
+}
+
+/// Edits applied to the text, by UTF-16 range in the normalized text.
+struct Applied {
+    target: TextRange,
+    rule: TypographyRule,
+}
+
+struct Normalized {
+    text: String,
+    segments: Vec<DiffSegment>,
+    applied: Vec<Applied>,
+}
+
+fn apply(text: &str, settings: &TypographySettings) -> Normalized {
+    let chars: Vec<char> = text.chars().collect();
+    let protected = protected_chars(&chars);
+    let context = Context {
+        chars: &chars,
+        scripts: sentence_scripts(&chars, &protected),
+        protected,
+        settings,
+    };
+
+    let mut out = String::with_capacity(text.len() + text.len() / 8);
+    let mut segments = Vec::new();
+    let mut applied = Vec::new();
+    let (mut source, mut target) = (0, 0);
+    let mut push = |kind: SegmentKind, text: String, source: &mut usize, target: &mut usize| {
+        if text.is_empty() {
+            return;
+        }
+        let len = text.encode_utf16().count();
+        let source_len = if kind == SegmentKind::Insert { 0 } else { len };
+        let target_len = if kind == SegmentKind::Delete { 0 } else { len };
+        if kind != SegmentKind::Delete {
+            out.push_str(&text);
+        }
+        segments.push(DiffSegment {
+            kind,
+            text,
+            source: TextRange { start: *source, end: *source + source_len },
+            target: TextRange { start: *target, end: *target + target_len },
+            move_id: None,
+            hunk_id: None,
+        });
+        *source += source_len;
+        *target += target_len;
+    };
+
+    let mut pos = 0;
+    for edit in context.edits() {
+        push(SegmentKind::Equal, chars[pos..edit.start].iter().collect(), &mut source, &mut target);
+        let start = target;
+        push(SegmentKind::Delete, chars[edit.start..edit.end].iter().collect(), &mut source, &mut target);
+        push(SegmentKind::Insert, edit.replacement, &mut source, &mut target);
+        applied.push(Applied { target: TextRange { start, end: target }, rule: edit.rule });
+        pos = edit.end;
+    }
+    push(SegmentKind::Equal, chars[pos..].iter().collect(), &mut source, &mut target);
+
+    Normalized { text: out, segments, applied }
+}
+
+/// Tag each hunk with the rules whose edits fall inside it.
+fn label(hunks: &mut [DiffHunk], applied: &[Applied]) {
+    for hunk in hunks {
+        let mut rules: Vec<&str> = Vec::new();
+        for edit in applied {
+            let (start, end) = (edit.target.start, edit.target.end);
+            let inside = if start == end {
+                hunk.target.start <= start && start <= hunk.target.end
+            } else {
+                start < hunk.target.end && hunk.target.start < end
+            };
+            if inside && !rules.contains(&edit.rule.name()) {
+                rules.push(edit.rule.name());
+            }
+        }
+        if !rules.is_empty() {
+            hunk.rule = Some(rules.join(", "));
+        }
+    }
+}
+
+/// Normalize `text`; each hunk is one or more rule applications.
+pub fn normalize(text: &str, settings: &TypographySettings) -> TypographyResult {
+    let Normalized { text: normalized, mut segments, applied } = apply(text, settings);
+    let mut hunks = diff::group_hunks(&mut segments);
+    label(&mut hunks, &applied);
+    let metrics = readability::compare(text, &normalized);
+    TypographyResult { text: normalized, segments, hunks, metrics }
+}
+
+/// Normalize a model's `revised` text and diff it against `original`, so
+/// the panel shows one diff with the typography hunks tagged.
+pub fn normalize_revision(original: &str, revised: &str, settings: &TypographySettings) -> TypographyResult {
+    let Normalized { text, applied, .. } = apply(revised, settings);
+    let options = DiffOptions {
+        granularity: Granularity::Word,
+        ignore_whitespace: false,
+        detect_moves: true,
+    };
+    let mut segments = diff::diff(original, &text, &options);
+    let mut hunks = diff::group_hunks(&mut segments);
+    label(&mut hunks, &applied);
+    let metrics = readability::compare(original, &text);
+    TypographyResult { text, segments, hunks, metrics }
+}
+
+/// Apply the configured typography rules to `text`. With `original`, `text`
+/// is a polished result and the diff is taken against `original`.
+#[tauri::command]
+pub async fn normalize_typography(text: String, original: Option<String>) -> TypographyResult {
+    let settings = settings::with(|s| s.typography.clone());
+    match original {
+        Some(original) => normalize_revision(&original, &text, &settings),
+        None => normalize(&text, &settings),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(text: &str) -> String {
+        normalize(text, &TypographySettings::default()).text
+    }
+
+    #[test]
+    fn spaces_cjk_and_latin() {
+        assert_eq!(normalized("我用iPhone拍照"), "我用 iPhone 拍照");
+        assert_eq!(normalized("共有3个苹果"), "共有 3 个苹果");
+        assert_eq!(normalized("日本語のテキストABC"), "日本語のテキスト ABC");
+    }
+
+    #[test]
+    fn punctuation_follows_the_sentence() {
+        assert_eq!(normalized("你好,世界!"), "你好，世界！");
+        assert_eq!(normalized("我用iPhone,很好"), "我用 iPhone，很好");
+        assert_eq!(normalized("我们(团队)很好"), "我们（团队）很好");
+        assert_eq!(normalized("价格是3.5元."), "价格是 3.5 元。");
+        assert_eq!(normalized("等等..."), "等等...");
+        assert_eq!(normalized("Hello，world。"), "Hello, world.");
+        assert_eq!(normalized("Call 王小明 at noon。"), "Call 王小明 at noon.");
+    }
+
+    #[test]
+    fn removes_spaces_around_fullwidth_punctuation() {
+        assert_eq!(normalized("你好 , 世界"), "你好，世界");
+        assert_eq!(normalized("他说 \"你好\" 然后走了。"), "他说“你好”然后走了。");
+        assert_eq!(normalized("第一行\n\n  缩进 行"), "第一行\n\n  缩进 行");
+    }
+
+    #[test]
+    fn quotes_follow_the_style() {
+        assert_eq!(normalized("他说\"你好\"然后走了。"), "他说“你好”然后走了。");
+        assert_eq!(normalized("He said “hello” to me."), "He said “hello” to me.");
+        let corner = TypographySettings {
+            quote_style: QuoteStyle::Corner,
+            ..Default::default()
+        };
+        assert_eq!(normalize("他说“你好”。", &corner).text, "他说「你好」。");
+    }
+
+    #[test]
+    fn leaves_code_and_links_alone() {
+        let unchanged = [
+            "运行`npm install`即可",
+            "请访问https://example.com/a,b获取",
+        ];
+        for text in unchanged {
+            assert_eq!(normalized(text), text);
+        }
+        assert_eq!(normalized("联系a.b@example.com,谢谢"), "联系a.b@example.com，谢谢");
+    }
+
+    #[test]
+    fn leaves_markdown_link_targets_alone() {
+        assert_eq!(normalized("见[文档](docs/a.md)中的说明"), "见[文档](docs/a.md)中的说明");
+        assert_eq!(normalized("见![图](img/a(1).png),如下"), "见![图](img/a(1).png)，如下");
+        assert_eq!(normalized("[文档]: docs/a(b).md"), "[文档]: docs/a(b).md");
+        // The link text is still prose.
+        assert_eq!(normalized("见[用iPhone拍](a.md)"), "见[用 iPhone 拍](a.md)");
+    }
+
+    #[test]
+    fn leaves_code_blocks_alone() {
+        let fenced = "示例如下:\n\n```rust\nfoo(a,b);\n中文,代码\n```\n\n结束,谢谢";
+        assert_eq!(
+            normalized(fenced),
+            "示例如下：\n\n```rust\nfoo(a,b);\n中文,代码\n```\n\n结束，谢谢"
+        );
+        let tilde = "~~~\n中文,代码\n~~~\n中文,正文";
+        assert_eq!(normalized(tilde), "~~~\n中文,代码\n~~~\n中文，正文");
+        let indented = "示例如下:\n\n    foo(a,b)\n    中文,代码\n\n结束,谢谢";
+        assert_eq!(
+            normalized(indented),
+            "示例如下：\n\n    foo(a,b)\n    中文,代码\n\n结束，谢谢"
+        );
+    }
+
+    #[test]
+    fn disabled_rules_do_nothing() {
+        let settings = TypographySettings {
+            rules: vec![TypographyRule::CjkLatinSpacing],
+            ..Default::default()
+        };
+        assert_eq!(normalize("我用iPhone,很好", &settings).text, "我用 iPhone,很好");
+    }
+
+    #[test]
+    fn hunks_name_their_rules() {
+        let result = normalize("你好 , 世界用iPhone", &TypographySettings::default());
+        let rules: Vec<_> = result.hunks.iter().map(|h| h.rule.as_deref()).collect();
+        assert_eq!(rules, [Some("fullwidth_punctuation"), Some("cjk_latin_spacing")]);
+        assert_eq!(result.hunks[0].deleted, " , ");
+        assert_eq!(result.hunks[0].inserted, "，");
+    }
+
+    #[test]
+    fn revision_hunks_are_tagged_when_rules_applied() {
+        let result = normalize_revision(
+            "我用iphone拍照",
+            "我用iPhone拍照,很好",
+            &TypographySettings::default(),
+        );
+        assert_eq!(result.text, "我用 iPhone 拍照，很好");
+        assert!(result.hunks.iter().any(|h| h.rule.as_deref() == Some("cjk_latin_spacing")));
+        assert!(result.hunks.iter().any(|h| h.rule.as_deref() == Some("fullwidth_punctuation")));
+    }
+}
@@ -3,8 +3,24 @@ import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 
-import type { PolishMode } from "@/core/llm/types";
+import type {
+  AppProfile,
+  PolishMode,
+  ShortcutBinding,
+  Suggestion,
+} from "@/core/llm/types";
 import { DiffView } from "@/components/DiffView";
+import { composeResult } from "@/core/diff/differ";
+import { detectLanguage } from "@/core/lang/detect";
+import {
+  describeReadability,
+  summarizeReadability,
+} from "@/core/readability/metrics";
+import {
+  addPersonalWord,
+  checkSpelling,
+  type Misspelling,
+} from "@/core/spell/checker";
 import { usePolish } from "@/hooks/usePolish";
 import { useSettings } from "@/hooks/useSettings";
 import { useHistory } from "@/hooks/useHistory";
@@ -16,6 +32,7 @@ import {
   CheckCheck,
   SendHorizonal,
   ArrowDownToLine,
+  Plus,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 
@@ -25,6 +42,37 @@ interface CaptureResult {
   y: number;
   width: number;
   height: number;
+  app: string | null;
+  /** Bundle identifier of `app`, if known. */
+  app_id: string | null;
+  profile: AppProfile | null;
+  /** Shortcut that opened the panel, if any. */
+  shortcut: ShortcutBinding | null;
+  /** Improve result already computed by the background check. */
+  suggestion: Suggestion | null;
+}
+
+/** Mode forced by a shortcut, if its action is a mode. */
+function shortcutMode(shortcut: ShortcutBinding | null): PolishMode | null {
+  switch (shortcut?.action) {
+    case "improve":
+    case "rephrase":
+    case "translate":
+      return shortcut.action;
+    default:
+      return null;
+  }
+}
+
+/** Combine the app profile's instruction with a one-off user request. */
+function joinInstructions(
+  profileInstruction?: string | null,
+  extra?: string,
+): string | undefined {
+  const parts = [profileInstruction, extra].filter(
+    (s): s is string => !!s && s.trim().length > 0,
+  );
+  return parts.length > 0 ? parts.join("\n") : undefined;
 }
 
 export function App() {
@@ -34,54 +82,153 @@ export function App() {
   const [isReplacing, setIsReplacing] = useState(false);
   const [copied, setCopied] = useState(false);
   const [changeInput, setChangeInput] = useState("");
+  const [captureApp, setCaptureApp] = useState<string | null>(null);
+  const [captureAppId, setCaptureAppId] = useState<string | null>(null);
+  const [misspellings, setMisspellings] = useState<Misspelling[]>([]);
+  const [profile, setProfile] = useState<AppProfile | null>(null);
+  const [acceptedIds, setAcceptedIds] = useState<Set<string>>(new Set());
+  const [focusedHunk, setFocusedHunk] = useState(0);
   const changeInputRef = useRef<HTMLInputElement>(null);
+  const lastRecordIdRef = useRef<string | null>(null);
+  const latestCaptureRef = useRef<string | null>(null);
+  const rootRef = useRef<HTMLDivElement>(null);
+  const scrollRef = useRef<HTMLDivElement>(null);
+  const contentRef = useRef<HTMLDivElement>(null);
 
-  const { config, isConfigured, defaultMode, activeProviderId } = useSettings();
-  const { addRecord } = useHistory();
+  const {
+    getConfig,
+    isConfigured,
+    defaultMode,
+    activeProviderId,
+    typography,
+  } = useSettings();
+  const { addRecord, updateRecord } = useHistory();
+
+  // Provider and instruction come from the app profile when one matched.
+  const config = getConfig(profile?.providerId);
+  // Typography and conversion run locally, so they work without a provider.
+  const canPolish = useCallback(
+    (m: PolishMode) => isConfigured || m === "typography" || m === "convert",
+    [isConfigured],
+  );
+  const ready = canPolish(mode);
 
   const handlePolishComplete = useCallback(
     (polishInputText: string, resultText: string, polishMode: PolishMode) => {
+      const id = crypto.randomUUID();
+      lastRecordIdRef.current = id;
       addRecord({
-        id: crypto.randomUUID(),
+        id,
         timestamp: Date.now(),
         mode: polishMode,
         inputText: polishInputText,
         resultText,
-        provider: activeProviderId,
+        provider: profile?.providerId ?? activeProviderId,
+        app: captureApp ?? undefined,
+        appId: captureAppId ?? undefined,
+        language: detectLanguage(polishInputText),
       });
     },
-    [addRecord, activeProviderId],
+    [addRecord, activeProviderId, profile, captureApp, captureAppId],
   );
 
   const {
     result,
     explanation,
     diffSegments,
+    hunks,
+    metrics,
     isStreaming,
     error,
     startPolish,
+    showSuggestion,
     cancelPolish,
     reset,
-  } = usePolish(handlePolishComplete);
+  } = usePolish(handlePolishComplete, typography);
+
+  // Every suggestion starts out accepted; the user can reject individual hunks.
+  useEffect(() => {
+    setAcceptedIds(new Set(hunks.map((h) => h.id)));
+    setFocusedHunk(0);
+  }, [hunks]);
+
+  /** Toggle a hunk, together with its partner if it is half of a move. */
+  const toggleHunk = useCallback(
+    (hunkId: string) => {
+      const hunk = hunks.find((h) => h.id === hunkId);
+      if (!hunk) return;
+      const linked = hunks.filter(
+        (h) => h.id === hunkId || (hunk.move_id !== null && h.move_id === hunk.move_id),
+      );
+      setAcceptedIds((prev) => {
+        const next = new Set(prev);
+        const accept = !prev.has(hunkId);
+        for (const h of linked) {
+          if (accept) next.add(h.id);
+          else next.delete(h.id);
+        }
+        return next;
+      });
+    },
+    [hunks],
+  );
+
+  /** The text to paste: the full result, or only the accepted hunks. */
+  const composeAccepted = useCallback(async (): Promise<string> => {
+    if (hunks.every((h) => acceptedIds.has(h.id))) {
+      return result;
+    }
+    return composeResult(inputText, hunks, [...acceptedIds]);
+  }, [hunks, acceptedIds, result, inputText]);
 
   // Sync mode with defaultMode from settings
   useEffect(() => {
     setMode(defaultMode);
   }, [defaultMode]);
 
-  // Auto-polish when text is captured
+  // Auto-polish when text is captured, using the app profile's defaults
   const handleCapture = useCallback(
-    (text: string) => {
+    (capture: CaptureResult) => {
+      const text = capture.text;
+      const captureProfile = capture.profile ?? null;
+      const shortcut = capture.shortcut ?? null;
+      const captureMode =
+        shortcutMode(shortcut) ?? captureProfile?.mode ?? defaultMode;
+      const shortcutInstruction =
+        shortcut?.action === "custom" ? shortcut.instruction : null;
       setInputText(text);
+      setCaptureApp(capture.app ?? null);
+      setCaptureAppId(capture.app_id ?? null);
+      // Local and offline, so typos show before the LLM answers.
+      latestCaptureRef.current = text;
+      setMisspellings([]);
+      checkSpelling(text)
+        .then((found) => {
+          if (latestCaptureRef.current === text) setMisspellings(found);
+        })
+        .catch(() => {});
+      setProfile(captureProfile);
+      setMode(captureMode);
       setAccessibilityError(false);
       setCopied(false);
       setChangeInput("");
       reset();
-      if (isConfigured && text.trim()) {
-        startPolish(text, mode, config);
+      // Clicking the issue badge: the background check already polished it.
+      if (capture.suggestion && captureMode === "improve" && !shortcut) {
+        showSuggestion(text, captureMode, capture.suggestion);
+      } else if (canPolish(captureMode) && text.trim()) {
+        startPolish(
+          text,
+          captureMode,
+          getConfig(captureProfile?.providerId),
+          joinInstructions(
+            captureProfile?.instruction,
+            shortcutInstruction ?? undefined,
+          ),
+        );
       }
     },
-    [isConfigured, mode, config, startPolish, reset],
+    [canPolish, defaultMode, getConfig, startPolish, showSuggestion, reset],
   );
 
   // Listen for events from Rust backend
@@ -89,7 +236,7 @@ export function App() {
     const unlistenCapture = listen<CaptureResult>(
       "selection-captured",
       (event) => {
-        handleCapture(event.payload.text);
+        handleCapture(event.payload);
       },
     );
 
@@ -131,11 +278,22 @@ export function App() {
     if (!result || isReplacing) return;
     setIsReplacing(true);
     try {
+      const text = await composeAccepted();
       const win = getCurrentWindow();
       await win.hide();
       await new Promise((r) => setTimeout(r, 50));
-      await invoke("replace_text", { text: result });
+      await invoke("replace_text", { text });
       console.log("[Polishr] replace_text succeeded");
+      if (lastRecordIdRef.current && hunks.length > 0) {
+        updateRecord(lastRecordIdRef.current, {
+          hunks: hunks.map((h) => ({
+            id: h.id,
+            deleted: h.deleted,
+            inserted: h.inserted,
+            accepted: acceptedIds.has(h.id),
+          })),
+        });
+      }
       reset();
       setInputText("");
       setChangeInput("");
@@ -146,7 +304,15 @@ export function App() {
     } finally {
       setIsReplacing(false);
     }
-  }, [result, reset, isReplacing]);
+  }, [
+    result,
+    reset,
+    isReplacing,
+    composeAccepted,
+    hunks,
+    acceptedIds,
+    updateRecord,
+  ]);
 
   const handleDismiss = useCallback(async () => {
     await invoke("dismiss");
@@ -160,24 +326,97 @@ export function App() {
 
   const handleRetry = useCallback(
     (customInstruction?: string) => {
-      if (inputText.trim() && isConfigured) {
+      if (inputText.trim() && canPolish(mode)) {
         setCopied(false);
-        startPolish(inputText, mode, config, customInstruction);
+        startPolish(
+          inputText,
+          mode,
+          config,
+          joinInstructions(profile?.instruction, customInstruction),
+        );
       }
     },
-    [inputText, mode, config, isConfigured, startPolish],
+    [inputText, mode, config, profile, canPolish, startPolish],
   );
 
   const handleCopy = useCallback(async () => {
     if (!result) return;
     try {
-      await navigator.clipboard.writeText(result);
+      await navigator.clipboard.writeText(await composeAccepted());
       setCopied(true);
       setTimeout(() => setCopied(false), 2000);
     } catch {
       console.error("Failed to copy");
     }
-  }, [result]);
+  }, [result, composeAccepted]);
+
+  // Hunk shortcuts while the "Ask for a change" input is not focused:
+  // ↑/↓ (or k/j) move between hunks, Space toggles, 1–9 toggle directly,
+  // Cmd/Ctrl+Enter accepts.
+  useEffect(() => {
+    const handler = (e: KeyboardEvent) => {
+      if (document.activeElement === changeInputRef.current) return;
+      if (isStreaming || hunks.length === 0) return;
+
+      if ((e.metaKey || e.ctrlKey) && e.key === "Enter") {
+        e.preventDefault();
+        handleAccept();
+      } else if (e.key === "ArrowDown" || e.key === "j") {
+        e.preventDefault();
+        setFocusedHunk((i) => Math.min(i + 1, hunks.length - 1));
+      } else if (e.key === "ArrowUp" || e.key === "k") {
+        e.preventDefault();
+        setFocusedHunk((i) => Math.max(i - 1, 0));
+      } else if (e.key === " ") {
+        e.preventDefault();
+        const hunk = hunks[focusedHunk];
+        if (hunk) toggleHunk(hunk.id);
+      } else if (/^[1-9]$/.test(e.key)) {
+        const hunk = hunks[Number(e.key) - 1];
+        if (hunk) {
+          setFocusedHunk(Number(e.key) - 1);
+          toggleHunk(hunk.id);
+        }
+      }
+    };
+    window.addEventListener("keydown", handler);
+    return () => window.removeEventListener("keydown", handler);
+  }, [isStreaming, hunks, focusedHunk, toggleHunk, handleAccept]);
+
+  // Size the panel to its content. The backend caps the height and keeps
+  // the panel on the same side of the selection while results stream in.
+  useEffect(() => {
+    const root = rootRef.current;
+    if (!root) return;
+    let frame = 0;
+    let lastHeight = 0;
+    const report = () => {
+      cancelAnimationFrame(frame);
+      frame = requestAnimationFrame(() => {
+        const scroll = scrollRef.current;
+        const content = contentRef.current;
+        const chrome = root.offsetHeight - (scroll?.clientHeight ?? 0);
+        const height = Math.ceil(chrome + (content?.offsetHeight ?? 0));
+        if (Math.abs(height - lastHeight) < 2) return;
+        lastHeight = height;
+        invoke("resize_panel", { height }).catch(() => {});
+      });
+    };
+    const observer = new ResizeObserver(report);
+    observer.observe(root);
+    if (contentRef.current) observer.observe(contentRef.current);
+    // The panel reopens at its minimum height, so report again even if
+    // the content did not change size.
+    const unlisten = listen("selection-captured", () => {
+      lastHeight = 0;
+      report();
+    });
+    return () => {
+      cancelAnimationFrame(frame);
+      observer.disconnect();
+      unlisten.then((fn) => fn());
+    };
+  }, [ready, accessibilityError]);
 
   const handleSendChange = useCallback(() => {
     const instruction = changeInput.trim();
@@ -188,16 +427,28 @@ export function App() {
 
   const showDiff = diffSegments.length > 0 && !isStreaming;
   const showStreamingResult = isStreaming && result.length > 0;
+  const readabilitySummary = metrics ? summarizeReadability(metrics) : null;
 
   const modeLabels: Record<PolishMode, string> = {
     improve: "Improve",
     rephrase: "Rephrase",
     translate: "Translate",
+    typography: "Typography",
+    convert: "简 ⇄ 繁",
   };
-  const modes: PolishMode[] = ["improve", "rephrase", "translate"];
+  const modes: PolishMode[] = [
+    "improve",
+    "rephrase",
+    "translate",
+    "typography",
+    "convert",
+  ];
 
   return (
-    <div className="flex h-screen flex-col overflow-hidden rounded-lg bg-card shadow-[0_4px_24px_rgba(0,0,0,0.1)]">
+    <div
+      ref={rootRef}
+      className="flex h-screen flex-col overflow-hidden rounded-lg bg-card shadow-[0_4px_24px_rgba(0,0,0,0.1)]"
+    >
       {/* ─── Top bar: "Ask for a change" input ─── */}
       <div
         data-tauri-drag-region
@@ -244,7 +495,7 @@ export function App() {
       )}
 
       {/* ─── Not configured ─── */}
-      {!isConfigured && !accessibilityError && (
+      {!ready && !accessibilityError && (
         <div className="flex items-center gap-2 px-4 py-3">
           <AlertCircle className="h-3.5 w-3.5 shrink-0 text-muted-foreground" />
           <p className="text-xs text-muted-foreground">
@@ -253,92 +504,154 @@ export function App() {
         </div>
       )}
 
-      {/* ─── Content area ─── */}
-      {isConfigured && !accessibilityError && (
-        <div className="min-h-0 flex-1 overflow-y-auto">
-          {/* Loading spinner */}
-          {isStreaming && !showStreamingResult && (
-            <div className="flex items-center gap-2 px-4 py-3">
-              <Loader2 className="h-4 w-4 animate-spin text-primary" />
-              <span className="text-[13px] text-muted-foreground">
-                Polishing...
-              </span>
-            </div>
-          )}
-
-          {/* Streaming raw result */}
-          {showStreamingResult && !showDiff && (
-            <div className="mx-4 my-2 border-l-[3px] border-primary px-4 py-0.5">
-              <p className="text-[13px] leading-relaxed text-foreground">
-                {result}
-                <span className="ml-0.5 inline-block h-3.5 w-[2px] animate-pulse bg-primary align-text-bottom" />
-              </p>
-            </div>
-          )}
-
-          {/* Suggestion card */}
-          {showDiff && (
-            <div className="mx-4 my-2 border-l-[3px] border-primary px-4 py-0.5">
-              {explanation && (
-                <p className="mb-1.5 text-[13px] font-bold leading-snug text-primary">
-                  {explanation}
-                </p>
+      {/* ─── Spelling, until the suggestion replaces it ─── */}
+      {misspellings.length > 0 && !showDiff && !accessibilityError && (
+        <div className="flex shrink-0 flex-wrap items-center gap-x-3 gap-y-1 px-4 py-2">
+          {misspellings.map((m) => (
+            <span
+              key={`${m.range.start}-${m.word}`}
+              className="flex items-center gap-1 text-[12px]"
+            >
+              <span className="text-destructive line-through">{m.word}</span>
+              {m.suggestions.length > 0 && (
+                <span className="text-foreground">
+                  {m.suggestions.slice(0, 3).join(", ")}
+                </span>
               )}
+              <button
+                onClick={() => {
+                  addPersonalWord(m.word)
+                    .then(() =>
+                      setMisspellings((prev) =>
+                        prev.filter((p) => p.word !== m.word),
+                      ),
+                    )
+                    .catch((err) => console.error("Failed to add word:", err));
+                }}
+                className="cursor-pointer text-muted-foreground hover:text-foreground"
+                title={`Add "${m.word}" to your dictionary`}
+              >
+                <Plus className="h-3 w-3" />
+              </button>
+            </span>
+          ))}
+        </div>
+      )}
 
-              <DiffView segments={diffSegments} />
-
-              {/* Action row */}
-              <div className="mt-2 flex items-center">
-                <button
-                  onClick={handleAccept}
-                  disabled={isReplacing}
-                  className="cursor-pointer rounded-full border border-border px-4 py-1 text-xs font-medium text-foreground transition-colors duration-200 hover:bg-accent disabled:pointer-events-none disabled:opacity-50"
-                >
-                  {isReplacing ? "Replacing..." : "Accept"}
-                </button>
-
-                <div className="flex-1" />
-
-                {/* Copy */}
-                <button
-                  onClick={handleCopy}
-                  className="flex h-7 w-7 cursor-pointer items-center justify-center rounded-md text-muted-foreground transition-colors duration-200 hover:text-foreground"
-                  title="Copy to clipboard"
-                >
-                  {copied ? (
-                    <CheckCheck className="h-4 w-4 text-primary" />
-                  ) : (
-                    <Copy className="h-4 w-4" />
+      {/* ─── Content area ─── */}
+      {ready && !accessibilityError && (
+        <div ref={scrollRef} className="min-h-0 flex-1 overflow-y-auto">
+          <div ref={contentRef}>
+            {/* Loading spinner */}
+            {isStreaming && !showStreamingResult && (
+              <div className="flex items-center gap-2 px-4 py-3">
+                <Loader2 className="h-4 w-4 animate-spin text-primary" />
+                <span className="text-[13px] text-muted-foreground">
+                  Polishing...
+                </span>
+              </div>
+            )}
+
+            {/* Streaming raw result */}
+            {showStreamingResult && !showDiff && (
+              <div className="mx-4 my-2 border-l-[3px] border-primary px-4 py-0.5">
+                <p className="text-[13px] leading-relaxed text-foreground">
+                  {result}
+                  <span className="ml-0.5 inline-block h-3.5 w-[2px] animate-pulse bg-primary align-text-bottom" />
+                </p>
+              </div>
+            )}
+
+            {/* Suggestion card */}
+            {showDiff && (
+              <div className="mx-4 my-2 border-l-[3px] border-primary px-4 py-0.5">
+                {explanation && (
+                  <p className="mb-1.5 text-[13px] font-bold leading-snug text-primary">
+                    {explanation}
+                  </p>
+                )}
+
+                <DiffView
+                  segments={diffSegments}
+                  hunks={hunks}
+                  acceptedIds={acceptedIds}
+                  focusedHunkId={hunks[focusedHunk]?.id ?? null}
+                  onToggleHunk={toggleHunk}
+                />
+
+                {readabilitySummary && metrics && (
+                  <p
+                    className="mt-1.5 text-[11px] text-muted-foreground"
+                    title={describeReadability(metrics)}
+                  >
+                    {readabilitySummary}
+                  </p>
+                )}
+
+                {/* Action row */}
+                <div className="mt-2 flex items-center">
+                  <button
+                    onClick={handleAccept}
+                    disabled={isReplacing}
+                    className="cursor-pointer rounded-full border border-border px-4 py-1 text-xs font-medium text-foreground transition-colors duration-200 hover:bg-accent disabled:pointer-events-none disabled:opacity-50"
+                  >
+                    {isReplacing
+                      ? "Replacing..."
+                      : acceptedIds.size < hunks.length
+                        ? `Accept ${acceptedIds.size} of ${hunks.length}`
+                        : "Accept"}
+                  </button>
+
+                  {hunks.length > 1 && (
+                    <span className="ml-3 text-[11px] text-muted-foreground">
+                      Click a change or press 1–{Math.min(hunks.length, 9)} to
+                      toggle it
+                    </span>
                   )}
-                </button>
+
+                  <div className="flex-1" />
+
+                  {/* Copy */}
+                  <button
+                    onClick={handleCopy}
+                    className="flex h-7 w-7 cursor-pointer items-center justify-center rounded-md text-muted-foreground transition-colors duration-200 hover:text-foreground"
+                    title="Copy to clipboard"
+                  >
+                    {copied ? (
+                      <CheckCheck className="h-4 w-4 text-primary" />
+                    ) : (
+                      <Copy className="h-4 w-4" />
+                    )}
+                  </button>
+                </div>
+              </div>
+            )}
+
+            {/* Error state */}
+            {error && (
+              <div className="flex items-start gap-2 px-4 py-3">
+                <AlertCircle className="mt-0.5 h-3.5 w-3.5 shrink-0 text-destructive" />
+                <div className="flex-1">
+                  <p className="text-xs text-destructive">{error}</p>
+                  <button
+                    onClick={() => handleRetry()}
+                    className="mt-1 cursor-pointer text-xs font-medium text-primary hover:underline"
+                  >
+                    Retry
+                  </button>
+                </div>
               </div>
-            </div>
-          )}
-
-          {/* Error state */}
-          {error && (
-            <div className="flex items-start gap-2 px-4 py-3">
-              <AlertCircle className="mt-0.5 h-3.5 w-3.5 shrink-0 text-destructive" />
-              <div className="flex-1">
-                <p className="text-xs text-destructive">{error}</p>
-                <button
-                  onClick={() => handleRetry()}
-                  className="mt-1 cursor-pointer text-xs font-medium text-primary hover:underline"
-                >
-                  Retry
-                </button>
+            )}
+
+            {/* Waiting state */}
+            {!result && !error && !isStreaming && !inputText && (
+              <div className="flex items-center justify-center px-4 py-4">
+                <p className="text-xs text-muted-foreground">
+                  Select text, then click the blue button or press a Polishr shortcut
+                </p>
               </div>
-            </div>
-          )}
-
-          {/* Waiting state */}
-          {!result && !error && !isStreaming && !inputText && (
-            <div className="flex items-center justify-center px-4 py-4">
-              <p className="text-xs text-muted-foreground">
-                Select text, then click the blue button or press Cmd+Option+P
-              </p>
-            </div>
-          )}
+            )}
+          </div>
         </div>
       )}
 
@@ -369,9 +682,14 @@ export function App() {
                 setMode(m);
                 setCopied(false);
                 setChangeInput("");
-                if (inputText.trim() && isConfigured && !isStreaming) {
+                if (inputText.trim() && canPolish(m) && !isStreaming) {
                   reset();
-                  startPolish(inputText, m, config);
+                  startPolish(
+                    inputText,
+                    m,
+                    config,
+                    joinInstructions(profile?.instruction),
+                  );
                 }
               }}
               disabled={isStreaming}
@@ -1,21 +1,28 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { ChevronDown, Loader2 } from "lucide-react";
-import { useCallback, useEffect, useState } from "react";
+import { useCallback, useEffect, useState, type MouseEvent } from "react";
 
-type TriggerMode = "selection" | "paragraph";
+/** Payload of the `trigger-state` event (`TriggerState` in Rust). */
+type TriggerState =
+  | { state: "hidden" }
+  | { state: "disabled" }
+  | { state: "selection" }
+  | { state: "busy" }
+  | { state: "paragraph" }
+  | { state: "issues_found"; count: number };
 
 export function TriggerWindow() {
   const [isOpening, setIsOpening] = useState(false);
-  const [mode, setMode] = useState<TriggerMode>("selection");
+  const [trigger, setTrigger] = useState<TriggerState>({ state: "selection" });
   const [isHovered, setIsHovered] = useState(false);
   const [isMultiLine, setIsMultiLine] = useState(false);
 
-  // Listen for mode changes from the Rust poller
+  // Listen for state changes from the Rust poller
   useEffect(() => {
-    const unlisten = listen<string>("trigger-mode", (event) => {
-      setMode(event.payload as TriggerMode);
-      // Reset hover when mode changes
+    const unlisten = listen<TriggerState>("trigger-state", (event) => {
+      setTrigger(event.payload);
+      // Reset hover when the state changes
       setIsHovered(false);
     });
     return () => {
@@ -32,10 +39,10 @@ export function TriggerWindow() {
   }, []);
 
   const handleClick = useCallback(async () => {
-    if (isOpening) return;
+    if (isOpening || trigger.state === "busy") return;
     setIsOpening(true);
     try {
-      if (mode === "paragraph") {
+      if (trigger.state === "paragraph" || trigger.state === "issues_found") {
         await invoke("select_paragraph_and_open");
       } else {
         await invoke("open_main_from_cached_selection");
@@ -45,7 +52,17 @@ export function TriggerWindow() {
     } finally {
       setTimeout(() => setIsOpening(false), 140);
     }
-  }, [isOpening, mode]);
+  }, [isOpening, trigger]);
+
+  // Native menu; the window is too small for an HTML one.
+  const handleContextMenu = useCallback(async (e: MouseEvent) => {
+    e.preventDefault();
+    try {
+      await invoke("show_trigger_menu");
+    } catch (err) {
+      console.error("Failed to show trigger menu:", err);
+    }
+  }, []);
 
   const handleMouseEnter = useCallback(async () => {
     setIsHovered(true);
@@ -65,8 +82,34 @@ export function TriggerWindow() {
     }
   }, []);
 
+  if (trigger.state === "hidden" || trigger.state === "disabled") {
+    return null;
+  }
+
+  // --- Paragraph with findings: issue count badge ---
+  if (trigger.state === "issues_found") {
+    return (
+      <button
+        onClick={handleClick}
+        onContextMenu={handleContextMenu}
+        disabled={isOpening}
+        title={`${trigger.count} ${trigger.count === 1 ? "issue" : "issues"} found`}
+        className={`flex cursor-pointer items-center justify-center bg-[#F59E0B] text-[9px] font-semibold text-white transition-opacity duration-150 hover:opacity-90 disabled:cursor-default disabled:opacity-70 ${isMultiLine ? "rounded-[8px]" : "rounded-full"}`}
+        style={{ width: "100vw", height: "100vh" }}
+      >
+        {isOpening ? (
+          <Loader2 className="h-2.5 w-2.5 animate-spin" />
+        ) : trigger.count > 9 ? (
+          "9+"
+        ) : (
+          trigger.count
+        )}
+      </button>
+    );
+  }
+
   // --- Paragraph mode: animated gray line → white button ---
-  if (mode === "paragraph") {
+  if (trigger.state === "paragraph") {
     const expanded = isHovered;
     return (
       <div
@@ -74,6 +117,7 @@ export function TriggerWindow() {
         style={{ width: "100vw", height: "100vh" }}
         onMouseEnter={handleMouseEnter}
         onMouseLeave={handleMouseLeave}
+        onContextMenu={handleContextMenu}
         onClick={expanded ? handleClick : undefined}
       >
         <div
@@ -98,16 +142,18 @@ export function TriggerWindow() {
     );
   }
 
-  // --- Selection mode: blue button ---
+  // --- Selection mode: blue button, spinning while a quick fix runs ---
+  const isBusy = trigger.state === "busy";
   return (
     <button
       onClick={handleClick}
-      disabled={isOpening}
-      title="Polish selected text"
+      onContextMenu={handleContextMenu}
+      disabled={isOpening || isBusy}
+      title={isBusy ? "Polishing…" : "Polish selected text"}
       className={`flex cursor-pointer items-center justify-center bg-[#217DE6] text-white transition-opacity duration-150 hover:opacity-90 disabled:cursor-default disabled:opacity-70 ${isMultiLine ? "rounded-[8px]" : "rounded-full"}`}
       style={{ width: "100vw", height: "100vh" }}
     >
-      {isOpening ? (
+      {isOpening || isBusy ? (
         <Loader2 className="h-2.5 w-2.5 animate-spin" />
       ) : (
         <ChevronDown className="h-2.5 w-2.5" />
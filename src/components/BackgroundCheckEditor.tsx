@@ -0,0 +1,103 @@
+import { useState } from "react";
+import type { BackgroundCheckSettings, Provider } from "@/core/llm/types";
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+interface BackgroundCheckEditorProps {
+  providers: Provider[];
+  backgroundCheck: BackgroundCheckSettings;
+  onSave: (next: BackgroundCheckSettings) => Promise<void>;
+}
+
+/**
+ * Card for the opt-in check of the paragraph under the caret. Every check
+ * is a request to the chosen provider, so a cheap model is the best fit.
+ */
+export function BackgroundCheckEditor({
+  providers,
+  backgroundCheck,
+  onSave,
+}: BackgroundCheckEditorProps) {
+  const [error, setError] = useState<string | null>(null);
+
+  const update = async (patch: Partial<BackgroundCheckSettings>) => {
+    try {
+      await onSave({ ...backgroundCheck, ...patch });
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <label className="flex cursor-pointer items-center justify-between">
+        <div>
+          <div className="text-[13px] font-medium text-[#18181b]">
+            Check while typing
+          </div>
+          <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+            When you stop typing, the paragraph is sent for a check and the
+            trigger shows how many issues were found.
+          </div>
+        </div>
+        <input
+          type="checkbox"
+          checked={backgroundCheck.enabled}
+          onChange={(e) => update({ enabled: e.target.checked })}
+          className="accent-blue-500"
+        />
+      </label>
+
+      {backgroundCheck.enabled && (
+        <div className="mt-3 space-y-2 text-[12px] text-[#52525b]">
+          <div className="flex items-center gap-2">
+            Provider
+            <select
+              value={backgroundCheck.providerId ?? ""}
+              onChange={(e) => update({ providerId: e.target.value || null })}
+              className={`cursor-pointer ${FIELD_CLASS}`}
+            >
+              <option value="">Same as the app</option>
+              {providers.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.name}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="flex items-center gap-2">
+            Check after
+            <input
+              type="number"
+              min={300}
+              step={100}
+              defaultValue={backgroundCheck.debounceMs}
+              onBlur={(e) =>
+                update({
+                  debounceMs: Math.max(300, parseInt(e.target.value, 10) || 300),
+                })
+              }
+              className={`w-20 ${FIELD_CLASS}`}
+            />
+            ms without typing, at most
+            <input
+              type="number"
+              min={1}
+              defaultValue={backgroundCheck.maxPerMinute}
+              onBlur={(e) =>
+                update({
+                  maxPerMinute: Math.max(1, parseInt(e.target.value, 10) || 1),
+                })
+              }
+              className={`w-16 ${FIELD_CLASS}`}
+            />
+            checks a minute.
+          </div>
+        </div>
+      )}
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
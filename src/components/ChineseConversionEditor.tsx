@@ -0,0 +1,92 @@
+import { useEffect, useState } from "react";
+import type {
+  ChineseConversionSettings,
+  ChineseVariant,
+} from "@/core/llm/types";
+import {
+  listConversionDictionaries,
+  VARIANT_LABELS,
+  type ConversionDictionaryList,
+} from "@/core/chinese/converter";
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const VARIANTS = Object.keys(VARIANT_LABELS) as ChineseVariant[];
+
+interface ChineseConversionEditorProps {
+  chineseConversion: ChineseConversionSettings;
+  onSave: (next: ChineseConversionSettings) => Promise<void>;
+}
+
+/** Chinese conversion card: target variant, regional vocabulary, dictionaries. */
+export function ChineseConversionEditor({
+  chineseConversion,
+  onSave,
+}: ChineseConversionEditorProps) {
+  const [dictionaries, setDictionaries] =
+    useState<ConversionDictionaryList | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listConversionDictionaries()
+      .then(setDictionaries)
+      .catch((err) => console.error("Failed to list dictionaries:", err));
+  }, []);
+
+  const update = async (patch: Partial<ChineseConversionSettings>) => {
+    try {
+      await onSave({ ...chineseConversion, ...patch });
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="text-[13px] font-medium text-[#18181b]">
+        Chinese Conversion
+      </div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+        Converts between Simplified and Traditional Chinese word by word,
+        offline. Glossary terms are kept or replaced as listed.
+      </div>
+
+      <div className="mt-3 flex items-center gap-2 text-[12px] text-[#52525b]">
+        Convert to
+        <select
+          value={chineseConversion.target}
+          onChange={(e) => update({ target: e.target.value as ChineseVariant })}
+          className={`cursor-pointer ${FIELD_CLASS}`}
+        >
+          {VARIANTS.map((variant) => (
+            <option key={variant} value={variant}>
+              {VARIANT_LABELS[variant]}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      <label className="mt-3 flex cursor-pointer items-center gap-2 text-[12px] text-[#52525b]">
+        <input
+          type="checkbox"
+          checked={chineseConversion.regionalPhrases}
+          onChange={(e) => update({ regionalPhrases: e.target.checked })}
+          className="accent-blue-500"
+        />
+        Use regional vocabulary (软件 → 軟體)
+      </label>
+
+      {dictionaries && (
+        <div className="mt-2 text-[11px] text-[#a1a1aa]">
+          {dictionaries.files.length > 0
+            ? `Also using ${dictionaries.files.join(", ")}. `
+            : ""}
+          Add OpenCC .txt dictionaries to {dictionaries.folder}
+        </div>
+      )}
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
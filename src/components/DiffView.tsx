@@ -1,10 +1,25 @@
-import type { DiffSegment } from "@/core/diff/differ";
+import type { DiffHunk, DiffSegment } from "@/core/diff/differ";
+import { describeRules } from "@/core/typography/normalizer";
+import { cn } from "@/lib/utils";
 
 interface DiffViewProps {
   segments: DiffSegment[];
+  /** Used to name the typography rule behind a change. */
+  hunks?: DiffHunk[];
+  /** Hunks the user kept; when omitted every change is shown as accepted. */
+  acceptedIds?: Set<string>;
+  /** Hunk highlighted for keyboard toggling. */
+  focusedHunkId?: string | null;
+  onToggleHunk?: (hunkId: string) => void;
 }
 
-export function DiffView({ segments }: DiffViewProps) {
+export function DiffView({
+  segments,
+  hunks,
+  acceptedIds,
+  focusedHunkId,
+  onToggleHunk,
+}: DiffViewProps) {
   if (segments.length === 0) {
     return null;
   }
@@ -12,14 +27,35 @@ export function DiffView({ segments }: DiffViewProps) {
   return (
     <div className="text-[13px] leading-relaxed text-foreground">
       {segments.map((segment, i) => {
-        if (segment.type === "equal") {
+        if (segment.type === "equal" || !segment.hunk_id) {
           return <span key={i}>{segment.text}</span>;
         }
+
+        const hunkId = segment.hunk_id;
+        const accepted = acceptedIds?.has(hunkId) ?? true;
+        const focused = focusedHunkId === hunkId;
+        const interactive = cn(
+          onToggleHunk && "cursor-pointer",
+          focused && "ring-1 ring-primary/60",
+        );
+        const toggle = onToggleHunk ? () => onToggleHunk(hunkId) : undefined;
+        const rule = hunks?.find((h) => h.id === hunkId)?.rule;
+        const title = rule ? describeRules(rule) : undefined;
+
         if (segment.type === "delete") {
+          // A rejected deletion means the original text stays.
           return (
             <span
               key={i}
-              className="rounded-sm bg-diff-delete-bg text-diff-delete-text line-through"
+              onClick={toggle}
+              title={title}
+              className={cn(
+                "rounded-sm",
+                accepted
+                  ? "bg-diff-delete-bg text-diff-delete-text line-through"
+                  : "text-foreground",
+                interactive,
+              )}
             >
               {segment.text}
             </span>
@@ -29,7 +65,15 @@ export function DiffView({ segments }: DiffViewProps) {
         return (
           <span
             key={i}
-            className="rounded-sm bg-diff-insert-bg text-diff-insert-text"
+            onClick={toggle}
+            title={title}
+            className={cn(
+              "rounded-sm",
+              accepted
+                ? "bg-diff-insert-bg text-diff-insert-text"
+                : "text-muted-foreground line-through opacity-50",
+              interactive,
+            )}
           >
             {segment.text}
           </span>
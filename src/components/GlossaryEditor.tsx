@@ -0,0 +1,115 @@
+import { useState } from "react";
+import type { GlossaryEntry, ManagedState } from "@/core/llm/types";
+import { managedField } from "@/hooks/useSettings";
+import { ManagedBadge } from "@/components/ManagedBadge";
+import { X } from "lucide-react";
+
+const INPUT_CLASS =
+  "min-w-0 flex-1 rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+interface GlossaryEditorProps {
+  glossary: GlossaryEntry[];
+  managed?: ManagedState;
+  onSave: (entries: GlossaryEntry[]) => Promise<void>;
+}
+
+/**
+ * Preferences card for glossary terms sent with every request. Entries from
+ * the config file or policy are listed but cannot be edited.
+ */
+export function GlossaryEditor({
+  glossary,
+  managed,
+  onSave,
+}: GlossaryEditorProps) {
+  const [term, setTerm] = useState("");
+  const [replacement, setReplacement] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const save = async (entries: GlossaryEntry[]) => {
+    try {
+      await onSave(entries);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleAdd = async () => {
+    const trimmed = term.trim();
+    if (!trimmed) return;
+    if (glossary.some((e) => e.term === trimmed)) {
+      setError(`"${trimmed}" is already in the glossary.`);
+      return;
+    }
+    await save([...glossary, { term: trimmed, replacement: replacement.trim() }]);
+    setTerm("");
+    setReplacement("");
+  };
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="text-[13px] font-medium text-[#18181b]">Glossary</div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+        Terms kept as written, or always replaced, in every result
+      </div>
+
+      {glossary.length > 0 && (
+        <ul className="mt-3 space-y-1.5">
+          {glossary.map((entry) => {
+            const lock = managedField(managed, `glossary.${entry.term}`);
+            return (
+              <li
+                key={entry.term}
+                className="flex items-center gap-2 text-[12px] text-[#52525b]"
+              >
+                <span className="font-medium text-[#18181b]">{entry.term}</span>
+                {entry.replacement && (
+                  <span className="text-[#a1a1aa]">→ {entry.replacement}</span>
+                )}
+                <span className="flex-1" />
+                {lock ? (
+                  <ManagedBadge entry={lock} />
+                ) : (
+                  <button
+                    onClick={() =>
+                      save(glossary.filter((e) => e.term !== entry.term))
+                    }
+                    className="cursor-pointer text-[#a1a1aa] hover:text-[#52525b]"
+                    aria-label={`Remove ${entry.term}`}
+                  >
+                    <X className="h-3.5 w-3.5" />
+                  </button>
+                )}
+              </li>
+            );
+          })}
+        </ul>
+      )}
+
+      <div className="mt-3 flex items-center gap-2">
+        <input
+          value={term}
+          onChange={(e) => setTerm(e.target.value)}
+          placeholder="Term"
+          className={INPUT_CLASS}
+        />
+        <input
+          value={replacement}
+          onChange={(e) => setReplacement(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder="Replacement (optional)"
+          className={INPUT_CLASS}
+        />
+        <button
+          onClick={handleAdd}
+          disabled={!term.trim()}
+          className="cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-3 py-1.5 text-[12px] font-medium text-[#18181b] transition-colors duration-150 hover:bg-[#f4f4f5] disabled:cursor-default disabled:opacity-50"
+        >
+          Add
+        </button>
+      </div>
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
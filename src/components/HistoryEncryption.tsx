@@ -0,0 +1,175 @@
+import { useState, useEffect, useCallback } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  HistoryEncryptionStatus,
+  HistoryKeySource,
+} from "@/core/llm/types";
+import { useHistory } from "@/hooks/useHistory";
+import { Lock, LockOpen } from "lucide-react";
+
+const ERROR_MESSAGES: Record<string, string> = {
+  history_locked: "Unlock history first.",
+  passphrase_required: "Enter a passphrase.",
+  wrong_passphrase: "Wrong passphrase.",
+};
+
+const INPUT_CLASS =
+  "w-full rounded-lg border border-[#e4e4e7] bg-white px-3 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const BUTTON_CLASS =
+  "cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-3 py-1.5 text-[12px] font-medium text-[#18181b] transition-colors duration-150 hover:bg-[#f4f4f5] disabled:cursor-default disabled:opacity-50";
+
+/** Preferences card for encrypting history at rest. */
+export function HistoryEncryption() {
+  const {
+    getEncryptionStatus,
+    enableEncryption,
+    rotateKey,
+    disableEncryption,
+    lockHistory,
+  } = useHistory();
+  const [status, setStatus] = useState<HistoryEncryptionStatus | null>(null);
+  const [source, setSource] = useState<HistoryKeySource>("keyring");
+  const [passphrase, setPassphrase] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setStatus(await getEncryptionStatus());
+    } catch (err) {
+      console.error("Failed to load history encryption status:", err);
+    }
+  }, [getEncryptionStatus]);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen("history-changed", () => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  useEffect(() => {
+    if (status?.source) setSource(status.source);
+  }, [status?.source]);
+
+  const run = async (action: () => Promise<void>, done: string) => {
+    setBusy(true);
+    setMessage(null);
+    try {
+      await action();
+      setPassphrase("");
+      setMessage(done);
+    } catch (err) {
+      setMessage(ERROR_MESSAGES[String(err)] ?? `Failed: ${err}`);
+    } finally {
+      setBusy(false);
+      refresh();
+    }
+  };
+
+  if (!status) return null;
+
+  const encrypted = status.source !== null;
+  const needsPassphrase = source === "passphrase";
+  const canSubmit = !busy && (!needsPassphrase || passphrase.length > 0);
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[13px] font-medium text-[#18181b]">
+            History Encryption
+          </div>
+          <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+            {encrypted
+              ? status.unlocked
+                ? "History is encrypted and unlocked for this session"
+                : "History is encrypted and locked — nothing is recorded until it is unlocked"
+              : "History is stored unencrypted on this device"}
+          </div>
+        </div>
+        {encrypted &&
+          (status.unlocked ? (
+            <LockOpen className="h-4 w-4 text-[#a1a1aa]" />
+          ) : (
+            <Lock className="h-4 w-4 text-[#a1a1aa]" />
+          ))}
+      </div>
+
+      <div className="mt-3 flex flex-wrap items-center gap-2">
+        <select
+          value={source}
+          onChange={(e) => setSource(e.target.value as HistoryKeySource)}
+          className="cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none"
+        >
+          <option value="keyring">Key in system keychain</option>
+          <option value="passphrase">Passphrase</option>
+        </select>
+        {needsPassphrase && (
+          <div className="min-w-[160px] flex-1">
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder={encrypted ? "New passphrase" : "Passphrase"}
+              className={INPUT_CLASS}
+            />
+          </div>
+        )}
+        {!encrypted ? (
+          <button
+            disabled={!canSubmit}
+            onClick={() =>
+              run(
+                () => enableEncryption(source, passphrase || undefined),
+                "History encrypted.",
+              )
+            }
+            className={BUTTON_CLASS}
+          >
+            Encrypt
+          </button>
+        ) : (
+          <>
+            <button
+              disabled={!canSubmit || !status.unlocked}
+              onClick={() =>
+                run(
+                  () => rotateKey(source, passphrase || undefined),
+                  "Key rotated.",
+                )
+              }
+              className={BUTTON_CLASS}
+            >
+              Rotate Key
+            </button>
+            {status.unlocked && status.source === "passphrase" && (
+              <button
+                disabled={busy}
+                onClick={() => run(lockHistory, "History locked.")}
+                className={BUTTON_CLASS}
+              >
+                Lock
+              </button>
+            )}
+            <button
+              disabled={busy || !status.unlocked}
+              onClick={() => run(disableEncryption, "History decrypted.")}
+              className={BUTTON_CLASS}
+            >
+              Turn Off
+            </button>
+          </>
+        )}
+      </div>
+
+      {message && (
+        <div className="mt-2 text-[11px] text-[#71717a]">{message}</div>
+      )}
+    </div>
+  );
+}
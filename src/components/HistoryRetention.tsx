@@ -0,0 +1,154 @@
+import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  HistoryPolicy,
+  ManagedState,
+  RetentionMode,
+  Settings,
+} from "@/core/llm/types";
+import { useHistory } from "@/hooks/useHistory";
+import { managedField } from "@/hooks/useSettings";
+import { ManagedBadge } from "@/components/ManagedBadge";
+
+const RETENTION_OPTIONS: { value: RetentionMode; label: string }[] = [
+  { value: "keep_all", label: "Keep everything" },
+  { value: "days", label: "Keep for a number of days" },
+  { value: "records", label: "Keep a number of sessions" },
+  { value: "never", label: "Never save history" },
+];
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)] disabled:cursor-default disabled:opacity-60";
+
+/**
+ * Preferences card for history retention and PII scrubbing. Fields set by
+ * the config file or policy are shown read-only.
+ */
+export function HistoryRetention({ managed }: { managed?: ManagedState }) {
+  const { getPolicy, savePolicy, clearHistory } = useHistory();
+  const [policy, setPolicy] = useState<HistoryPolicy | null>(null);
+  const [confirmingDelete, setConfirmingDelete] = useState(false);
+
+  useEffect(() => {
+    getPolicy()
+      .then(setPolicy)
+      .catch((err) => console.error("Failed to load history policy:", err));
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      setPolicy(event.payload.historyPolicy);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [getPolicy]);
+
+  if (!policy) return null;
+
+  const update = async (patch: Partial<HistoryPolicy>) => {
+    const next = { ...policy, ...patch };
+    setPolicy(next);
+    try {
+      await savePolicy(next);
+    } catch (err) {
+      console.error("Failed to save history policy:", err);
+    }
+  };
+
+  const parseCount = (value: string) => Math.max(1, parseInt(value, 10) || 1);
+  const lock = (field: keyof HistoryPolicy) =>
+    managedField(managed, `historyPolicy.${field}`);
+  const retentionLock = lock("retention");
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="flex items-center gap-2 text-[13px] font-medium text-[#18181b]">
+        History Retention
+        <ManagedBadge entry={retentionLock ?? lock("scrubPii")} />
+      </div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+        Older sessions are removed automatically. Apps can be excluded on the
+        Apps page.
+      </div>
+
+      <div className="mt-3 flex flex-wrap items-center gap-2">
+        <select
+          value={policy.retention}
+          disabled={Boolean(retentionLock)}
+          onChange={(e) =>
+            update({ retention: e.target.value as RetentionMode })
+          }
+          className={`cursor-pointer ${FIELD_CLASS}`}
+        >
+          {RETENTION_OPTIONS.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+        {policy.retention === "days" && (
+          <input
+            type="number"
+            min={1}
+            defaultValue={policy.retentionDays}
+            disabled={Boolean(retentionLock ?? lock("retentionDays"))}
+            // Commit on blur so intermediate values never prune history.
+            onBlur={(e) => update({ retentionDays: parseCount(e.target.value) })}
+            className={`w-20 ${FIELD_CLASS}`}
+          />
+        )}
+        {policy.retention === "records" && (
+          <input
+            type="number"
+            min={1}
+            defaultValue={policy.retentionRecords}
+            disabled={Boolean(retentionLock ?? lock("retentionRecords"))}
+            // Commit on blur so intermediate values never prune history.
+            onBlur={(e) => update({ retentionRecords: parseCount(e.target.value) })}
+            className={`w-24 ${FIELD_CLASS}`}
+          />
+        )}
+      </div>
+      {policy.retention === "never" && (
+        <div className="mt-2 flex flex-wrap items-center gap-2 text-[11px] text-[#a1a1aa]">
+          Nothing new is recorded. Existing history is kept.
+          {confirmingDelete ? (
+            <>
+              <button
+                onClick={async () => {
+                  setConfirmingDelete(false);
+                  await clearHistory();
+                }}
+                className="cursor-pointer rounded-md px-2 py-0.5 font-medium text-red-500 transition-colors duration-150 hover:bg-red-50"
+              >
+                Delete all history
+              </button>
+              <button
+                onClick={() => setConfirmingDelete(false)}
+                className="cursor-pointer rounded-md px-2 py-0.5 font-medium text-[#71717a] transition-colors duration-150 hover:bg-[#f4f4f5]"
+              >
+                Cancel
+              </button>
+            </>
+          ) : (
+            <button
+              onClick={() => setConfirmingDelete(true)}
+              className="cursor-pointer rounded-md px-2 py-0.5 font-medium text-[#71717a] transition-colors duration-150 hover:bg-[#f4f4f5] hover:text-[#18181b]"
+            >
+              Delete existing history…
+            </button>
+          )}
+        </div>
+      )}
+
+      <label className="mt-3 flex cursor-pointer items-center gap-2 text-[12px] text-[#52525b]">
+        <input
+          type="checkbox"
+          checked={policy.scrubPii}
+          disabled={Boolean(lock("scrubPii"))}
+          onChange={(e) => update({ scrubPii: e.target.checked })}
+          className="accent-blue-500"
+        />
+        Remove emails, phone numbers, card numbers and API keys before saving
+      </label>
+    </div>
+  );
+}
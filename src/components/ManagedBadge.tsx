@@ -0,0 +1,14 @@
+import type { ManagedField } from "@/core/llm/types";
+import { managedLabel } from "@/hooks/useSettings";
+import { Lock } from "lucide-react";
+
+/** Marks a setting locked by the config file or organization policy. */
+export function ManagedBadge({ entry }: { entry: ManagedField | undefined }) {
+  if (!entry) return null;
+  return (
+    <span className="inline-flex items-center gap-1 rounded-md bg-[#f4f4f5] px-1.5 py-0.5 text-[10px] font-medium text-[#71717a]">
+      <Lock className="h-2.5 w-2.5" />
+      {managedLabel(entry)}
+    </span>
+  );
+}
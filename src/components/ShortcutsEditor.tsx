@@ -0,0 +1,262 @@
+import { useState, useEffect, type KeyboardEvent } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  QuickReplaceSettings,
+  ShortcutAction,
+  ShortcutBinding,
+  ShortcutStatus,
+} from "@/core/llm/types";
+import { Plus, X } from "lucide-react";
+
+const ACTION_OPTIONS: { value: ShortcutAction; label: string }[] = [
+  { value: "open_panel", label: "Open panel" },
+  { value: "improve", label: "Improve" },
+  { value: "rephrase", label: "Rephrase" },
+  { value: "translate", label: "Translate" },
+  { value: "custom", label: "Custom…" },
+  { value: "quick_replace", label: "Improve & replace (no panel)" },
+];
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const MODIFIER_SYMBOLS: Record<string, string> = {
+  Super: "⌘",
+  Alt: "⌥",
+  Control: "⌃",
+  Shift: "⇧",
+};
+
+/** Accelerator for a key press, or null while only modifiers are held. */
+function acceleratorFromEvent(e: KeyboardEvent): string | null {
+  if (["Meta", "Alt", "Control", "Shift"].includes(e.key)) return null;
+  const modifiers = [
+    e.ctrlKey && "Control",
+    e.altKey && "Alt",
+    e.shiftKey && "Shift",
+    e.metaKey && "Super",
+  ].filter((m): m is string => !!m);
+  // Bare keys would swallow normal typing; only function keys may go alone.
+  if (modifiers.length === 0 && !/^F\d+$/.test(e.code)) return null;
+  return [...modifiers, e.code].join("+");
+}
+
+/** `Alt+Super+KeyP` → `⌥⌘P`. */
+function formatAccelerator(accelerator: string): string {
+  return accelerator
+    .split("+")
+    .map((part) => {
+      const modifier = MODIFIER_SYMBOLS[part];
+      if (modifier) return modifier;
+      return part.replace(/^Key|^Digit/, "");
+    })
+    .join("");
+}
+
+function describeError(
+  error: string,
+  bindings: ShortcutBinding[],
+): string {
+  const [code, ...rest] = error.split(":");
+  const detail = rest.join(":");
+  switch (code) {
+    case "invalid_accelerator":
+      return "Not a valid shortcut.";
+    case "conflict": {
+      const index = bindings.findIndex((b) => b.id === detail);
+      return `Same keys as shortcut ${index + 1}.`;
+    }
+    case "unavailable":
+      return "Already used by another app or the system.";
+    default:
+      return error;
+  }
+}
+
+interface ShortcutsEditorProps {
+  shortcuts: ShortcutBinding[];
+  onSave: (bindings: ShortcutBinding[]) => Promise<void>;
+  quickReplace?: QuickReplaceSettings;
+  onSaveQuickReplace?: (next: QuickReplaceSettings) => Promise<void>;
+}
+
+/**
+ * Preferences card for global shortcuts. Changes are registered by the
+ * backend right away; bindings it could not register are flagged.
+ */
+export function ShortcutsEditor({
+  shortcuts,
+  onSave,
+  quickReplace,
+  onSaveQuickReplace,
+}: ShortcutsEditorProps) {
+  const [status, setStatus] = useState<ShortcutStatus[]>([]);
+  const [recording, setRecording] = useState<string | null>(null);
+  // Binding switched to "custom" that has no instruction yet; it is saved
+  // once one is entered, since the backend rejects it without.
+  const [pendingCustom, setPendingCustom] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ShortcutStatus[]>("get_shortcut_status")
+      .then(setStatus)
+      .catch((err) => console.error("Failed to load shortcut status:", err));
+    const unlisten = listen<ShortcutStatus[]>("shortcuts-changed", (event) => {
+      setStatus(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const save = async (bindings: ShortcutBinding[]) => {
+    try {
+      await onSave(bindings);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const update = (id: string, patch: Partial<ShortcutBinding>) =>
+    save(shortcuts.map((b) => (b.id === id ? { ...b, ...patch } : b)));
+
+  const handleAdd = () => {
+    save([
+      ...shortcuts,
+      {
+        id: crypto.randomUUID(),
+        accelerator: "Alt+Super+Shift+KeyP",
+        action: "rephrase",
+        instruction: null,
+      },
+    ]);
+  };
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="text-[13px] font-medium text-[#18181b]">
+        Global Shortcuts
+      </div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+        Each shortcut captures the selection and opens Polishr. Click a
+        shortcut and press new keys to change it.
+      </div>
+
+      <div className="mt-3 space-y-2">
+        {shortcuts.map((binding) => {
+          const bindingError = status.find((s) => s.id === binding.id)?.error;
+          const action =
+            pendingCustom === binding.id ? "custom" : binding.action;
+          return (
+            <div key={binding.id}>
+              <div className="flex items-center gap-2">
+                <button
+                  onClick={() => setRecording(binding.id)}
+                  onBlur={() => setRecording(null)}
+                  onKeyDown={(e) => {
+                    if (recording !== binding.id) return;
+                    e.preventDefault();
+                    if (e.key === "Escape") {
+                      setRecording(null);
+                      return;
+                    }
+                    const accelerator = acceleratorFromEvent(e);
+                    if (!accelerator) return;
+                    setRecording(null);
+                    update(binding.id, { accelerator });
+                  }}
+                  className={`min-w-[96px] cursor-pointer text-left font-medium ${FIELD_CLASS} ${
+                    bindingError ? "border-red-300" : ""
+                  }`}
+                >
+                  {recording === binding.id
+                    ? "Press keys…"
+                    : formatAccelerator(binding.accelerator)}
+                </button>
+                <select
+                  value={action}
+                  onChange={(e) => {
+                    const next = e.target.value as ShortcutAction;
+                    if (next === "custom" && !binding.instruction) {
+                      setPendingCustom(binding.id);
+                      return;
+                    }
+                    setPendingCustom(null);
+                    update(binding.id, { action: next });
+                  }}
+                  className={`cursor-pointer ${FIELD_CLASS}`}
+                >
+                  {ACTION_OPTIONS.map((opt) => (
+                    <option key={opt.value} value={opt.value}>
+                      {opt.label}
+                    </option>
+                  ))}
+                </select>
+                {action === "custom" && (
+                  <input
+                    defaultValue={binding.instruction ?? ""}
+                    placeholder="Instruction, e.g. make it more formal"
+                    autoFocus={pendingCustom === binding.id}
+                    onBlur={(e) => {
+                      const instruction = e.target.value.trim();
+                      if (!instruction) return;
+                      setPendingCustom(null);
+                      update(binding.id, { action: "custom", instruction });
+                    }}
+                    className={`min-w-0 flex-1 ${FIELD_CLASS}`}
+                  />
+                )}
+                <span className="flex-1" />
+                <button
+                  onClick={() =>
+                    save(shortcuts.filter((b) => b.id !== binding.id))
+                  }
+                  className="cursor-pointer text-[#a1a1aa] hover:text-[#52525b]"
+                  aria-label="Remove shortcut"
+                >
+                  <X className="h-3.5 w-3.5" />
+                </button>
+              </div>
+              {bindingError && (
+                <div className="mt-1 text-[11px] text-red-500">
+                  {describeError(bindingError, shortcuts)}
+                </div>
+              )}
+            </div>
+          );
+        })}
+      </div>
+
+      {quickReplace &&
+        onSaveQuickReplace &&
+        shortcuts.some((b) => b.action === "quick_replace") && (
+          <div className="mt-3 flex items-center gap-2 text-[12px] text-[#52525b]">
+            Replace without review up to
+            <input
+              type="number"
+              min={1}
+              defaultValue={quickReplace.maxChars}
+              onBlur={(e) =>
+                onSaveQuickReplace({
+                  maxChars: Math.max(1, parseInt(e.target.value, 10) || 1),
+                }).catch((err) => setError(String(err)))
+              }
+              className={`w-20 ${FIELD_CLASS}`}
+            />
+            characters; longer selections are left alone.
+          </div>
+        )}
+
+      <button
+        onClick={handleAdd}
+        className="mt-3 flex cursor-pointer items-center gap-1 text-[12px] font-medium text-[#52525b] hover:text-[#18181b]"
+      >
+        <Plus className="h-3.5 w-3.5" />
+        Add shortcut
+      </button>
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
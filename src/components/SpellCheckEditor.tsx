@@ -0,0 +1,160 @@
+import { useEffect, useState } from "react";
+import type { SpellCheckSettings } from "@/core/llm/types";
+import { listDictionaries, type DictionaryList } from "@/core/spell/checker";
+import { Plus, X } from "lucide-react";
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const CHIP_CLASS =
+  "flex items-center gap-1 rounded-md bg-[#f4f4f5] px-2 py-1 text-[11px] text-[#52525b]";
+
+interface SpellCheckEditorProps {
+  spellCheck: SpellCheckSettings;
+  onSave: (next: SpellCheckSettings) => Promise<void>;
+}
+
+/** Spelling card: dictionaries to check against and the personal word list. */
+export function SpellCheckEditor({ spellCheck, onSave }: SpellCheckEditorProps) {
+  const [dictionaries, setDictionaries] = useState<DictionaryList | null>(null);
+  const [draft, setDraft] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    listDictionaries()
+      .then(setDictionaries)
+      .catch((err) => console.error("Failed to list dictionaries:", err));
+  }, []);
+
+  const save = async (patch: Partial<SpellCheckSettings>) => {
+    try {
+      await onSave({ ...spellCheck, ...patch });
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleAddWord = () => {
+    const word = draft.trim();
+    if (!word) return;
+    setDraft("");
+    if (spellCheck.personalWords.includes(word)) return;
+    save({ personalWords: [...spellCheck.personalWords, word] });
+  };
+
+  const available = (dictionaries?.languages ?? []).filter(
+    (l) => !spellCheck.languages.includes(l),
+  );
+  const missing = spellCheck.languages.filter(
+    (l) => dictionaries && !dictionaries.languages.includes(l),
+  );
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <label className="flex cursor-pointer items-center justify-between">
+        <div>
+          <div className="text-[13px] font-medium text-[#18181b]">Spelling</div>
+          <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+            Typos are shown right away, before the suggestion arrives, and
+            without a network connection.
+          </div>
+        </div>
+        <input
+          type="checkbox"
+          checked={spellCheck.enabled}
+          onChange={(e) => save({ enabled: e.target.checked })}
+          className="accent-blue-500"
+        />
+      </label>
+
+      {spellCheck.enabled && (
+        <>
+          <div className="mt-3 flex flex-wrap items-center gap-1.5">
+            {spellCheck.languages.map((language) => (
+              <span key={language} className={CHIP_CLASS}>
+                {language}
+                <button
+                  onClick={() =>
+                    save({
+                      languages: spellCheck.languages.filter((l) => l !== language),
+                    })
+                  }
+                  className="cursor-pointer text-[#a1a1aa] hover:text-[#52525b]"
+                  aria-label={`Remove ${language}`}
+                >
+                  <X className="h-3 w-3" />
+                </button>
+              </span>
+            ))}
+            {available.length > 0 && (
+              <select
+                value=""
+                onChange={(e) =>
+                  e.target.value &&
+                  save({ languages: [...spellCheck.languages, e.target.value] })
+                }
+                className={`cursor-pointer ${FIELD_CLASS}`}
+              >
+                <option value="">Add dictionary…</option>
+                {available.map((language) => (
+                  <option key={language} value={language}>
+                    {language}
+                  </option>
+                ))}
+              </select>
+            )}
+          </div>
+          {dictionaries && (missing.length > 0 || dictionaries.languages.length === 0) && (
+            <div className="mt-2 text-[11px] text-[#a1a1aa]">
+              {missing.length > 0 && `No dictionary found for ${missing.join(", ")}. `}
+              Add Hunspell .aff and .dic files to {dictionaries.folder}
+            </div>
+          )}
+
+          <div className="mt-4 text-[12px] font-medium text-[#52525b]">
+            Personal words
+          </div>
+          <div className="mt-1.5 flex flex-wrap gap-1.5">
+            {spellCheck.personalWords.map((word) => (
+              <span key={word} className={CHIP_CLASS}>
+                {word}
+                <button
+                  onClick={() =>
+                    save({
+                      personalWords: spellCheck.personalWords.filter((w) => w !== word),
+                    })
+                  }
+                  className="cursor-pointer text-[#a1a1aa] hover:text-[#52525b]"
+                  aria-label={`Remove ${word}`}
+                >
+                  <X className="h-3 w-3" />
+                </button>
+              </span>
+            ))}
+            {spellCheck.personalWords.length === 0 && (
+              <span className="text-[11px] text-[#a1a1aa]">No words yet.</span>
+            )}
+          </div>
+          <div className="mt-2 flex items-center gap-2">
+            <input
+              value={draft}
+              onChange={(e) => setDraft(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handleAddWord()}
+              placeholder="e.g. Polishr"
+              className={`min-w-0 flex-1 ${FIELD_CLASS}`}
+            />
+            <button
+              onClick={handleAddWord}
+              className="flex cursor-pointer items-center gap-1 text-[12px] font-medium text-[#52525b] hover:text-[#18181b]"
+            >
+              <Plus className="h-3.5 w-3.5" />
+              Add
+            </button>
+          </div>
+        </>
+      )}
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
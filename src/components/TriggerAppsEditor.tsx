@@ -0,0 +1,116 @@
+import { useState } from "react";
+import type { TriggerApps, TriggerAppsMode } from "@/core/llm/types";
+import { Plus, X } from "lucide-react";
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const MODE_OPTIONS: { value: TriggerAppsMode; label: string }[] = [
+  { value: "deny", label: "Everywhere except" },
+  { value: "allow", label: "Only in" },
+];
+
+interface TriggerAppsEditorProps {
+  triggerApps: TriggerApps;
+  onSave: (next: TriggerApps) => Promise<void>;
+}
+
+/**
+ * Apps card for where the trigger button may appear. Password fields are
+ * always skipped, whatever the list says.
+ */
+export function TriggerAppsEditor({ triggerApps, onSave }: TriggerAppsEditorProps) {
+  const [draft, setDraft] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const save = async (next: TriggerApps) => {
+    try {
+      await onSave(next);
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleAdd = () => {
+    const id = draft.trim();
+    if (!id) return;
+    setDraft("");
+    if (triggerApps.apps.some((a) => a.id === id)) return;
+    save({ ...triggerApps, apps: [...triggerApps.apps, { id, name: id }] });
+  };
+
+  return (
+    <div className="mb-6 rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[13px] font-medium text-[#18181b]">
+            Trigger button
+          </div>
+          <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+            Never shown in password fields. Right-click the button to turn it
+            off for an app.
+          </div>
+        </div>
+        <select
+          value={triggerApps.mode}
+          onChange={(e) =>
+            save({ ...triggerApps, mode: e.target.value as TriggerAppsMode })
+          }
+          className={`cursor-pointer ${FIELD_CLASS}`}
+        >
+          {MODE_OPTIONS.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      <div className="mt-3 flex flex-wrap gap-1.5">
+        {triggerApps.apps.map((app) => (
+          <span
+            key={app.id}
+            title={app.id}
+            className="flex items-center gap-1 rounded-md bg-[#f4f4f5] px-2 py-1 text-[11px] text-[#52525b]"
+          >
+            {app.name || app.id}
+            <button
+              onClick={() =>
+                save({
+                  ...triggerApps,
+                  apps: triggerApps.apps.filter((a) => a.id !== app.id),
+                })
+              }
+              className="cursor-pointer text-[#a1a1aa] hover:text-[#52525b]"
+              aria-label={`Remove ${app.name || app.id}`}
+            >
+              <X className="h-3 w-3" />
+            </button>
+          </span>
+        ))}
+        {triggerApps.apps.length === 0 && (
+          <span className="text-[11px] text-[#a1a1aa]">No apps listed.</span>
+        )}
+      </div>
+
+      <div className="mt-3 flex items-center gap-2">
+        <input
+          value={draft}
+          onChange={(e) => setDraft(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder="App name or bundle id, e.g. com.apple.Terminal"
+          className={`min-w-0 flex-1 ${FIELD_CLASS}`}
+        />
+        <button
+          onClick={handleAdd}
+          className="flex cursor-pointer items-center gap-1 text-[12px] font-medium text-[#52525b] hover:text-[#18181b]"
+        >
+          <Plus className="h-3.5 w-3.5" />
+          Add
+        </button>
+      </div>
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,96 @@
+import { useState } from "react";
+import type {
+  QuoteStyle,
+  TypographyRule,
+  TypographySettings,
+} from "@/core/llm/types";
+import { RULE_LABELS } from "@/core/typography/normalizer";
+
+const FIELD_CLASS =
+  "rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const RULES = Object.keys(RULE_LABELS) as TypographyRule[];
+
+interface TypographyEditorProps {
+  typography: TypographySettings;
+  onSave: (next: TypographySettings) => Promise<void>;
+}
+
+/** Typography card: which rules run, and whether they follow Improve/Translate. */
+export function TypographyEditor({ typography, onSave }: TypographyEditorProps) {
+  const [error, setError] = useState<string | null>(null);
+
+  const update = async (patch: Partial<TypographySettings>) => {
+    try {
+      await onSave({ ...typography, ...patch });
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const toggleRule = (rule: TypographyRule, on: boolean) =>
+    update({
+      rules: on
+        ? [...typography.rules, rule]
+        : typography.rules.filter((r) => r !== rule),
+    });
+
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="text-[13px] font-medium text-[#18181b]">Typography</div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
+        Fixes spacing, punctuation and quotes in Chinese and mixed text
+        locally, without a model.
+      </div>
+
+      <div className="mt-3 space-y-1.5 text-[12px] text-[#52525b]">
+        {RULES.map((rule) => (
+          <label key={rule} className="flex cursor-pointer items-center gap-2">
+            <input
+              type="checkbox"
+              checked={typography.rules.includes(rule)}
+              onChange={(e) => toggleRule(rule, e.target.checked)}
+              className="accent-blue-500"
+            />
+            {RULE_LABELS[rule]}
+          </label>
+        ))}
+      </div>
+
+      <div className="mt-3 flex items-center gap-2 text-[12px] text-[#52525b]">
+        Quotation marks
+        <select
+          value={typography.quoteStyle}
+          onChange={(e) => update({ quoteStyle: e.target.value as QuoteStyle })}
+          className={`cursor-pointer ${FIELD_CLASS}`}
+        >
+          <option value="curly">“ ” (Simplified)</option>
+          <option value="corner">「 」 (Traditional)</option>
+        </select>
+      </div>
+
+      <div className="mt-3 space-y-1.5 text-[12px] text-[#52525b]">
+        <label className="flex cursor-pointer items-center gap-2">
+          <input
+            type="checkbox"
+            checked={typography.afterImprove}
+            onChange={(e) => update({ afterImprove: e.target.checked })}
+            className="accent-blue-500"
+          />
+          Also apply after Improve
+        </label>
+        <label className="flex cursor-pointer items-center gap-2">
+          <input
+            type="checkbox"
+            checked={typography.afterTranslate}
+            onChange={(e) => update({ afterTranslate: e.target.checked })}
+            className="accent-blue-500"
+          />
+          Also apply after Translate
+        </label>
+      </div>
+      {error && <div className="mt-2 text-[11px] text-red-500">{error}</div>}
+    </div>
+  );
+}
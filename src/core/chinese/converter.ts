@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { DiffResult } from "@/core/diff/differ";
+import type { ChineseVariant } from "@/core/llm/types";
+
+/** Converted text with the diff from the input. */
+export interface ConversionResult extends DiffResult {
+  text: string;
+}
+
+/** Where user OpenCC dictionaries go and which ones were found. */
+export interface ConversionDictionaryList {
+  folder: string;
+  files: string[];
+}
+
+export const VARIANT_LABELS: Record<ChineseVariant, string> = {
+  simplified: "Simplified (简体)",
+  traditional: "Traditional (繁體)",
+  taiwan: "Taiwan (臺灣正體)",
+  hong_kong: "Hong Kong (香港繁體)",
+};
+
+/** Convert `text` to the configured Chinese variant, locally. */
+export function convertChinese(text: string): Promise<ConversionResult> {
+  return invoke<ConversionResult>("convert_chinese", { text });
+}
+
+/** User dictionaries found; they are reloaded on the next conversion. */
+export function listConversionDictionaries(): Promise<ConversionDictionaryList> {
+  return invoke<ConversionDictionaryList>("list_conversion_dictionaries");
+}
@@ -1,29 +1,81 @@
-import DiffMatchPatch from "diff-match-patch";
+import { invoke } from "@tauri-apps/api/core";
+import type { Readability } from "@/core/readability/metrics";
 
 export type DiffType = "equal" | "insert" | "delete";
 
+export type DiffGranularity = "char" | "word" | "sentence";
+
+/** Half-open range of UTF-16 code units. */
+export interface TextRange {
+  start: number;
+  end: number;
+}
+
 export interface DiffSegment {
   type: DiffType;
   text: string;
+  /** Covered range in the original text (empty for insertions). */
+  source: TextRange;
+  /** Covered range in the polished text (empty for deletions). */
+  target: TextRange;
+  /** Shared by a deletion and the insertion it was moved to. */
+  move_id: number | null;
+  /** Hunk this segment belongs to (null for equal segments). */
+  hunk_id: string | null;
 }
 
-const dmp = new DiffMatchPatch();
+/** A contiguous change that can be accepted or rejected on its own. */
+export interface DiffHunk {
+  /** Stable id derived from the hunk's position and content. */
+  id: string;
+  source: TextRange;
+  target: TextRange;
+  deleted: string;
+  inserted: string;
+  move_id: number | null;
+  /** Typography rules that made this change, comma-separated. */
+  rule?: string | null;
+}
+
+export interface DiffResult {
+  segments: DiffSegment[];
+  hunks: DiffHunk[];
+  /** Length and readability of the original and the revised text. */
+  metrics: Readability;
+}
+
+export interface DiffOptions {
+  granularity?: DiffGranularity;
+  ignore_whitespace?: boolean;
+  detect_moves?: boolean;
+}
 
 /**
- * Compute an inline diff between original and polished text.
- * Returns an array of segments, each tagged as equal / insert / delete.
+ * Compute an inline diff between original and polished text in the
+ * backend. Returns segments tagged as equal / insert / delete, with
+ * offsets into both texts, grouped into hunks, plus readability metrics.
  */
 export function computeDiff(
   original: string,
   polished: string,
-): DiffSegment[] {
-  const diffs = dmp.diff_main(original, polished);
-  dmp.diff_cleanupSemantic(diffs);
-
-  return diffs.map(([op, text]) => ({
-    type: op === 0 ? "equal" : op === 1 ? "insert" : "delete",
-    text,
-  }));
+  options: DiffOptions = { granularity: "word", detect_moves: true },
+): Promise<DiffResult> {
+  return invoke<DiffResult>("compute_diff", {
+    original,
+    revised: polished,
+    options,
+  });
+}
+
+/**
+ * Build the final text from the original, applying only the accepted hunks.
+ */
+export function composeResult(
+  original: string,
+  hunks: DiffHunk[],
+  acceptedIds: string[],
+): Promise<string> {
+  return invoke<string>("compose_result", { original, hunks, acceptedIds });
 }
 
 /**
@@ -1,7 +1,11 @@
-import type { PolishrConfig, PolishMode, ChatMessage } from "./types";
+import { invoke, Channel } from "@tauri-apps/api/core";
+import type {
+  PolishrConfig,
+  PolishMode,
+  LlmStreamEvent,
+} from "./types";
 import type { DetectedLang } from "../lang/detect";
-import { getPrompt } from "../prompts";
-import { parseSSEStream } from "./stream";
+import { promptMessages } from "../prompts";
 
 export class PolishError extends Error {
   constructor(
@@ -13,9 +17,40 @@ export class PolishError extends Error {
   }
 }
 
+/** Map a backend error code to a user-facing error. */
+function toPolishError(err: unknown): PolishError {
+  const [code, ...rest] = String(err).split(":");
+  const detail = rest.join(":");
+  switch (code) {
+    case "missing_api_key":
+    case "no_provider":
+      return new PolishError("No API key configured. Check your settings.");
+    case "invalid_api_key":
+      return new PolishError("Invalid API key. Check your settings.", 401);
+    case "rate_limited":
+      return new PolishError("Rate limited. Please wait and try again.", 429);
+    case "model_not_found":
+      return new PolishError(
+        `Model "${detail}" not found. Check your settings.`,
+        404,
+      );
+    case "api_error": {
+      const [status, ...body] = detail.split(":");
+      return new PolishError(
+        `API error (${status}): ${body.join(":") || "no details"}`,
+        Number(status),
+      );
+    }
+    case "network_error":
+      return new PolishError(`Network error: ${detail}`);
+    default:
+      return new PolishError(String(err));
+  }
+}
+
 /**
- * Stream a polishing/translation request to an OpenAI-compatible API.
- * Yields content tokens as they arrive.
+ * Stream a polishing/translation request through the backend, which holds
+ * the provider's API key. Yields content tokens as they arrive.
  *
  * @param lang - Detected language of the input text.
  * @param customInstruction - Optional free-form instruction from the user
@@ -29,56 +64,72 @@ export async function* polishStream(
   signal?: AbortSignal,
   customInstruction?: string,
 ): AsyncGenerator<string> {
-  const systemPrompt = getPrompt(mode, lang);
-
-  const userContent = customInstruction
-    ? `${text}\n\n[Additional instruction from user: ${customInstruction}]`
-    : text;
+  const messages = await promptMessages(text, mode, lang, customInstruction);
 
-  const messages: ChatMessage[] = [
-    { role: "system", content: systemPrompt },
-    { role: "user", content: userContent },
-  ];
+  if (signal?.aborted) {
+    throw new DOMException("Aborted", "AbortError");
+  }
 
-  const response = await fetch(
-    `${config.apiEndpoint.replace(/\/+$/, "")}/chat/completions`,
-    {
-      method: "POST",
-      headers: {
-        "Content-Type": "application/json",
-        Authorization: `Bearer ${config.apiKey}`,
-      },
-      body: JSON.stringify({
-        model: config.model,
-        messages,
-        stream: true,
-        temperature: config.temperature,
-      }),
-      signal,
-    },
-  );
+  const requestId = crypto.randomUUID();
+  const tokens: string[] = [];
+  let done = false;
+  let failure: unknown = null;
+  let wake: (() => void) | null = null;
+  const notify = () => {
+    wake?.();
+    wake = null;
+  };
 
-  if (!response.ok) {
-    const errorBody = await response.text().catch(() => "");
-    if (response.status === 401) {
-      throw new PolishError("Invalid API key. Check your settings.", 401);
+  const onEvent = new Channel<LlmStreamEvent>();
+  onEvent.onmessage = (message) => {
+    if (message.event === "token") {
+      tokens.push(message.data);
+    } else {
+      done = true;
     }
-    if (response.status === 429) {
-      throw new PolishError("Rate limited. Please wait and try again.", 429);
+    notify();
+  };
+
+  const onAbort = () => {
+    invoke("llm_cancel", { requestId }).catch(() => {});
+    notify();
+  };
+  signal?.addEventListener("abort", onAbort, { once: true });
+
+  invoke("llm_chat_stream", {
+    requestId,
+    providerId: config.providerId,
+    messages,
+    onEvent,
+  }).catch((err) => {
+    failure = err;
+    notify();
+  });
+
+  try {
+    // The command resolves once the stream ends; the final "done" event
+    // guarantees every token has been delivered before we stop.
+    while (true) {
+      if (signal?.aborted) {
+        throw new DOMException("Aborted", "AbortError");
+      }
+      if (tokens.length > 0) {
+        yield tokens.shift()!;
+        continue;
+      }
+      if (failure !== null) throw toPolishError(failure);
+      if (done) return;
+      await new Promise<void>((resolve) => {
+        wake = resolve;
+      });
     }
-    if (response.status === 404) {
-      throw new PolishError(
-        `Model "${config.model}" not found. Check your settings.`,
-        404,
-      );
+  } finally {
+    signal?.removeEventListener("abort", onAbort);
+    // Stop the backend request if the consumer stopped early.
+    if (!done && failure === null) {
+      invoke("llm_cancel", { requestId }).catch(() => {});
     }
-    throw new PolishError(
-      `API error (${response.status}): ${errorBody || response.statusText}`,
-      response.status,
-    );
   }
-
-  yield* parseSSEStream(response);
 }
 
 /**
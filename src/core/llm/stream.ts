@@ -1,52 +0,0 @@
-import type { ChatCompletionChunk } from "./types";
-
-/**
- * Parse an SSE stream from an OpenAI-compatible API response.
- * Yields the content delta from each chunk.
- */
-export async function* parseSSEStream(
-  response: Response,
-): AsyncGenerator<string> {
-  const reader = response.body?.getReader();
-  if (!reader) {
-    throw new Error("Response body is not readable");
-  }
-
-  const decoder = new TextDecoder();
-  let buffer = "";
-
-  try {
-    while (true) {
-      const { done, value } = await reader.read();
-      if (done) break;
-
-      buffer += decoder.decode(value, { stream: true });
-
-      const lines = buffer.split("\n");
-      // Keep the last potentially incomplete line in the buffer
-      buffer = lines.pop() ?? "";
-
-      for (const line of lines) {
-        const trimmed = line.trim();
-
-        if (trimmed === "") continue;
-        if (trimmed === "data: [DONE]") return;
-
-        if (trimmed.startsWith("data: ")) {
-          const jsonStr = trimmed.slice(6);
-          try {
-            const chunk: ChatCompletionChunk = JSON.parse(jsonStr);
-            const content = chunk.choices[0]?.delta?.content;
-            if (content) {
-              yield content;
-            }
-          } catch {
-            // Skip malformed JSON lines
-          }
-        }
-      }
-    }
-  } finally {
-    reader.releaseLock();
-  }
-}
@@ -1,40 +1,47 @@
+/**
+ * Which provider a request goes to. Endpoint, model and API key are
+ * resolved by the backend, so the key never reaches the webview.
+ */
 export interface PolishrConfig {
-  apiEndpoint: string;
-  apiKey: string;
-  model: string;
-  temperature: number;
+  /** null uses the active provider. */
+  providerId: string | null;
 }
 
-export type PolishMode = "improve" | "rephrase" | "translate";
+/** `typography` and `convert` run locally with no model involved. */
+export type PolishMode =
+  | "improve"
+  | "rephrase"
+  | "translate"
+  | "typography"
+  | "convert";
 
 export interface ChatMessage {
   role: "system" | "user" | "assistant";
   content: string;
 }
 
-export interface ChatCompletionChunk {
-  id: string;
-  object: string;
-  created: number;
-  model: string;
-  choices: {
-    index: number;
-    delta: {
-      role?: string;
-      content?: string;
-    };
-    finish_reason: string | null;
-  }[];
-}
+/** Events streamed back by the backend while a chat request runs. */
+export type LlmStreamEvent =
+  | { event: "token"; data: string }
+  | { event: "done" };
 
 /** A configured LLM provider (e.g. OpenAI, DeepSeek). */
 export interface Provider {
   id: string;
   name: string;
   endpoint: string;
-  apiKey: string;
   model: string;
   temperature: number;
+  /** Keyring entry holding the API key; absent when no key is set. */
+  apiKeyRef?: string | null;
+}
+
+/** A suggested change and whether the user kept it. */
+export interface HistoryHunk {
+  id: string;
+  deleted: string;
+  inserted: string;
+  accepted: boolean;
 }
 
 /** A saved polish history record. */
@@ -45,10 +52,334 @@ export interface HistoryRecord {
   inputText: string;
   resultText: string;
   provider: string;
+  /** Application the input was captured from, if known. */
+  app?: string;
+  /** Bundle identifier of `app`, if known. */
+  appId?: string;
+  /** Detected language of the input ("en" / "zh"). */
+  language?: string;
+  /** Per-hunk decisions, recorded when the result is accepted. */
+  hunks?: HistoryHunk[];
+}
+
+/** Search text, filters and pagination for the history store. */
+export interface HistoryQuery {
+  text?: string;
+  modes?: string[];
+  providers?: string[];
+  apps?: string[];
+  languages?: string[];
+  /** Inclusive lower bound, Unix ms. */
+  from?: number;
+  /** Exclusive upper bound, Unix ms. */
+  to?: number;
+  offset?: number;
+  limit?: number;
+}
+
+export interface HistoryPage {
+  records: HistoryRecord[];
+  /** Number of matching records, ignoring pagination. */
+  total: number;
+}
+
+/** Distinct values present in history, for filter dropdowns. */
+export interface HistoryFacets {
+  modes: string[];
+  providers: string[];
+  apps: string[];
+  languages: string[];
+}
+
+export type HistoryExportFormat = "json" | "csv" | "markdown";
+
+export interface HistoryExportResult {
+  path: string;
+  count: number;
+}
+
+export interface HistoryImportResult {
+  imported: number;
+  /** Records whose id was already in history. */
+  duplicates: number;
+  /** Records left out by the retention policy: excluded apps, or history off. */
+  excluded: number;
+  /** CSV rows without a readable timestamp. */
+  invalid: number;
+}
+
+/** Where the history encryption key comes from. */
+export type HistoryKeySource = "keyring" | "passphrase";
+
+export interface HistoryEncryptionStatus {
+  /** null when history is stored in plaintext. */
+  source: HistoryKeySource | null;
+  unlocked: boolean;
+}
+
+export type RetentionMode = "keep_all" | "days" | "records" | "never";
+
+/**
+ * An app history is never recorded from, matched by bundle id when both
+ * sides know it, else by name.
+ */
+export interface ExcludedApp {
+  /** Application name, or a bundle id from the config file. */
+  app: string;
+  appId: string | null;
+}
+
+/** What gets saved to history and for how long. */
+export interface HistoryPolicy {
+  retention: RetentionMode;
+  retentionDays: number;
+  retentionRecords: number;
+  /** Apps whose text is never recorded. */
+  excludedApps: ExcludedApp[];
+  /** Replace emails, phone numbers, card numbers and secrets before saving. */
+  scrubPii: boolean;
+}
+
+export type ErrorCategory =
+  | "spelling"
+  | "punctuation"
+  | "capitalization"
+  | "spacing"
+  | "article"
+  | "preposition"
+  | "confused_words"
+  | "grammar"
+  | "word_choice"
+  | "rephrasing";
+
+export interface InsightsBreakdown {
+  key: string;
+  sessions: number;
+  words: number;
+}
+
+/** A correction the writer received more than once. */
+export interface RecurringCorrection {
+  category: ErrorCategory;
+  deleted: string;
+  inserted: string;
+  count: number;
+  accepted: number;
+  last_seen: number;
+}
+
+export interface DayStats {
+  /** Local date, YYYY-MM-DD. */
+  date: string;
+  sessions: number;
+  words: number;
+  corrections: number;
+  by_category: Partial<Record<ErrorCategory, number>>;
+}
+
+/** Writing statistics aggregated from history by the backend. */
+export interface Insights {
+  sessions: number;
+  words: number;
+  corrections: number;
+  by_mode: InsightsBreakdown[];
+  by_language: InsightsBreakdown[];
+  by_app: InsightsBreakdown[];
+  categories: { category: ErrorCategory; count: number }[];
+  top_corrections: RecurringCorrection[];
+  trend: DayStats[];
+}
+
+/** Settings as owned and validated by the backend. */
+export interface Settings {
+  schemaVersion: number;
+  providers: Provider[];
+  /** Empty when no provider has been activated yet. */
+  activeProviderId: string;
+  defaultMode: PolishMode;
+  appProfiles: AppProfile[];
+  historyPolicy: HistoryPolicy;
+  glossary: GlossaryEntry[];
+  shortcuts: ShortcutBinding[];
+  quickReplace: QuickReplaceSettings;
+  /** Trigger button paused from the tray. */
+  triggerPaused: boolean;
+  triggerApps: TriggerApps;
+  backgroundCheck: BackgroundCheckSettings;
+  spellCheck: SpellCheckSettings;
+  typography: TypographySettings;
+  chineseConversion: ChineseConversionSettings;
+  /** Present on settings read from the backend; never saved. */
+  managed?: ManagedState;
+}
+
+/** What a global shortcut does after capturing the selection. */
+export type ShortcutAction =
+  | "open_panel"
+  | "improve"
+  | "rephrase"
+  | "translate"
+  | "custom"
+  /** Improve and paste back without opening the panel. */
+  | "quick_replace";
+
+export interface ShortcutBinding {
+  id: string;
+  /** e.g. `Alt+Super+KeyP`. */
+  accelerator: string;
+  action: ShortcutAction;
+  /** Instruction sent by `custom` bindings. */
+  instruction?: string | null;
+}
+
+export interface QuickReplaceSettings {
+  /** Longest selection, in characters, replaced without review. */
+  maxChars: number;
+}
+
+/** Opt-in grammar check of the paragraph under the caret. */
+export interface BackgroundCheckSettings {
+  enabled: boolean;
+  /** Provider for background checks; the app's or the active one when null. */
+  providerId: string | null;
+  /** How long typing has to pause before a paragraph is checked. */
+  debounceMs: number;
+  /** Most requests sent in any minute. */
+  maxPerMinute: number;
+}
+
+/** Offline spell checking with Hunspell dictionaries. */
+export interface SpellCheckSettings {
+  enabled: boolean;
+  /** Dictionary names, e.g. `en_US`, `de_DE`. */
+  languages: string[];
+  /** Words never reported, whatever the dictionaries say. */
+  personalWords: string[];
+}
+
+/** Rules of the CJK typography normalizer. */
+export type TypographyRule =
+  | "cjk_latin_spacing"
+  | "fullwidth_punctuation"
+  | "halfwidth_punctuation"
+  | "fullwidth_spacing"
+  | "quotation_marks";
+
+/** `curly` is “” (Simplified Chinese), `corner` is 「」 (Traditional). */
+export type QuoteStyle = "curly" | "corner";
+
+export interface TypographySettings {
+  rules: TypographyRule[];
+  quoteStyle: QuoteStyle;
+  /** Normalize the result of Improve. */
+  afterImprove: boolean;
+  /** Normalize the result of Translate. */
+  afterTranslate: boolean;
+}
+
+/** `traditional` is OpenCC's standard forms, without regional variants. */
+export type ChineseVariant = "simplified" | "traditional" | "taiwan" | "hong_kong";
+
+export interface ChineseConversionSettings {
+  target: ChineseVariant;
+  /** Also swap regional vocabulary (软件 → 軟體 for Taiwan). */
+  regionalPhrases: boolean;
+}
+
+/** Improve result computed by the background check. */
+export interface Suggestion {
+  explanation: string;
+  text: string;
+  /** Number of separate changes. */
+  issues: number;
+}
+
+/** Registration outcome reported by the backend for one binding. */
+export interface ShortcutStatus {
+  id: string;
+  /** `invalid_accelerator`, `conflict:<id>` or `unavailable:<reason>`. */
+  error: string | null;
 }
 
+/** A term the model must keep, or always write as `replacement`. */
+export interface GlossaryEntry {
+  term: string;
+  /** Empty to keep the term exactly as written. */
+  replacement: string;
+}
+
+/** A setting locked by the config file or organization policy. */
+export interface ManagedField {
+  /** e.g. `defaultMode`, `providers.openai`, `historyPolicy.retention`. */
+  field: string;
+  source: "config" | "policy";
+}
+
+export interface ManagedState {
+  configPath: string | null;
+  policyPath: string | null;
+  configError: string | null;
+  policyError: string | null;
+  fields: ManagedField[];
+  /** Provider ids allowed by policy; null when unrestricted. */
+  allowedProviders: string[] | null;
+}
+
+/** Per-application overrides, resolved by the backend at capture time. */
+export interface AppProfile {
+  app: string;
+  /** Bundle identifier; matched before the name when both sides know it. */
+  appId: string | null;
+  mode: PolishMode | null;
+  providerId: string | null;
+  instruction: string | null;
+  triggerEnabled: boolean;
+}
+
+/** `deny`: the trigger appears everywhere except the listed apps. */
+export type TriggerAppsMode = "deny" | "allow";
+
+export interface TriggerApp {
+  /** Bundle identifier, or the app name when the bundle id is unknown. */
+  id: string;
+  name: string;
+}
+
+/** Apps the trigger button may appear in. */
+export interface TriggerApps {
+  mode: TriggerAppsMode;
+  apps: TriggerApp[];
+}
+
+/** An application text was recently captured from. */
+export interface RecentApp {
+  app: string;
+  appId: string | null;
+  lastSeen: number;
+}
+
+/** Reusable instructions offered when editing an app profile. */
+export const INSTRUCTION_PRESETS: { id: string; label: string; instruction: string }[] = [
+  {
+    id: "comment",
+    label: "Code comment",
+    instruction:
+      "This is a source code comment. Keep comment markers, identifiers and code references unchanged; make the wording concise and precise.",
+  },
+  {
+    id: "casual",
+    label: "Casual chat",
+    instruction: "Keep the tone casual and friendly, suitable for a chat message.",
+  },
+  {
+    id: "formal",
+    label: "Formal email",
+    instruction: "Use a formal, professional tone suitable for email.",
+  },
+];
+
 /** Built-in provider presets with default endpoints and models. */
-export const PROVIDER_PRESETS: Omit<Provider, "apiKey">[] = [
+export const PROVIDER_PRESETS: Omit<Provider, "apiKeyRef">[] = [
   {
     id: "deepseek",
     name: "DeepSeek",
@@ -78,10 +409,3 @@ export const PROVIDER_PRESETS: Omit<Provider, "apiKey">[] = [
     temperature: 0.3,
   },
 ];
-
-export const DEFAULT_CONFIG: PolishrConfig = {
-  apiEndpoint: "https://api.openai.com/v1",
-  apiKey: "",
-  model: "gpt-4o",
-  temperature: 0.3,
-};
@@ -0,0 +1,52 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type MarkdownBlockKind =
+  | "front_matter"
+  | "code_block"
+  | "table"
+  | "html"
+  | "rule"
+  | "blank"
+  | "heading"
+  | "list_item"
+  | "block_quote"
+  | "paragraph";
+
+/** One block of a segmented Markdown document (see `markdown.rs`). */
+export interface MarkdownBlock {
+  kind: MarkdownBlockKind;
+  raw: string;
+  /** Line ending after the block; empty at the end of the text. */
+  line_ending: string;
+  /** Prose to polish; null for blocks kept verbatim (code, tables, ...). */
+  prose: string | null;
+  first_prefix: string;
+  continuation_prefix: string;
+  wrap_width: number | null;
+  /** Marker ending hard-broken lines (two spaces or a backslash). */
+  hard_break: string | null;
+  underline: string | null;
+}
+
+export interface MarkdownDocument {
+  blocks: MarkdownBlock[];
+}
+
+/**
+ * Split the text into Markdown blocks if it looks like Markdown.
+ * Resolves to null for plain prose.
+ */
+export function segmentMarkdown(text: string): Promise<MarkdownDocument | null> {
+  return invoke<MarkdownDocument | null>("segment_markdown", { text });
+}
+
+/**
+ * Rebuild the document, replacing the prose of block `i` with `polished[i]`
+ * while keeping markers, verbatim blocks and line wrapping.
+ */
+export function renderMarkdown(
+  document: MarkdownDocument,
+  polished: (string | null)[],
+): Promise<string> {
+  return invoke<string>("render_markdown", { document, polished });
+}
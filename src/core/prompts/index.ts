@@ -1,20 +1,39 @@
-import type { PolishMode } from "../llm/types";
+import { invoke } from "@tauri-apps/api/core";
+import type { ChatMessage, PolishMode } from "../llm/types";
 import type { DetectedLang } from "../lang/detect";
-import { IMPROVE_PROMPT } from "./improve";
-import { REPHRASE_PROMPT } from "./rephrase";
-import { getTranslatePrompt } from "./translate";
 
-export function getPrompt(mode: PolishMode, lang: DetectedLang): string {
-  switch (mode) {
-    case "improve":
-      return IMPROVE_PROMPT;
-    case "rephrase":
-      return REPHRASE_PROMPT;
-    case "translate":
-      return getTranslatePrompt(lang);
-  }
+/** A model response split into its explanation line and the result. */
+export interface ParsedResponse {
+  explanation: string;
+  text: string;
 }
 
-export { IMPROVE_PROMPT } from "./improve";
-export { REPHRASE_PROMPT } from "./rephrase";
-export { getTranslatePrompt } from "./translate";
+/**
+ * System and user messages for a request. The prompts live in the backend
+ * (`prompts.rs`) so every entry point sends the same ones.
+ *
+ * @param lang - Language of the whole selection; decides the translation
+ *   direction even when `text` is one Markdown block of it.
+ */
+export function promptMessages(
+  text: string,
+  mode: PolishMode,
+  lang: DetectedLang,
+  customInstruction?: string,
+): Promise<ChatMessage[]> {
+  return invoke<ChatMessage[]>("prompt_messages", {
+    mode,
+    text,
+    lang,
+    instruction: customInstruction ?? null,
+  });
+}
+
+/**
+ * Parse a complete response: `<explanation>\n\n<text>`. Without the
+ * separator, or with an implausibly long first paragraph, the whole
+ * response is the text.
+ */
+export function parseResponse(raw: string): Promise<ParsedResponse> {
+  return invoke<ParsedResponse>("parse_polish_response", { raw });
+}
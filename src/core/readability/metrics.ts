@@ -0,0 +1,86 @@
+/** Length and readability statistics of one text, computed in the backend. */
+export interface TextMetrics {
+  /** Space-separated words plus Han and kana characters. */
+  words: number;
+  /** Characters other than whitespace. */
+  characters: number;
+  sentences: number;
+  /** Words per sentence. */
+  avg_sentence_length: number;
+  /** Flesch reading ease, higher is easier; null for non-Latin text. */
+  reading_ease: number | null;
+  /** Flesch-Kincaid grade level; null for non-Latin text. */
+  grade_level: number | null;
+  reading_time_secs: number;
+}
+
+/** Metrics of the text before and after a change. */
+export interface Readability {
+  before: TextMetrics;
+  after: TextMetrics;
+}
+
+function percentChange(before: number, after: number): number {
+  return before === 0 ? 0 : Math.round(((after - before) / before) * 100);
+}
+
+function readingTime(secs: number): string {
+  return secs < 60 ? `${secs}s` : `${Math.round(secs / 60)} min`;
+}
+
+/** One-line summary such as "Grade 12 → 9 · −18% words", or null if nothing changed. */
+export function summarizeReadability({ before, after }: Readability): string | null {
+  const parts: string[] = [];
+  if (before.grade_level !== null && after.grade_level !== null) {
+    const from = Math.round(before.grade_level);
+    const to = Math.round(after.grade_level);
+    if (from !== to) parts.push(`Grade ${from} → ${to}`);
+  }
+  const words = percentChange(before.words, after.words);
+  if (words !== 0) {
+    parts.push(`${words > 0 ? "+" : "−"}${Math.abs(words)}% words`);
+  }
+  const sentence = percentChange(
+    before.avg_sentence_length,
+    after.avg_sentence_length,
+  );
+  if (sentence !== 0 && parts.length < 2) {
+    parts.push(
+      `${sentence > 0 ? "+" : "−"}${Math.abs(sentence)}% sentence length`,
+    );
+  }
+  return parts.length > 0 ? parts.join(" · ") : null;
+}
+
+/** Before/after table for a tooltip. */
+export function describeReadability({ before, after }: Readability): string {
+  const row = (label: string, from: string, to: string) =>
+    `${label}: ${from} → ${to}`;
+  const rows = [
+    row("Words", String(before.words), String(after.words)),
+    row("Characters", String(before.characters), String(after.characters)),
+    row("Sentences", String(before.sentences), String(after.sentences)),
+    row(
+      "Words per sentence",
+      before.avg_sentence_length.toFixed(1),
+      after.avg_sentence_length.toFixed(1),
+    ),
+  ];
+  if (before.reading_ease !== null && after.reading_ease !== null) {
+    rows.push(
+      row(
+        "Reading ease",
+        before.reading_ease.toFixed(0),
+        after.reading_ease.toFixed(0),
+      ),
+    );
+  }
+  rows.push(
+    row(
+      "Reading time",
+      readingTime(before.reading_time_secs),
+      readingTime(after.reading_time_secs),
+    ),
+  );
+  return rows.join("\n");
+}
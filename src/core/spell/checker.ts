@@ -0,0 +1,34 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { TextRange } from "@/core/diff/differ";
+
+/** A word no dictionary knows, with replacements, most likely first. */
+export interface Misspelling {
+  word: string;
+  /** UTF-16 range of the word in the checked text. */
+  range: TextRange;
+  suggestions: string[];
+}
+
+/** Where dictionaries are looked up and which ones were found. */
+export interface DictionaryList {
+  /** Folder to drop `.aff`/`.dic` pairs into. */
+  folder: string;
+  languages: string[];
+}
+
+/**
+ * Check spelling locally with the configured Hunspell dictionaries. Works
+ * offline; rejects with `no_dictionary` when none could be loaded.
+ */
+export function checkSpelling(text: string): Promise<Misspelling[]> {
+  return invoke<Misspelling[]>("check_spelling", { text });
+}
+
+/** Never report `word` again. */
+export function addPersonalWord(word: string): Promise<void> {
+  return invoke("add_personal_word", { word });
+}
+
+export function listDictionaries(): Promise<DictionaryList> {
+  return invoke<DictionaryList>("list_dictionaries");
+}
@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { DiffResult } from "@/core/diff/differ";
+import type { TypographyRule } from "@/core/llm/types";
+
+/** Normalized text, with hunks tagged by the rules that made them. */
+export interface TypographyResult extends DiffResult {
+  text: string;
+}
+
+export const RULE_LABELS: Record<TypographyRule, string> = {
+  cjk_latin_spacing: "Space between CJK and Latin",
+  fullwidth_punctuation: "Full-width punctuation in Chinese",
+  halfwidth_punctuation: "Half-width punctuation in English",
+  fullwidth_spacing: "No spaces around full-width punctuation",
+  quotation_marks: "Quotation marks",
+};
+
+/** Readable form of a hunk's comma-separated `rule`. */
+export function describeRules(rule: string): string {
+  return rule
+    .split(", ")
+    .map((name) => RULE_LABELS[name as TypographyRule] ?? name)
+    .join(", ");
+}
+
+/**
+ * Apply the configured typography rules locally. With `original`, `text` is
+ * a polished result and the diff is taken against `original`.
+ */
+export function normalizeTypography(
+  text: string,
+  original?: string,
+): Promise<TypographyResult> {
+  return invoke<TypographyResult>("normalize_typography", {
+    text,
+    original: original ?? null,
+  });
+}
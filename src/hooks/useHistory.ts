@@ -1,69 +1,146 @@
-import { useState, useEffect, useCallback } from "react";
-import { load } from "@tauri-apps/plugin-store";
-import type { HistoryRecord } from "@/core/llm/types";
-
-const STORE_PATH = "history.json";
-const MAX_RECORDS = 200;
-
-const STORE_OPTIONS = {
-  defaults: {
-    records: [] as HistoryRecord[],
-  },
-  autoSave: true as const,
-};
+import { useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  HistoryEncryptionStatus,
+  HistoryExportFormat,
+  HistoryExportResult,
+  HistoryFacets,
+  HistoryImportResult,
+  HistoryKeySource,
+  HistoryPolicy,
+  HistoryPage,
+  HistoryQuery,
+  HistoryRecord,
+} from "@/core/llm/types";
 
+/**
+ * History lives in the backend SQLite store. Searching, filtering and
+ * pagination happen there; this hook only wraps the commands.
+ */
 export function useHistory() {
-  const [records, setRecords] = useState<HistoryRecord[]>([]);
-  const [loading, setLoading] = useState(true);
-
-  useEffect(() => {
-    let cancelled = false;
-
-    async function loadHistory() {
-      try {
-        const store = await load(STORE_PATH, STORE_OPTIONS);
-        const saved = await store.get<HistoryRecord[]>("records");
-        if (!cancelled && saved) {
-          setRecords(saved);
-        }
-      } catch (err) {
-        console.error("Failed to load history:", err);
-      } finally {
-        if (!cancelled) {
-          setLoading(false);
-        }
-      }
-    }
-
-    loadHistory();
-    return () => {
-      cancelled = true;
-    };
-  }, []);
-
   const addRecord = useCallback(async (record: HistoryRecord) => {
     try {
-      const store = await load(STORE_PATH, STORE_OPTIONS);
-      const existing = (await store.get<HistoryRecord[]>("records")) ?? [];
-      const updated = [record, ...existing].slice(0, MAX_RECORDS);
-      await store.set("records", updated);
-      await store.save();
-      setRecords(updated);
+      await invoke("history_add", { record });
     } catch (err) {
+      // "history_locked": the record is dropped rather than stored unencrypted.
       console.error("Failed to save history record:", err);
     }
   }, []);
 
+  const updateRecord = useCallback(
+    async (id: string, patch: Pick<HistoryRecord, "hunks">) => {
+      try {
+        await invoke("history_update_hunks", { id, hunks: patch.hunks ?? [] });
+      } catch (err) {
+        console.error("Failed to update history record:", err);
+      }
+    },
+    [],
+  );
+
+  const queryHistory = useCallback(
+    (query: HistoryQuery) => invoke<HistoryPage>("history_query", { query }),
+    [],
+  );
+
+  const getFacets = useCallback(
+    () => invoke<HistoryFacets>("history_facets"),
+    [],
+  );
+
+  /** Export the given ids, or every record matching `query`. */
+  const exportHistory = useCallback(
+    (format: HistoryExportFormat, ids?: string[], query?: HistoryQuery) =>
+      invoke<HistoryExportResult>("history_export", {
+        format,
+        ids: ids && ids.length > 0 ? ids : null,
+        query: query ?? null,
+      }),
+    [],
+  );
+
+  const importHistory = useCallback(
+    (content: string, format: HistoryExportFormat) =>
+      invoke<HistoryImportResult>("history_import", { content, format }),
+    [],
+  );
+
+  const getEncryptionStatus = useCallback(
+    () => invoke<HistoryEncryptionStatus>("history_encryption_status"),
+    [],
+  );
+
+  const enableEncryption = useCallback(
+    (source: HistoryKeySource, passphrase?: string) =>
+      invoke<void>("history_enable_encryption", {
+        source,
+        passphrase: passphrase ?? null,
+      }),
+    [],
+  );
+
+  const rotateKey = useCallback(
+    (source: HistoryKeySource, passphrase?: string) =>
+      invoke<void>("history_rotate_key", {
+        source,
+        passphrase: passphrase ?? null,
+      }),
+    [],
+  );
+
+  const disableEncryption = useCallback(
+    () => invoke<void>("history_disable_encryption"),
+    [],
+  );
+
+  const unlockHistory = useCallback(
+    (passphrase: string) => invoke<void>("history_unlock", { passphrase }),
+    [],
+  );
+
+  const lockHistory = useCallback(() => invoke<void>("history_lock"), []);
+
+  const getPolicy = useCallback(
+    () => invoke<HistoryPolicy>("get_history_policy"),
+    [],
+  );
+
+  const savePolicy = useCallback(
+    (policy: HistoryPolicy) => invoke<void>("save_history_policy", { policy }),
+    [],
+  );
+
+  /** Delete every record captured from an app; resolves to the count removed. */
+  const purgeApp = useCallback(
+    (appId: string | null, appName: string) =>
+      invoke<number>("history_purge_app", { appId, appName }),
+    [],
+  );
+
   const clearHistory = useCallback(async () => {
     try {
-      const store = await load(STORE_PATH, STORE_OPTIONS);
-      await store.set("records", []);
-      await store.save();
-      setRecords([]);
+      await invoke("history_clear");
     } catch (err) {
       console.error("Failed to clear history:", err);
     }
   }, []);
 
-  return { records, loading, addRecord, clearHistory };
+  return {
+    addRecord,
+    updateRecord,
+    queryHistory,
+    getFacets,
+    exportHistory,
+    importHistory,
+    clearHistory,
+    getEncryptionStatus,
+    enableEncryption,
+    rotateKey,
+    disableEncryption,
+    unlockHistory,
+    lockHistory,
+    getPolicy,
+    savePolicy,
+    purgeApp,
+  };
 }
@@ -1,30 +1,111 @@
 import { useState, useCallback, useRef } from "react";
-import type { PolishrConfig, PolishMode } from "@/core/llm/types";
+import type {
+  PolishrConfig,
+  PolishMode,
+  TypographySettings,
+} from "@/core/llm/types";
 import { polishStream, PolishError } from "@/core/llm/client";
-import { computeDiff, type DiffSegment } from "@/core/diff/differ";
-import { detectLanguage } from "@/core/lang/detect";
+import { parseResponse } from "@/core/prompts";
+import {
+  computeDiff,
+  type DiffHunk,
+  type DiffSegment,
+} from "@/core/diff/differ";
+import type { Readability } from "@/core/readability/metrics";
+import { detectLanguage, type DetectedLang } from "@/core/lang/detect";
+import {
+  segmentMarkdown,
+  renderMarkdown,
+  type MarkdownDocument,
+} from "@/core/markdown/segment";
+import { normalizeTypography } from "@/core/typography/normalizer";
+import { convertChinese } from "@/core/chinese/converter";
+
+/** Markdown blocks polished at the same time; more risks rate limits. */
+const MAX_CONCURRENT_BLOCKS = 4;
 
 /**
- * Parse the LLM response into explanation + polished text.
- *
- * Expected format:
- *   <explanation>\n\n<polished text>
- *
- * Falls back gracefully: if no blank-line separator is found the
- * whole response is treated as the polished text with no explanation.
+ * Polish only the prose blocks of a Markdown document, a few blocks at a
+ * time, then re-emit it with the original structure. `onProgress` receives
+ * the document with the blocks finished so far plus the first block still
+ * in flight.
  */
-function parseResponse(raw: string): { explanation: string; text: string } {
-  const idx = raw.indexOf("\n\n");
-  if (idx === -1) {
-    return { explanation: "", text: raw.trim() };
-  }
-  const explanation = raw.slice(0, idx).trim();
-  const text = raw.slice(idx + 2).trim();
-  // Guard: if the "explanation" is suspiciously long it probably isn't one
-  if (explanation.length > 60) {
-    return { explanation: "", text: raw.trim() };
+async function polishMarkdown(
+  document: MarkdownDocument,
+  mode: PolishMode,
+  lang: DetectedLang,
+  config: PolishrConfig,
+  signal: AbortSignal,
+  onProgress: (preview: string) => void,
+  customInstruction?: string,
+): Promise<{ explanation: string; text: string }> {
+  const polished: (string | null)[] = document.blocks.map(() => null);
+  const streaming: string[] = document.blocks.map(() => "");
+  const explanations: string[] = document.blocks.map(() => "");
+  const proseBlocks = [...document.blocks.keys()].filter(
+    (i) => document.blocks[i].prose !== null,
+  );
+  let preview = await renderMarkdown(document, polished);
+  let renders = 0;
+
+  const showProgress = () => {
+    const next = proseBlocks.find((i) => polished[i] === null);
+    onProgress(
+      next === undefined || !streaming[next]
+        ? preview
+        : `${preview}\n\n${streaming[next]}`,
+    );
+  };
+
+  // One failed block stops the others.
+  const controller = new AbortController();
+  const stop = () => controller.abort();
+  signal.addEventListener("abort", stop, { once: true });
+
+  const polishBlock = async (i: number) => {
+    for await (const token of polishStream(
+      document.blocks[i].prose!,
+      mode,
+      lang,
+      config,
+      controller.signal,
+      customInstruction,
+    )) {
+      streaming[i] += token;
+      showProgress();
+    }
+    const parsed = await parseResponse(streaming[i]);
+    polished[i] = parsed.text;
+    explanations[i] = parsed.explanation;
+    // Blocks finish out of order; keep only the latest render.
+    const render = ++renders;
+    const rendered = await renderMarkdown(document, polished);
+    if (render === renders) preview = rendered;
+    showProgress();
+  };
+
+  const queue = [...proseBlocks];
+  const worker = async () => {
+    for (let i = queue.shift(); i !== undefined; i = queue.shift()) {
+      await polishBlock(i);
+    }
+  };
+
+  try {
+    await Promise.all(
+      Array.from({ length: Math.min(MAX_CONCURRENT_BLOCKS, queue.length) }, worker),
+    );
+  } catch (err) {
+    controller.abort();
+    throw err;
+  } finally {
+    signal.removeEventListener("abort", stop);
   }
-  return { explanation, text };
+
+  return {
+    explanation: explanations.find((e) => e) ?? "",
+    text: await renderMarkdown(document, polished),
+  };
 }
 
 /** Callback invoked when a polish completes successfully. */
@@ -40,6 +121,10 @@ interface UsePolishReturn {
   /** Short explanation of what was changed */
   explanation: string;
   diffSegments: DiffSegment[];
+  /** Changes that can be accepted or rejected individually */
+  hunks: DiffHunk[];
+  /** Readability of the input and the result, once the diff is in. */
+  metrics: Readability | null;
   isStreaming: boolean;
   error: string | null;
   startPolish: (
@@ -48,14 +133,36 @@ interface UsePolishReturn {
     config: PolishrConfig,
     customInstruction?: string,
   ) => void;
+  /** Show a result computed elsewhere (the background check) as if polished. */
+  showSuggestion: (
+    text: string,
+    mode: PolishMode,
+    suggestion: { explanation: string; text: string },
+  ) => void;
   cancelPolish: () => void;
   reset: () => void;
 }
 
-export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
+/** Whether the typography rules run on the result of `mode`. */
+function wantsTypography(
+  mode: PolishMode,
+  typography?: TypographySettings,
+): boolean {
+  return (
+    (mode === "improve" && !!typography?.afterImprove) ||
+    (mode === "translate" && !!typography?.afterTranslate)
+  );
+}
+
+export function usePolish(
+  onComplete?: OnPolishComplete,
+  typography?: TypographySettings,
+): UsePolishReturn {
   const [result, setResult] = useState("");
   const [explanation, setExplanation] = useState("");
   const [diffSegments, setDiffSegments] = useState<DiffSegment[]>([]);
+  const [hunks, setHunks] = useState<DiffHunk[]>([]);
+  const [metrics, setMetrics] = useState<Readability | null>(null);
   const [isStreaming, setIsStreaming] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const abortRef = useRef<AbortController | null>(null);
@@ -71,6 +178,8 @@ export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
     setResult("");
     setExplanation("");
     setDiffSegments([]);
+    setHunks([]);
+    setMetrics(null);
     setError(null);
   }, [cancelPolish]);
 
@@ -86,6 +195,8 @@ export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
       setResult("");
       setExplanation("");
       setDiffSegments([]);
+      setHunks([]);
+      setMetrics(null);
       setError(null);
       setIsStreaming(true);
 
@@ -94,30 +205,71 @@ export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
 
       const lang = detectLanguage(text);
 
-      let accumulated = "";
-
       try {
-        for await (const token of polishStream(
-          text,
-          mode,
-          lang,
-          config,
-          controller.signal,
-          customInstruction,
-        )) {
-          accumulated += token;
-          setResult(accumulated);
+        // Deterministic and local: no model, no streaming.
+        if (mode === "typography" || mode === "convert") {
+          const normalized =
+            mode === "typography"
+              ? await normalizeTypography(text)
+              : await convertChinese(text);
+          setResult(normalized.text);
+          setDiffSegments(normalized.segments);
+          setHunks(normalized.hunks);
+          setMetrics(normalized.metrics);
+          onComplete?.(text, normalized.text, mode);
+          return;
+        }
+
+        // Markdown selections are polished block by block so code,
+        // tables and list structure survive the round trip.
+        const markdown = await segmentMarkdown(text).catch(() => null);
+
+        let parsed: { explanation: string; text: string };
+        if (markdown) {
+          parsed = await polishMarkdown(
+            markdown,
+            mode,
+            lang,
+            config,
+            controller.signal,
+            setResult,
+            customInstruction,
+          );
+        } else {
+          let accumulated = "";
+          for await (const token of polishStream(
+            text,
+            mode,
+            lang,
+            config,
+            controller.signal,
+            customInstruction,
+          )) {
+            accumulated += token;
+            setResult(accumulated);
+          }
+          // Parse the complete response
+          parsed = await parseResponse(accumulated);
         }
 
-        // Parse the complete response
-        const { explanation: exp, text: polished } =
-          parseResponse(accumulated);
+        const { explanation: exp } = parsed;
+        let polished = parsed.text;
         setExplanation(exp);
-        setResult(polished);
 
-        // Compute diff on the clean polished text
-        const segments = computeDiff(text, polished);
-        setDiffSegments(segments);
+        // Compute diff on the clean polished text, after the typography
+        // rules when they are set to follow this mode.
+        let diff;
+        if (wantsTypography(mode, typography)) {
+          const normalized = await normalizeTypography(polished, text);
+          polished = normalized.text;
+          diff = normalized;
+        } else {
+          diff = await computeDiff(text, polished);
+        }
+        setResult(polished);
+        setDiffSegments(diff.segments);
+        setHunks(diff.hunks);
+        setMetrics(diff.metrics);
 
         // Notify completion for history saving
         onComplete?.(text, polished, mode);
@@ -137,6 +289,31 @@ export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
         abortRef.current = null;
       }
     },
+    [cancelPolish, onComplete, typography],
+  );
+
+  const showSuggestion = useCallback(
+    async (
+      text: string,
+      mode: PolishMode,
+      suggestion: { explanation: string; text: string },
+    ) => {
+      cancelPolish();
+      setError(null);
+      setExplanation(suggestion.explanation);
+      setResult(suggestion.text);
+      try {
+        const diff = await computeDiff(text, suggestion.text);
+        setDiffSegments(diff.segments);
+        setHunks(diff.hunks);
+        setMetrics(diff.metrics);
+        onComplete?.(text, suggestion.text, mode);
+      } catch (err) {
+        setError(
+          err instanceof Error ? err.message : "An unknown error occurred",
+        );
+      }
+    },
     [cancelPolish, onComplete],
   );
 
@@ -144,9 +321,12 @@ export function usePolish(onComplete?: OnPolishComplete): UsePolishReturn {
     result,
     explanation,
     diffSegments,
+    hunks,
+    metrics,
     isStreaming,
     error,
     startPolish,
+    showSuggestion,
     cancelPolish,
     reset,
   };
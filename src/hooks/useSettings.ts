@@ -1,180 +1,282 @@
 import { useState, useEffect, useCallback } from "react";
-import { load } from "@tauri-apps/plugin-store";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type {
+  BackgroundCheckSettings,
+  ChineseConversionSettings,
+  GlossaryEntry,
+  ManagedField,
+  ManagedState,
   PolishrConfig,
   PolishMode,
+  QuickReplaceSettings,
   Provider,
+  Settings,
+  ShortcutBinding,
+  SpellCheckSettings,
+  TriggerApps,
+  TypographySettings,
 } from "@/core/llm/types";
-import { DEFAULT_CONFIG, PROVIDER_PRESETS } from "@/core/llm/types";
-
-const STORE_PATH = "settings.json";
-
-const STORE_OPTIONS = {
-  defaults: {
-    providers: [] as Provider[],
-    activeProviderId: "",
-    defaultMode: "improve" as PolishMode,
-    // Legacy flat keys for backward compatibility
-    apiEndpoint: DEFAULT_CONFIG.apiEndpoint,
-    apiKey: DEFAULT_CONFIG.apiKey,
-    model: DEFAULT_CONFIG.model,
-    temperature: DEFAULT_CONFIG.temperature,
-  },
-  autoSave: true as const,
-};
+
+/** Whether a provider has an API key stored in the keyring. */
+export function hasApiKey(provider: Provider | undefined): boolean {
+  return Boolean(provider?.apiKeyRef);
+}
+
+/**
+ * The managed entry locking `field`, if any. A field is also locked when a
+ * parent path is managed, e.g. `providers` locks `providers.openai`.
+ */
+export function managedField(
+  managed: ManagedState | undefined,
+  field: string,
+): ManagedField | undefined {
+  return managed?.fields.find(
+    (f) => f.field === field || field.startsWith(`${f.field}.`),
+  );
+}
+
+export function managedLabel(entry: ManagedField): string {
+  return entry.source === "policy"
+    ? "Managed by organization policy"
+    : "Managed by config file";
+}
 
 export function useSettings() {
   const [providers, setProviders] = useState<Provider[]>([]);
   const [activeProviderId, setActiveProviderId] = useState("");
   const [defaultMode, setDefaultModeState] = useState<PolishMode>("improve");
+  const [glossary, setGlossary] = useState<GlossaryEntry[]>([]);
+  const [shortcuts, setShortcutsState] = useState<ShortcutBinding[]>([]);
+  const [quickReplace, setQuickReplaceState] = useState<QuickReplaceSettings>({
+    maxChars: 2000,
+  });
+  const [triggerApps, setTriggerAppsState] = useState<TriggerApps>({
+    mode: "deny",
+    apps: [],
+  });
+  const [backgroundCheck, setBackgroundCheckState] =
+    useState<BackgroundCheckSettings>({
+      enabled: false,
+      providerId: null,
+      debounceMs: 1500,
+      maxPerMinute: 6,
+    });
+  const [spellCheck, setSpellCheckState] = useState<SpellCheckSettings>({
+    enabled: true,
+    languages: ["en_US"],
+    personalWords: [],
+  });
+  const [typography, setTypographyState] = useState<TypographySettings>({
+    rules: [
+      "cjk_latin_spacing",
+      "fullwidth_punctuation",
+      "halfwidth_punctuation",
+      "fullwidth_spacing",
+      "quotation_marks",
+    ],
+    quoteStyle: "curly",
+    afterImprove: false,
+    afterTranslate: false,
+  });
+  const [chineseConversion, setChineseConversionState] =
+    useState<ChineseConversionSettings>({
+      target: "taiwan",
+      regionalPhrases: true,
+    });
+  const [managed, setManaged] = useState<ManagedState | undefined>();
   const [loading, setLoading] = useState(true);
 
-  // Derive config from active provider
   const activeProvider = providers.find((p) => p.id === activeProviderId);
-  const config: PolishrConfig = activeProvider
-    ? {
-        apiEndpoint: activeProvider.endpoint,
-        apiKey: activeProvider.apiKey,
-        model: activeProvider.model,
-        temperature: activeProvider.temperature,
-      }
-    : DEFAULT_CONFIG;
+  const config: PolishrConfig = { providerId: activeProvider?.id ?? null };
+
+  const isConfigured = hasApiKey(activeProvider);
+
+  /** Config for a specific provider, falling back to the active one. */
+  const getConfig = useCallback(
+    (providerId?: string | null): PolishrConfig => {
+      const provider = providers.find((p) => p.id === providerId);
+      if (!hasApiKey(provider)) return { providerId: activeProviderId || null };
+      return { providerId: provider!.id };
+    },
+    [providers, activeProviderId],
+  );
 
-  const isConfigured = config.apiKey.length > 0;
+  const applySettings = useCallback((settings: Settings) => {
+    setProviders(settings.providers);
+    setActiveProviderId(settings.activeProviderId);
+    setDefaultModeState(settings.defaultMode);
+    setGlossary(settings.glossary);
+    setShortcutsState(settings.shortcuts);
+    setQuickReplaceState(settings.quickReplace);
+    setTriggerAppsState(settings.triggerApps);
+    setBackgroundCheckState(settings.backgroundCheck);
+    setSpellCheckState(settings.spellCheck);
+    setTypographyState(settings.typography);
+    setChineseConversionState(settings.chineseConversion);
+    setManaged(settings.managed);
+  }, []);
 
+  // Settings are owned by the backend, which migrates old files and moves
+  // plaintext API keys to the keyring at startup. Every window gets
+  // "settings-changed" whenever any of them saves.
   useEffect(() => {
     let cancelled = false;
 
-    async function loadSettings() {
-      try {
-        const store = await load(STORE_PATH, STORE_OPTIONS);
-
-        const savedProviders = await store.get<Provider[]>("providers");
-        const savedActiveId = await store.get<string>("activeProviderId");
-        const savedMode = await store.get<PolishMode>("defaultMode");
-
-        // Migrate from legacy flat config if no providers saved
-        if (!savedProviders || savedProviders.length === 0) {
-          const legacyKey = await store.get<string>("apiKey");
-          const legacyEndpoint = await store.get<string>("apiEndpoint");
-          const legacyModel = await store.get<string>("model");
-          const legacyTemp = await store.get<number>("temperature");
-
-          if (legacyKey && legacyKey.length > 0) {
-            // Find matching preset or create custom
-            const matchedPreset = PROVIDER_PRESETS.find(
-              (p) => p.endpoint === legacyEndpoint,
-            );
-            const migratedProvider: Provider = {
-              id: matchedPreset?.id ?? "custom",
-              name: matchedPreset?.name ?? "Custom",
-              endpoint: legacyEndpoint ?? DEFAULT_CONFIG.apiEndpoint,
-              apiKey: legacyKey,
-              model: legacyModel ?? DEFAULT_CONFIG.model,
-              temperature: legacyTemp ?? DEFAULT_CONFIG.temperature,
-            };
-            if (!cancelled) {
-              setProviders([migratedProvider]);
-              setActiveProviderId(migratedProvider.id);
-              // Persist migration
-              await store.set("providers", [migratedProvider]);
-              await store.set("activeProviderId", migratedProvider.id);
-              await store.save();
-            }
-          }
-        } else if (!cancelled) {
-          setProviders(savedProviders);
-          setActiveProviderId(savedActiveId ?? "");
-        }
-
-        if (!cancelled && savedMode) {
-          setDefaultModeState(savedMode);
-        }
-      } catch (err) {
-        console.error("Failed to load settings:", err);
-      } finally {
-        if (!cancelled) {
-          setLoading(false);
-        }
-      }
-    }
+    invoke<Settings>("get_settings")
+      .then((settings) => {
+        if (!cancelled) applySettings(settings);
+      })
+      .catch((err) => console.error("Failed to load settings:", err))
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
 
-    loadSettings();
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      applySettings(event.payload);
+    });
     return () => {
       cancelled = true;
+      unlisten.then((fn) => fn());
     };
-  }, []);
+  }, [applySettings]);
+
+  /** Save a partial settings update; rejects with a validation error code. */
+  const updateSettings = useCallback(
+    async (patch: Partial<Omit<Settings, "schemaVersion">>) => {
+      applySettings(await invoke<Settings>("update_settings", { patch }));
+    },
+    [applySettings],
+  );
 
-  const saveProviders = useCallback(
-    async (newProviders: Provider[], newActiveId?: string) => {
+  /**
+   * Create or update a provider. A non-empty `apiKey` is stored in the OS
+   * keyring by the backend; an empty string removes the stored key and
+   * `undefined` keeps it, unless the endpoint changed, which drops it.
+   */
+  const saveProvider = useCallback(
+    async (provider: Provider, apiKey?: string) => {
       try {
-        const store = await load(STORE_PATH, STORE_OPTIONS);
-        await store.set("providers", newProviders);
-        if (newActiveId !== undefined) {
-          await store.set("activeProviderId", newActiveId);
-          setActiveProviderId(newActiveId);
-        }
-        await store.save();
-        setProviders(newProviders);
+        const updated = await invoke<Provider[]>("save_provider", {
+          provider,
+          apiKey: apiKey ?? null,
+        });
+        setProviders(updated);
       } catch (err) {
-        console.error("Failed to save providers:", err);
+        console.error("Failed to save provider:", err);
+        throw err;
       }
     },
     [],
   );
 
-  const setActiveProvider = useCallback(async (id: string) => {
-    try {
-      const store = await load(STORE_PATH, STORE_OPTIONS);
-      await store.set("activeProviderId", id);
-      await store.save();
-      setActiveProviderId(id);
-    } catch (err) {
-      console.error("Failed to set active provider:", err);
-    }
-  }, []);
+  const setActiveProvider = useCallback(
+    async (id: string) => {
+      try {
+        await updateSettings({ activeProviderId: id });
+      } catch (err) {
+        console.error("Failed to set active provider:", err);
+      }
+    },
+    [updateSettings],
+  );
 
-  const setDefaultMode = useCallback(async (mode: PolishMode) => {
-    try {
-      const store = await load(STORE_PATH, STORE_OPTIONS);
-      await store.set("defaultMode", mode);
-      await store.save();
-      setDefaultModeState(mode);
-    } catch (err) {
-      console.error("Failed to set default mode:", err);
-    }
-  }, []);
+  const setDefaultMode = useCallback(
+    async (mode: PolishMode) => {
+      try {
+        await updateSettings({ defaultMode: mode });
+      } catch (err) {
+        console.error("Failed to set default mode:", err);
+      }
+    },
+    [updateSettings],
+  );
 
-  // Legacy saveConfig for backward compatibility
-  const saveConfig = useCallback(
-    async (newConfig: PolishrConfig) => {
-      const updated = providers.map((p) =>
-        p.id === activeProviderId
-          ? {
-              ...p,
-              endpoint: newConfig.apiEndpoint,
-              apiKey: newConfig.apiKey,
-              model: newConfig.model,
-              temperature: newConfig.temperature,
-            }
-          : p,
-      );
-      await saveProviders(updated);
+  /** Replace the user's glossary. Managed entries are kept by the backend. */
+  const setUserGlossary = useCallback(
+    async (entries: GlossaryEntry[]) => {
+      await updateSettings({
+        glossary: entries.filter(
+          (e) => !managedField(managed, `glossary.${e.term}`),
+        ),
+      });
     },
-    [providers, activeProviderId, saveProviders],
+    [updateSettings, managed],
+  );
+
+  /** Replace the shortcut bindings; the backend re-registers them. */
+  const setShortcuts = useCallback(
+    (bindings: ShortcutBinding[]) => updateSettings({ shortcuts: bindings }),
+    [updateSettings],
+  );
+
+  const setQuickReplace = useCallback(
+    (next: QuickReplaceSettings) => updateSettings({ quickReplace: next }),
+    [updateSettings],
+  );
+
+  const setTriggerApps = useCallback(
+    (next: TriggerApps) => updateSettings({ triggerApps: next }),
+    [updateSettings],
+  );
+
+  const setBackgroundCheck = useCallback(
+    (next: BackgroundCheckSettings) =>
+      updateSettings({ backgroundCheck: next }),
+    [updateSettings],
+  );
+
+  const setSpellCheck = useCallback(
+    (next: SpellCheckSettings) => updateSettings({ spellCheck: next }),
+    [updateSettings],
+  );
+
+  const setTypography = useCallback(
+    (next: TypographySettings) => updateSettings({ typography: next }),
+    [updateSettings],
+  );
+
+  const setChineseConversion = useCallback(
+    (next: ChineseConversionSettings) =>
+      updateSettings({ chineseConversion: next }),
+    [updateSettings],
+  );
+
+  const isManaged = useCallback(
+    (field: string) => managedField(managed, field),
+    [managed],
   );
 
   return {
     config,
-    saveConfig,
+    getConfig,
     loading,
     isConfigured,
     providers,
     activeProviderId,
     activeProvider,
-    saveProviders,
+    saveProvider,
     setActiveProvider,
     defaultMode,
     setDefaultMode,
+    updateSettings,
+    glossary,
+    setGlossary: setUserGlossary,
+    shortcuts,
+    setShortcuts,
+    quickReplace,
+    setQuickReplace,
+    triggerApps,
+    setTriggerApps,
+    backgroundCheck,
+    setBackgroundCheck,
+    spellCheck,
+    setSpellCheck,
+    typography,
+    setTypography,
+    chineseConversion,
+    setChineseConversion,
+    managed,
+    isManaged,
   };
 }
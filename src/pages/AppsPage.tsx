@@ -0,0 +1,337 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  AppProfile,
+  ExcludedApp,
+  HistoryPolicy,
+  PolishMode,
+  Provider,
+  RecentApp,
+  TriggerApps,
+} from "@/core/llm/types";
+import { INSTRUCTION_PRESETS } from "@/core/llm/types";
+import { useHistory } from "@/hooks/useHistory";
+import { TriggerAppsEditor } from "@/components/TriggerAppsEditor";
+import { Trash2, Eraser } from "lucide-react";
+
+interface AppsPageProps {
+  providers: Provider[];
+  triggerApps?: TriggerApps;
+  onSaveTriggerApps?: (next: TriggerApps) => Promise<void>;
+}
+
+const MODE_OPTIONS: { value: PolishMode; label: string }[] = [
+  { value: "improve", label: "Improve" },
+  { value: "rephrase", label: "Rephrase" },
+  { value: "translate", label: "Translate" },
+  { value: "typography", label: "Typography" },
+  { value: "convert", label: "简 ⇄ 繁" },
+];
+
+const SELECT_CLASS =
+  "cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+/** Same rule as `ExcludedApp::matches` in the backend. */
+function isExcluded(
+  entry: ExcludedApp,
+  appId: string | null,
+  appName: string,
+): boolean {
+  const sameName = entry.app.toLowerCase() === appName.toLowerCase();
+  if (entry.appId && appId) return entry.appId === appId;
+  return sameName || (!entry.appId && entry.app === appId);
+}
+
+function emptyProfile(app: string, appId: string | null): AppProfile {
+  return {
+    app,
+    appId,
+    mode: null,
+    providerId: null,
+    instruction: null,
+    triggerEnabled: true,
+  };
+}
+
+export function AppsPage({
+  providers,
+  triggerApps,
+  onSaveTriggerApps,
+}: AppsPageProps) {
+  const [recentApps, setRecentApps] = useState<RecentApp[]>([]);
+  const [profiles, setProfiles] = useState<AppProfile[]>([]);
+  const [customApp, setCustomApp] = useState<string | null>(null);
+  const [policy, setPolicy] = useState<HistoryPolicy | null>(null);
+  const [purged, setPurged] = useState<{ app: string; count: number } | null>(
+    null,
+  );
+  const { getPolicy, savePolicy, purgeApp } = useHistory();
+
+  const refresh = useCallback(async () => {
+    try {
+      const [recent, saved, historyPolicy] = await Promise.all([
+        invoke<RecentApp[]>("list_recent_apps"),
+        invoke<AppProfile[]>("get_app_profiles"),
+        getPolicy(),
+      ]);
+      setRecentApps(recent);
+      setProfiles(saved);
+      setPolicy(historyPolicy);
+    } catch (err) {
+      console.error("Failed to load app profiles:", err);
+    }
+  }, [getPolicy]);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen("settings-changed", () => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const saveProfile = useCallback(
+    async (profile: AppProfile) => {
+      try {
+        await invoke("save_app_profile", { profile });
+        await refresh();
+      } catch (err) {
+        console.error("Failed to save app profile:", err);
+      }
+    },
+    [refresh],
+  );
+
+  const deleteProfile = useCallback(
+    async (appId: string | null, appName: string) => {
+      try {
+        await invoke("delete_app_profile", { appId, appName });
+        await refresh();
+      } catch (err) {
+        console.error("Failed to delete app profile:", err);
+      }
+    },
+    [refresh],
+  );
+
+  const setRecordHistory = async (
+    appId: string | null,
+    appName: string,
+    record: boolean,
+  ) => {
+    if (!policy) return;
+    const others = policy.excludedApps.filter(
+      (entry) => !isExcluded(entry, appId, appName),
+    );
+    const next = {
+      ...policy,
+      excludedApps: record ? others : [...others, { app: appName, appId }],
+    };
+    setPolicy(next);
+    try {
+      await savePolicy(next);
+    } catch (err) {
+      console.error("Failed to save history policy:", err);
+    }
+  };
+
+  const handlePurge = async (appId: string | null, appName: string) => {
+    try {
+      const count = await purgeApp(appId, appName);
+      setPurged({ app: appName, count });
+    } catch (err) {
+      console.error("Failed to purge history:", err);
+    }
+  };
+
+  // Configured apps first, then recently seen and excluded apps without a
+  // profile.
+  const appNames = [
+    ...new Set([
+      ...profiles.map((p) => p.app),
+      ...recentApps.map((r) => r.app),
+      ...(policy?.excludedApps ?? []).map((entry) => entry.app),
+    ]),
+  ];
+
+  return (
+    <div className="mx-auto max-w-[640px] px-10 py-10">
+      <div className="mb-8">
+        <h1 className="text-[20px] font-semibold tracking-tight text-[#18181b]">
+          Apps
+        </h1>
+        <p className="mt-1.5 text-[13px] leading-relaxed text-[#71717a]">
+          Per-app defaults for mode, provider and instructions.
+        </p>
+      </div>
+
+      {triggerApps && onSaveTriggerApps && (
+        <TriggerAppsEditor triggerApps={triggerApps} onSave={onSaveTriggerApps} />
+      )}
+
+      {appNames.length === 0 && (
+        <div className="flex flex-col items-center justify-center py-16 text-center">
+          <p className="text-[14px] text-[#a1a1aa]">No apps seen yet</p>
+          <p className="mt-1 text-[12px] text-[#d4d4d8]">
+            Apps you polish text in will show up here.
+          </p>
+        </div>
+      )}
+
+      <div className="space-y-3">
+        {appNames.map((appName) => {
+          const appId =
+            recentApps.find((r) => r.app === appName)?.appId ??
+            policy?.excludedApps.find((e) => e.app === appName)?.appId ??
+            null;
+          const saved =
+            profiles.find((p) => p.appId && p.appId === appId) ??
+            profiles.find((p) => p.app === appName);
+          const profile = saved ?? emptyProfile(appName, appId);
+          const presetId =
+            customApp === appName
+              ? "custom"
+              : (INSTRUCTION_PRESETS.find(
+                  (p) => p.instruction === profile.instruction,
+                )?.id ?? (profile.instruction ? "custom" : ""));
+          const update = (patch: Partial<AppProfile>) =>
+            saveProfile({ ...profile, appId: profile.appId ?? appId, ...patch });
+
+          return (
+            <div
+              key={appName}
+              className="rounded-xl border border-[#f4f4f5] px-5 py-4"
+            >
+              <div className="mb-3 flex items-center justify-between">
+                <div className="text-[13px] font-medium text-[#18181b]">
+                  {appName}
+                </div>
+                <div className="flex items-center gap-3">
+                  <label className="flex cursor-pointer items-center gap-1.5 text-[11px] text-[#71717a]">
+                    <input
+                      type="checkbox"
+                      checked={profile.triggerEnabled}
+                      onChange={(e) =>
+                        update({ triggerEnabled: e.target.checked })
+                      }
+                      className="accent-blue-500"
+                    />
+                    Show trigger
+                  </label>
+                  <label className="flex cursor-pointer items-center gap-1.5 text-[11px] text-[#71717a]">
+                    <input
+                      type="checkbox"
+                      checked={
+                        !policy?.excludedApps.some((entry) =>
+                          isExcluded(entry, appId, appName),
+                        )
+                      }
+                      onChange={(e) =>
+                        setRecordHistory(appId, appName, e.target.checked)
+                      }
+                      className="accent-blue-500"
+                    />
+                    Record history
+                  </label>
+                  <button
+                    onClick={() => handlePurge(appId, appName)}
+                    className="cursor-pointer rounded-md p-1 text-[#a1a1aa] transition-colors duration-150 hover:bg-[#f4f4f5] hover:text-[#18181b]"
+                    title="Delete all history from this app"
+                  >
+                    <Eraser className="h-3.5 w-3.5" />
+                  </button>
+                  {saved && (
+                    <button
+                      onClick={() => deleteProfile(profile.appId, appName)}
+                      className="cursor-pointer rounded-md p-1 text-[#a1a1aa] transition-colors duration-150 hover:bg-[#f4f4f5] hover:text-[#18181b]"
+                      title="Reset to defaults"
+                    >
+                      <Trash2 className="h-3.5 w-3.5" />
+                    </button>
+                  )}
+                </div>
+              </div>
+
+              <div className="flex flex-wrap items-center gap-2">
+                <select
+                  value={profile.mode ?? ""}
+                  onChange={(e) =>
+                    update({ mode: (e.target.value || null) as PolishMode | null })
+                  }
+                  className={SELECT_CLASS}
+                >
+                  <option value="">Default mode</option>
+                  {MODE_OPTIONS.map((opt) => (
+                    <option key={opt.value} value={opt.value}>
+                      {opt.label}
+                    </option>
+                  ))}
+                </select>
+
+                <select
+                  value={profile.providerId ?? ""}
+                  onChange={(e) =>
+                    update({ providerId: e.target.value || null })
+                  }
+                  className={SELECT_CLASS}
+                >
+                  <option value="">Active provider</option>
+                  {providers.map((p) => (
+                    <option key={p.id} value={p.id}>
+                      {p.name} · {p.model}
+                    </option>
+                  ))}
+                </select>
+
+                <select
+                  value={presetId}
+                  onChange={(e) => {
+                    const preset = INSTRUCTION_PRESETS.find(
+                      (p) => p.id === e.target.value,
+                    );
+                    if (e.target.value === "custom") {
+                      setCustomApp(appName);
+                    } else {
+                      setCustomApp(null);
+                      update({ instruction: preset?.instruction ?? null });
+                    }
+                  }}
+                  className={SELECT_CLASS}
+                >
+                  <option value="">No extra instruction</option>
+                  {INSTRUCTION_PRESETS.map((p) => (
+                    <option key={p.id} value={p.id}>
+                      {p.label}
+                    </option>
+                  ))}
+                  <option value="custom">Custom…</option>
+                </select>
+              </div>
+
+              {purged?.app === appName && (
+                <div className="mt-2 text-[11px] text-[#a1a1aa]">
+                  Deleted {purged.count} history records from {appName}.
+                </div>
+              )}
+
+              {presetId === "custom" && (
+                <input
+                  type="text"
+                  defaultValue={profile.instruction ?? ""}
+                  onBlur={(e) =>
+                    update({ instruction: e.target.value.trim() || null })
+                  }
+                  placeholder="e.g. Keep it short and friendly"
+                  className="mt-2 w-full rounded-lg border border-[#e4e4e7] bg-white px-3 py-2 text-[12px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]"
+                />
+              )}
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
@@ -1,35 +1,67 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { ProvidersPage } from "./ProvidersPage";
 import { HistoryPage } from "./HistoryPage";
 import { PreferencesPage } from "./PreferencesPage";
+import { AppsPage } from "./AppsPage";
+import { InsightsPage } from "./InsightsPage";
 import { useSettings } from "@/hooks/useSettings";
-import { useHistory } from "@/hooks/useHistory";
 import { cn } from "@/lib/utils";
 import {
   Server,
   Clock,
   SlidersHorizontal,
+  AppWindow,
+  BarChart3,
 } from "lucide-react";
 
-type Page = "providers" | "history" | "preferences";
+type Page = "providers" | "apps" | "history" | "insights" | "preferences";
 
 const NAV_ITEMS: { id: Page; label: string; icon: typeof Server }[] = [
   { id: "providers", label: "Providers", icon: Server },
+  { id: "apps", label: "Apps", icon: AppWindow },
   { id: "history", label: "History", icon: Clock },
+  { id: "insights", label: "Insights", icon: BarChart3 },
   { id: "preferences", label: "Preferences", icon: SlidersHorizontal },
 ];
 
 export function DesktopApp() {
   const [page, setPage] = useState<Page>("providers");
+
+  // The tray menu opens pages directly, e.g. History for exporting.
+  useEffect(() => {
+    const unlisten = listen<Page>("open-page", (event) => {
+      setPage(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
   const {
     providers,
     activeProviderId,
-    saveProviders,
+    saveProvider,
     setActiveProvider,
     defaultMode,
     setDefaultMode,
+    shortcuts,
+    setShortcuts,
+    quickReplace,
+    setQuickReplace,
+    triggerApps,
+    setTriggerApps,
+    backgroundCheck,
+    setBackgroundCheck,
+    spellCheck,
+    setSpellCheck,
+    typography,
+    setTypography,
+    chineseConversion,
+    setChineseConversion,
+    glossary,
+    setGlossary,
+    managed,
   } = useSettings();
-  const { records, clearHistory } = useHistory();
 
   return (
     <div className="flex h-screen select-none overflow-hidden bg-[#fafbfc] text-[#18181b]">
@@ -96,17 +128,40 @@ export function DesktopApp() {
           <ProvidersPage
             providers={providers}
             activeProviderId={activeProviderId}
-            onSave={saveProviders}
+            managed={managed}
+            onSave={saveProvider}
             onSetActive={setActiveProvider}
           />
         )}
-        {page === "history" && (
-          <HistoryPage records={records} onClear={clearHistory} />
+        {page === "apps" && (
+          <AppsPage
+            providers={providers}
+            triggerApps={triggerApps}
+            onSaveTriggerApps={setTriggerApps}
+          />
         )}
+        {page === "history" && <HistoryPage providers={providers} />}
+        {page === "insights" && <InsightsPage />}
         {page === "preferences" && (
           <PreferencesPage
             defaultMode={defaultMode}
             onSetDefaultMode={setDefaultMode}
+            shortcuts={shortcuts}
+            onSaveShortcuts={setShortcuts}
+            quickReplace={quickReplace}
+            onSaveQuickReplace={setQuickReplace}
+            providers={providers}
+            backgroundCheck={backgroundCheck}
+            onSaveBackgroundCheck={setBackgroundCheck}
+            spellCheck={spellCheck}
+            onSaveSpellCheck={setSpellCheck}
+            typography={typography}
+            onSaveTypography={setTypography}
+            chineseConversion={chineseConversion}
+            onSaveChineseConversion={setChineseConversion}
+            glossary={glossary}
+            onSaveGlossary={setGlossary}
+            managed={managed}
           />
         )}
       </main>
@@ -1,23 +1,97 @@
-import { useState, useMemo } from "react";
-import type { HistoryRecord, PolishMode } from "@/core/llm/types";
-import { Search, Clipboard, Trash2 } from "lucide-react";
+import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  HistoryExportFormat,
+  HistoryFacets,
+  HistoryQuery,
+  HistoryRecord,
+  PolishMode,
+  Provider,
+} from "@/core/llm/types";
+import { useHistory } from "@/hooks/useHistory";
+import {
+  Search,
+  Clipboard,
+  Trash2,
+  Download,
+  Upload,
+  Lock,
+} from "lucide-react";
 import { cn } from "@/lib/utils";
 
 interface HistoryPageProps {
-  records: HistoryRecord[];
-  onClear: () => void;
+  providers: Provider[];
+}
+
+const PAGE_SIZE = 50;
+const SEARCH_DEBOUNCE_MS = 200;
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+const SELECT_CLASS =
+  "cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none transition-all duration-150 focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]";
+
+const EXPORT_FORMATS: { value: HistoryExportFormat; label: string }[] = [
+  { value: "json", label: "JSON" },
+  { value: "csv", label: "CSV" },
+  { value: "markdown", label: "Markdown" },
+];
+
+const LANGUAGE_LABELS: Record<string, string> = {
+  en: "English",
+  zh: "Chinese",
+};
+
+interface Filters {
+  mode: string;
+  provider: string;
+  app: string;
+  language: string;
+  /** yyyy-mm-dd from a date input, or empty. */
+  from: string;
+  to: string;
+}
+
+const EMPTY_FILTERS: Filters = {
+  mode: "",
+  provider: "",
+  app: "",
+  language: "",
+  from: "",
+  to: "",
+};
+
+function buildQuery(text: string, filters: Filters, offset: number): HistoryQuery {
+  const parseDay = (day: string) =>
+    day ? new Date(`${day}T00:00:00`).getTime() : undefined;
+  const to = parseDay(filters.to);
+  return {
+    text: text.trim() || undefined,
+    modes: filters.mode ? [filters.mode] : [],
+    providers: filters.provider ? [filters.provider] : [],
+    apps: filters.app ? [filters.app] : [],
+    languages: filters.language ? [filters.language] : [],
+    from: parseDay(filters.from),
+    // The end date is inclusive in the UI.
+    to: to !== undefined ? to + DAY_MS : undefined,
+    offset,
+    limit: PAGE_SIZE,
+  };
 }
 
 const MODE_STYLES: Record<PolishMode, { bg: string; text: string }> = {
   improve: { bg: "bg-blue-50", text: "text-blue-600" },
   rephrase: { bg: "bg-violet-50", text: "text-violet-600" },
   translate: { bg: "bg-emerald-50", text: "text-emerald-600" },
+  typography: { bg: "bg-amber-50", text: "text-amber-600" },
+  convert: { bg: "bg-rose-50", text: "text-rose-600" },
 };
 
 const MODE_LABELS: Record<PolishMode, string> = {
   improve: "Improve",
   rephrase: "Rephrase",
   translate: "Translate",
+  typography: "Typography",
+  convert: "简 ⇄ 繁",
 };
 
 function timeAgo(ts: number): string {
@@ -32,19 +106,155 @@ function timeAgo(ts: number): string {
   return new Date(ts).toLocaleDateString();
 }
 
-export function HistoryPage({ records, onClear }: HistoryPageProps) {
+export function HistoryPage({ providers }: HistoryPageProps) {
+  const {
+    queryHistory,
+    getFacets,
+    exportHistory,
+    importHistory,
+    clearHistory,
+    unlockHistory,
+  } = useHistory();
   const [query, setQuery] = useState("");
+  const [debouncedQuery, setDebouncedQuery] = useState("");
+  const [filters, setFilters] = useState<Filters>(EMPTY_FILTERS);
+  const [records, setRecords] = useState<HistoryRecord[]>([]);
+  const [total, setTotal] = useState(0);
+  const [facets, setFacets] = useState<HistoryFacets | null>(null);
+  const [loading, setLoading] = useState(true);
   const [copiedId, setCopiedId] = useState<string | null>(null);
+  const [selectedIds, setSelectedIds] = useState<Set<string>>(new Set());
+  const [notice, setNotice] = useState<string | null>(null);
+  const [locked, setLocked] = useState(false);
+  const [passphrase, setPassphrase] = useState("");
+  const [unlockError, setUnlockError] = useState<string | null>(null);
+  const fileInputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    const timer = setTimeout(() => setDebouncedQuery(query), SEARCH_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [query]);
+
+  const load = useCallback(async () => {
+    try {
+      const [page, nextFacets] = await Promise.all([
+        queryHistory(buildQuery(debouncedQuery, filters, 0)),
+        getFacets(),
+      ]);
+      setRecords(page.records);
+      setTotal(page.total);
+      setFacets(nextFacets);
+      setLocked(false);
+    } catch (err) {
+      if (err === "history_locked") {
+        setLocked(true);
+      } else {
+        console.error("Failed to load history:", err);
+      }
+    } finally {
+      setLoading(false);
+    }
+  }, [queryHistory, getFacets, debouncedQuery, filters]);
 
-  const filtered = useMemo(() => {
-    if (!query.trim()) return records;
-    const q = query.toLowerCase();
-    return records.filter(
-      (r) =>
-        r.inputText.toLowerCase().includes(q) ||
-        r.resultText.toLowerCase().includes(q),
-    );
-  }, [records, query]);
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  // Records are added from the floating panel window.
+  useEffect(() => {
+    const unlisten = listen("history-changed", () => {
+      load();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  const loadMore = async () => {
+    try {
+      const page = await queryHistory(
+        buildQuery(debouncedQuery, filters, records.length),
+      );
+      setRecords((prev) => [...prev, ...page.records]);
+      setTotal(page.total);
+    } catch (err) {
+      console.error("Failed to load more history:", err);
+    }
+  };
+
+  const handleUnlock = async () => {
+    try {
+      await unlockHistory(passphrase);
+      setPassphrase("");
+      setUnlockError(null);
+    } catch (err) {
+      setUnlockError(
+        err === "wrong_passphrase" ? "Wrong passphrase." : `Failed: ${err}`,
+      );
+    }
+  };
+
+  const handleClear = async () => {
+    await clearHistory();
+    setSelectedIds(new Set());
+    await load();
+  };
+
+  const toggleSelected = (id: string) =>
+    setSelectedIds((prev) => {
+      const next = new Set(prev);
+      if (next.has(id)) next.delete(id);
+      else next.add(id);
+      return next;
+    });
+
+  // Selected records take precedence over the current search and filters.
+  const handleExport = async (format: HistoryExportFormat) => {
+    try {
+      const result = await exportHistory(
+        format,
+        [...selectedIds],
+        buildQuery(debouncedQuery, filters, 0),
+      );
+      setNotice(`Exported ${result.count} records to ${result.path}`);
+    } catch (err) {
+      setNotice(`Export failed: ${err}`);
+    }
+  };
+
+  const handleImportFile = async (file: File) => {
+    const format: HistoryExportFormat = file.name.toLowerCase().endsWith(".csv")
+      ? "csv"
+      : "json";
+    try {
+      const result = await importHistory(await file.text(), format);
+      setNotice(
+        `Imported ${result.imported} records` +
+          (result.duplicates > 0
+            ? `, skipped ${result.duplicates} already in history`
+            : "") +
+          (result.excluded > 0
+            ? `, left out ${result.excluded} by the retention settings`
+            : "") +
+          (result.invalid > 0
+            ? `, skipped ${result.invalid} rows without a readable timestamp`
+            : ""),
+      );
+    } catch (err) {
+      setNotice(`Import failed: ${err}`);
+    }
+  };
+
+  const setFilter = (key: keyof Filters, value: string) =>
+    setFilters((prev) => ({ ...prev, [key]: value }));
+
+  const providerName = (id: string) =>
+    providers.find((p) => p.id === id)?.name ?? id;
+
+  const isFiltered =
+    debouncedQuery.trim() !== "" ||
+    Object.values(filters).some((value) => value !== "");
+  const hasHistory = isFiltered || records.length > 0;
 
   const handleCopy = async (text: string, id: string) => {
     try {
@@ -65,13 +275,32 @@ export function HistoryPage({ records, onClear }: HistoryPageProps) {
             History
           </h1>
           <p className="mt-1.5 text-[13px] text-[#71717a]">
-            Your recent polishing sessions.
+            Your polishing sessions.
           </p>
         </div>
         <div className="flex items-center gap-2">
-          {records.length > 0 && (
+          <input
+            ref={fileInputRef}
+            type="file"
+            accept=".json,.csv"
+            className="hidden"
+            onChange={(e) => {
+              const file = e.target.files?.[0];
+              if (file) handleImportFile(file);
+              e.target.value = "";
+            }}
+          />
+          <button
+            onClick={() => fileInputRef.current?.click()}
+            className="flex cursor-pointer items-center gap-1.5 rounded-lg px-3 py-1.5 text-[12px] font-medium text-[#a1a1aa] transition-colors duration-200 hover:bg-[#f4f4f5] hover:text-[#18181b]"
+            title="Import JSON or CSV"
+          >
+            <Upload className="h-3.5 w-3.5" />
+            Import
+          </button>
+          {hasHistory && (
             <button
-              onClick={onClear}
+              onClick={handleClear}
               className="flex cursor-pointer items-center gap-1.5 rounded-lg px-3 py-1.5 text-[12px] font-medium text-[#a1a1aa] transition-colors duration-200 hover:bg-[#f4f4f5] hover:text-[#18181b]"
             >
               <Trash2 className="h-3.5 w-3.5" />
@@ -91,8 +320,152 @@ export function HistoryPage({ records, onClear }: HistoryPageProps) {
         </div>
       </div>
 
+      {/* Locked: history is encrypted and the key is not loaded yet */}
+      {locked && (
+        <div className="flex flex-col items-center justify-center py-16 text-center">
+          <Lock className="mb-3 h-5 w-5 text-[#a1a1aa]" />
+          <p className="text-[14px] text-[#71717a]">History is locked</p>
+          <p className="mt-1 text-[12px] text-[#a1a1aa]">
+            Enter your passphrase to view and record history.
+          </p>
+          <form
+            className="mt-4 flex items-center gap-2"
+            onSubmit={(e) => {
+              e.preventDefault();
+              handleUnlock();
+            }}
+          >
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder="Passphrase"
+              autoFocus
+              className="w-52 rounded-lg border border-[#e4e4e7] bg-white px-3 py-2 text-[13px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]"
+            />
+            <button
+              type="submit"
+              disabled={!passphrase}
+              className="cursor-pointer rounded-lg bg-[#18181b] px-3 py-2 text-[12px] font-medium text-white transition-opacity duration-150 disabled:cursor-default disabled:opacity-40"
+            >
+              Unlock
+            </button>
+          </form>
+          {unlockError && (
+            <p className="mt-2 text-[12px] text-red-500">{unlockError}</p>
+          )}
+        </div>
+      )}
+
+      {/* Filters */}
+      {hasHistory && facets && (
+        <div className="mb-4 flex flex-wrap items-center gap-2">
+          <select
+            value={filters.mode}
+            onChange={(e) => setFilter("mode", e.target.value)}
+            className={SELECT_CLASS}
+          >
+            <option value="">All modes</option>
+            {facets.modes.map((mode) => (
+              <option key={mode} value={mode}>
+                {MODE_LABELS[mode as PolishMode] ?? mode}
+              </option>
+            ))}
+          </select>
+          <select
+            value={filters.provider}
+            onChange={(e) => setFilter("provider", e.target.value)}
+            className={SELECT_CLASS}
+          >
+            <option value="">All providers</option>
+            {facets.providers.map((id) => (
+              <option key={id} value={id}>
+                {providerName(id)}
+              </option>
+            ))}
+          </select>
+          <select
+            value={filters.app}
+            onChange={(e) => setFilter("app", e.target.value)}
+            className={SELECT_CLASS}
+          >
+            <option value="">All apps</option>
+            {facets.apps.map((app) => (
+              <option key={app} value={app}>
+                {app}
+              </option>
+            ))}
+          </select>
+          <select
+            value={filters.language}
+            onChange={(e) => setFilter("language", e.target.value)}
+            className={SELECT_CLASS}
+          >
+            <option value="">All languages</option>
+            {facets.languages.map((lang) => (
+              <option key={lang} value={lang}>
+                {LANGUAGE_LABELS[lang] ?? lang}
+              </option>
+            ))}
+          </select>
+          <input
+            type="date"
+            value={filters.from}
+            onChange={(e) => setFilter("from", e.target.value)}
+            className={SELECT_CLASS}
+            title="From"
+          />
+          <input
+            type="date"
+            value={filters.to}
+            onChange={(e) => setFilter("to", e.target.value)}
+            className={SELECT_CLASS}
+            title="To"
+          />
+          <div className="ml-auto flex items-center gap-1.5">
+            <Download className="h-3.5 w-3.5 text-[#a1a1aa]" />
+            <select
+              value=""
+              onChange={(e) =>
+                e.target.value &&
+                handleExport(e.target.value as HistoryExportFormat)
+              }
+              className={SELECT_CLASS}
+              title={
+                selectedIds.size > 0
+                  ? "Export selected records"
+                  : "Export records matching the filters"
+              }
+            >
+              <option value="">
+                {selectedIds.size > 0
+                  ? `Export ${selectedIds.size} selected`
+                  : "Export"}
+              </option>
+              {EXPORT_FORMATS.map((f) => (
+                <option key={f.value} value={f.value}>
+                  {f.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+      )}
+
+      {notice && (
+        <div className="mb-4 flex items-start justify-between gap-3 rounded-lg bg-[#f4f4f5] px-3 py-2 text-[12px] text-[#71717a]">
+          <span className="break-all">{notice}</span>
+          <button
+            onClick={() => setNotice(null)}
+            className="cursor-pointer text-[#a1a1aa] hover:text-[#18181b]"
+          >
+            &times;
+          </button>
+        </div>
+      )}
+
       {/* Empty state */}
-      {records.length === 0 && (
+      {!loading && !locked && !hasHistory && (
         <div className="flex flex-col items-center justify-center py-16 text-center">
           <p className="text-[14px] text-[#a1a1aa]">No history yet</p>
           <p className="mt-1 text-[12px] text-[#d4d4d8]">
@@ -102,19 +475,26 @@ export function HistoryPage({ records, onClear }: HistoryPageProps) {
       )}
 
       {/* History list */}
-      {filtered.length > 0 && (
+      {records.length > 0 && (
         <div className="overflow-hidden rounded-xl border border-[#f4f4f5]">
-          {filtered.map((record, i) => {
+          {records.map((record, i) => {
             const style = MODE_STYLES[record.mode];
             return (
               <div
                 key={record.id}
                 className={cn(
                   "group cursor-default px-5 py-4 transition-colors duration-100 hover:bg-[#f8fafc]",
-                  i < filtered.length - 1 && "border-b border-[#f4f4f5]",
+                  i < records.length - 1 && "border-b border-[#f4f4f5]",
                 )}
               >
                 <div className="mb-2 flex items-center gap-2">
+                  <input
+                    type="checkbox"
+                    checked={selectedIds.has(record.id)}
+                    onChange={() => toggleSelected(record.id)}
+                    className="cursor-pointer accent-blue-500"
+                    title="Select for export"
+                  />
                   <span
                     className={cn(
                       "inline-flex items-center rounded-md px-2 py-0.5 text-[10px] font-semibold",
@@ -124,6 +504,14 @@ export function HistoryPage({ records, onClear }: HistoryPageProps) {
                   >
                     {MODE_LABELS[record.mode]}
                   </span>
+                  {record.app && (
+                    <>
+                      <span className="text-[11px] text-[#d4d4d8]">&middot;</span>
+                      <span className="text-[11px] text-[#71717a]">
+                        {record.app}
+                      </span>
+                    </>
+                  )}
                   <span className="text-[11px] text-[#d4d4d8]">&middot;</span>
                   <span className="text-[11px] text-[#a1a1aa]">
                     {timeAgo(record.timestamp)}
@@ -150,12 +538,24 @@ export function HistoryPage({ records, onClear }: HistoryPageProps) {
         </div>
       )}
 
+      {/* Pagination */}
+      {records.length < total && (
+        <div className="mt-4 flex justify-center">
+          <button
+            onClick={loadMore}
+            className="cursor-pointer rounded-lg px-3 py-1.5 text-[12px] font-medium text-[#71717a] transition-colors duration-200 hover:bg-[#f4f4f5] hover:text-[#18181b]"
+          >
+            Load more ({total - records.length} remaining)
+          </button>
+        </div>
+      )}
+
       {/* No results for search */}
-      {filtered.length === 0 && records.length > 0 && (
+      {!loading && !locked && records.length === 0 && isFiltered && (
         <div className="flex flex-col items-center justify-center py-16 text-center">
           <p className="text-[14px] text-[#a1a1aa]">No results found</p>
           <p className="mt-1 text-[12px] text-[#d4d4d8]">
-            Try a different search term.
+            Try a different search term or filter.
           </p>
         </div>
       )}
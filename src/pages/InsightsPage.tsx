@@ -0,0 +1,271 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  ErrorCategory,
+  Insights,
+  InsightsBreakdown,
+} from "@/core/llm/types";
+import { cn } from "@/lib/utils";
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+const RANGE_OPTIONS: { value: number; label: string }[] = [
+  { value: 7, label: "Last 7 days" },
+  { value: 30, label: "Last 30 days" },
+  { value: 90, label: "Last 90 days" },
+  { value: 0, label: "All time" },
+];
+
+const CATEGORY_LABELS: Record<ErrorCategory, string> = {
+  spelling: "Spelling",
+  punctuation: "Punctuation",
+  capitalization: "Capitalization",
+  spacing: "Spacing",
+  article: "Articles",
+  preposition: "Prepositions",
+  confused_words: "Confused words",
+  grammar: "Grammar",
+  word_choice: "Word choice",
+  rephrasing: "Rephrasing",
+};
+
+const MODE_LABELS: Record<string, string> = {
+  improve: "Improve",
+  rephrase: "Rephrase",
+  translate: "Translate",
+  typography: "Typography",
+  convert: "简 ⇄ 繁",
+};
+
+const LANGUAGE_LABELS: Record<string, string> = {
+  en: "English",
+  zh: "Chinese",
+};
+
+function Stat({ label, value }: { label: string; value: number }) {
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="text-[20px] font-semibold tracking-tight text-[#18181b]">
+        {value.toLocaleString()}
+      </div>
+      <div className="mt-0.5 text-[11px] text-[#a1a1aa]">{label}</div>
+    </div>
+  );
+}
+
+function BarList({
+  title,
+  items,
+  labels,
+}: {
+  title: string;
+  items: InsightsBreakdown[];
+  labels?: Record<string, string>;
+}) {
+  const max = Math.max(1, ...items.map((i) => i.sessions));
+  return (
+    <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+      <div className="mb-3 text-[12px] font-medium text-[#18181b]">{title}</div>
+      <div className="space-y-2">
+        {items.slice(0, 6).map((item) => (
+          <div key={item.key} className="text-[11px]">
+            <div className="mb-1 flex justify-between text-[#71717a]">
+              <span className="truncate">{labels?.[item.key] ?? item.key}</span>
+              <span>{item.sessions}</span>
+            </div>
+            <div className="h-1.5 rounded-full bg-[#f4f4f5]">
+              <div
+                className="h-1.5 rounded-full bg-blue-400"
+                style={{ width: `${(item.sessions / max) * 100}%` }}
+              />
+            </div>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+export function InsightsPage() {
+  const [days, setDays] = useState(30);
+  const [insights, setInsights] = useState<Insights | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const range = days > 0 ? { from: Date.now() - days * DAY_MS } : null;
+      setInsights(await invoke<Insights>("get_insights", { range }));
+      setError(null);
+    } catch (err) {
+      setError(
+        err === "history_locked"
+          ? "History is locked. Unlock it on the History page to see insights."
+          : `Failed to load insights: ${err}`,
+      );
+    }
+  }, [days]);
+
+  useEffect(() => {
+    load();
+    const unlisten = listen("history-changed", () => {
+      load();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  const maxDay = Math.max(1, ...(insights?.trend.map((d) => d.sessions) ?? []));
+  const maxCategory = Math.max(
+    1,
+    ...(insights?.categories.map((c) => c.count) ?? []),
+  );
+
+  return (
+    <div className="mx-auto max-w-[640px] px-10 py-10">
+      <div className="mb-6 flex items-end justify-between">
+        <div>
+          <h1 className="text-[20px] font-semibold tracking-tight text-[#18181b]">
+            Insights
+          </h1>
+          <p className="mt-1.5 text-[13px] text-[#71717a]">
+            What you write and the corrections you keep getting.
+          </p>
+        </div>
+        <select
+          value={days}
+          onChange={(e) => setDays(Number(e.target.value))}
+          className="cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-2.5 py-1.5 text-[12px] text-[#18181b] outline-none"
+        >
+          {RANGE_OPTIONS.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      {error && (
+        <div className="py-16 text-center text-[13px] text-[#a1a1aa]">
+          {error}
+        </div>
+      )}
+
+      {!error && insights && insights.sessions === 0 && (
+        <div className="flex flex-col items-center justify-center py-16 text-center">
+          <p className="text-[14px] text-[#a1a1aa]">No sessions in this period</p>
+          <p className="mt-1 text-[12px] text-[#d4d4d8]">
+            Insights appear once you have polished some text.
+          </p>
+        </div>
+      )}
+
+      {!error && insights && insights.sessions > 0 && (
+        <div className="space-y-4">
+          <div className="grid grid-cols-3 gap-3">
+            <Stat label="Sessions" value={insights.sessions} />
+            <Stat label="Words polished" value={insights.words} />
+            <Stat label="Corrections" value={insights.corrections} />
+          </div>
+
+          {/* Activity trend */}
+          <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+            <div className="mb-3 text-[12px] font-medium text-[#18181b]">
+              Activity
+            </div>
+            <div className="flex h-24 items-end gap-[2px]">
+              {insights.trend.map((day) => (
+                <div
+                  key={day.date}
+                  className="flex-1 rounded-t-sm bg-blue-400/80"
+                  style={{ height: `${(day.sessions / maxDay) * 100}%` }}
+                  title={`${day.date}: ${day.sessions} sessions, ${day.corrections} corrections`}
+                />
+              ))}
+            </div>
+            <div className="mt-1 flex justify-between text-[10px] text-[#a1a1aa]">
+              <span>{insights.trend[0]?.date}</span>
+              <span>{insights.trend[insights.trend.length - 1]?.date}</span>
+            </div>
+          </div>
+
+          {/* Error categories */}
+          {insights.categories.length > 0 && (
+            <div className="rounded-xl border border-[#f4f4f5] px-5 py-4">
+              <div className="mb-3 text-[12px] font-medium text-[#18181b]">
+                Corrections by type
+              </div>
+              <div className="space-y-2">
+                {insights.categories.map((c) => (
+                  <div key={c.category} className="flex items-center gap-3 text-[11px]">
+                    <span className="w-28 shrink-0 text-[#71717a]">
+                      {CATEGORY_LABELS[c.category]}
+                    </span>
+                    <div className="h-1.5 flex-1 rounded-full bg-[#f4f4f5]">
+                      <div
+                        className="h-1.5 rounded-full bg-violet-400"
+                        style={{ width: `${(c.count / maxCategory) * 100}%` }}
+                      />
+                    </div>
+                    <span className="w-8 text-right text-[#a1a1aa]">{c.count}</span>
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+
+          {/* Recurring corrections */}
+          {insights.top_corrections.length > 0 && (
+            <div className="overflow-hidden rounded-xl border border-[#f4f4f5]">
+              <div className="px-5 pt-4 pb-2 text-[12px] font-medium text-[#18181b]">
+                Top recurring corrections
+              </div>
+              {insights.top_corrections.map((c, i) => (
+                <div
+                  key={`${c.deleted}->${c.inserted}`}
+                  className={cn(
+                    "flex items-center gap-3 px-5 py-2.5 text-[12px]",
+                    i > 0 && "border-t border-[#f4f4f5]",
+                  )}
+                >
+                  <span className="min-w-0 flex-1 truncate">
+                    {c.deleted && (
+                      <span className="rounded-sm bg-diff-delete-bg text-diff-delete-text line-through">
+                        {c.deleted}
+                      </span>
+                    )}
+                    {c.deleted && c.inserted && (
+                      <span className="mx-1.5 text-[#d4d4d8]">&rarr;</span>
+                    )}
+                    {c.inserted && (
+                      <span className="rounded-sm bg-diff-insert-bg text-diff-insert-text">
+                        {c.inserted}
+                      </span>
+                    )}
+                  </span>
+                  <span className="shrink-0 text-[10px] text-[#a1a1aa]">
+                    {CATEGORY_LABELS[c.category]}
+                  </span>
+                  <span className="w-8 shrink-0 text-right font-medium text-[#52525b]">
+                    &times;{c.count}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
+          <div className="grid grid-cols-3 gap-3">
+            <BarList title="Modes" items={insights.by_mode} labels={MODE_LABELS} />
+            <BarList
+              title="Languages"
+              items={insights.by_language}
+              labels={LANGUAGE_LABELS}
+            />
+            <BarList title="Apps" items={insights.by_app} />
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
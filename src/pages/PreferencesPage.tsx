@@ -1,20 +1,79 @@
-import type { PolishMode } from "@/core/llm/types";
+import type {
+  BackgroundCheckSettings,
+  ChineseConversionSettings,
+  GlossaryEntry,
+  ManagedState,
+  PolishMode,
+  Provider,
+  QuickReplaceSettings,
+  ShortcutBinding,
+  SpellCheckSettings,
+  TypographySettings,
+} from "@/core/llm/types";
+import { managedField } from "@/hooks/useSettings";
+import { BackgroundCheckEditor } from "@/components/BackgroundCheckEditor";
+import { ChineseConversionEditor } from "@/components/ChineseConversionEditor";
+import { HistoryEncryption } from "@/components/HistoryEncryption";
+import { HistoryRetention } from "@/components/HistoryRetention";
+import { GlossaryEditor } from "@/components/GlossaryEditor";
+import { ManagedBadge } from "@/components/ManagedBadge";
+import { ShortcutsEditor } from "@/components/ShortcutsEditor";
+import { SpellCheckEditor } from "@/components/SpellCheckEditor";
+import { TypographyEditor } from "@/components/TypographyEditor";
 
 interface PreferencesPageProps {
   defaultMode: PolishMode;
   onSetDefaultMode: (mode: PolishMode) => void;
+  shortcuts?: ShortcutBinding[];
+  onSaveShortcuts?: (bindings: ShortcutBinding[]) => Promise<void>;
+  quickReplace?: QuickReplaceSettings;
+  onSaveQuickReplace?: (next: QuickReplaceSettings) => Promise<void>;
+  providers?: Provider[];
+  backgroundCheck?: BackgroundCheckSettings;
+  onSaveBackgroundCheck?: (next: BackgroundCheckSettings) => Promise<void>;
+  spellCheck?: SpellCheckSettings;
+  onSaveSpellCheck?: (next: SpellCheckSettings) => Promise<void>;
+  typography?: TypographySettings;
+  onSaveTypography?: (next: TypographySettings) => Promise<void>;
+  chineseConversion?: ChineseConversionSettings;
+  onSaveChineseConversion?: (next: ChineseConversionSettings) => Promise<void>;
+  glossary?: GlossaryEntry[];
+  onSaveGlossary?: (entries: GlossaryEntry[]) => Promise<void>;
+  /** Fields locked by the config file or organization policy. */
+  managed?: ManagedState;
 }
 
 const MODE_OPTIONS: { value: PolishMode; label: string }[] = [
   { value: "improve", label: "Improve" },
   { value: "rephrase", label: "Rephrase" },
   { value: "translate", label: "Translate" },
+  { value: "typography", label: "Typography" },
+  { value: "convert", label: "简 ⇄ 繁" },
 ];
 
 export function PreferencesPage({
   defaultMode,
   onSetDefaultMode,
+  shortcuts,
+  onSaveShortcuts,
+  quickReplace,
+  onSaveQuickReplace,
+  providers,
+  backgroundCheck,
+  onSaveBackgroundCheck,
+  spellCheck,
+  onSaveSpellCheck,
+  typography,
+  onSaveTypography,
+  chineseConversion,
+  onSaveChineseConversion,
+  glossary,
+  onSaveGlossary,
+  managed,
 }: PreferencesPageProps) {
+  const modeLock = managedField(managed, "defaultMode");
+  const fileError = managed?.policyError ?? managed?.configError;
+
   return (
     <div className="mx-auto max-w-[640px] px-10 py-10">
       <div className="mb-8">
@@ -24,14 +83,23 @@ export function PreferencesPage({
         <p className="mt-1.5 text-[13px] leading-relaxed text-[#71717a]">
           Customize Polishr behavior.
         </p>
+        {fileError && (
+          <p className="mt-2 text-[12px] text-red-500">
+            {managed?.policyError
+              ? `Organization policy ${managed.policyPath} could not be read: `
+              : `Config file ${managed?.configPath} is ignored: `}
+            {fileError}
+          </p>
+        )}
       </div>
 
       <div className="space-y-4">
         {/* Default Action */}
         <div className="flex items-center justify-between rounded-xl border border-[#f4f4f5] px-5 py-4">
           <div>
-            <div className="text-[13px] font-medium text-[#18181b]">
+            <div className="flex items-center gap-2 text-[13px] font-medium text-[#18181b]">
               Default Action
+              <ManagedBadge entry={modeLock} />
             </div>
             <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
               Action used when pressing the hotkey
@@ -39,10 +107,11 @@ export function PreferencesPage({
           </div>
           <select
             value={defaultMode}
+            disabled={Boolean(modeLock)}
             onChange={(e) =>
               onSetDefaultMode(e.target.value as PolishMode)
             }
-            className="cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-3 py-1.5 text-[13px] text-[#18181b] outline-none transition-all duration-150 focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]"
+            className="cursor-pointer rounded-lg border border-[#e4e4e7] bg-white px-3 py-1.5 text-[13px] text-[#18181b] outline-none transition-all duration-150 focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)] disabled:cursor-default disabled:opacity-60"
           >
             {MODE_OPTIONS.map((opt) => (
               <option key={opt.value} value={opt.value}>
@@ -52,20 +121,57 @@ export function PreferencesPage({
           </select>
         </div>
 
-        {/* Hotkey */}
-        <div className="flex items-center justify-between rounded-xl border border-[#f4f4f5] px-5 py-4">
-          <div>
-            <div className="text-[13px] font-medium text-[#18181b]">
-              Global Hotkey
-            </div>
-            <div className="mt-0.5 text-[11px] text-[#a1a1aa]">
-              Shortcut to activate Polishr
-            </div>
-          </div>
-          <kbd className="rounded-lg border border-[#e4e4e7] bg-[#fafafa] px-3 py-1.5 text-[12px] font-medium text-[#52525b]">
-            &#8984; &#8997; P
-          </kbd>
-        </div>
+        {/* Shortcuts */}
+        {shortcuts && onSaveShortcuts && (
+          <ShortcutsEditor
+            shortcuts={shortcuts}
+            onSave={onSaveShortcuts}
+            quickReplace={quickReplace}
+            onSaveQuickReplace={onSaveQuickReplace}
+          />
+        )}
+
+        {/* Background Check */}
+        {providers && backgroundCheck && onSaveBackgroundCheck && (
+          <BackgroundCheckEditor
+            providers={providers}
+            backgroundCheck={backgroundCheck}
+            onSave={onSaveBackgroundCheck}
+          />
+        )}
+
+        {/* Spelling */}
+        {spellCheck && onSaveSpellCheck && (
+          <SpellCheckEditor spellCheck={spellCheck} onSave={onSaveSpellCheck} />
+        )}
+
+        {/* Typography */}
+        {typography && onSaveTypography && (
+          <TypographyEditor typography={typography} onSave={onSaveTypography} />
+        )}
+
+        {/* Chinese Conversion */}
+        {chineseConversion && onSaveChineseConversion && (
+          <ChineseConversionEditor
+            chineseConversion={chineseConversion}
+            onSave={onSaveChineseConversion}
+          />
+        )}
+
+        {/* Glossary */}
+        {glossary && onSaveGlossary && (
+          <GlossaryEditor
+            glossary={glossary}
+            managed={managed}
+            onSave={onSaveGlossary}
+          />
+        )}
+
+        {/* History Retention */}
+        <HistoryRetention managed={managed} />
+
+        {/* History Encryption */}
+        <HistoryEncryption />
       </div>
     </div>
   );
@@ -1,14 +1,19 @@
 import { useState, useEffect } from "react";
-import type { Provider } from "@/core/llm/types";
+import type { ManagedState, Provider } from "@/core/llm/types";
 import { PROVIDER_PRESETS } from "@/core/llm/types";
+import { hasApiKey, managedField } from "@/hooks/useSettings";
+import { ManagedBadge } from "@/components/ManagedBadge";
 import { cn } from "@/lib/utils";
-import { Check } from "lucide-react";
+import { Check, KeyRound } from "lucide-react";
 
 interface ProvidersPageProps {
   providers: Provider[];
   activeProviderId: string;
-  onSave: (providers: Provider[], activeId?: string) => void;
+  /** Save a provider; `apiKey` replaces the key stored in the keyring. */
+  onSave: (provider: Provider, apiKey?: string) => Promise<void>;
   onSetActive: (id: string) => void;
+  /** Providers and fields locked by the config file or organization policy. */
+  managed?: ManagedState;
 }
 
 const PROVIDER_LOGOS: Record<string, string> = {
@@ -27,36 +32,64 @@ export function ProvidersPage({
   activeProviderId,
   onSave,
   onSetActive,
+  managed,
 }: ProvidersPageProps) {
   const [selectedId, setSelectedId] = useState(
     activeProviderId || PROVIDER_PRESETS[0]?.id || "",
   );
   const [draft, setDraft] = useState<Provider | null>(null);
+  // A newly typed key. Stored keys are never read back into the webview.
+  const [apiKey, setApiKey] = useState("");
+  const [saveError, setSaveError] = useState<string | null>(null);
 
   // Get the provider data (from saved or preset)
   function getProvider(id: string): Provider {
     const saved = providers.find((p) => p.id === id);
     if (saved) return saved;
     const preset = PROVIDER_PRESETS.find((p) => p.id === id);
-    if (preset) return { ...preset, apiKey: "" };
-    return { id, name: id, endpoint: "", apiKey: "", model: "", temperature: 0.3 };
+    if (preset) return { ...preset };
+    return { id, name: id, endpoint: "", model: "", temperature: 0.3 };
   }
 
   useEffect(() => {
     setDraft(getProvider(selectedId));
+    setApiKey("");
+    setSaveError(null);
   }, [selectedId, providers]);
 
-  const handleSave = () => {
+  const handleSave = async () => {
     if (!draft) return;
-    const existing = providers.filter((p) => p.id !== draft.id);
-    const updated = [...existing, draft];
-    onSave(updated, draft.id);
-    onSetActive(draft.id);
+    try {
+      await onSave(draft, apiKey || undefined);
+      if (!activeLock) onSetActive(draft.id);
+    } catch (err) {
+      setSaveError(`Failed to save: ${err}`);
+    }
   };
 
+  const handleRemoveKey = async () => {
+    if (!draft) return;
+    try {
+      await onSave(draft, "");
+    } catch (err) {
+      setSaveError(`Failed to remove key: ${err}`);
+    }
+  };
+
+  // Presets first, then providers only defined in the config file.
+  const choices: Provider[] = [
+    ...PROVIDER_PRESETS,
+    ...providers.filter((p) => !PROVIDER_PRESETS.some((pr) => pr.id === p.id)),
+  ];
+  const isAllowed = (id: string) =>
+    !managed?.allowedProviders || managed.allowedProviders.includes(id);
+  const fieldLock = draft ? managedField(managed, `providers.${draft.id}`) : undefined;
+  const activeLock = managedField(managed, "activeProviderId");
+
   const isActive = activeProviderId === selectedId;
   const isConfigured = (id: string) =>
-    providers.find((p) => p.id === id)?.apiKey?.length ?? 0 > 0;
+    hasApiKey(providers.find((p) => p.id === id));
+  const keyStored = draft ? isConfigured(draft.id) : false;
 
   return (
     <div className="mx-auto max-w-[640px] px-10 py-10">
@@ -71,17 +104,21 @@ export function ProvidersPage({
 
       {/* Provider grid */}
       <div className="grid grid-cols-2 gap-3">
-        {PROVIDER_PRESETS.map((preset) => {
+        {choices.map((preset) => {
           const isSelected = selectedId === preset.id;
           const isActiveProvider = activeProviderId === preset.id;
           const configured = isConfigured(preset.id);
+          const allowed = isAllowed(preset.id);
           return (
             <button
               key={preset.id}
               onClick={() => setSelectedId(preset.id)}
+              disabled={!allowed}
               className={cn(
                 "flex cursor-pointer items-center gap-3 rounded-xl border-[1.5px] px-4 py-3.5 text-left transition-all duration-150",
-                isSelected
+                !allowed
+                  ? "cursor-default border-[#f4f4f5] opacity-50"
+                  : isSelected
                   ? "border-blue-500 bg-gradient-to-br from-blue-500/[0.04] to-blue-500/[0.01] shadow-[0_0_0_3px_rgba(59,130,246,0.1)]"
                   : "border-[#e5e7eb] hover:border-blue-300 hover:-translate-y-px hover:shadow-[0_0_0_3px_rgba(59,130,246,0.06)]",
               )}
@@ -96,7 +133,11 @@ export function ProvidersPage({
                   {preset.name}
                 </div>
                 <div className="truncate text-[11px] text-[#a1a1aa]">
-                  {configured ? preset.model : "Not configured"}
+                  {!allowed
+                    ? "Not allowed by policy"
+                    : configured
+                      ? preset.model
+                      : "Not configured"}
                 </div>
               </div>
               {isActiveProvider && (
@@ -122,8 +163,9 @@ export function ProvidersPage({
               className="h-10 w-10 rounded-[10px] object-contain"
             />
             <div>
-              <h2 className="text-[14px] font-semibold text-[#18181b]">
+              <h2 className="flex items-center gap-2 text-[14px] font-semibold text-[#18181b]">
                 {draft.name} Configuration
+                <ManagedBadge entry={fieldLock} />
               </h2>
               <p className="text-[11px] text-[#a1a1aa]">
                 {new URL(draft.endpoint).hostname}
@@ -139,6 +181,7 @@ export function ProvidersPage({
               <input
                 type="url"
                 value={draft.endpoint}
+                readOnly={Boolean(fieldLock)}
                 onChange={(e) =>
                   setDraft({ ...draft, endpoint: e.target.value })
                 }
@@ -151,13 +194,23 @@ export function ProvidersPage({
               </label>
               <input
                 type="password"
-                value={draft.apiKey}
-                onChange={(e) =>
-                  setDraft({ ...draft, apiKey: e.target.value })
-                }
-                placeholder="sk-..."
+                value={apiKey}
+                onChange={(e) => setApiKey(e.target.value)}
+                placeholder={keyStored ? "Stored in system keychain — type to replace" : "sk-..."}
                 className="w-full rounded-lg border border-[#e4e4e7] bg-white px-3.5 py-2.5 text-[13px] text-[#18181b] outline-none transition-all duration-150 placeholder:text-[#d4d4d8] focus:border-blue-500 focus:shadow-[0_0_0_3px_rgba(59,130,246,0.1)]"
               />
+              {keyStored && (
+                <div className="mt-2 flex items-center gap-1.5 text-[11px] text-[#a1a1aa]">
+                  <KeyRound className="h-3 w-3" />
+                  <span>Key stored in the system keychain.</span>
+                  <button
+                    onClick={handleRemoveKey}
+                    className="cursor-pointer text-[#71717a] underline-offset-2 hover:underline"
+                  >
+                    Remove
+                  </button>
+                </div>
+              )}
             </div>
             <div>
               <label className="mb-2 block text-[12px] font-medium text-[#52525b]">
@@ -166,6 +219,7 @@ export function ProvidersPage({
               <input
                 type="text"
                 value={draft.model}
+                readOnly={Boolean(fieldLock)}
                 onChange={(e) =>
                   setDraft({ ...draft, model: e.target.value })
                 }
@@ -185,6 +239,7 @@ export function ProvidersPage({
                 max="1"
                 step="0.1"
                 value={draft.temperature}
+                disabled={Boolean(fieldLock)}
                 onChange={(e) =>
                   setDraft({
                     ...draft,
@@ -205,8 +260,11 @@ export function ProvidersPage({
               onClick={handleSave}
               className="cursor-pointer rounded-lg bg-[#18181b] px-5 py-2 text-[13px] font-medium text-white shadow-sm transition-colors duration-200 hover:bg-[#27272a]"
             >
-              {isActive ? "Save Changes" : "Save & Activate"}
+              {isActive || activeLock ? "Save Changes" : "Save & Activate"}
             </button>
+            {saveError && (
+              <span className="text-[12px] text-red-500">{saveError}</span>
+            )}
           </div>
         </div>
       )}
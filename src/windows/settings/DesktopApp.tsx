@@ -24,7 +24,7 @@ export function DesktopApp() {
   const {
     providers,
     activeProviderId,
-    saveProviders,
+    saveProvider,
     setActiveProvider,
     defaultMode,
     setDefaultMode,
@@ -96,7 +96,7 @@ export function DesktopApp() {
           <ProvidersPage
             providers={providers}
             activeProviderId={activeProviderId}
-            onSave={saveProviders}
+            onSave={saveProvider}
             onSetActive={setActiveProvider}
           />
         )}